@@ -0,0 +1,166 @@
+//! Alignment of framebuffer memory.
+//!
+//! Targets with a data cache (Cortex-M7, ESP32-S3 with PSRAM, ...) have to
+//! clean the cache before starting a DMA transfer, and cache maintenance works
+//! on whole cache lines. If a framebuffer shares a cache line with unrelated
+//! data, cleaning or invalidating that line can corrupt the neighbouring
+//! data. Some peripherals additionally require their buffers to start on a
+//! 16, 32 or 64-byte boundary.
+//!
+//! Every framebuffer takes an [`Alignment`] type parameter that sets the
+//! minimum alignment of the whole buffer. Since the size of a Rust type is
+//! always a multiple of its alignment, the buffer is also padded at the end
+//! to a whole number of alignment units, so it never shares a cache line with
+//! anything else. The parameter defaults to [`Align4`], which is enough for
+//! targets without a data cache.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::align::Align32;
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::latched::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//! // Cortex-M7 D-cache lines are 32 bytes
+//! type FBType = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, DefaultOrdering, Align32>;
+//!
+//! assert_eq!(core::mem::align_of::<FBType>(), 32);
+//! assert_eq!(core::mem::size_of::<FBType>() % 32, 0);
+//! ```
+
+/// Minimum alignment of a framebuffer.
+///
+/// Implemented by zero-sized marker types whose own alignment is the
+/// requested one; framebuffers embed a zero-length array of the marker.
+pub trait Alignment: Copy + core::fmt::Debug {
+    /// Alignment in bytes.
+    const BYTES: usize = core::mem::align_of::<Self>();
+}
+
+macro_rules! alignment {
+    ($(#[$meta:meta])* $name:ident, $bytes:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        #[repr(align($bytes))]
+        pub struct $name;
+
+        impl Alignment for $name {}
+    };
+}
+
+alignment!(
+    /// 4-byte alignment, enough for word-sized DMA on uncached memory.
+    Align4,
+    4
+);
+alignment!(
+    /// 8-byte alignment.
+    Align8,
+    8
+);
+alignment!(
+    /// 16-byte alignment, e.g. for ESP32-S3 internal-RAM cache lines.
+    Align16,
+    16
+);
+alignment!(
+    /// 32-byte alignment, matching Cortex-M7 and ESP32-S3 data cache lines.
+    Align32,
+    32
+);
+alignment!(
+    /// 64-byte alignment, e.g. for ESP32-S3 PSRAM cache lines.
+    Align64,
+    64
+);
+
+/// Alignment used when a framebuffer's alignment parameter is left out.
+pub type DefaultAlignment = Align4;
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{align_of, size_of};
+
+    use super::*;
+    use crate::ordering::DefaultOrdering;
+    use crate::{compute_frame_count, compute_rows};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    fn assert_aligned<T, A: Alignment>(expected_min: usize) {
+        assert_eq!(align_of::<T>(), A::BYTES.max(expected_min));
+        assert_eq!(size_of::<T>() % A::BYTES, 0);
+    }
+
+    #[test]
+    fn test_marker_bytes() {
+        assert_eq!(Align4::BYTES, 4);
+        assert_eq!(Align8::BYTES, 8);
+        assert_eq!(Align16::BYTES, 16);
+        assert_eq!(Align32::BYTES, 32);
+        assert_eq!(Align64::BYTES, 64);
+        assert_eq!(size_of::<Align64>(), 0);
+    }
+
+    #[test]
+    fn test_framebuffers_are_aligned_and_padded() {
+        // bitplane latched rows are COLS + 4 bytes, so the unpadded size is
+        // not a multiple of the cache line
+        const ODD_COLS: usize = 21;
+        assert_ne!((NROWS * (ODD_COLS + 4)) % 64, 0);
+
+        assert_aligned::<
+            crate::plain::DmaFrameBuffer<
+                ROWS,
+                COLS,
+                NROWS,
+                BITS,
+                FRAME_COUNT,
+                DefaultOrdering,
+                Align32,
+            >,
+            Align32,
+        >(8);
+        assert_aligned::<
+            crate::latched::DmaFrameBuffer<
+                ROWS,
+                COLS,
+                NROWS,
+                BITS,
+                FRAME_COUNT,
+                DefaultOrdering,
+                Align64,
+            >,
+            Align64,
+        >(1);
+        assert_aligned::<
+            crate::bitplane::plain::DmaFrameBuffer<NROWS, COLS, 3, DefaultOrdering, Align16>,
+            Align16,
+        >(2);
+        assert_aligned::<
+            crate::bitplane::latched::DmaFrameBuffer<NROWS, ODD_COLS, 1, DefaultOrdering, Align64>,
+            Align64,
+        >(1);
+    }
+
+    #[test]
+    fn test_default_alignment_is_unchanged() {
+        assert_aligned::<crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>, Align4>(
+            8,
+        );
+        assert_aligned::<
+            crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>,
+            Align4,
+        >(4);
+    }
+}
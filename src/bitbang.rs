@@ -0,0 +1,374 @@
+//! Bit-banged reference driver using `embedded-hal` GPIOs.
+//!
+//! Setting up a DMA-driven parallel peripheral is the hardest part of bringing
+//! up a HUB75 panel, and when nothing lights up it is hard to tell whether the
+//! wiring, the framebuffer or the DMA configuration is at fault. [`BitBang`]
+//! removes the last variable: it clocks the exact word stream a DMA engine
+//! would see out of ordinary [`OutputPin`]s, one word per clock pulse.
+//!
+//! Every control signal (blanking, row address, latch, unblanking) is already
+//! encoded in the framebuffer words, so the driver reproduces the same
+//! sequence a DMA transfer would, just much more slowly. Expect a visibly
+//! flickering image; the goal is validating wiring and buffer content on any
+//! MCU, not performance.
+//!
+//! Data pin `i` is driven from bit `i` of each word, so pass 16 pins for
+//! 16-bit framebuffers (see the bit mapping in [`crate::plain`]), 8 pins
+//! for 8-bit framebuffers (wired to the external latch circuit of
+//! [`crate::latched`]) and 32 pins for [`crate::plain32`]. Use `None` for
+//! spare bits that are not connected.
+//! Framebuffers must use [`crate::ordering::Linear`] ordering.
+//!
+//! For bit-plane framebuffers, plane `n` of `PLANES` is repeated
+//! `2^(PLANES - 1 - n)` times per refresh to produce the BCM weighting.
+//!
+//! # Example
+//! ```rust,no_run
+//! use core::convert::Infallible;
+//! use embedded_hal::delay::DelayNs;
+//! use embedded_hal::digital::{ErrorType, OutputPin};
+//! use hub75_framebuffer::bitbang::BitBang;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! # struct Pin;
+//! # impl ErrorType for Pin { type Error = Infallible; }
+//! # impl OutputPin for Pin {
+//! #     fn set_low(&mut self) -> Result<(), Infallible> { Ok(()) }
+//! #     fn set_high(&mut self) -> Result<(), Infallible> { Ok(()) }
+//! # }
+//! # struct Delay;
+//! # impl DelayNs for Delay { fn delay_ns(&mut self, _ns: u32) {} }
+//! # fn pin() -> Pin { Pin }
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//!
+//! // bits 0-4: A..E, 5: LAT, 6-7: spare, 8: OE, 9-14: R1 G1 B1 R2 G2 B2, 15: spare
+//! let data = [
+//!     Some(pin()), Some(pin()), Some(pin()), Some(pin()), Some(pin()), Some(pin()),
+//!     None, None,
+//!     Some(pin()), Some(pin()), Some(pin()), Some(pin()), Some(pin()), Some(pin()),
+//!     Some(pin()),
+//!     None,
+//! ];
+//! let mut driver = BitBang::new(data, pin(), Delay);
+//! loop {
+//!     driver.output(&fb).unwrap();
+//! }
+//! ```
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+use crate::{FrameBuffer, WordSize};
+
+/// Default time the clock is held in each state, in nanoseconds.
+pub const DEFAULT_CLOCK_HALF_PERIOD_NS: u32 = 100;
+
+/// Clocks framebuffer words out of GPIO pins.
+///
+/// # Type Parameters
+/// - `P`: Data pin type
+/// - `C`: Clock pin type
+/// - `D`: Delay provider
+/// - `N`: Number of data pins, `8`, `16` or `32` to match the framebuffer
+///   word size
+pub struct BitBang<P, C, D, const N: usize> {
+    data: [Option<P>; N],
+    clk: C,
+    delay: D,
+    half_period_ns: u32,
+    last_word: Option<u32>,
+}
+
+impl<P, C, D, const N: usize> BitBang<P, C, D, N>
+where
+    P: OutputPin,
+    C: OutputPin<Error = P::Error>,
+    D: DelayNs,
+{
+    /// Create a driver from the data pins, the clock pin and a delay.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is not 8, 16 or 32.
+    pub fn new(data: [Option<P>; N], clk: C, delay: D) -> Self {
+        assert!(
+            N == 8 || N == 16 || N == 32,
+            "BitBang needs 8, 16 or 32 data pins"
+        );
+        Self {
+            data,
+            clk,
+            delay,
+            half_period_ns: DEFAULT_CLOCK_HALF_PERIOD_NS,
+            last_word: None,
+        }
+    }
+
+    /// Set how long the clock is held high and low for each word.
+    #[must_use]
+    pub fn with_clock_half_period_ns(mut self, half_period_ns: u32) -> Self {
+        self.half_period_ns = half_period_ns;
+        self
+    }
+
+    /// Give back the pins and the delay.
+    pub fn release(self) -> ([Option<P>; N], C, D) {
+        (self.data, self.clk, self.delay)
+    }
+
+    /// Clock the whole framebuffer out once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a pin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffer's word size does not match `N`.
+    pub fn output<FB: FrameBuffer + ?Sized>(&mut self, fb: &FB) -> Result<(), P::Error> {
+        let word_bytes = match fb.get_word_size() {
+            WordSize::Eight => 1,
+            WordSize::Sixteen => 2,
+            WordSize::ThirtyTwo => 4,
+        };
+        assert!(
+            word_bytes * 8 == N,
+            "framebuffer word size does not match the number of data pins"
+        );
+
+        let planes = fb.plane_count();
+        for plane_idx in 0..planes {
+            let (ptr, len) = fb.plane_ptr_len(plane_idx);
+            // SAFETY: the plane stays borrowed through `fb` while we read it.
+            let bytes = unsafe { core::slice::from_raw_parts(ptr, len) };
+            let repeat = 1usize << (planes - 1 - plane_idx);
+            for _ in 0..repeat {
+                for word in bytes.chunks_exact(word_bytes) {
+                    let word = match word {
+                        [b] => u32::from(*b),
+                        [lo, hi] => u32::from(u16::from_ne_bytes([*lo, *hi])),
+                        [b0, b1, b2, b3] => u32::from_ne_bytes([*b0, *b1, *b2, *b3]),
+                        _ => unreachable!(),
+                    };
+                    self.clock_word(word)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn clock_word(&mut self, word: u32) -> Result<(), P::Error> {
+        let changed = self.last_word.map_or(u32::MAX, |last| last ^ word);
+        for (bit, pin) in self.data.iter_mut().enumerate() {
+            if let Some(pin) = pin {
+                if changed & (1 << bit) != 0 {
+                    pin.set_state((word & (1 << bit) != 0).into())?;
+                }
+            }
+        }
+        self.last_word = Some(word);
+
+        self.delay.delay_ns(self.half_period_ns);
+        self.clk.set_high()?;
+        self.delay.delay_ns(self.half_period_ns);
+        self.clk.set_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use std::vec::Vec;
+
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::Point;
+    use embedded_hal::digital::ErrorType;
+
+    use super::*;
+    use crate::ordering::Linear;
+    use crate::{compute_frame_count, compute_rows, Color};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 2;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+    type BitplaneFB = crate::bitplane::plain::DmaFrameBuffer<NROWS, COLS, 2, Linear>;
+    type Plain32FB = crate::plain32::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+
+    #[derive(Default)]
+    struct Bus {
+        level: u32,
+        clk: bool,
+        captured: Vec<u32>,
+        pin_writes: usize,
+    }
+
+    struct DataPin<'a> {
+        bit: u8,
+        bus: &'a RefCell<Bus>,
+    }
+
+    impl ErrorType for DataPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for DataPin<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            let mut bus = self.bus.borrow_mut();
+            bus.level &= !(1 << self.bit);
+            bus.pin_writes += 1;
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            let mut bus = self.bus.borrow_mut();
+            bus.level |= 1 << self.bit;
+            bus.pin_writes += 1;
+            Ok(())
+        }
+    }
+
+    struct ClockPin<'a> {
+        bus: &'a RefCell<Bus>,
+    }
+
+    impl ErrorType for ClockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for ClockPin<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.bus.borrow_mut().clk = false;
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            let mut bus = self.bus.borrow_mut();
+            assert!(!bus.clk, "clock must toggle");
+            bus.clk = true;
+            let level = bus.level;
+            bus.captured.push(level);
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn driver<const N: usize>(
+        bus: &RefCell<Bus>,
+    ) -> BitBang<DataPin<'_>, ClockPin<'_>, NoDelay, N> {
+        let data = core::array::from_fn(|bit| {
+            Some(DataPin {
+                bit: bit as u8,
+                bus,
+            })
+        });
+        BitBang::new(data, ClockPin { bus }, NoDelay)
+    }
+
+    #[test]
+    fn test_plain_stream_is_reproduced() {
+        let mut fb = PlainFB::new();
+        fb.set_pixel(Point::new(3, 20), Color::GREEN);
+        let bus = RefCell::new(Bus::default());
+
+        driver::<16>(&bus).output(&fb).unwrap();
+
+        let expected: Vec<u32> = fb.as_words().iter().map(|&w| u32::from(w)).collect();
+        assert_eq!(bus.borrow().captured, expected);
+    }
+
+    #[test]
+    fn test_plain32_stream_is_reproduced() {
+        let mut fb = Plain32FB::new();
+        fb.set_pixel(Point::new(3, 20), Color::RED);
+        let bus = RefCell::new(Bus::default());
+
+        driver::<32>(&bus).output(&fb).unwrap();
+
+        assert_eq!(bus.borrow().captured, fb.as_words());
+    }
+
+    #[test]
+    fn test_latched_stream_is_reproduced() {
+        let mut fb = LatchedFB::new();
+        fb.set_pixel(Point::new(3, 20), Color::BLUE);
+        let bus = RefCell::new(Bus::default());
+
+        driver::<8>(&bus).output(&fb).unwrap();
+
+        let expected: Vec<u32> = fb.as_bytes().iter().map(|&b| u32::from(b)).collect();
+        assert_eq!(bus.borrow().captured, expected);
+    }
+
+    #[test]
+    fn test_bitplanes_are_weighted() {
+        let fb = BitplaneFB::new();
+        let bus = RefCell::new(Bus::default());
+
+        driver::<16>(&bus).output(&fb).unwrap();
+
+        let plane_words = fb.plane_ptr_len(0).1 / 2;
+        assert_eq!(bus.borrow().captured.len(), 3 * plane_words);
+    }
+
+    #[test]
+    fn test_only_changed_pins_are_written() {
+        let fb = PlainFB::new();
+        let bus = RefCell::new(Bus::default());
+        let mut driver = driver::<16>(&bus);
+
+        driver.output(&fb).unwrap();
+        let words = fb.as_words();
+        let expected: u32 = 16
+            + words
+                .windows(2)
+                .map(|w| (w[0] ^ w[1]).count_ones())
+                .sum::<u32>();
+        assert_eq!(bus.borrow().pin_writes, expected as usize);
+    }
+
+    #[test]
+    fn test_unconnected_pins_are_skipped() {
+        let mut fb = PlainFB::new();
+        fb.set_pixel(Point::new(0, 0), Color::WHITE);
+        let bus = RefCell::new(Bus::default());
+        let mut data: [Option<DataPin<'_>>; 16] = core::array::from_fn(|bit| {
+            Some(DataPin {
+                bit: bit as u8,
+                bus: &bus,
+            })
+        });
+        data[9] = None; // R1 not wired
+        let mut driver = BitBang::new(data, ClockPin { bus: &bus }, NoDelay);
+
+        driver.output(&fb).unwrap();
+        assert!(bus.borrow().captured.iter().all(|w| w & (1 << 9) == 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match")]
+    fn test_word_size_mismatch() {
+        let fb = LatchedFB::new();
+        let bus = RefCell::new(Bus::default());
+        let _ = driver::<16>(&bus).output(&fb);
+    }
+}
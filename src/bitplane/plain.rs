@@ -68,11 +68,15 @@
 //! `PLANES * NROWS * COLS * 2` bytes.
 
 use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
 
 use bitfield::bitfield;
 use embedded_graphics::pixelcolor::RgbColor;
 use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
 
+use crate::align::{Alignment, DefaultAlignment};
+use crate::ordering::{map_half_word_index, DefaultOrdering, Ordering};
 use crate::Color;
 use crate::FrameBuffer;
 use crate::WordSize;
@@ -97,19 +101,10 @@ const BLANKING_DELAY: usize = 8;
 const BLANKING_DELAY: usize = 1;
 
 #[inline]
-const fn map_index(i: usize) -> usize {
-    #[cfg(feature = "esp32-ordering")]
-    {
-        i ^ 1
-    }
-    #[cfg(not(feature = "esp32-ordering"))]
-    {
-        i
-    }
-}
-
-#[inline]
-const fn make_data_template<const COLS: usize>(addr: u8, prev_addr: u8) -> [Entry; COLS] {
+const fn make_data_template<const COLS: usize, O: Ordering>(
+    addr: u8,
+    prev_addr: u8,
+) -> [Entry; COLS] {
     let mut data = [Entry::new(); COLS];
     let mut i = 0;
 
@@ -128,7 +123,7 @@ const fn make_data_template<const COLS: usize>(addr: u8, prev_addr: u8) -> [Entr
             entry.0 |= 0b1_0000_0000; // OE
         }
 
-        data[map_index(i)] = entry;
+        data[map_half_word_index::<O>(i)] = entry;
         i += 1;
     }
 
@@ -188,11 +183,12 @@ impl Entry {
 ///
 /// Row addressing, latch, OE, and pixel colour data are all encoded into the
 /// 16-bit `Entry` words -- no separate address bytes are needed.
-pub struct Row<const COLS: usize> {
+pub struct Row<const COLS: usize, O: Ordering = DefaultOrdering> {
     pub(crate) data: [Entry; COLS],
+    _ordering: PhantomData<O>,
 }
 
-impl<const COLS: usize> Row<COLS> {
+impl<const COLS: usize, O: Ordering> Row<COLS, O> {
     /// Creates a zero-initialized row.
     ///
     /// Call [`Self::format`] before first use to populate row control metadata.
@@ -200,6 +196,7 @@ impl<const COLS: usize> Row<COLS> {
     pub const fn new() -> Self {
         Self {
             data: [Entry::new(); COLS],
+            _ordering: PhantomData,
         }
     }
 
@@ -209,37 +206,62 @@ impl<const COLS: usize> Row<COLS> {
     /// pixel stream template.
     #[inline]
     pub fn format(&mut self, addr: u8, prev_addr: u8) {
-        let template = make_data_template::<COLS>(addr, prev_addr);
+        let template = make_data_template::<COLS, O>(addr, prev_addr);
         self.data.copy_from_slice(&template);
     }
 }
 
-impl<const COLS: usize> Default for Row<COLS> {
+impl<const COLS: usize, O: Ordering> Default for Row<COLS, O> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// The entire BCM Frame Buffer (per-plane storage).
+/// The entire BCM Frame Buffer (per-plane storage), aligned to `A`.
 #[derive(Copy, Clone)]
 #[repr(C)]
-pub struct DmaFrameBuffer<const NROWS: usize, const COLS: usize, const PLANES: usize> {
-    pub(crate) planes: [[Row<COLS>; NROWS]; PLANES],
+pub struct DmaFrameBuffer<
+    const NROWS: usize,
+    const COLS: usize,
+    const PLANES: usize,
+    O: Ordering = DefaultOrdering,
+    A: Alignment = DefaultAlignment,
+> {
+    _alignment: [A; 0],
+    pub(crate) planes: [[Row<COLS, O>; NROWS]; PLANES],
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize>
-    DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     /// Creates a new frame buffer, pre-formatted and ready for use.
     #[must_use]
     pub fn new() -> Self {
         let mut instance = Self {
+            _alignment: [],
             planes: [[Row::new(); NROWS]; PLANES],
         };
         instance.format();
         instance
     }
 
+    /// Initialize a framebuffer in place, without building it on the stack.
+    ///
+    /// This is the building block for placing a buffer in a `static`, for
+    /// example in a DMA-capable RAM section (see the `placement` module). The
+    /// returned buffer is formatted and ready to use, like one from
+    /// [`Self::new`].
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        // SAFETY: every field is an integer wrapper, a `PhantomData` or a
+        // zero-length array, so all-zero bytes are a valid framebuffer.
+        let fb = unsafe {
+            slot.as_mut_ptr().write_bytes(0, 1);
+            slot.assume_init_mut()
+        };
+        fb.format();
+        fb
+    }
+
     /// Returns the number of BCM chunks (one per bit-plane).
     #[must_use]
     pub const fn bcm_chunk_count() -> usize {
@@ -249,7 +271,91 @@ impl<const NROWS: usize, const COLS: usize, const PLANES: usize>
     /// Returns the byte size of one BCM chunk (a single bit-plane).
     #[must_use]
     pub const fn bcm_chunk_bytes() -> usize {
-        NROWS * core::mem::size_of::<Row<COLS>>()
+        NROWS * core::mem::size_of::<Row<COLS, O>>()
+    }
+
+    /// Estimated complete refreshes per second when the DMA stream is
+    /// clocked out at `clk_hz`, rounded down.
+    ///
+    /// See [`crate::timing::BcmTiming`] for the underlying model.
+    #[must_use]
+    pub const fn estimated_refresh_hz(clk_hz: u32) -> u32 {
+        let row_clocks = core::mem::size_of::<Row<COLS, O>>() / 2;
+        crate::timing::BcmTiming::new(clk_hz, row_clocks, NROWS, PLANES as u8, 0).refresh_hz()
+    }
+
+    /// Memory bandwidth the DMA engine must sustain to clock out one
+    /// 16-bit word per cycle at `clk_hz`.
+    #[must_use]
+    pub const fn dma_bandwidth_bytes_per_sec(clk_hz: u32) -> u64 {
+        clk_hz as u64 * 2
+    }
+
+    /// Returns all planes as bytes, plane 0 first.
+    ///
+    /// Plane `n` is the `n`-th span of [`FrameBuffer::plane_ptr_len`] bytes,
+    /// in the byte order of the ordering parameter `O`. The slice borrows
+    /// the framebuffer; DMA transfers started from the plane pointers are
+    /// not tracked by the borrow, so the buffer must not be written while
+    /// they run.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = core::mem::size_of_val(&self.planes);
+        // SAFETY: the planes are `repr(C)` arrays of rows made of integer
+        // wrappers, without padding.
+        unsafe { core::slice::from_raw_parts((&raw const self.planes).cast::<u8>(), len) }
+    }
+
+    /// Returns all planes as mutable bytes, see [`Self::as_bytes`].
+    ///
+    /// Any byte pattern is a valid buffer content, but overwriting control
+    /// bits or address words will corrupt the output until [`Self::format`]
+    /// is called.
+    #[must_use]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let len = core::mem::size_of_val(&self.planes);
+        // SAFETY: as for `as_bytes`, and every bit pattern is a valid row.
+        unsafe { core::slice::from_raw_parts_mut((&raw mut self.planes).cast::<u8>(), len) }
+    }
+
+    /// Splits every plane into spans of at most `max_len` bytes for DMA
+    /// engines that limit the size of a single transfer.
+    ///
+    /// Spans end on row boundaries and never cross a plane, so the spans of
+    /// one plane can be repeated for BCM weighting like the plane itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is smaller than one row.
+    pub fn chunks(&self, max_len: usize) -> impl Iterator<Item = (*const u8, usize)> + '_ {
+        let row_bytes = core::mem::size_of::<Row<COLS, O>>();
+        assert!(max_len >= row_bytes, "max_len is smaller than a single row");
+        self.planes.iter().flat_map(move |plane| {
+            crate::row_chunks(
+                plane.as_ptr().cast::<u8>(),
+                core::mem::size_of_val(plane),
+                row_bytes,
+                max_len,
+            )
+        })
+    }
+
+    /// Write the DMA stream, prefixed with a container header, to `sink`.
+    ///
+    /// See [`crate::export`] for the format and a matching parser.
+    pub fn export(&self, sink: impl FnMut(&[u8])) {
+        let header = crate::export::Header {
+            layout: crate::export::Layout::BitplanePlain,
+            word_size: WordSize::Sixteen,
+            rows: (NROWS * 2) as u16,
+            cols: COLS as u16,
+            nrows: NROWS as u16,
+            bits: PLANES as u8,
+            esp32_ordering: crate::ordering::is_swapped::<O>(),
+            plane_count: PLANES as u16,
+            plane_bytes: Self::bcm_chunk_bytes() as u32,
+        };
+        crate::export::export(self, &header, sink);
     }
 
     /// Formats the frame buffer with row addresses and control bits.
@@ -306,7 +412,7 @@ impl<const NROWS: usize, const COLS: usize, const PLANES: usize>
             let bits = ((u8::from(((blue >> bit) & 1) != 0)) << 2)
                 | ((u8::from(((green >> bit) & 1) != 0)) << 1)
                 | u8::from(((red >> bit) & 1) != 0);
-            let col_idx = map_index(x);
+            let col_idx = map_half_word_index::<O>(x);
             let entry = &mut self.planes[plane_idx][row_idx].data[col_idx];
             if is_top {
                 entry.set_color0_bits(bits);
@@ -317,29 +423,30 @@ impl<const NROWS: usize, const COLS: usize, const PLANES: usize>
     }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> Default
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment> Default
+    for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> core::fmt::Debug
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    core::fmt::Debug for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("DmaFrameBuffer")
             .field("size", &core::mem::size_of_val(&self.planes))
             .field("plane_count", &self.planes.len())
             .field("plane_size", &core::mem::size_of_val(&self.planes[0]))
-            .finish()
+            .field("alignment", &A::BYTES)
+            .finish_non_exhaustive()
     }
 }
 
 #[cfg(feature = "defmt")]
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> defmt::Format
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    defmt::Format for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     fn format(&self, f: defmt::Formatter) {
         defmt::write!(f, "DmaFrameBuffer<{}, {}, {}>", NROWS, COLS, PLANES);
@@ -352,8 +459,8 @@ impl<const NROWS: usize, const COLS: usize, const PLANES: usize> defmt::Format
     }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> FrameBuffer
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    FrameBuffer for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     fn get_word_size(&self) -> WordSize {
         WordSize::Sixteen
@@ -369,40 +476,60 @@ impl<const NROWS: usize, const COLS: usize, const PLANES: usize> FrameBuffer
             "plane_idx {plane_idx} out of range for {PLANES} planes"
         );
         let ptr = self.planes[plane_idx].as_ptr().cast::<u8>();
-        let len = NROWS * core::mem::size_of::<Row<COLS>>();
+        let len = NROWS * core::mem::size_of::<Row<COLS, O>>();
         (ptr, len)
     }
+
+    fn rows(&self) -> usize {
+        2 * NROWS
+    }
+
+    fn cols(&self) -> usize {
+        COLS
+    }
+
+    fn bits(&self) -> u8 {
+        PLANES as u8
+    }
+
+    fn frame_count(&self) -> usize {
+        PLANES
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        core::mem::size_of_val(&self.planes)
+    }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> FrameBufferOperations
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    FrameBufferOperations for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     #[inline]
     fn erase(&mut self) {
-        DmaFrameBuffer::<NROWS, COLS, PLANES>::erase(self);
+        DmaFrameBuffer::<NROWS, COLS, PLANES, O, A>::erase(self);
     }
 
     #[inline]
     fn set_pixel(&mut self, p: Point, color: Color) {
-        DmaFrameBuffer::<NROWS, COLS, PLANES>::set_pixel(self, p, color);
+        DmaFrameBuffer::<NROWS, COLS, PLANES, O, A>::set_pixel(self, p, color);
     }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> MutableFrameBuffer
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    MutableFrameBuffer for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> OriginDimensions
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    OriginDimensions for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     fn size(&self) -> Size {
         Size::new(COLS as u32, (NROWS * 2) as u32)
     }
 }
 
-impl<const NROWS: usize, const COLS: usize, const PLANES: usize> DrawTarget
-    for DmaFrameBuffer<NROWS, COLS, PLANES>
+impl<const NROWS: usize, const COLS: usize, const PLANES: usize, O: Ordering, A: Alignment>
+    DrawTarget for DmaFrameBuffer<NROWS, COLS, PLANES, O, A>
 {
     type Color = Color;
     type Error = Infallible;
@@ -426,6 +553,10 @@ mod tests {
     use embedded_graphics::prelude::*;
     use std::format;
 
+    fn map_index(index: usize) -> usize {
+        map_half_word_index::<DefaultOrdering>(index)
+    }
+
     type TestBuffer = DmaFrameBuffer<16, 64, 8>;
 
     #[test]
@@ -561,6 +692,27 @@ mod tests {
         let (ptr, len) = as_trait.plane_ptr_len(0);
         assert_eq!(len, 16 * core::mem::size_of::<Row<64>>());
         assert_eq!(ptr, fb.planes[0].as_ptr().cast::<u8>());
+
+        assert_eq!((as_trait.rows(), as_trait.cols()), (32, 64));
+        assert_eq!((as_trait.bits(), as_trait.frame_count()), (8, 8));
+        assert_eq!(as_trait.buffer_len_bytes(), 8 * len);
+    }
+
+    #[test]
+    fn as_bytes_cover_all_planes() {
+        let mut fb = TestBuffer::new();
+        let (ptr, len) = fb.plane_ptr_len(1);
+        let bytes = fb.as_bytes();
+        assert_eq!(bytes.len(), fb.buffer_len_bytes());
+        assert_eq!(bytes.as_ptr(), fb.plane_ptr_len(0).0);
+        assert_eq!(&bytes[len..2 * len], unsafe {
+            core::slice::from_raw_parts(ptr, len)
+        });
+
+        let mut restored = TestBuffer::new();
+        fb.set_pixel(Point::new(3, 20), Color::new(200, 100, 50));
+        restored.as_bytes_mut().copy_from_slice(fb.as_bytes());
+        assert_eq!(restored.planes, fb.planes);
     }
 
     #[test]
@@ -638,6 +790,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn refresh_and_bandwidth_estimates() {
+        // 8 planes weighted 128..1 make 255 row shifts per scan row
+        assert_eq!(TestBuffer::estimated_refresh_hz(10_000_000), 38);
+        assert_eq!(
+            TestBuffer::dma_bandwidth_bytes_per_sec(10_000_000),
+            20_000_000
+        );
+    }
+
+    #[test]
+    fn chunks_do_not_cross_planes() {
+        let fb = TestBuffer::new();
+        let row_bytes = 64 * 2;
+        let chunks: std::vec::Vec<_> = fb.chunks(row_bytes * 3).collect();
+
+        // 16 rows per plane split into 3 + 3 + 3 + 3 + 3 + 1
+        assert_eq!(chunks.len(), 8 * 6);
+        for (plane_idx, plane) in chunks.chunks(6).enumerate() {
+            let (ptr, len) = fb.plane_ptr_len(plane_idx);
+            assert_eq!(plane[0].0, ptr);
+            assert_eq!(plane.iter().map(|&(_, l)| l).sum::<usize>(), len);
+            assert_eq!(plane[5].1, row_bytes);
+        }
+    }
+
     #[test]
     fn entry_debug_shows_hex_value() {
         let mut entry = Entry::new();
@@ -0,0 +1,372 @@
+//! A drawing surface larger than the display.
+//!
+//! The BCM frames of a framebuffer only hold what the panel shows. To pan
+//! over a map or scroll long text, [`VirtualCanvas`] keeps a `W` by `H`
+//! pixel shadow buffer of the whole content next to the framebuffer it
+//! owns and shows the window of it at [`VirtualCanvas::viewport`].
+//!
+//! Applications draw in canvas coordinates only: drawing updates the shadow
+//! buffer and the visible part of the framebuffer at once, and
+//! [`VirtualCanvas::set_viewport`] moves the window and re-renders it into
+//! the framebuffer. Parts of the window outside the canvas are black.
+//!
+//! The shadow buffer stores every pixel as RGB888, i.e. it takes
+//! `3 * W * H` bytes: a 512 by 32 pixel canvas takes 48 KiB.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::mono_font::{ascii::FONT_6X10, MonoTextStyle};
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::text::{Baseline, Text};
+//! use hub75_framebuffer::canvas::VirtualCanvas;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::Color;
+//!
+//! let fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let mut canvas = VirtualCanvas::<_, 256, 32>::new(fb);
+//!
+//! let style = MonoTextStyle::new(&FONT_6X10, Color::WHITE);
+//! Text::with_baseline("a long line of text that does not fit", Point::zero(), style, Baseline::Top)
+//!     .draw(&mut canvas)
+//!     .unwrap();
+//!
+//! // scroll by one column per frame
+//! for x in 0..=192 {
+//!     canvas.set_viewport(Point::new(x, 0)).unwrap();
+//! }
+//! ```
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, WordSize};
+
+/// A `W` by `H` pixel canvas shown through a framebuffer, see the
+/// [module documentation](self).
+pub struct VirtualCanvas<FB, const W: usize, const H: usize> {
+    fb: FB,
+    pixels: [[Color; W]; H],
+    viewport: Point,
+}
+
+impl<FB, const W: usize, const H: usize> VirtualCanvas<FB, W, H> {
+    /// Wrap `fb` with a black canvas and the viewport at its top left
+    /// corner. The framebuffer is not changed until something is drawn.
+    #[must_use]
+    pub const fn new(fb: FB) -> Self {
+        Self {
+            fb,
+            pixels: [[Color::BLACK; W]; H],
+            viewport: Point::zero(),
+        }
+    }
+
+    /// Top left corner of the visible window, in canvas coordinates.
+    #[must_use]
+    pub const fn viewport(&self) -> Point {
+        self.viewport
+    }
+
+    /// Colour of canvas pixel `p`, or `None` if it is outside the canvas.
+    #[must_use]
+    pub fn pixel(&self, p: Point) -> Option<Color> {
+        let (x, y) = index::<W, H>(p)?;
+        Some(self.pixels[y][x])
+    }
+
+    /// The wrapped framebuffer.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, e.g. to hand it to DMA. Drawing on it
+    /// directly is overwritten by the next re-render.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// Unwrap the framebuffer.
+    pub fn into_inner(self) -> FB {
+        self.fb
+    }
+}
+
+impl<FB: DrawTarget<Color = Color>, const W: usize, const H: usize> VirtualCanvas<FB, W, H> {
+    /// Move the visible window to `top_left` and re-render it into the
+    /// framebuffer.
+    ///
+    /// # Errors
+    /// Returns the error of the framebuffer.
+    pub fn set_viewport(&mut self, top_left: Point) -> Result<(), FB::Error> {
+        self.viewport = top_left;
+        self.render()
+    }
+
+    /// Render the visible window into the framebuffer, e.g. after drawing
+    /// on the framebuffer directly.
+    ///
+    /// # Errors
+    /// Returns the error of the framebuffer.
+    pub fn render(&mut self) -> Result<(), FB::Error> {
+        let area = self.fb.bounding_box();
+        let shift = self.viewport - area.top_left;
+        let pixels = &self.pixels;
+        self.fb.fill_contiguous(
+            &area,
+            area.points()
+                .map(|p| index::<W, H>(p + shift).map_or(Color::BLACK, |(x, y)| pixels[y][x])),
+        )
+    }
+
+    /// The visible window in canvas coordinates, and the offset from canvas
+    /// to framebuffer coordinates.
+    fn window(&self) -> (Rectangle, Point) {
+        let area = self.fb.bounding_box();
+        (
+            Rectangle::new(self.viewport, area.size),
+            area.top_left - self.viewport,
+        )
+    }
+}
+
+/// Indices of canvas pixel `p` in the shadow buffer.
+fn index<const W: usize, const H: usize>(p: Point) -> Option<(usize, usize)> {
+    let x = usize::try_from(p.x).ok().filter(|&x| x < W)?;
+    let y = usize::try_from(p.y).ok().filter(|&y| y < H)?;
+    Some((x, y))
+}
+
+impl<FB, const W: usize, const H: usize> OriginDimensions for VirtualCanvas<FB, W, H> {
+    fn size(&self) -> Size {
+        Size::new(W as u32, H as u32)
+    }
+}
+
+impl<FB, const W: usize, const H: usize> DrawTarget for VirtualCanvas<FB, W, H>
+where
+    FB: DrawTarget<Color = Color>,
+{
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (window, offset) = self.window();
+        let shadow = &mut self.pixels;
+        self.fb
+            .draw_iter(pixels.into_iter().filter_map(|Pixel(p, color)| {
+                let (x, y) = index::<W, H>(p)?;
+                shadow[y][x] = color;
+                window.contains(p).then_some(Pixel(p + offset, color))
+            }))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let canvas = Rectangle::new(Point::zero(), self.size());
+        let area = area.intersection(&canvas);
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let (Some((left, top)), Some((right, bottom))) =
+            (index::<W, H>(area.top_left), index::<W, H>(bottom_right))
+        else {
+            return Ok(());
+        };
+        for row in &mut self.pixels[top..=bottom] {
+            row[left..=right].fill(color);
+        }
+        let (window, offset) = self.window();
+        let visible = area.intersection(&window);
+        if visible.is_zero_sized() {
+            return Ok(());
+        }
+        self.fb.fill_solid(
+            &Rectangle::new(visible.top_left + offset, visible.size),
+            color,
+        )
+    }
+}
+
+impl<FB: FrameBuffer, const W: usize, const H: usize> FrameBuffer for VirtualCanvas<FB, W, H> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>, const W: usize, const H: usize> ReadBuffer
+    for VirtualCanvas<FB, W, H>
+{
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+impl<FB: core::fmt::Debug, const W: usize, const H: usize> core::fmt::Debug
+    for VirtualCanvas<FB, W, H>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VirtualCanvas")
+            .field("fb", &self.fb)
+            .field("size", &self.size())
+            .field("viewport", &self.viewport)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plain::DmaFrameBuffer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    /// A 4 by 2 pixel window on an 8 by 3 canvas.
+    struct Window(MockDisplay<Color>);
+
+    impl OriginDimensions for Window {
+        fn size(&self) -> Size {
+            Size::new(4, 2)
+        }
+    }
+
+    impl DrawTarget for Window {
+        type Color = Color;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Color>>,
+        {
+            self.0.draw_iter(pixels)
+        }
+    }
+
+    fn canvas() -> VirtualCanvas<Window, 8, 3> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        VirtualCanvas::new(Window(mock))
+    }
+
+    fn shown(canvas: &VirtualCanvas<Window, 8, 3>) -> &MockDisplay<Color> {
+        &canvas.framebuffer().0
+    }
+
+    #[test]
+    fn test_draw_shows_visible_part() {
+        let mut canvas = canvas();
+        Line::new(Point::new(2, 1), Point::new(6, 1))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut canvas)
+            .unwrap();
+        Pixel(Point::new(8, 0), Color::RED)
+            .draw(&mut canvas)
+            .unwrap();
+        shown(&canvas).assert_pattern(&["    ", "  RR"]);
+        assert_eq!(canvas.pixel(Point::new(6, 1)), Some(Color::RED));
+        assert_eq!(canvas.pixel(Point::new(8, 0)), None);
+    }
+
+    #[test]
+    fn test_set_viewport() {
+        let mut canvas = canvas();
+        Line::new(Point::new(2, 1), Point::new(6, 1))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut canvas)
+            .unwrap();
+        canvas.set_viewport(Point::new(3, 1)).unwrap();
+        assert_eq!(canvas.viewport(), Point::new(3, 1));
+        shown(&canvas).assert_pattern(&["RRRR", "KKKK"]);
+
+        // outside the canvas is black
+        canvas.set_viewport(Point::new(-2, 0)).unwrap();
+        shown(&canvas).assert_pattern(&["KKKK", "KKKK"]);
+
+        // drawing follows the viewport
+        canvas.set_viewport(Point::new(4, 1)).unwrap();
+        Pixel(Point::new(7, 2), Color::GREEN)
+            .draw(&mut canvas)
+            .unwrap();
+        shown(&canvas).assert_pattern(&["RRRK", "KKKG"]);
+    }
+
+    #[test]
+    fn test_fill_solid() {
+        let mut canvas = canvas();
+        canvas.set_viewport(Point::new(1, 1)).unwrap();
+        canvas
+            .fill_solid(
+                &Rectangle::new(Point::new(-1, 0), Size::new(4, 10)),
+                Color::BLUE,
+            )
+            .unwrap();
+        shown(&canvas).assert_pattern(&["BBKK", "BBKK"]);
+        assert_eq!(canvas.pixel(Point::new(0, 0)), Some(Color::BLUE));
+        assert_eq!(canvas.pixel(Point::new(3, 2)), Some(Color::BLACK));
+
+        // invisible fills still update the canvas
+        canvas
+            .fill_solid(
+                &Rectangle::new(Point::new(7, 0), Size::new(1, 1)),
+                Color::RED,
+            )
+            .unwrap();
+        assert_eq!(canvas.pixel(Point::new(7, 0)), Some(Color::RED));
+        shown(&canvas).assert_pattern(&["BBKK", "BBKK"]);
+    }
+
+    #[test]
+    fn test_framebuffer() {
+        let fb = DmaFrameBuffer::<16, 32, 8, 3, 7>::new();
+        let mut canvas = VirtualCanvas::<_, 64, 16>::new(fb);
+        assert_eq!(canvas.size(), Size::new(64, 16));
+        Pixel(Point::new(40, 3), Color::WHITE)
+            .draw(&mut canvas)
+            .unwrap();
+        canvas.set_viewport(Point::new(10, 0)).unwrap();
+        let mut expected = DmaFrameBuffer::<16, 32, 8, 3, 7>::new();
+        expected.set_pixel(Point::new(30, 3), Color::WHITE);
+        assert_eq!(canvas.framebuffer().as_bytes(), expected.as_bytes());
+
+        let (ptr, len) = unsafe { canvas.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { canvas.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!((canvas.rows(), canvas.cols()), (16, 32));
+    }
+}
@@ -0,0 +1,1063 @@
+//! Reconstruct the image a DMA stream shows on a panel.
+//!
+//! [`Decoded`] replays a stream the way a panel sees it: colour bits are
+//! shifted in on every clock, `LAT` copies the shift register into the
+//! column drivers, and the copy lights up on the row selected by the
+//! address lines once `OE` enables the output. Nothing but the signals in
+//! the words is used, so it checks the timing as well as the pixels of any
+//! format parameters, pin map and [`Ordering`].
+//!
+//! The result holds one on/off plane per BCM frame and an approximate
+//! colour per pixel, for black-box tests and for debugging captured
+//! streams, e.g. from [`crate::export`]. It works without `std`, but the
+//! planes take `FRAME_COUNT * ROWS * COLS` bytes.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::decode::Decoded;
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::plain::{DefaultPins, DmaFrameBuffer};
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! fb.set_pixel(Point::new(5, 20), Color::new(0, 128, 255));
+//!
+//! let decoded =
+//!     Decoded::<32, 64, 16, 7>::plain::<DefaultOrdering, DefaultPins>(fb.as_words()).unwrap();
+//! // colours are quantised to the 3-bit depth
+//! assert_eq!(decoded.pixel(5, 20), Color::new(0, 128, 224));
+//! assert_eq!(decoded.pixel(5, 19), Color::BLACK);
+//! ```
+//!
+//! [`Decoded`] is also an [`ImageDrawable`], so the decoded image can be
+//! drawn into any other display with
+//! [`Image`](embedded_graphics::image::Image):
+//!
+//! ```rust
+//! use embedded_graphics::image::Image;
+//! use embedded_graphics::mock_display::MockDisplay;
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::decode::Decoded;
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::plain::{DefaultPins, DmaFrameBuffer};
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<16, 32, 8, 3, 7>::new();
+//! fb.set_pixel(Point::new(3, 9), Color::RED);
+//!
+//! let decoded = Decoded::<16, 32, 8, 7>::plain::<DefaultOrdering, DefaultPins>(fb.as_words())
+//!     .unwrap();
+//! let mut display = MockDisplay::<Color>::new();
+//! Image::new(&decoded, Point::zero()).draw(&mut display).unwrap();
+//! assert_eq!(display.get_pixel(Point::new(3, 9)), Some(Color::new(224, 0, 0)));
+//! ```
+//!
+//! With the `std` feature, `SimulatorBridge` does this for every frame:
+//! given a `SimulatorDisplay` of the `embedded-graphics-simulator` crate,
+//! it shows exactly what a buffer puts on the panel in a desktop window,
+//! e.g. to iterate on layouts and [`crate::tiling`] remaps before flashing
+//! hardware. The bridge takes any [`DrawTarget`], so this crate does not
+//! depend on the simulator and its SDL2 library.
+//!
+//! With the `std` feature the decoded image can be stored as a PPM or PNG
+//! file with `write_ppm()`, `write_png()` or `save_png()`.
+//!
+//! Frames are numbered in the order each row is shown, starting with the
+//! rows shifted in during one pass of the stream. A row latched twice
+//! without a full row of clocks in between, as with several latch pulses,
+//! counts once.
+
+use crate::ordering::{map_byte_index, map_half_word_index, Ordering};
+use crate::{latched, plain, Color};
+use embedded_graphics::image::ImageDrawable;
+use embedded_graphics::prelude::{
+    Dimensions, DrawTarget, OriginDimensions, Point, PointsIter, Size,
+};
+use embedded_graphics::primitives::Rectangle;
+
+/// Signal levels of one clock, with the polarity already applied.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Signals {
+    /// Colour bits: red, green and blue of the upper half in bits 0 to 2,
+    /// of the lower half in bits 3 to 5.
+    pub(crate) colors: u8,
+    pub(crate) addr: u8,
+    pub(crate) lat: bool,
+    pub(crate) oe: bool,
+}
+
+const fn bit(word: u16, pin: u8) -> u8 {
+    ((word >> pin) & 1) as u8
+}
+
+pub(crate) const fn plain_signals<P: plain::PinMap>(word: u16) -> Signals {
+    let pins = [P::R1, P::G1, P::B1, P::R2, P::G2, P::B2];
+    let mut colors = 0;
+    let mut addr = 0;
+    let mut i = 0;
+    while i < 6 {
+        colors |= bit(word, pins[i]) << i;
+        if i < 5 {
+            addr |= bit(word, P::ADDR[i]) << i;
+        }
+        i += 1;
+    }
+    Signals {
+        colors,
+        addr,
+        lat: (bit(word, P::LAT) == 1) != P::LAT_ACTIVE_LOW,
+        oe: (bit(word, P::OE) == 1) != P::OE_ACTIVE_LOW,
+    }
+}
+
+pub(crate) const fn latched_signals<P: latched::PinMap>(word: u8) -> Signals {
+    let word = word as u16;
+    let pins = [P::R1, P::G1, P::B1, P::R2, P::G2, P::B2];
+    let mut colors = 0;
+    let mut addr = 0;
+    let mut i = 0;
+    while i < 6 {
+        colors |= bit(word, pins[i]) << i;
+        if i < 5 {
+            addr |= bit(word, P::ADDR[i]) << i;
+        }
+        i += 1;
+    }
+    Signals {
+        colors,
+        addr,
+        lat: (bit(word, P::LAT) == 1) != P::LAT_ACTIVE_LOW,
+        oe: (bit(word, P::OE) == 1) != P::OE_ACTIVE_LOW,
+    }
+}
+
+/// Errors returned when a stream cannot be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DecodeError {
+    /// A row was shown on an address of `NROWS` or more.
+    AddressOutOfRange(u8),
+    /// A row was shown more than `FRAME_COUNT` times in one pass.
+    TooManyFrames(u8),
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AddressOutOfRange(addr) => write!(f, "row address {addr} out of range"),
+            Self::TooManyFrames(addr) => write!(f, "row {addr} shown too often"),
+        }
+    }
+}
+
+/// A control-signal invariant broken by a framebuffer's rows, found by
+/// `verify_control_bits()`.
+///
+/// `frame` and `row` locate the row in the buffer, counting `NROWS` rows
+/// per frame, not the scan row it selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ControlBitError {
+    /// The row never raises `LAT`, so it is never shown.
+    MissingLatch {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+    },
+    /// `LAT` stays high for more than one clock on a panel without an
+    /// address latch, so the drivers follow the shifting data.
+    LatchHeldOpen {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+    },
+    /// `LAT` is high while the output is enabled.
+    LatchWhileLit {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+    },
+    /// The selected row changes while the output is enabled, which shows
+    /// up as ghosting.
+    AddressChangeWhileLit {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+    },
+    /// The row latches an address of `NROWS` or more.
+    AddressOutOfRange {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+        /// The latched address.
+        addr: u8,
+    },
+    /// The row latches an address that earlier rows already latched once
+    /// per frame, so another scan row is shown less often.
+    RepeatedAddress {
+        /// Frame of the row.
+        frame: usize,
+        /// Row within the frame.
+        row: usize,
+        /// The latched address.
+        addr: u8,
+    },
+}
+
+impl core::fmt::Display for ControlBitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingLatch { frame, row } => {
+                write!(f, "frame {frame} row {row}: no latch")
+            }
+            Self::LatchHeldOpen { frame, row } => {
+                write!(f, "frame {frame} row {row}: latch held open")
+            }
+            Self::LatchWhileLit { frame, row } => {
+                write!(f, "frame {frame} row {row}: latch while output enabled")
+            }
+            Self::AddressChangeWhileLit { frame, row } => {
+                write!(
+                    f,
+                    "frame {frame} row {row}: address change while output enabled"
+                )
+            }
+            Self::AddressOutOfRange { frame, row, addr } => {
+                write!(f, "frame {frame} row {row}: address {addr} out of range")
+            }
+            Self::RepeatedAddress { frame, row, addr } => {
+                write!(
+                    f,
+                    "frame {frame} row {row}: address {addr} latched too often"
+                )
+            }
+        }
+    }
+}
+
+/// Walk a stream of `len` words with `row_words` words per row and check
+/// the control-signal invariants of every row, see [`ControlBitError`].
+///
+/// With `address_latch` the address is taken from the words with `LAT`
+/// high and held, like the reference latch circuit of [`crate::latched`]
+/// does.
+pub(crate) fn verify_control_signals(
+    len: usize,
+    row_words: usize,
+    nrows: usize,
+    signals: impl Fn(usize) -> Signals,
+    address_latch: bool,
+) -> Result<(), ControlBitError> {
+    let mut held_addr = 0;
+    let mut prev: Option<(u8, bool)> = None;
+    let mut prev_lat = false;
+    let mut row_addr = None;
+    let frames = len / row_words / nrows;
+    let mut latched = [0usize; 32];
+
+    // the stream repeats: the first pass sets up the state at its end
+    for pass in 0..2 {
+        for i in 0..len {
+            let s = signals(i);
+            if s.lat {
+                held_addr = s.addr;
+            }
+            let addr = if address_latch { held_addr } else { s.addr };
+            let (frame, row) = (i / row_words / nrows, i / row_words % nrows);
+
+            if pass == 1 {
+                if s.lat && s.oe {
+                    return Err(ControlBitError::LatchWhileLit { frame, row });
+                }
+                if !address_latch && s.lat && prev_lat {
+                    return Err(ControlBitError::LatchHeldOpen { frame, row });
+                }
+                if let Some((prev_addr, prev_oe)) = prev {
+                    if addr != prev_addr && (s.oe || prev_oe) {
+                        return Err(ControlBitError::AddressChangeWhileLit { frame, row });
+                    }
+                }
+                if s.lat {
+                    row_addr = Some(s.addr);
+                }
+                if i % row_words == row_words - 1 {
+                    let Some(addr) = row_addr.take() else {
+                        return Err(ControlBitError::MissingLatch { frame, row });
+                    };
+                    if usize::from(addr) >= nrows {
+                        return Err(ControlBitError::AddressOutOfRange { frame, row, addr });
+                    }
+                    let count = &mut latched[usize::from(addr)];
+                    if *count == frames {
+                        return Err(ControlBitError::RepeatedAddress { frame, row, addr });
+                    }
+                    *count += 1;
+                }
+            }
+            prev = Some((addr, s.oe));
+            prev_lat = s.lat;
+        }
+    }
+    Ok(())
+}
+
+/// The image of a decoded stream: which channels of each pixel are lit in
+/// every BCM frame.
+///
+/// # Type Parameters
+/// - `ROWS`: Total number of rows in the panel
+/// - `COLS`: Number of columns in the panel
+/// - `NROWS`: Number of rows per scan
+/// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+#[derive(Clone, PartialEq, Eq)]
+pub struct Decoded<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const FRAME_COUNT: usize,
+> {
+    /// Colour bits per frame, row and column: red, green and blue in bits
+    /// 0 to 2.
+    planes: [[[u8; COLS]; ROWS]; FRAME_COUNT],
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    core::fmt::Debug for Decoded<ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Decoded")
+            .field("rows", &ROWS)
+            .field("cols", &COLS)
+            .field("frame_count", &FRAME_COUNT)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    Decoded<ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    /// Decode a [`crate::plain`] stream, e.g. from `as_words()`, with the
+    /// signals assigned by `P`.
+    ///
+    /// # Errors
+    /// See [`DecodeError`].
+    pub fn plain<O: Ordering, P: plain::PinMap>(words: &[u16]) -> Result<Self, DecodeError> {
+        Self::decode(
+            words.len(),
+            |i| plain_signals::<P>(words[map_half_word_index::<O>(i)]),
+            false,
+        )
+    }
+
+    /// Decode a [`crate::latched`] stream, e.g. from `as_bytes()`, with the
+    /// signals assigned by `P`.
+    ///
+    /// The row address is taken from the words with `LAT` high and held,
+    /// and the clock is gated while `LAT` is high, like the reference latch
+    /// circuit does.
+    ///
+    /// # Errors
+    /// See [`DecodeError`].
+    pub fn latched<O: Ordering, P: latched::PinMap>(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::decode(
+            bytes.len(),
+            |i| latched_signals::<P>(bytes[map_byte_index::<O>(i)]),
+            true,
+        )
+    }
+
+    fn decode(
+        len: usize,
+        signals: impl Fn(usize) -> Signals,
+        address_latch: bool,
+    ) -> Result<Self, DecodeError> {
+        let mut decoded = Self {
+            planes: [[[0; COLS]; ROWS]; FRAME_COUNT],
+        };
+        let mut shown = [0usize; NROWS];
+
+        let mut shift = [0u8; COLS];
+        let mut head = 0;
+        let mut shifts = 0;
+        let mut last_shift_pass = 0;
+        let mut held_addr = 0;
+        let mut prev_lat = false;
+        // row in the column drivers not shown yet, and the pass it was
+        // shifted in
+        let mut pending: Option<([u8; COLS], usize)> = None;
+
+        // the stream repeats: the middle pass sees every row shifted in,
+        // latched and shown, whatever point of the cycle the stream starts
+        for pass in 0..3 {
+            for i in 0..len {
+                let s = signals(i);
+                if prev_lat && !s.lat && shifts >= COLS {
+                    pending = Some((
+                        core::array::from_fn(|x| shift[(head + x) % COLS]),
+                        last_shift_pass,
+                    ));
+                    shifts = 0;
+                }
+                if s.lat {
+                    held_addr = s.addr;
+                }
+                if s.oe {
+                    if let Some((row, row_pass)) = pending.take() {
+                        let addr = if address_latch { held_addr } else { s.addr };
+                        if row_pass == 1 {
+                            decoded.show(&mut shown, addr, &row)?;
+                        }
+                    }
+                }
+                if !(address_latch && s.lat) {
+                    shift[head] = s.colors;
+                    head = (head + 1) % COLS;
+                    shifts += 1;
+                    last_shift_pass = pass;
+                }
+                prev_lat = s.lat;
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn show(
+        &mut self,
+        shown: &mut [usize; NROWS],
+        addr: u8,
+        row: &[u8; COLS],
+    ) -> Result<(), DecodeError> {
+        let scan_row = usize::from(addr);
+        if scan_row >= NROWS {
+            return Err(DecodeError::AddressOutOfRange(addr));
+        }
+        let frame = shown[scan_row];
+        if frame >= FRAME_COUNT {
+            return Err(DecodeError::TooManyFrames(addr));
+        }
+        shown[scan_row] += 1;
+        for (half, shift) in [(0, 0), (1, 3)] {
+            let y = half * NROWS + scan_row;
+            if y < ROWS {
+                for (pixel, colors) in self.planes[frame][y].iter_mut().zip(row) {
+                    *pixel = (colors >> shift) & 0b111;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Which of red, green and blue are lit at column `x` of row `y` in
+    /// `frame`.
+    ///
+    /// # Panics
+    /// Panics if `frame`, `x` or `y` is out of range.
+    #[must_use]
+    pub fn lit(&self, frame: usize, x: usize, y: usize) -> (bool, bool, bool) {
+        let bits = self.planes[frame][y][x];
+        (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0)
+    }
+
+    /// Approximate colour of column `x` of row `y`, from the number of
+    /// frames each channel is lit in.
+    ///
+    /// Drawing a colour and decoding it gives the colour rounded down to
+    /// the framebuffer's colour depth.
+    ///
+    /// # Panics
+    /// Panics if `x` or `y` is out of range.
+    #[must_use]
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let mut counts = [0usize; 3];
+        for plane in &self.planes {
+            for (channel, count) in counts.iter_mut().enumerate() {
+                *count += usize::from(plane[y][x] >> channel & 1);
+            }
+        }
+        let level = |count: usize| (count * 256 / (FRAME_COUNT + 1)) as u8;
+        Color::new(level(counts[0]), level(counts[1]), level(counts[2]))
+    }
+
+    /// Whether every pixel is dark in every frame.
+    #[must_use]
+    pub fn is_black(&self) -> bool {
+        self.planes
+            .iter()
+            .flatten()
+            .flatten()
+            .all(|&bits| bits == 0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    Decoded<ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    /// Red, green and blue bytes of every pixel in row `y`.
+    fn rgb_row(&self, y: usize) -> impl Iterator<Item = u8> + '_ {
+        use embedded_graphics::prelude::RgbColor;
+
+        (0..COLS).flat_map(move |x| {
+            let color = self.pixel(x, y);
+            [color.r(), color.g(), color.b()]
+        })
+    }
+
+    /// Write the image of [`Decoded::pixel`] to `w` as a binary PPM (`P6`)
+    /// file, one image pixel per LED.
+    ///
+    /// # Errors
+    /// Returns the errors of `w`.
+    pub fn write_ppm<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        write!(w, "P6\n{COLS} {ROWS}\n255\n")?;
+        for y in 0..ROWS {
+            let row: std::vec::Vec<u8> = self.rgb_row(y).collect();
+            w.write_all(&row)?;
+        }
+        Ok(())
+    }
+
+    /// Write the image of [`Decoded::pixel`] to `w` as an 8-bit RGB PNG
+    /// file, one image pixel per LED.
+    ///
+    /// The image data is stored uncompressed, which keeps the encoder small
+    /// and the files byte-for-byte reproducible.
+    ///
+    /// # Errors
+    /// Returns the errors of `w`.
+    pub fn write_png<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        let mut raw = std::vec::Vec::with_capacity(ROWS * (1 + COLS * 3));
+        for y in 0..ROWS {
+            // filter type "none"
+            raw.push(0);
+            raw.extend(self.rgb_row(y));
+        }
+
+        let mut header = [0u8; 13];
+        header[0..4].copy_from_slice(&(COLS as u32).to_be_bytes());
+        header[4..8].copy_from_slice(&(ROWS as u32).to_be_bytes());
+        // 8 bits per channel, truecolour, deflate, adaptive filters, no
+        // interlace
+        header[8..].copy_from_slice(&[8, 2, 0, 0, 0]);
+
+        // a zlib stream of stored deflate blocks
+        let mut data = std::vec![0x78, 0x01];
+        let mut blocks = raw.chunks(usize::from(u16::MAX)).peekable();
+        while let Some(block) = blocks.next() {
+            let len = block.len() as u16;
+            data.push(u8::from(blocks.peek().is_none()));
+            data.extend_from_slice(&len.to_le_bytes());
+            data.extend_from_slice(&(!len).to_le_bytes());
+            data.extend_from_slice(block);
+        }
+        data.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_png_chunk(&mut w, *b"IHDR", &header)?;
+        write_png_chunk(&mut w, *b"IDAT", &data)?;
+        write_png_chunk(&mut w, *b"IEND", &[])
+    }
+
+    /// Save the image of [`Decoded::pixel`] as a PNG file at `path`, see
+    /// [`Decoded::write_png`].
+    ///
+    /// # Errors
+    /// Returns the errors of creating or writing the file.
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_png(&mut w)?;
+        std::io::Write::flush(&mut w)
+    }
+}
+
+#[cfg(feature = "std")]
+fn write_png_chunk<W: std::io::Write>(
+    w: &mut W,
+    kind: [u8; 4],
+    data: &[u8],
+) -> std::io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(&kind)?;
+    w.write_all(data)?;
+    w.write_all(&crc32(kind.iter().chain(data)).to_be_bytes())
+}
+
+/// CRC-32 as used by PNG chunks.
+#[cfg(feature = "std")]
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Adler-32 checksum of a zlib stream.
+#[cfg(feature = "std")]
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut low, mut high) = (1u32, 0u32);
+    for &byte in bytes {
+        low = (low + u32::from(byte)) % 65521;
+        high = (high + low) % 65521;
+    }
+    high << 16 | low
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    OriginDimensions for Decoded<ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    fn size(&self) -> Size {
+        Size::new(COLS as u32, ROWS as u32)
+    }
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    ImageDrawable for Decoded<ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    type Color = Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let colors = (0..ROWS).flat_map(|y| (0..COLS).map(move |x| self.pixel(x, y)));
+        target.fill_contiguous(&self.bounding_box(), colors)
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let area = area.intersection(&self.bounding_box());
+        let colors = area
+            .points()
+            .map(|p| self.pixel(p.x as usize, p.y as usize));
+        target.fill_contiguous(&Rectangle::new(Point::zero(), area.size), colors)
+    }
+}
+
+/// Errors returned by [`SimulatorBridge`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowError<E> {
+    /// The stream could not be decoded.
+    Decode(DecodeError),
+    /// The display returned an error.
+    Draw(E),
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> core::fmt::Display for ShowError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Decode(err) => write!(f, "decoding failed: {err}"),
+            Self::Draw(err) => write!(f, "drawing failed: {err:?}"),
+        }
+    }
+}
+
+/// Shows the image a stream puts on the panel in another display, decoded
+/// anew every frame, see the [module documentation](self).
+///
+/// Meant for a `SimulatorDisplay<Rgb888>` of the
+/// `embedded-graphics-simulator` crate, but any [`DrawTarget`] will do.
+/// The last decoded frame is kept on the heap, so large panels do not need
+/// a second copy on the stack between frames.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::mock_display::MockDisplay;
+/// use embedded_graphics::prelude::*;
+/// use hub75_framebuffer::decode::SimulatorBridge;
+/// use hub75_framebuffer::ordering::DefaultOrdering;
+/// use hub75_framebuffer::plain::{DefaultPins, DmaFrameBuffer};
+/// use hub75_framebuffer::Color;
+///
+/// let mut fb = DmaFrameBuffer::<16, 32, 8, 3, 7>::new();
+/// // e.g. a `SimulatorDisplay`, followed by `window.update(bridge.display())`
+/// let mut display = MockDisplay::<Color>::new();
+/// display.set_allow_overdraw(true);
+/// let mut bridge = SimulatorBridge::<_, 16, 32, 8, 7>::new(display);
+///
+/// for x in 0..3 {
+///     fb.erase();
+///     fb.set_pixel(Point::new(x, 9), Color::RED);
+///     bridge
+///         .show_plain::<DefaultOrdering, DefaultPins>(fb.as_words())
+///         .unwrap();
+/// }
+/// let red = Color::new(224, 0, 0);
+/// assert_eq!(bridge.display().get_pixel(Point::new(2, 9)), Some(red));
+/// assert_eq!(bridge.display().get_pixel(Point::new(1, 9)), Some(Color::BLACK));
+/// ```
+#[cfg(feature = "std")]
+pub struct SimulatorBridge<
+    D,
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const FRAME_COUNT: usize,
+> {
+    display: D,
+    frame: Option<std::boxed::Box<Decoded<ROWS, COLS, NROWS, FRAME_COUNT>>>,
+}
+
+#[cfg(feature = "std")]
+impl<D, const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    SimulatorBridge<D, ROWS, COLS, NROWS, FRAME_COUNT>
+where
+    D: DrawTarget<Color = Color>,
+{
+    /// Show the frames in `display`.
+    #[must_use]
+    pub const fn new(display: D) -> Self {
+        Self {
+            display,
+            frame: None,
+        }
+    }
+
+    /// Decode a [`crate::plain`] stream like [`Decoded::plain`] and draw it.
+    ///
+    /// # Errors
+    /// See [`ShowError`]; the display keeps the previous frame if the
+    /// stream cannot be decoded.
+    pub fn show_plain<O: Ordering, P: plain::PinMap>(
+        &mut self,
+        words: &[u16],
+    ) -> Result<(), ShowError<D::Error>> {
+        let decoded = Decoded::plain::<O, P>(words).map_err(ShowError::Decode)?;
+        self.show(decoded)
+    }
+
+    /// Decode a [`crate::latched`] stream like [`Decoded::latched`] and
+    /// draw it.
+    ///
+    /// # Errors
+    /// See [`Self::show_plain`].
+    pub fn show_latched<O: Ordering, P: latched::PinMap>(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<(), ShowError<D::Error>> {
+        let decoded = Decoded::latched::<O, P>(bytes).map_err(ShowError::Decode)?;
+        self.show(decoded)
+    }
+
+    fn show(
+        &mut self,
+        decoded: Decoded<ROWS, COLS, NROWS, FRAME_COUNT>,
+    ) -> Result<(), ShowError<D::Error>> {
+        let frame = match &mut self.frame {
+            Some(frame) => {
+                **frame = decoded;
+                frame
+            }
+            None => self.frame.insert(std::boxed::Box::new(decoded)),
+        };
+        frame.draw(&mut self.display).map_err(ShowError::Draw)
+    }
+
+    /// The last frame shown, e.g. to save it with [`Decoded::save_png`].
+    #[must_use]
+    pub fn frame(&self) -> Option<&Decoded<ROWS, COLS, NROWS, FRAME_COUNT>> {
+        self.frame.as_deref()
+    }
+
+    /// The display, e.g. to update the simulator window.
+    #[must_use]
+    pub const fn display(&self) -> &D {
+        &self.display
+    }
+
+    /// The display, e.g. to draw overlays on top of the frame.
+    pub fn display_mut(&mut self) -> &mut D {
+        &mut self.display
+    }
+
+    /// Unwrap the display.
+    pub fn into_inner(self) -> D {
+        self.display
+    }
+}
+
+#[cfg(feature = "std")]
+impl<D, const ROWS: usize, const COLS: usize, const NROWS: usize, const FRAME_COUNT: usize>
+    core::fmt::Debug for SimulatorBridge<D, ROWS, COLS, NROWS, FRAME_COUNT>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SimulatorBridge")
+            .field("frame", &self.frame)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::format::{DmaFrameBuffer, Interleaved};
+    use crate::latched::LatchedFormat;
+    use crate::ordering::{Esp32, Linear};
+    use crate::plain::PlainFormat;
+    use embedded_graphics::prelude::*;
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = 16;
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = 7;
+
+    type Image = Decoded<ROWS, COLS, NROWS, FRAME_COUNT>;
+
+    const PIXELS: [(i32, i32, Color); 4] = [
+        (0, 0, Color::WHITE),
+        (5, 20, Color::new(0, 128, 255)),
+        (63, 15, Color::new(96, 32, 0)),
+        (31, 31, Color::new(224, 224, 64)),
+    ];
+
+    fn draw<F: crate::format::EntryFormat, O: Ordering>(
+        fb: &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O>,
+    ) {
+        for (x, y, color) in PIXELS {
+            fb.set_pixel(Point::new(x, y), color);
+        }
+    }
+
+    fn assert_image(image: &Image) {
+        for (x, y, color) in PIXELS {
+            let expected = Color::new(color.r() & 0xe0, color.g() & 0xe0, color.b() & 0xe0);
+            assert_eq!(image.pixel(x as usize, y as usize), expected, "({x}, {y})");
+        }
+        assert_eq!(image.pixel(1, 0), Color::BLACK);
+        assert_eq!(image.pixel(5, 4), Color::BLACK);
+    }
+
+    #[test]
+    fn test_plain() {
+        let mut fb =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        assert!(Image::plain::<Linear, plain::DefaultPins>(fb.as_words())
+            .unwrap()
+            .is_black());
+
+        draw(&mut fb);
+        let image = Image::plain::<Linear, plain::DefaultPins>(fb.as_words()).unwrap();
+        assert_image(&image);
+        // 96 lights red in the first three frames
+        let red: std::vec::Vec<bool> = (0..FRAME_COUNT).map(|f| image.lit(f, 63, 15).0).collect();
+        assert_eq!(red, [true, true, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_plain_variants() {
+        type Blank = PlainFormat<plain::DefaultPins, 2, Interleaved, 2>;
+        let mut fb = DmaFrameBuffer::<Blank, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+        draw(&mut fb);
+        assert_image(&Image::plain::<Esp32, plain::DefaultPins>(fb.as_words()).unwrap());
+
+        // nothing is shown with the output disabled throughout
+        fb.set_oe_duty(0);
+        assert!(Image::plain::<Esp32, plain::DefaultPins>(fb.as_words())
+            .unwrap()
+            .is_black());
+    }
+
+    #[test]
+    fn test_latched() {
+        let mut fb =
+            crate::latched::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        draw(&mut fb);
+        assert_image(&Image::latched::<Linear, latched::DefaultPins>(fb.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_latched_variants() {
+        // address words in front of the pixels latch the previous row
+        type Leading = LatchedFormat<4, 0>;
+        type Pulses = LatchedFormat<0, 4, latched::DefaultPins, 0, 0, crate::format::Sequential, 2>;
+        let mut fb = DmaFrameBuffer::<Leading, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+        draw(&mut fb);
+        let image = Image::latched::<Esp32, latched::DefaultPins>(fb.as_bytes()).unwrap();
+        assert_image(&image);
+        let red: std::vec::Vec<bool> = (0..FRAME_COUNT).map(|f| image.lit(f, 63, 15).0).collect();
+        assert_eq!(red, [true, true, true, false, false, false, false]);
+
+        let mut fb = DmaFrameBuffer::<Pulses, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        draw(&mut fb);
+        assert_image(&Image::latched::<Linear, latched::DefaultPins>(fb.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_image_drawable() {
+        use embedded_graphics::mock_display::MockDisplay;
+
+        let mut fb =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        draw(&mut fb);
+        let image = Image::plain::<Linear, plain::DefaultPins>(fb.as_words()).unwrap();
+
+        // drawing the decoded image reproduces the buffer
+        let mut copy =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        embedded_graphics::image::Image::new(&image, Point::zero())
+            .draw(&mut copy)
+            .unwrap();
+        assert_eq!(copy.as_words(), fb.as_words());
+
+        let area = Rectangle::new(Point::new(60, 14), Size::new(4, 2));
+        let mut display: MockDisplay<Color> = MockDisplay::new();
+        embedded_graphics::image::Image::new(&image.sub_image(&area), Point::zero())
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(
+            display.affected_area(),
+            Rectangle::new(Point::zero(), area.size)
+        );
+        assert_eq!(
+            display.get_pixel(Point::new(3, 1)),
+            Some(Color::new(96, 32, 0))
+        );
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Color::BLACK));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_image_files() {
+        let mut fb =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        draw(&mut fb);
+        let image = Image::plain::<Linear, plain::DefaultPins>(fb.as_words()).unwrap();
+
+        let mut ppm = std::vec::Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        let header = b"P6\n64 32\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), ROWS * COLS * 3);
+        let offset = (20 * COLS + 5) * 3;
+        assert_eq!(&pixels[offset..offset + 3], &[0, 128, 224]);
+
+        let mut png = std::vec::Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..24], b"IHDR\0\0\0\x40\0\0\0\x20");
+        // IEND carries the well-known CRC
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+        // the single stored block holds the PPM pixels behind filter bytes
+        let idat = &png[33 + 8..png.len() - 12 - 4];
+        let raw = &idat[7..idat.len() - 4];
+        assert_eq!(raw.len(), ROWS * (1 + COLS * 3));
+        for (row, pixels) in raw.chunks(1 + COLS * 3).zip(pixels.chunks(COLS * 3)) {
+            assert_eq!(row[0], 0);
+            assert_eq!(&row[1..], pixels);
+        }
+        assert_eq!(&idat[idat.len() - 4..], &adler32(raw).to_be_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_simulator_bridge() {
+        let mut fb =
+            crate::latched::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        draw(&mut fb);
+        let mut copy =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        let mut bridge = SimulatorBridge::<_, ROWS, COLS, NROWS, FRAME_COUNT>::new(&mut copy);
+        assert!(bridge.frame().is_none());
+        bridge
+            .show_latched::<Linear, latched::DefaultPins>(fb.as_bytes())
+            .unwrap();
+        assert_image(bridge.frame().unwrap());
+
+        // a stream that does not decode leaves the last frame shown
+        let deeper = DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, 4, 15, Linear>::new();
+        assert_eq!(
+            bridge.show_plain::<Linear, plain::DefaultPins>(deeper.as_words()),
+            Err(ShowError::Decode(DecodeError::TooManyFrames(0)))
+        );
+        assert_image(bridge.frame().unwrap());
+        drop(bridge);
+        assert_image(&Image::plain::<Linear, plain::DefaultPins>(copy.as_words()).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let fb = DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        // decoded as a panel with fewer scan rows
+        assert_eq!(
+            Decoded::<16, COLS, 8, FRAME_COUNT>::plain::<Linear, plain::DefaultPins>(fb.as_words()),
+            Err(DecodeError::AddressOutOfRange(8))
+        );
+        // decoded with fewer frames
+        assert_eq!(
+            Decoded::<ROWS, COLS, NROWS, 3>::plain::<Linear, plain::DefaultPins>(fb.as_words()),
+            Err(DecodeError::TooManyFrames(0))
+        );
+    }
+
+    mod equivalence {
+        use super::*;
+        use proptest::prelude::*;
+
+        type Pixels = std::vec::Vec<(i32, i32, (u8, u8, u8))>;
+
+        fn pixels() -> impl Strategy<Value = Pixels> {
+            let (width, height) = (i32::try_from(COLS).unwrap(), i32::try_from(ROWS).unwrap());
+            proptest::collection::vec((0..width, 0..height, any::<(u8, u8, u8)>()), 0..64)
+        }
+
+        fn draw_all<F: crate::format::EntryFormat, O: Ordering>(
+            fb: &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O>,
+            pixels: &Pixels,
+        ) {
+            for &(x, y, (red, green, blue)) in pixels {
+                fb.set_pixel(Point::new(x, y), Color::new(red, green, blue));
+            }
+        }
+
+        fn plain<O: Ordering>(pixels: &Pixels) -> Image {
+            let mut fb =
+                DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O>::new();
+            draw_all(&mut fb, pixels);
+            Image::plain::<O, plain::DefaultPins>(fb.as_words()).unwrap()
+        }
+
+        fn latched<O: Ordering>(pixels: &Pixels) -> Image {
+            let mut fb =
+                DmaFrameBuffer::<LatchedFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O>::new();
+            draw_all(&mut fb, pixels);
+            Image::latched::<O, latched::DefaultPins>(fb.as_bytes()).unwrap()
+        }
+
+        proptest! {
+            #[test]
+            fn plain_and_latched_show_the_same_image(pixels in pixels()) {
+                let image = plain::<Linear>(&pixels);
+                prop_assert!(image == plain::<Esp32>(&pixels));
+                prop_assert!(image == latched::<Linear>(&pixels));
+                prop_assert!(image == latched::<Esp32>(&pixels));
+
+                // the last colour drawn on a pixel wins
+                for &(x, y, _) in &pixels {
+                    let (_, _, (red, green, blue)) =
+                        *pixels.iter().rev().find(|p| (p.0, p.1) == (x, y)).unwrap();
+                    prop_assert_eq!(
+                        image.pixel(x as usize, y as usize),
+                        Color::new(red & 0xe0, green & 0xe0, blue & 0xe0)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1359 @@
+//! High-level display wrapper.
+//!
+//! Applications usually need more than a framebuffer: a brightness setting,
+//! the orientation the panel is mounted in and whether it is seen through a
+//! mirror, whether black pixels paint over
+//! what is already there, and something to run at every frame boundary.
+//! [`Hub75Display`] bundles these around any framebuffer (including a
+//! [`TiledFrameBuffer`](crate::tiling::TiledFrameBuffer)), so application
+//! code talks to one object whose settings can change at runtime.
+//!
+//! All settings apply while drawing: changing the brightness or rotation
+//! affects what is drawn afterwards, so redraw the content after changing
+//! them. The wrapper passes `ReadBuffer` and [`FrameBuffer`] through to the
+//! framebuffer it owns.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::display::{Hub75Display, Rotation, Transparency};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::vsync::FrameCounter;
+//! use hub75_framebuffer::Color;
+//!
+//! let fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let mut display = Hub75Display::with_hook(fb, FrameCounter::new());
+//! display.set_brightness(128);
+//! display.set_rotation(Rotation::Deg90);
+//! display.set_transparency(Transparency::BlackIsTransparent);
+//!
+//! // the panel is mounted on its side: 32 wide and 64 high
+//! assert_eq!(display.size(), Size::new(32, 64));
+//! Rectangle::new(Point::new(0, 0), Size::new(32, 8))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::RED))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! ```
+
+use core::convert::Infallible;
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::Pixel;
+
+use crate::ingest::{round_to_depth, BAYER};
+use crate::vsync::{Vsync, VsyncHook};
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+
+/// Clockwise rotation of the drawing coordinates relative to the panel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// Drawing coordinates are panel coordinates.
+    #[default]
+    Deg0,
+    /// The top left of the drawing is at the top right of the panel.
+    Deg90,
+    /// The drawing is upside down.
+    Deg180,
+    /// The top left of the drawing is at the bottom left of the panel.
+    Deg270,
+}
+
+impl Rotation {
+    /// The rotation by `degrees` clockwise, e.g. read from a configuration
+    /// file, or `None` if it is not a multiple of 90.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::display::Rotation;
+    ///
+    /// assert_eq!(Rotation::from_degrees(270), Some(Rotation::Deg270));
+    /// assert_eq!(Rotation::from_degrees(-90), Some(Rotation::Deg270));
+    /// assert_eq!(Rotation::from_degrees(45), None);
+    /// ```
+    #[must_use]
+    pub const fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::Deg0),
+            90 => Some(Self::Deg90),
+            180 => Some(Self::Deg180),
+            270 => Some(Self::Deg270),
+            _ => None,
+        }
+    }
+
+    /// The clockwise rotation in degrees.
+    #[must_use]
+    pub const fn degrees(self) -> u16 {
+        match self {
+            Self::Deg0 => 0,
+            Self::Deg90 => 90,
+            Self::Deg180 => 180,
+            Self::Deg270 => 270,
+        }
+    }
+
+    /// Whether width and height are swapped.
+    #[must_use]
+    pub const fn is_transposed(self) -> bool {
+        matches!(self, Self::Deg90 | Self::Deg270)
+    }
+
+    /// Panel coordinates of drawing point `p`, on a panel of `size`.
+    fn apply(self, p: Point, size: Size) -> Point {
+        let last = Point::zero() + size - Point::new(1, 1);
+        match self {
+            Self::Deg0 => p,
+            Self::Deg90 => Point::new(last.x - p.y, p.x),
+            Self::Deg180 => last - p,
+            Self::Deg270 => Point::new(p.y, last.y - p.x),
+        }
+    }
+}
+
+/// Rotation and mirroring of drawing coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Mapping {
+    rotation: Rotation,
+    mirror_x: bool,
+    mirror_y: bool,
+}
+
+impl Mapping {
+    /// Panel coordinates of drawing point `p`, on a panel of `panel`.
+    ///
+    /// Mirroring flips the drawing as seen after rotation, so a mirrored
+    /// image stays mirrored left to right whatever the mounting.
+    fn apply(self, p: Point, panel: Size) -> Point {
+        let last = Point::zero() + self.canvas(panel) - Point::new(1, 1);
+        let mirrored = Point::new(
+            if self.mirror_x { last.x - p.x } else { p.x },
+            if self.mirror_y { last.y - p.y } else { p.y },
+        );
+        self.rotation.apply(mirrored, panel)
+    }
+
+    /// Size of the drawing canvas on a panel of `panel`.
+    const fn canvas(self, panel: Size) -> Size {
+        if self.rotation.is_transposed() {
+            Size::new(panel.height, panel.width)
+        } else {
+            panel
+        }
+    }
+}
+
+/// What drawing black does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transparency {
+    /// Black pixels are drawn like any other colour.
+    #[default]
+    Opaque,
+    /// Black pixels are skipped and leave the content below them, e.g. for
+    /// drawing text over a background. Unlike the `skip-black-pixels`
+    /// feature this can be changed at runtime.
+    BlackIsTransparent,
+}
+
+/// How colours are dimmed below full brightness, see
+/// [`Hub75Display::set_dimming`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimming {
+    /// Colours are scaled by the brightness only. The framebuffer truncates
+    /// them to its colour depth, so at low brightness the few levels left
+    /// show as visible steps and fades jump.
+    #[default]
+    Scale,
+    /// Colours are scaled, and the remainder below one colour step of a
+    /// framebuffer with `bits` of colour depth, its `BITS` parameter, is
+    /// spread over successive redraws: the lowest BCM frame a colour does
+    /// not fill is lit in a matching share of them.
+    ///
+    /// Every redraw uses the next of 16 phases of a 4x4 ordered dither
+    /// pattern, so a pixel averages to the scaled colour over 16 redraws
+    /// and neighbouring pixels take turns instead of all switching at once.
+    /// It needs the content redrawn for every refresh, e.g. into the back
+    /// buffer of the `embassy` feature's `FrameSwap`, with
+    /// [`Hub75Display::next_dimming_phase`] called once per redraw.
+    Temporal {
+        /// Colour depth of the framebuffer.
+        bits: u8,
+    },
+}
+
+impl Dimming {
+    /// Already scaled `color` at panel point `p` as drawn in `phase`.
+    fn apply(self, phase: u8, p: Point, color: Color) -> Color {
+        let Self::Temporal { bits } = self else {
+            return color;
+        };
+        // bit-reversed phases spread a pixel's lit redraws evenly
+        let threshold = BAYER[(p.y & 3) as usize][(p.x & 3) as usize] ^ (phase.reverse_bits() >> 4);
+        round_to_depth(color, bits, threshold)
+    }
+}
+
+/// Most points a [`BrightnessCurve`] can hold.
+pub const MAX_CURVE_POINTS: usize = 8;
+
+/// Maps ambient light readings to display brightness, see
+/// [`Hub75Display::set_auto_brightness_curve`].
+///
+/// The curve is linear between its points and flat beyond the first and
+/// last one. Eyes respond to light roughly logarithmically, so space the
+/// points by factors rather than steps, e.g. 1, 10, 100 and 1000 lux.
+///
+/// Readings are smoothed with an exponential moving average, and the
+/// brightness only changes once it would move by more than the hysteresis,
+/// so a flickering sensor or a passing shadow does not make the display
+/// pump.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BrightnessCurve {
+    points: [(f32, u8); MAX_CURVE_POINTS],
+    len: usize,
+    smoothing: f32,
+    hysteresis: u8,
+}
+
+impl BrightnessCurve {
+    /// A curve through `points` of `(lux, brightness)`, without smoothing
+    /// and with a hysteresis of 4 brightness steps.
+    ///
+    /// # Panics
+    /// Panics if `points` is empty, has more than [`MAX_CURVE_POINTS`]
+    /// points, or its lux values are not strictly increasing.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::display::BrightnessCurve;
+    ///
+    /// const CURVE: BrightnessCurve =
+    ///     BrightnessCurve::new(&[(1.0, 8), (50.0, 64), (1000.0, 255)]).with_smoothing(0.2);
+    /// assert_eq!(CURVE.brightness(0.0), 8);
+    /// assert_eq!(CURVE.brightness(525.0), 160);
+    /// ```
+    #[must_use]
+    pub const fn new(points: &[(f32, u8)]) -> Self {
+        assert!(
+            !points.is_empty() && points.len() <= MAX_CURVE_POINTS,
+            "a brightness curve needs 1 to MAX_CURVE_POINTS points"
+        );
+        let mut curve = Self {
+            points: [(0.0, 0); MAX_CURVE_POINTS],
+            len: points.len(),
+            smoothing: 1.0,
+            hysteresis: 4,
+        };
+        let mut i = 0;
+        while i < points.len() {
+            assert!(
+                i == 0 || points[i].0 > points[i - 1].0,
+                "brightness curve lux values must increase"
+            );
+            curve.points[i] = points[i];
+            i += 1;
+        }
+        curve
+    }
+
+    /// Weight of each new reading in the moving average, from just above 0
+    /// (very slow) to 1 (no smoothing). Readings arrive at whatever rate the
+    /// application samples the sensor, so tune it together with that rate.
+    ///
+    /// # Panics
+    /// Panics if `smoothing` is not in `(0, 1]`.
+    #[must_use]
+    pub const fn with_smoothing(mut self, smoothing: f32) -> Self {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "smoothing must be in (0, 1]"
+        );
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Smallest brightness change that is applied; smaller changes are
+    /// held back until the reading moves further.
+    #[must_use]
+    pub const fn with_hysteresis(mut self, steps: u8) -> Self {
+        self.hysteresis = steps;
+        self
+    }
+
+    /// Brightness for an ambient light level of `lux`, before smoothing
+    /// and hysteresis.
+    #[must_use]
+    pub fn brightness(&self, lux: f32) -> u8 {
+        let points = &self.points[..self.len];
+        let (first, last) = (points[0], points[self.len - 1]);
+        if lux.is_nan() || lux <= first.0 {
+            return first.1;
+        }
+        if lux >= last.0 {
+            return last.1;
+        }
+        let upper = points.iter().position(|&(l, _)| l > lux).unwrap_or(0);
+        let ((lux0, b0), (lux1, b1)) = (points[upper - 1], points[upper]);
+        let t = (lux - lux0) / (lux1 - lux0);
+        let brightness = f32::from(b0) + (f32::from(b1) - f32::from(b0)) * t;
+        (brightness + 0.5) as u8
+    }
+}
+
+// Serialized as its points, smoothing and hysteresis; deserializing checks
+// the same invariants as the constructors.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BrightnessCurve {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("BrightnessCurve", 3)?;
+        state.serialize_field("points", &self.points[..self.len])?;
+        state.serialize_field("smoothing", &self.smoothing)?;
+        state.serialize_field("hysteresis", &self.hysteresis)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BrightnessCurve {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        /// Up to [`MAX_CURVE_POINTS`] points.
+        struct Points([(f32, u8); MAX_CURVE_POINTS], usize);
+
+        impl<'de> serde::Deserialize<'de> for Points {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct Visitor;
+
+                impl<'de> serde::de::Visitor<'de> for Visitor {
+                    type Value = Points;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        write!(f, "1 to {MAX_CURVE_POINTS} (lux, brightness) points")
+                    }
+
+                    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                        self,
+                        mut seq: A,
+                    ) -> Result<Points, A::Error> {
+                        let mut points = Points([(0.0, 0); MAX_CURVE_POINTS], 0);
+                        while let Some(point) = seq.next_element()? {
+                            if points.1 == MAX_CURVE_POINTS {
+                                return Err(A::Error::invalid_length(points.1 + 1, &self));
+                            }
+                            points.0[points.1] = point;
+                            points.1 += 1;
+                        }
+                        Ok(points)
+                    }
+                }
+
+                deserializer.deserialize_seq(Visitor)
+            }
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "BrightnessCurve")]
+        struct Fields {
+            points: Points,
+            smoothing: f32,
+            hysteresis: u8,
+        }
+
+        let Fields {
+            points: Points(points, len),
+            smoothing,
+            hysteresis,
+        } = Fields::deserialize(deserializer)?;
+        if len == 0 {
+            return Err(D::Error::invalid_length(0, &"at least 1 point"));
+        }
+        if points[..len].windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(D::Error::custom(
+                "brightness curve lux values must increase",
+            ));
+        }
+        if !(smoothing > 0.0 && smoothing <= 1.0) {
+            return Err(D::Error::custom("smoothing must be in (0, 1]"));
+        }
+        Ok(Self {
+            points,
+            len,
+            smoothing,
+            hysteresis,
+        })
+    }
+}
+
+/// A framebuffer with runtime brightness, rotation, mirroring,
+/// transparency and a vsync hook.
+///
+/// `FB` is any framebuffer that is a `DrawTarget` for [`Color`], owned or
+/// as `&mut`. `H` is called by [`VsyncHook::on_vsync`], which the refresh
+/// loop calls at every frame boundary; it is `()` unless created with
+/// [`Hub75Display::with_hook`].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hub75Display<FB, H = ()> {
+    fb: FB,
+    hook: H,
+    brightness: u8,
+    mapping: Mapping,
+    transparency: Transparency,
+    dimming: Dimming,
+    phase: u8,
+    auto_brightness: Option<BrightnessCurve>,
+    ambient_lux: Option<f32>,
+}
+
+impl<FB> Hub75Display<FB> {
+    /// Wrap `fb` at full brightness, without rotation and with opaque
+    /// black.
+    #[must_use]
+    pub const fn new(fb: FB) -> Self {
+        Self::with_hook(fb, ())
+    }
+}
+
+impl<FB, H> Hub75Display<FB, H> {
+    /// Wrap `fb` like [`Hub75Display::new`], calling `hook` at every frame
+    /// boundary.
+    #[must_use]
+    pub const fn with_hook(fb: FB, hook: H) -> Self {
+        Self {
+            fb,
+            hook,
+            brightness: u8::MAX,
+            mapping: Mapping {
+                rotation: Rotation::Deg0,
+                mirror_x: false,
+                mirror_y: false,
+            },
+            transparency: Transparency::Opaque,
+            dimming: Dimming::Scale,
+            phase: 0,
+            auto_brightness: None,
+            ambient_lux: None,
+        }
+    }
+
+    /// Scale all colours drawn from now on by `brightness / 255`.
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Select how colours are dimmed below full brightness from now on.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::display::{Dimming, Hub75Display};
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// let mut display = Hub75Display::new(fb);
+    /// display.set_dimming(Dimming::Temporal { bits: 3 });
+    ///
+    /// // a slow fade out, redrawn for every refresh
+    /// for brightness in (0..=255).rev() {
+    ///     display.set_brightness(brightness);
+    ///     display.clear(Color::new(0, 40, 80)).unwrap();
+    ///     display.next_dimming_phase();
+    ///     // wait for the next refresh
+    /// }
+    /// ```
+    pub fn set_dimming(&mut self, dimming: Dimming) {
+        self.dimming = dimming;
+    }
+
+    /// Current dimming method.
+    #[must_use]
+    pub const fn dimming(&self) -> Dimming {
+        self.dimming
+    }
+
+    /// Advance [`Dimming::Temporal`] to the next of its 16 phases. Call it
+    /// once per redraw.
+    pub fn next_dimming_phase(&mut self) {
+        self.phase = (self.phase + 1) % 16;
+    }
+
+    /// Set the brightness from ambient light readings from now on, or stop
+    /// doing so with `None`.
+    ///
+    /// The brightness then follows [`Self::set_ambient_lux`]; it can still be
+    /// set directly, and the next reading takes over again.
+    pub fn set_auto_brightness_curve(&mut self, curve: Option<BrightnessCurve>) {
+        self.auto_brightness = curve;
+        self.ambient_lux = None;
+    }
+
+    /// Feed an ambient light reading in lux.
+    ///
+    /// With a curve set by [`Self::set_auto_brightness_curve`], this updates
+    /// the smoothed light level and, if it moved far enough, the brightness.
+    /// Returns `true` if the brightness changed, i.e. the content should be
+    /// redrawn. Without a curve the reading is ignored.
+    pub fn set_ambient_lux(&mut self, lux: f32) -> bool {
+        let Some(curve) = self.auto_brightness else {
+            return false;
+        };
+        if lux.is_nan() {
+            return false;
+        }
+        let smoothed = match self.ambient_lux {
+            Some(previous) => previous + (lux - previous) * curve.smoothing,
+            None => lux,
+        };
+        self.ambient_lux = Some(smoothed);
+        let target = curve.brightness(smoothed);
+        // the ends of the curve are always reached, so the display can get
+        // fully dim or bright however large the hysteresis
+        let at_end = target == curve.points[0].1 || target == curve.points[curve.len - 1].1;
+        if target == self.brightness
+            || (self.brightness.abs_diff(target) < curve.hysteresis && !at_end)
+        {
+            return false;
+        }
+        self.brightness = target;
+        true
+    }
+
+    /// Smoothed ambient light level, once a reading has been fed with a
+    /// curve set.
+    #[must_use]
+    pub const fn ambient_lux(&self) -> Option<f32> {
+        self.ambient_lux
+    }
+
+    /// Current brightness, 255 being the colours as drawn.
+    #[must_use]
+    pub const fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Rotate drawing coordinates from now on.
+    ///
+    /// Drawing, including `fill_solid` and
+    /// [`FrameBufferOperations::set_pixel`], maps points to the panel, and
+    /// the size reported by [`OriginDimensions`] swaps width and height for
+    /// 90° and 270°.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.mapping.rotation = rotation;
+    }
+
+    /// Current rotation.
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation {
+        self.mapping.rotation
+    }
+
+    /// Mirror drawing coordinates from now on: left to right if `x`, top to
+    /// bottom if `y`, e.g. for panels viewed through a mirror or from behind
+    /// rear-projection acrylic.
+    ///
+    /// Mirroring applies to the rotated canvas, so `set_mirror(true, false)`
+    /// flips what the viewer sees left to right for any rotation. Both
+    /// together are the same as rotating by a further 180°. Remapping by a
+    /// wrapped [`TiledFrameBuffer`](crate::tiling::TiledFrameBuffer) happens
+    /// after both.
+    pub fn set_mirror(&mut self, x: bool, y: bool) {
+        self.mapping.mirror_x = x;
+        self.mapping.mirror_y = y;
+    }
+
+    /// Current mirroring, left to right and top to bottom.
+    #[must_use]
+    pub const fn mirror(&self) -> (bool, bool) {
+        (self.mapping.mirror_x, self.mapping.mirror_y)
+    }
+
+    /// Select what drawing black does from now on.
+    pub fn set_transparency(&mut self, transparency: Transparency) {
+        self.transparency = transparency;
+    }
+
+    /// Current transparency policy.
+    #[must_use]
+    pub const fn transparency(&self) -> Transparency {
+        self.transparency
+    }
+
+    /// The wrapped framebuffer, e.g. to hand it to the DMA.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, bypassing all settings.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// The vsync hook.
+    #[must_use]
+    pub const fn hook(&self) -> &H {
+        &self.hook
+    }
+
+    /// The vsync hook.
+    pub fn hook_mut(&mut self) -> &mut H {
+        &mut self.hook
+    }
+
+    /// Unwrap the framebuffer and the hook.
+    pub fn into_parts(self) -> (FB, H) {
+        (self.fb, self.hook)
+    }
+
+    /// `color` as drawn with the current settings, or `None` if it is
+    /// skipped.
+    fn map_color(&self, color: Color) -> Option<Color> {
+        map_color(color, self.brightness, self.transparency)
+    }
+
+    /// Dimming to apply to scaled colours, `Scale` at full brightness.
+    fn active_dimming(&self) -> Dimming {
+        if self.brightness == u8::MAX {
+            Dimming::Scale
+        } else {
+            self.dimming
+        }
+    }
+}
+
+fn map_color(color: Color, brightness: u8, transparency: Transparency) -> Option<Color> {
+    if transparency == Transparency::BlackIsTransparent && color == Color::BLACK {
+        None
+    } else {
+        Some(scale(color, brightness))
+    }
+}
+
+/// `color` scaled by `brightness / 255`, rounding so that 255 keeps it.
+pub(crate) fn scale(color: Color, brightness: u8) -> Color {
+    if brightness == u8::MAX {
+        return color;
+    }
+    let channel = |c: u8| {
+        let scaled = (u16::from(c) * u16::from(brightness) + 255) >> 8;
+        u8::try_from(scaled).unwrap_or(u8::MAX)
+    };
+    Color::new(channel(color.r()), channel(color.g()), channel(color.b()))
+}
+
+impl<FB: OriginDimensions, H> Hub75Display<FB, H> {
+    /// Panel coordinates of drawing point `p`.
+    fn map_point(&self, p: Point) -> Point {
+        self.mapping.apply(p, self.fb.size())
+    }
+}
+
+impl<FB: OriginDimensions, H> OriginDimensions for Hub75Display<FB, H> {
+    fn size(&self) -> Size {
+        self.mapping.canvas(self.fb.size())
+    }
+}
+
+impl<FB, H> DrawTarget for Hub75Display<FB, H>
+where
+    FB: DrawTarget<Color = Color, Error = Infallible> + OriginDimensions,
+{
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.fb.size();
+        let (brightness, mapping, transparency) =
+            (self.brightness, self.mapping, self.transparency);
+        let (dimming, phase) = (self.active_dimming(), self.phase);
+        self.fb
+            .draw_iter(pixels.into_iter().filter_map(|Pixel(p, color)| {
+                map_color(color, brightness, transparency).map(|color| {
+                    let p = mapping.apply(p, size);
+                    Pixel(p, dimming.apply(phase, p, color))
+                })
+            }))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.active_dimming() != Dimming::Scale {
+            // the colour varies from pixel to pixel
+            return self.draw_iter(area.points().map(|p| Pixel(p, color)));
+        }
+        let Some(color) = self.map_color(color) else {
+            return Ok(());
+        };
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let corner = self.map_point(area.top_left);
+        let opposite = self.map_point(bottom_right);
+        let area = Rectangle::with_corners(corner, opposite);
+        self.fb.fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        // clearing is explicit, so black clears even when transparent
+        let color = scale(color, self.brightness);
+        let (dimming, phase) = (self.active_dimming(), self.phase);
+        if dimming != Dimming::Scale {
+            let area = self.fb.bounding_box();
+            return self.fb.draw_iter(
+                area.points()
+                    .map(|p| Pixel(p, dimming.apply(phase, p, color))),
+            );
+        }
+        self.fb.clear(color)
+    }
+}
+
+impl<FB, H> FrameBufferOperations for Hub75Display<FB, H>
+where
+    FB: FrameBufferOperations + OriginDimensions,
+{
+    #[inline]
+    fn erase(&mut self) {
+        self.fb.erase();
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        if let Some(color) = self.map_color(color) {
+            let p = self.map_point(p);
+            self.fb
+                .set_pixel(p, self.active_dimming().apply(self.phase, p, color));
+        }
+    }
+}
+
+impl<FB: FrameBuffer, H> FrameBuffer for Hub75Display<FB, H> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+impl<FB: MutableFrameBuffer + OriginDimensions, H> MutableFrameBuffer for Hub75Display<FB, H> {}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>, H> ReadBuffer for Hub75Display<FB, H> {
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+impl<FB, H: VsyncHook> VsyncHook for Hub75Display<FB, H> {
+    fn on_vsync(&mut self, vsync: Vsync) {
+        self.hook.on_vsync(vsync);
+    }
+}
+
+/// Several fully independent displays driven by one controller, such as
+/// the two sides of a double-sided sign.
+///
+/// Each display has its own framebuffer, content, rotation and mirroring,
+/// while brightness and the ambient light curve are set for all of them at
+/// once so the sides always match. [`MultiDisplay::iter`] hands every
+/// display to the DMA layer, which takes them as `ReadBuffer`s.
+///
+/// # Example
+/// ```rust
+/// use embedded_dma::ReadBuffer;
+/// use embedded_graphics::prelude::*;
+/// use hub75_framebuffer::display::{MultiDisplay, Rotation};
+/// use hub75_framebuffer::plain::DmaFrameBuffer;
+/// use hub75_framebuffer::Color;
+///
+/// type FBType = DmaFrameBuffer<32, 64, 16, 3, 7>;
+///
+/// let mut sign = MultiDisplay::new([FBType::new(), FBType::new()]);
+/// sign.set_brightness(100);
+/// // the back side is mounted upside down
+/// sign.display_mut(1).set_rotation(Rotation::Deg180);
+///
+/// sign.display_mut(0).clear(Color::RED).unwrap();
+/// sign.display_mut(1).clear(Color::BLUE).unwrap();
+///
+/// for display in sign.iter() {
+///     // hand each buffer to its own DMA channel
+///     let (ptr, len) = unsafe { display.read_buffer() };
+///     assert!(!ptr.is_null() && len > 0);
+/// }
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MultiDisplay<FB, const N: usize, H = ()> {
+    displays: [Hub75Display<FB, H>; N],
+}
+
+impl<FB, const N: usize> MultiDisplay<FB, N> {
+    /// Wrap each of `framebuffers` in a [`Hub75Display`].
+    #[must_use]
+    pub fn new(framebuffers: [FB; N]) -> Self {
+        Self::from_displays(framebuffers.map(Hub75Display::new))
+    }
+}
+
+impl<FB, const N: usize, H> MultiDisplay<FB, N, H> {
+    /// Coordinate `displays`, e.g. created with their own vsync hooks.
+    ///
+    /// The displays keep their settings until changed through the
+    /// coordinator.
+    #[must_use]
+    pub const fn from_displays(displays: [Hub75Display<FB, H>; N]) -> Self {
+        Self { displays }
+    }
+
+    /// Set the brightness of all displays, see
+    /// [`Hub75Display::set_brightness`].
+    pub fn set_brightness(&mut self, brightness: u8) {
+        for display in &mut self.displays {
+            display.set_brightness(brightness);
+        }
+    }
+
+    /// Brightness of the first display, 255 if there is none.
+    #[must_use]
+    pub fn brightness(&self) -> u8 {
+        self.displays
+            .first()
+            .map_or(u8::MAX, Hub75Display::brightness)
+    }
+
+    /// Set the ambient light curve of all displays, see
+    /// [`Hub75Display::set_auto_brightness_curve`].
+    pub fn set_auto_brightness_curve(&mut self, curve: Option<BrightnessCurve>) {
+        for display in &mut self.displays {
+            display.set_auto_brightness_curve(curve);
+        }
+    }
+
+    /// Feed an ambient light reading to all displays. Returns `true` if the
+    /// brightness changed and the content should be redrawn.
+    pub fn set_ambient_lux(&mut self, lux: f32) -> bool {
+        let mut changed = false;
+        for display in &mut self.displays {
+            changed |= display.set_ambient_lux(lux);
+        }
+        changed
+    }
+
+    /// Display number `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `N` or more.
+    #[must_use]
+    pub fn display(&self, index: usize) -> &Hub75Display<FB, H> {
+        &self.displays[index]
+    }
+
+    /// Display number `index`, to draw into or change its own settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `N` or more.
+    pub fn display_mut(&mut self, index: usize) -> &mut Hub75Display<FB, H> {
+        &mut self.displays[index]
+    }
+
+    /// All displays in order, e.g. to start DMA on each.
+    pub fn iter(&self) -> core::slice::Iter<'_, Hub75Display<FB, H>> {
+        self.displays.iter()
+    }
+
+    /// All displays in order, e.g. to redraw each.
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, Hub75Display<FB, H>> {
+        self.displays.iter_mut()
+    }
+
+    /// Unwrap the displays.
+    pub fn into_displays(self) -> [Hub75Display<FB, H>; N] {
+        self.displays
+    }
+}
+
+impl<'a, FB, const N: usize, H> IntoIterator for &'a MultiDisplay<FB, N, H> {
+    type Item = &'a Hub75Display<FB, H>;
+    type IntoIter = core::slice::Iter<'a, Hub75Display<FB, H>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, FB, const N: usize, H> IntoIterator for &'a mut MultiDisplay<FB, N, H> {
+    type Item = &'a mut Hub75Display<FB, H>;
+    type IntoIter = core::slice::IterMut<'a, Hub75Display<FB, H>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::Decoded;
+    use crate::ordering::DefaultOrdering;
+    use crate::plain::{DefaultPins, DmaFrameBuffer};
+    use crate::vsync::FrameCounter;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::Dimensions;
+    use embedded_graphics::primitives::{PointsIter, Primitive, PrimitiveStyle};
+    use embedded_graphics::Drawable;
+
+    fn mock() -> MockDisplay<Color> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        mock
+    }
+
+    #[test]
+    fn test_rotation() {
+        // the mock display is 64x64
+        for (rotation, expected) in [
+            (Rotation::Deg0, Point::new(1, 2)),
+            (Rotation::Deg90, Point::new(61, 1)),
+            (Rotation::Deg180, Point::new(62, 61)),
+            (Rotation::Deg270, Point::new(2, 62)),
+        ] {
+            let mut display = Hub75Display::new(mock());
+            display.set_rotation(rotation);
+            Pixel(Point::new(1, 2), Color::RED)
+                .draw(&mut display)
+                .unwrap();
+            let (fb, ()) = display.into_parts();
+            assert_eq!(fb.get_pixel(expected), Some(Color::RED), "{rotation:?}");
+        }
+
+        let mut display = Hub75Display::new(DmaFrameBuffer::<32, 64, 16, 3, 7>::new());
+        assert_eq!(display.size(), Size::new(64, 32));
+        display.set_rotation(Rotation::Deg270);
+        assert_eq!(display.size(), Size::new(32, 64));
+    }
+
+    #[test]
+    fn test_rotation_covers_panel() {
+        const ROTATIONS: [Rotation; 4] = [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ];
+        for rotation in ROTATIONS {
+            assert_eq!(
+                Rotation::from_degrees(rotation.degrees().into()),
+                Some(rotation)
+            );
+
+            // every point of the rotated canvas lands on a distinct panel
+            // point, and points outside it stay off the panel
+            let mut display = Hub75Display::new(DmaFrameBuffer::<32, 64, 16, 3, 7>::new());
+            display.set_rotation(rotation);
+            let canvas = display.bounding_box();
+            let panel = display.framebuffer().bounding_box();
+            let mut seen = [[false; 64]; 32];
+            for p in canvas.offset(1).points() {
+                let mapped = display.map_point(p);
+                assert_eq!(
+                    panel.contains(mapped),
+                    canvas.contains(p),
+                    "{rotation:?} {p}"
+                );
+                if panel.contains(mapped) {
+                    let [x, y] = [mapped.x, mapped.y].map(|c| usize::try_from(c).unwrap());
+                    assert!(!seen[y][x], "{rotation:?} {p}");
+                    seen[y][x] = true;
+                }
+            }
+        }
+        assert_eq!(Rotation::from_degrees(450), Some(Rotation::Deg90));
+        assert_eq!(Rotation::from_degrees(100), None);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut display = Hub75Display::new(DmaFrameBuffer::<32, 64, 16, 3, 7>::new());
+        display.set_mirror(true, false);
+        assert_eq!(display.mirror(), (true, false));
+        assert_eq!(display.map_point(Point::new(1, 2)), Point::new(62, 2));
+        display.set_mirror(false, true);
+        assert_eq!(display.map_point(Point::new(1, 2)), Point::new(1, 29));
+
+        // mirroring applies to the rotated canvas: at 90° the canvas is 32
+        // wide, so x = 1 becomes x = 30 before rotating
+        display.set_mirror(true, false);
+        display.set_rotation(Rotation::Deg90);
+        assert_eq!(display.map_point(Point::new(1, 2)), Point::new(61, 30));
+
+        // mirroring both ways is a further half turn
+        for rotation in [Rotation::Deg0, Rotation::Deg90] {
+            let half_turn = Rotation::from_degrees(i32::from(rotation.degrees()) + 180).unwrap();
+            let mut mirrored = Hub75Display::new(mock());
+            mirrored.set_rotation(rotation);
+            mirrored.set_mirror(true, true);
+            let mut rotated = Hub75Display::new(mock());
+            rotated.set_rotation(half_turn);
+            for display in [&mut mirrored, &mut rotated] {
+                Rectangle::new(Point::new(3, 5), Size::new(7, 2))
+                    .into_styled(PrimitiveStyle::with_fill(Color::GREEN))
+                    .draw(display)
+                    .unwrap();
+                Pixel(Point::new(20, 1), Color::RED).draw(display).unwrap();
+            }
+            mirrored.framebuffer().assert_eq(rotated.framebuffer());
+        }
+    }
+
+    #[test]
+    fn test_mirror_tiled() {
+        use crate::tiling::{compute_tiled_cols, ChainTopRightDown, TiledFrameBuffer};
+
+        const FB_COLS: usize = compute_tiled_cols(16, 2, 2);
+        type Tiled = TiledFrameBuffer<
+            DmaFrameBuffer<8, FB_COLS, 4, 3, 7>,
+            ChainTopRightDown<8, 16, 2, 2>,
+            8,
+            16,
+            4,
+            3,
+            7,
+            2,
+            2,
+            FB_COLS,
+        >;
+
+        // mirroring the wrapper is the same as mirroring before tiling
+        let mut display = Hub75Display::new(Tiled::new());
+        display.set_mirror(true, false);
+        display.set_pixel(Point::new(0, 0), Color::WHITE);
+        let mut tiled = Tiled::new();
+        tiled.set_pixel(Point::new(31, 0), Color::WHITE);
+        let bytes = |fb: &Tiled| {
+            let (ptr, len) = unsafe { fb.read_buffer() };
+            unsafe { core::slice::from_raw_parts(ptr, len) }.to_vec()
+        };
+        assert_eq!(bytes(display.framebuffer()), bytes(&tiled));
+    }
+
+    #[test]
+    fn test_fill_solid_matches_pixels() {
+        let area = Rectangle::new(Point::new(3, 5), Size::new(7, 2));
+        for (rotation, mirror_x) in [
+            (Rotation::Deg0, false),
+            (Rotation::Deg90, true),
+            (Rotation::Deg180, false),
+            (Rotation::Deg270, true),
+        ] {
+            let mut filled = Hub75Display::new(mock());
+            filled.set_rotation(rotation);
+            filled.set_mirror(mirror_x, false);
+            filled.fill_solid(&area, Color::GREEN).unwrap();
+
+            let mut drawn = Hub75Display::new(mock());
+            drawn.set_rotation(rotation);
+            drawn.set_mirror(mirror_x, false);
+            drawn
+                .draw_iter(area.points().map(|p| Pixel(p, Color::GREEN)))
+                .unwrap();
+
+            filled.framebuffer().assert_eq(drawn.framebuffer());
+        }
+    }
+
+    #[test]
+    fn test_brightness() {
+        let mut display = Hub75Display::new(mock());
+        display.set_brightness(128);
+        assert_eq!(display.brightness(), 128);
+        display
+            .draw_iter([Pixel(Point::new(0, 0), Color::new(255, 64, 0))])
+            .unwrap();
+        assert_eq!(
+            display.framebuffer().get_pixel(Point::new(0, 0)),
+            Some(Color::new(128, 32, 0))
+        );
+
+        assert_eq!(scale(Color::WHITE, u8::MAX), Color::WHITE);
+        assert_eq!(scale(Color::WHITE, 0), Color::BLACK);
+        assert_eq!(scale(Color::new(1, 0, 0), 1), Color::new(1, 0, 0));
+    }
+
+    #[test]
+    fn test_temporal_dimming() {
+        let mut display = Hub75Display::new(mock());
+        display.set_dimming(Dimming::Temporal { bits: 3 });
+        assert_eq!(display.dimming(), Dimming::Temporal { bits: 3 });
+        // scaled to (100, 0, 0): 3 steps of 32 and an eighth of one more
+        display.set_brightness(254);
+        let color = Color::new(100, 0, 0);
+        let mut sum = 0;
+        let mut previous = None;
+        for _ in 0..16 {
+            display.draw_iter([Pixel(Point::zero(), color)]).unwrap();
+            let shown = display.framebuffer().get_pixel(Point::new(0, 0)).unwrap();
+            assert!(shown == Color::new(96, 0, 0) || shown == Color::new(128, 0, 0));
+            // never lit in two redraws in a row
+            assert!(!(previous == Some(shown) && shown.r() == 128));
+            previous = Some(shown);
+            sum += u32::from(shown.r());
+            display.next_dimming_phase();
+        }
+        assert_eq!(sum, 16 * 100);
+
+        // within one redraw the pixels take turns
+        let area = Rectangle::new(Point::zero(), Size::new(4, 4));
+        display.fill_solid(&area, color).unwrap();
+        let lit = area
+            .points()
+            .filter(|&p| display.framebuffer().get_pixel(p) == Some(Color::new(128, 0, 0)))
+            .count();
+        assert_eq!(lit, 2);
+
+        // full brightness and plain scaling leave colours to the framebuffer
+        display.set_brightness(u8::MAX);
+        display.clear(color).unwrap();
+        assert_eq!(
+            display.framebuffer().get_pixel(Point::new(1, 1)),
+            Some(color)
+        );
+        display.set_brightness(254);
+        display.set_dimming(Dimming::Scale);
+        display.fill_solid(&area, color).unwrap();
+        assert_eq!(
+            display.framebuffer().get_pixel(Point::new(1, 1)),
+            Some(color)
+        );
+    }
+
+    #[test]
+    fn test_brightness_curve() {
+        let curve = BrightnessCurve::new(&[(10.0, 20), (100.0, 200), (1000.0, 250)]);
+        assert_eq!(curve.brightness(-5.0), 20);
+        assert_eq!(curve.brightness(f32::NAN), 20);
+        assert_eq!(curve.brightness(10.0), 20);
+        assert_eq!(curve.brightness(55.0), 110);
+        assert_eq!(curve.brightness(100.0), 200);
+        assert_eq!(curve.brightness(550.0), 225);
+        assert_eq!(curve.brightness(1e6), 250);
+        assert_eq!(BrightnessCurve::new(&[(5.0, 7)]).brightness(100.0), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "lux values must increase")]
+    fn test_brightness_curve_unsorted() {
+        let _ = BrightnessCurve::new(&[(10.0, 20), (10.0, 200)]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_brightness_curve_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let curve = BrightnessCurve::new(&[(1.0, 8), (50.0, 64)]).with_hysteresis(2);
+        let tokens = |first_lux: f32| {
+            [
+                Token::Struct {
+                    name: "BrightnessCurve",
+                    len: 3,
+                },
+                Token::Str("points"),
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::F32(first_lux),
+                Token::U8(8),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::F32(50.0),
+                Token::U8(64),
+                Token::TupleEnd,
+                Token::SeqEnd,
+                Token::Str("smoothing"),
+                Token::F32(1.0),
+                Token::Str("hysteresis"),
+                Token::U8(2),
+                Token::StructEnd,
+            ]
+        };
+        assert_tokens(&curve, &tokens(1.0));
+        assert_de_tokens_error::<BrightnessCurve>(
+            &tokens(60.0),
+            "brightness curve lux values must increase",
+        );
+    }
+
+    #[test]
+    fn test_auto_brightness() {
+        let mut display = Hub75Display::new(mock());
+        // ignored without a curve
+        assert!(!display.set_ambient_lux(100.0));
+        assert_eq!(display.brightness(), u8::MAX);
+
+        let curve = BrightnessCurve::new(&[(0.0, 0), (100.0, 100), (200.0, 200)])
+            .with_smoothing(0.5)
+            .with_hysteresis(10);
+        display.set_auto_brightness_curve(Some(curve));
+        assert!(display.set_ambient_lux(100.0));
+        assert_eq!(display.brightness(), 100);
+        assert_eq!(display.ambient_lux(), Some(100.0));
+
+        // halfway to 110: within the hysteresis
+        assert!(!display.set_ambient_lux(110.0));
+        assert_eq!(display.brightness(), 100);
+        // 105 + (140 - 105) / 2 = 122.5
+        assert!(display.set_ambient_lux(140.0));
+        assert_eq!(display.brightness(), 123);
+        assert!(!display.set_ambient_lux(f32::NAN));
+
+        // the ends are reached despite the hysteresis
+        for _ in 0..20 {
+            display.set_ambient_lux(195.0);
+        }
+        assert!(display.set_ambient_lux(1000.0));
+        assert_eq!(display.brightness(), 200);
+
+        display.set_auto_brightness_curve(None);
+        assert_eq!(display.ambient_lux(), None);
+        assert!(!display.set_ambient_lux(0.0));
+        assert_eq!(display.brightness(), 200);
+    }
+
+    #[test]
+    fn test_black_is_transparent() {
+        let mut display = Hub75Display::new(mock());
+        display.set_transparency(Transparency::BlackIsTransparent);
+        display
+            .fill_solid(&Rectangle::new(Point::zero(), Size::new(2, 1)), Color::BLUE)
+            .unwrap();
+        Rectangle::new(Point::zero(), Size::new(2, 1))
+            .into_styled(PrimitiveStyle::with_fill(Color::BLACK))
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(
+            display.framebuffer().get_pixel(Point::new(0, 0)),
+            Some(Color::BLUE)
+        );
+
+        // clearing is not drawing
+        display.clear(Color::BLACK).unwrap();
+        assert_eq!(
+            display.framebuffer().get_pixel(Point::new(0, 0)),
+            Some(Color::BLACK)
+        );
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let mut display = Hub75Display::with_hook(
+            DmaFrameBuffer::<32, 64, 16, 3, 7>::new(),
+            FrameCounter::new(),
+        );
+        display.set_rotation(Rotation::Deg180);
+        FrameBufferOperations::set_pixel(&mut display, Point::new(0, 0), Color::WHITE);
+        let decoded = Decoded::<32, 64, 16, 7>::plain::<DefaultOrdering, DefaultPins>(
+            display.framebuffer().as_words(),
+        )
+        .unwrap();
+        assert_ne!(decoded.pixel(63, 31), Color::BLACK);
+        assert_eq!(decoded.pixel(0, 0), Color::BLACK);
+
+        let (ptr, len) = unsafe { display.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { display.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!(display.get_word_size(), WordSize::Sixteen);
+
+        display.on_vsync(Vsync {
+            frame: 0,
+            swapped: true,
+        });
+        assert_eq!(display.hook().swaps(), 1);
+    }
+
+    type SmallFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+
+    fn decode(fb: &SmallFB) -> Decoded<16, 32, 8, 7> {
+        Decoded::plain::<DefaultOrdering, DefaultPins>(fb.as_words()).unwrap()
+    }
+
+    #[test]
+    fn test_multi_display() {
+        let mut multi = MultiDisplay::new([SmallFB::new(), SmallFB::new()]);
+        assert_eq!(multi.brightness(), 255);
+        multi.set_brightness(128);
+        multi.display_mut(1).set_rotation(Rotation::Deg180);
+        assert!(multi.iter().all(|d| d.brightness() == 128));
+
+        Pixel(Point::new(1, 2), Color::WHITE)
+            .draw(multi.display_mut(0))
+            .unwrap();
+        Pixel(Point::new(1, 2), Color::RED)
+            .draw(multi.display_mut(1))
+            .unwrap();
+        let [front, back] = multi.into_displays().map(|d| decode(d.framebuffer()));
+        assert_eq!(front.pixel(1, 2), Color::new(128, 128, 128));
+        assert_eq!(back.pixel(30, 13), Color::new(128, 0, 0));
+        assert_eq!(back.pixel(1, 2), Color::BLACK);
+
+        let mut multi = MultiDisplay::new([SmallFB::new(), SmallFB::new(), SmallFB::new()]);
+        multi.set_auto_brightness_curve(Some(BrightnessCurve::new(&[(0.0, 10), (100.0, 200)])));
+        assert!(multi.set_ambient_lux(0.0));
+        assert!((&multi).into_iter().all(|d| d.brightness() == 10));
+        assert!(!multi.set_ambient_lux(0.0));
+    }
+
+    #[test]
+    fn test_multi_display_buffers() {
+        let multi = MultiDisplay::new([SmallFB::new(), SmallFB::new()]);
+        let buffers: [_; 2] = core::array::from_fn(|i| unsafe { multi.display(i).read_buffer() });
+        assert_ne!(buffers[0].0, buffers[1].0);
+        assert_eq!(buffers[0].1, buffers[1].1);
+        assert_eq!(multi.iter().count(), 2);
+    }
+}
@@ -0,0 +1,584 @@
+//! Procedural animations.
+//!
+//! Generators for classic LED matrix effects: [`Plasma`], [`Fire`],
+//! [`GradientSweep`] and [`Sparkle`]. They make good screensavers and,
+//! because they change every pixel on every frame, good stress tests for a
+//! refresh pipeline.
+//!
+//! Every effect implements [`Effect`]: [`Effect::tick`] advances the
+//! animation by one step and [`Effect::render`] writes the current state to
+//! the whole target with a single `fill_contiguous`. All of them use integer
+//! arithmetic only and need neither `std` nor an allocator.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::effects::{Effect, Fire, Plasma};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//!
+//! let mut plasma = Plasma::new();
+//! for _ in 0..10 {
+//!     plasma.tick();
+//!     plasma.render(&mut fb).unwrap();
+//! }
+//!
+//! // one heat cell per pixel
+//! let mut fire = Fire::<64, 32>::new();
+//! fire.tick();
+//! fire.render(&mut fb).unwrap();
+//! ```
+
+use embedded_graphics::prelude::{DrawTarget, Point, RgbColor, Size};
+use embedded_graphics::primitives::PointsIter;
+
+use crate::Color;
+
+/// An animation rendered over the whole target.
+pub trait Effect {
+    /// Advance the animation by one step.
+    fn tick(&mut self);
+
+    /// Colour of `point` on a target of `size`, `point` being relative to
+    /// the top left corner of the target.
+    fn color_at(&self, point: Point, size: Size) -> Color;
+
+    /// Draw the current state over the whole target.
+    ///
+    /// # Errors
+    /// Returns the error of the target.
+    fn render<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+        Self: Sized,
+    {
+        let area = target.bounding_box();
+        let colors = area
+            .points()
+            .map(|point| self.color_at(point - area.top_left, area.size));
+        target.fill_contiguous(&area, colors)
+    }
+}
+
+/// Approximated sine of `angle`, a full turn being 256, from 0 to 255 with
+/// 128 as the zero line.
+#[must_use]
+pub const fn sin8(angle: u8) -> u8 {
+    // a parabola per half wave is within 6% of the sine
+    let x = (angle & 0x7f) as u32;
+    let y = x * (128 - x) / 32;
+    if angle < 128 {
+        if y >= 127 {
+            255
+        } else {
+            128 + y as u8
+        }
+    } else {
+        128 - y as u8
+    }
+}
+
+/// Fully saturated colour of `hue` on a 256 step colour wheel, red at 0,
+/// green at 85 and blue at 170.
+#[must_use]
+pub const fn wheel(hue: u8) -> Color {
+    match hue {
+        0..85 => Color::new(255 - hue * 3, hue * 3, 0),
+        85..170 => {
+            let h = hue - 85;
+            Color::new(0, 255 - h * 3, h * 3)
+        }
+        _ => {
+            let h = hue - 170;
+            Color::new(h * 3, 0, 255 - h * 3)
+        }
+    }
+}
+
+/// Mix `a` and `b`, `amount` 0 being all `a` and 255 all `b`.
+fn mix(a: Color, b: Color, amount: u8) -> Color {
+    const fn channel(a: u8, b: u8, amount: u8) -> u8 {
+        let a = a as u32;
+        let b = b as u32;
+        let amount = amount as u32;
+        ((a * (255 - amount) + b * amount) / 255) as u8
+    }
+    Color::new(
+        channel(a.r(), b.r(), amount),
+        channel(a.g(), b.g(), amount),
+        channel(a.b(), b.b(), amount),
+    )
+}
+
+/// Small xorshift generator, plenty for flickering flames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rng(u32);
+
+impl Rng {
+    const fn new(seed: u32) -> Self {
+        // xorshift gets stuck at zero
+        Self(if seed == 0 { 0x9e37_79b9 } else { seed })
+    }
+
+    const fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// A value below `bound`, which must not be zero.
+    const fn below(&mut self, bound: u32) -> u32 {
+        self.next() % bound
+    }
+}
+
+/// Mixes the bits of `value` into a well distributed hash.
+const fn hash(mut value: u32) -> u32 {
+    value ^= value >> 16;
+    value = value.wrapping_mul(0x7feb_352d);
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x846c_a68b);
+    value ^ (value >> 16)
+}
+
+/// Overlapping sine waves cycling through the colour wheel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Plasma {
+    time: u32,
+    scale: u8,
+    speed: u8,
+}
+
+impl Default for Plasma {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plasma {
+    /// Create a plasma with waves about 32 pixels long.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            time: 0,
+            scale: 8,
+            speed: 1,
+        }
+    }
+
+    /// Angle per pixel, a full wave being 256: larger values give smaller
+    /// blobs.
+    #[must_use]
+    pub const fn with_scale(mut self, scale: u8) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Advance the waves `speed` steps per tick.
+    #[must_use]
+    pub const fn with_speed(mut self, speed: u8) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl Effect for Plasma {
+    fn tick(&mut self) {
+        self.time = self.time.wrapping_add(u32::from(self.speed));
+    }
+
+    fn color_at(&self, point: Point, _size: Size) -> Color {
+        // angles wrap around, so truncating is intended
+        let t = self.time as u8;
+        let scale = i32::from(self.scale);
+        let x = (point.x * scale) as u8;
+        let y = (point.y * scale) as u8;
+        let diagonal = ((point.x + point.y) * scale / 2) as u8;
+        let sum = u32::from(sin8(x.wrapping_add(t)))
+            + u32::from(sin8(y.wrapping_sub(t.wrapping_mul(2))))
+            + u32::from(sin8(diagonal.wrapping_add(t.wrapping_mul(3))));
+        wheel((sum / 3) as u8 ^ t)
+    }
+}
+
+/// Flames rising from the bottom edge.
+///
+/// The fire keeps a `W` by `H` heat map that is scaled to the target, so a
+/// coarse map saves memory and time on large panels. Each tick the heat
+/// rises and cools while new sparks ignite along the bottom row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fire<const W: usize, const H: usize> {
+    heat: [[u8; W]; H],
+    rng: Rng,
+    cooling: u8,
+    sparking: u8,
+}
+
+impl<const W: usize, const H: usize> Default for Fire<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> Fire<W, H> {
+    /// Create a cold fire.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            heat: [[0; W]; H],
+            rng: Rng::new(1),
+            cooling: 24,
+            sparking: 120,
+        }
+    }
+
+    /// Seed the random generator, e.g. to desynchronise several fires.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// Maximum heat lost per cell and tick: more cooling gives shorter
+    /// flames.
+    #[must_use]
+    pub const fn with_cooling(mut self, cooling: u8) -> Self {
+        self.cooling = cooling;
+        self
+    }
+
+    /// Chance out of 255 of a bottom cell igniting on a tick.
+    #[must_use]
+    pub const fn with_sparking(mut self, sparking: u8) -> Self {
+        self.sparking = sparking;
+        self
+    }
+
+    /// Heat of cell `x`, `y` of the heat map, `y` 0 being the top row.
+    #[must_use]
+    pub const fn heat(&self, x: usize, y: usize) -> u8 {
+        self.heat[y][x]
+    }
+}
+
+/// Black through red and yellow to white.
+const fn heat_color(heat: u8) -> Color {
+    let level = heat as u32 * 3;
+    match level {
+        0..256 => Color::new(level as u8, 0, 0),
+        256..512 => Color::new(255, (level - 256) as u8, 0),
+        // at most 765
+        _ => Color::new(255, 255, (level - 512) as u8),
+    }
+}
+
+impl<const W: usize, const H: usize> Effect for Fire<W, H> {
+    fn tick(&mut self) {
+        if W == 0 || H == 0 {
+            return;
+        }
+        let cooling = u32::from(self.cooling) + 1;
+        for row in &mut self.heat {
+            for cell in row {
+                *cell = cell.saturating_sub(self.rng.below(cooling) as u8);
+            }
+        }
+        // heat rises from the two rows below, spreading sideways
+        for y in 0..H.saturating_sub(1) {
+            for x in 0..W {
+                let below = u32::from(self.heat[y + 1][x]);
+                let left = u32::from(self.heat[y + 1][x.saturating_sub(1)]);
+                let right = u32::from(self.heat[y + 1][(x + 1).min(W - 1)]);
+                let further = u32::from(self.heat[(y + 2).min(H - 1)][x]);
+                self.heat[y][x] = ((below * 2 + left + right + further * 2) / 6) as u8;
+            }
+        }
+        for x in 0..W {
+            if self.rng.below(255) < u32::from(self.sparking) {
+                let spark = 160 + self.rng.below(96) as u8;
+                self.heat[H - 1][x] = self.heat[H - 1][x].max(spark);
+            }
+        }
+    }
+
+    fn color_at(&self, point: Point, size: Size) -> Color {
+        if W == 0 || H == 0 || size.width == 0 || size.height == 0 {
+            return Color::BLACK;
+        }
+        let x = point.x as usize * W / size.width as usize;
+        let y = point.y as usize * H / size.height as usize;
+        heat_color(self.heat[y.min(H - 1)][x.min(W - 1)])
+    }
+}
+
+/// A gradient between two colours sweeping across the target.
+///
+/// The gradient runs from the first colour to the second and back over the
+/// width of the target and moves left by one 256th of the width per step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GradientSweep {
+    from: Color,
+    to: Color,
+    phase: u32,
+    speed: u8,
+    vertical: bool,
+}
+
+impl GradientSweep {
+    /// Sweep between `from` and `to` horizontally.
+    #[must_use]
+    pub const fn new(from: Color, to: Color) -> Self {
+        Self {
+            from,
+            to,
+            phase: 0,
+            speed: 2,
+            vertical: false,
+        }
+    }
+
+    /// Sweep from the bottom to the top instead.
+    #[must_use]
+    pub const fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    /// Move `speed` 256ths of the target per tick.
+    #[must_use]
+    pub const fn with_speed(mut self, speed: u8) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl Effect for GradientSweep {
+    fn tick(&mut self) {
+        self.phase = (self.phase + u32::from(self.speed)) % 512;
+    }
+
+    fn color_at(&self, point: Point, size: Size) -> Color {
+        let (position, length) = if self.vertical {
+            let y = point.y.max(0) as u32;
+            (size.height.saturating_sub(1).saturating_sub(y), size.height)
+        } else {
+            (point.x.max(0) as u32, size.width)
+        };
+        let along = (position * 512 / length.max(1) + self.phase) % 512;
+        // there and back again
+        let amount = if along < 256 { along } else { 511 - along };
+        mix(self.from, self.to, amount as u8)
+    }
+}
+
+/// Pixels randomly flashing up and fading out.
+///
+/// Each pixel gets a random slot in a 64 tick cycle and, for every cycle, a
+/// random chance of sparkling. Nothing is stored per pixel, so sparkles
+/// cover targets of any size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sparkle {
+    color: Color,
+    background: Color,
+    density: u8,
+    seed: u32,
+    time: u32,
+}
+
+/// Ticks between two chances of a pixel to sparkle.
+const SPARKLE_CYCLE: u32 = 64;
+/// Ticks a sparkle lasts.
+const SPARKLE_LENGTH: u32 = 8;
+
+impl Sparkle {
+    /// Sparkle in `color` on black.
+    #[must_use]
+    pub const fn new(color: Color) -> Self {
+        Self {
+            color,
+            background: Color::BLACK,
+            density: 32,
+            seed: 0,
+            time: 0,
+        }
+    }
+
+    /// Draw sparkles on `background`.
+    #[must_use]
+    pub const fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Chance out of 256 of a pixel sparkling once per 64 ticks.
+    #[must_use]
+    pub const fn with_density(mut self, density: u8) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Seed the pattern, e.g. to desynchronise several sparkles.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Effect for Sparkle {
+    fn tick(&mut self) {
+        self.time = self.time.wrapping_add(1);
+    }
+
+    fn color_at(&self, point: Point, size: Size) -> Color {
+        let index = point.y as u32 * size.width + point.x as u32;
+        let pixel = hash(index ^ self.seed.rotate_left(16));
+        let time = self.time.wrapping_add(pixel);
+        let age = time % SPARKLE_CYCLE;
+        if age >= SPARKLE_LENGTH {
+            return self.background;
+        }
+        let cycle = time / SPARKLE_CYCLE;
+        if hash(pixel ^ cycle) & 0xff >= u32::from(self.density) {
+            return self.background;
+        }
+        // full brightness at once, then fading out
+        let amount = 255 - age * 255 / SPARKLE_LENGTH;
+        mix(self.background, self.color, amount as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::Dimensions;
+    use embedded_graphics::primitives::Rectangle;
+
+    use crate::plain::DmaFrameBuffer;
+
+    #[test]
+    fn test_sin8() {
+        assert_eq!(sin8(0), 128);
+        assert_eq!(sin8(64), 255);
+        assert_eq!(sin8(128), 128);
+        assert_eq!(sin8(192), 0);
+        assert!(sin8(32) > 200 && sin8(32) < 240);
+        assert!(sin8(160) > 16 && sin8(160) < 56);
+    }
+
+    #[test]
+    fn test_wheel() {
+        assert_eq!(wheel(0), Color::RED);
+        assert_eq!(wheel(85), Color::GREEN);
+        assert_eq!(wheel(170), Color::BLUE);
+        assert_eq!(wheel(255), Color::new(255, 0, 0));
+    }
+
+    #[test]
+    fn test_render_fills_target() {
+        let mut display = MockDisplay::new();
+        Plasma::new().render(&mut display).unwrap();
+        assert_eq!(display.affected_area(), display.bounding_box());
+    }
+
+    #[test]
+    fn test_plasma_moves() {
+        let mut plasma = Plasma::new();
+        let size = Size::new(64, 32);
+        let before = plasma.color_at(Point::new(10, 10), size);
+        assert_eq!(plasma.color_at(Point::new(10, 10), size), before);
+        plasma.tick();
+        plasma.tick();
+        assert_ne!(plasma.color_at(Point::new(10, 10), size), before);
+    }
+
+    #[test]
+    fn test_fire_rises() {
+        let mut fire = Fire::<16, 16>::new().with_seed(7);
+        assert!((0..16).all(|x| fire.heat(x, 15) == 0));
+        for _ in 0..50 {
+            fire.tick();
+        }
+        let row = |y| (0..16).map(|x| u32::from(fire.heat(x, y))).sum::<u32>();
+        assert!(row(15) > row(8), "{} {}", row(15), row(8));
+        assert!(row(8) >= row(0), "{} {}", row(8), row(0));
+
+        // scaled to the target
+        let size = Size::new(64, 32);
+        assert_eq!(
+            fire.color_at(Point::new(63, 31), size),
+            heat_color(fire.heat(15, 15))
+        );
+    }
+
+    #[test]
+    fn test_gradient_sweep() {
+        let sweep = GradientSweep::new(Color::BLACK, Color::WHITE);
+        let size = Size::new(64, 32);
+        assert_eq!(sweep.color_at(Point::new(0, 0), size), Color::BLACK);
+        assert_eq!(
+            sweep.color_at(Point::new(32, 5), size),
+            Color::new(255, 255, 255)
+        );
+        assert_eq!(
+            sweep.color_at(Point::new(16, 0), size),
+            Color::new(128, 128, 128)
+        );
+
+        let mut sweep = sweep.with_speed(128);
+        sweep.tick();
+        assert_eq!(
+            sweep.color_at(Point::new(0, 0), size),
+            Color::new(128, 128, 128)
+        );
+
+        let vertical = GradientSweep::new(Color::BLACK, Color::WHITE).vertical();
+        assert_eq!(vertical.color_at(Point::new(5, 31), size), Color::BLACK);
+    }
+
+    #[test]
+    fn test_sparkle_density() {
+        let size = Size::new(64, 64);
+        let lit = |sparkle: &Sparkle| {
+            size.width as usize * size.height as usize
+                - Rectangle::new(Point::zero(), size)
+                    .points()
+                    .filter(|&p| sparkle.color_at(p, size) == Color::BLACK)
+                    .count()
+        };
+        // 4096 pixels, one in 8 ticks of a cycle, a chance of 32 in 256
+        let mut sparkle = Sparkle::new(Color::WHITE);
+        let mut total = 0;
+        for _ in 0..SPARKLE_CYCLE {
+            total += lit(&sparkle);
+            sparkle.tick();
+        }
+        assert!((3000..5200).contains(&total), "{total}");
+
+        let mut none = Sparkle::new(Color::WHITE).with_density(0);
+        for _ in 0..SPARKLE_CYCLE {
+            assert_eq!(lit(&none), 0);
+            none.tick();
+        }
+    }
+
+    #[test]
+    fn test_render_framebuffer() {
+        let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+        let mut fire = Fire::<64, 32>::new();
+        for _ in 0..20 {
+            fire.tick();
+        }
+        fire.render(&mut fb).unwrap();
+        GradientSweep::new(Color::RED, Color::BLUE)
+            .render(&mut fb)
+            .unwrap();
+        // full-frame writes leave the control bits intact
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+    }
+}
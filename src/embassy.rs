@@ -0,0 +1,452 @@
+//! Async refresh loop for the embassy ecosystem.
+//!
+//! Every application that drives a HUB75 panel from an embassy executor ends
+//! up writing the same loop: stream the framebuffer to the parallel
+//! peripheral, wait for the DMA transfer to complete, repeat forever, and
+//! swap in a freshly drawn buffer at a frame boundary so the panel never
+//! shows a half-drawn image. This module provides that loop.
+//!
+//! - [`DmaOutput`] is the only thing a HAL has to provide: an async method
+//!   that transfers a whole buffer once and resolves when the DMA is done.
+//! - [`FrameSwap`] connects the two sides and is split into a
+//!   [`Refresher`], which owns the front buffer and runs the refresh loop,
+//!   and a [`Drawer`], which owns the back buffer and offers an async
+//!   [`Drawer::flush`].
+//! - [`Refresher::run_with_hook`] additionally calls a
+//!   [`VsyncHook`] at every frame boundary, e.g. a
+//!   [`FrameCounter`](crate::vsync::FrameCounter) for measuring FPS.
+//!
+//! After [`Drawer::flush`] returns, the drawer owns the buffer that was
+//! previously on screen, so each frame should be drawn in full (for example
+//! after an `erase()`).
+//!
+//! # Example
+//! ```rust,no_run
+//! use core::convert::Infallible;
+//! use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+//! use embedded_dma::ReadBuffer;
+//! use embedded_graphics::pixelcolor::RgbColor;
+//! use embedded_graphics::prelude::Point;
+//! use hub75_framebuffer::embassy::{DmaOutput, FrameSwap};
+//! use hub75_framebuffer::latched::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows, Color};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//! type FBType = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+//!
+//! struct MyParallelBus;
+//!
+//! impl DmaOutput for MyParallelBus {
+//!     type Error = Infallible;
+//!
+//!     async fn output<B: ReadBuffer<Word = u8>>(&mut self, buffer: &B) -> Result<(), Infallible> {
+//!         let (_ptr, _len) = unsafe { buffer.read_buffer() };
+//!         // start the DMA transfer and await its completion interrupt
+//!         Ok(())
+//!     }
+//! }
+//!
+//! async fn example(
+//!     swap: &'static FrameSwap<'static, CriticalSectionRawMutex, FBType>,
+//!     front: &'static mut FBType,
+//!     back: &'static mut FBType,
+//! ) {
+//!     let (refresher, mut drawer) = swap.split(front, back);
+//!     // spawn `refresher.run(MyParallelBus)` as its own task, then:
+//!     drawer.erase();
+//!     drawer.set_pixel(Point::new(1, 1), Color::RED);
+//!     drawer.flush().await;
+//! }
+//! ```
+
+use core::convert::Infallible;
+use core::ops::{Deref, DerefMut};
+
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::signal::Signal;
+use embedded_dma::ReadBuffer;
+
+use crate::vsync::{Vsync, VsyncHook};
+
+/// HAL glue: transfer one complete framebuffer to the panel.
+pub trait DmaOutput {
+    /// Error reported by the underlying DMA driver.
+    type Error;
+
+    /// Stream `buffer` to the panel once.
+    ///
+    /// The returned future must not resolve before the DMA engine has
+    /// finished reading `buffer`. If the future is dropped early, the
+    /// implementation must stop the transfer before giving up the borrow.
+    #[allow(async_fn_in_trait)]
+    async fn output<B: ReadBuffer<Word = u8>>(&mut self, buffer: &B) -> Result<(), Self::Error>;
+}
+
+/// Hand-over point between the refresh task and the drawing side.
+///
+/// Usually declared as a `static` (e.g. with `static_cell`) and then split
+/// once at startup with [`FrameSwap::split`].
+pub struct FrameSwap<'a, M: RawMutex, FB> {
+    pending: Signal<M, &'a mut FB>,
+    released: Signal<M, &'a mut FB>,
+}
+
+impl<M: RawMutex, FB> Default for FrameSwap<'_, M, FB> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, M: RawMutex, FB> FrameSwap<'a, M, FB> {
+    /// Create a new, empty hand-over point.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pending: Signal::new(),
+            released: Signal::new(),
+        }
+    }
+
+    /// Split into the refresh side, owning `front`, and the drawing side,
+    /// owning `back`.
+    pub fn split(
+        &'a self,
+        front: &'a mut FB,
+        back: &'a mut FB,
+    ) -> (Refresher<'a, M, FB>, Drawer<'a, M, FB>) {
+        (
+            Refresher {
+                swap: self,
+                front,
+                frame: 0,
+            },
+            Drawer {
+                swap: self,
+                back: Some(back),
+            },
+        )
+    }
+}
+
+/// Owns the buffer currently on screen and keeps streaming it.
+pub struct Refresher<'a, M: RawMutex, FB> {
+    swap: &'a FrameSwap<'a, M, FB>,
+    front: &'a mut FB,
+    frame: u32,
+}
+
+impl<M: RawMutex, FB: ReadBuffer<Word = u8>> Refresher<'_, M, FB> {
+    /// Stream the front buffer once, then swap in a flushed back buffer if
+    /// one is waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by `output`.
+    pub async fn refresh<D: DmaOutput>(&mut self, output: &mut D) -> Result<(), D::Error> {
+        self.refresh_with_hook(output, &mut ()).await
+    }
+
+    /// Like [`Refresher::refresh`], and calls `hook` at the frame boundary
+    /// after the swap decision has been made.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by `output`; `hook` is not called then.
+    pub async fn refresh_with_hook<D: DmaOutput, H: VsyncHook>(
+        &mut self,
+        output: &mut D,
+        hook: &mut H,
+    ) -> Result<(), D::Error> {
+        output.output(&*self.front).await?;
+        let swapped = if let Some(next) = self.swap.pending.try_take() {
+            let previous = core::mem::replace(&mut self.front, next);
+            self.swap.released.signal(previous);
+            true
+        } else {
+            false
+        };
+        hook.on_vsync(Vsync {
+            frame: self.frame,
+            swapped,
+        });
+        self.frame = self.frame.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Refresh the panel forever. Intended to be the body of an embassy task.
+    ///
+    /// # Errors
+    ///
+    /// Only returns if `output` reports an error.
+    pub async fn run<D: DmaOutput>(self, output: D) -> Result<Infallible, D::Error> {
+        self.run_with_hook(output, ()).await
+    }
+
+    /// Like [`Refresher::run`], calling `hook` at every frame boundary.
+    ///
+    /// # Errors
+    ///
+    /// Only returns if `output` reports an error.
+    pub async fn run_with_hook<D: DmaOutput, H: VsyncHook>(
+        mut self,
+        mut output: D,
+        mut hook: H,
+    ) -> Result<Infallible, D::Error> {
+        loop {
+            self.refresh_with_hook(&mut output, &mut hook).await?;
+        }
+    }
+}
+
+/// Owns the back buffer that the application draws into.
+///
+/// Dereferences to the framebuffer, so all drawing APIs are available
+/// directly on the drawer.
+///
+/// # Panics
+///
+/// Dereferencing panics while the drawer holds no buffer. That only
+/// happens if a [`Drawer::flush`] is dropped at the very moment a
+/// [`Refresher`] on another core is swapping the buffers, see
+/// [`Drawer::flush`]; the next completed flush hands a buffer back.
+pub struct Drawer<'a, M: RawMutex, FB> {
+    swap: &'a FrameSwap<'a, M, FB>,
+    back: Option<&'a mut FB>,
+}
+
+impl<M: RawMutex, FB> Drawer<'_, M, FB> {
+    /// Put the back buffer on screen at the next frame boundary and wait
+    /// until the previous front buffer has been handed back for drawing.
+    ///
+    /// Dropping the future before it completes, e.g. in a `select` with a
+    /// timeout, keeps the drawer usable: before the swap the drawer takes
+    /// its back buffer back and it is not shown, after the swap the drawer
+    /// gets the previous front buffer as if the flush had completed.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic itself, but if the future is dropped while a
+    /// [`Refresher`] on another core is between taking the back buffer and
+    /// handing back the front buffer, the drawer is left without a buffer
+    /// and dereferencing it panics until the next flush completes.
+    pub async fn flush(&mut self) {
+        if let Some(back) = self.back.take() {
+            self.swap.pending.signal(back);
+        }
+        let guard = Reclaim(self);
+        let front = guard.0.swap.released.wait().await;
+        guard.0.back = Some(front);
+    }
+}
+
+/// Gives a [`Drawer`] its buffer back if [`Drawer::flush`] is dropped
+/// before it completes.
+struct Reclaim<'d, 'a, M: RawMutex, FB>(&'d mut Drawer<'a, M, FB>);
+
+impl<M: RawMutex, FB> Drop for Reclaim<'_, '_, M, FB> {
+    fn drop(&mut self) {
+        let drawer = &mut *self.0;
+        if drawer.back.is_none() {
+            drawer.back = drawer
+                .swap
+                .pending
+                .try_take()
+                .or_else(|| drawer.swap.released.try_take());
+        }
+    }
+}
+
+impl<M: RawMutex, FB> Deref for Drawer<'_, M, FB> {
+    type Target = FB;
+
+    fn deref(&self) -> &FB {
+        self.back
+            .as_deref()
+            .expect("Drawer used after a flush cancelled mid-swap")
+    }
+}
+
+impl<M: RawMutex, FB> DerefMut for Drawer<'_, M, FB> {
+    fn deref_mut(&mut self) -> &mut FB {
+        self.back
+            .as_deref_mut()
+            .expect("Drawer used after a flush cancelled mid-swap")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::Point;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::{compute_frame_count, compute_rows, Color};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type TestFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+
+    #[derive(Default)]
+    struct MockOutput {
+        streamed: Vec<*const u8>,
+        fail_after: Option<usize>,
+    }
+
+    impl DmaOutput for MockOutput {
+        type Error = usize;
+
+        async fn output<B: ReadBuffer<Word = u8>>(&mut self, buffer: &B) -> Result<(), usize> {
+            if self.fail_after == Some(self.streamed.len()) {
+                return Err(self.streamed.len());
+            }
+            let (ptr, _) = unsafe { buffer.read_buffer() };
+            self.streamed.push(ptr);
+            Ok(())
+        }
+    }
+
+    fn poll_once<F: Future>(fut: core::pin::Pin<&mut F>) -> Poll<F::Output> {
+        let mut cx = Context::from_waker(Waker::noop());
+        fut.poll(&mut cx)
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(out) = poll_once(fut.as_mut()) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn test_refresh_streams_front_buffer() {
+        let swap = FrameSwap::<NoopRawMutex, TestFB>::new();
+        let mut front = TestFB::new();
+        let mut back = TestFB::new();
+        let front_ptr = core::ptr::from_ref(&front).cast::<u8>();
+        let (mut refresher, _drawer) = swap.split(&mut front, &mut back);
+
+        let mut output = MockOutput::default();
+        block_on(refresher.refresh(&mut output)).unwrap();
+        block_on(refresher.refresh(&mut output)).unwrap();
+        assert_eq!(output.streamed, [front_ptr, front_ptr]);
+    }
+
+    #[test]
+    fn test_flush_swaps_at_frame_boundary() {
+        let swap = FrameSwap::<NoopRawMutex, TestFB>::new();
+        let mut front = TestFB::new();
+        let mut back = TestFB::new();
+        let front_ptr = core::ptr::from_ref(&front).cast::<u8>();
+        let back_ptr = core::ptr::from_ref(&back).cast::<u8>();
+        let (mut refresher, mut drawer) = swap.split(&mut front, &mut back);
+
+        drawer.set_pixel(Point::new(0, 0), Color::WHITE);
+
+        let mut output = MockOutput::default();
+        {
+            let mut flush = pin!(drawer.flush());
+            assert!(poll_once(flush.as_mut()).is_pending());
+
+            // the swap happens after the transfer that was in flight
+            block_on(refresher.refresh(&mut output)).unwrap();
+            assert!(poll_once(flush.as_mut()).is_ready());
+        }
+        block_on(refresher.refresh(&mut output)).unwrap();
+        assert_eq!(output.streamed, [front_ptr, back_ptr]);
+
+        // the drawer now owns the old front buffer
+        let drawer_ptr = core::ptr::from_ref::<TestFB>(&drawer).cast::<u8>();
+        assert_eq!(drawer_ptr, front_ptr);
+    }
+
+    #[test]
+    fn test_cancelled_flush_keeps_a_buffer() {
+        let swap = FrameSwap::<NoopRawMutex, TestFB>::new();
+        let mut front = TestFB::new();
+        let mut back = TestFB::new();
+        let front_ptr = core::ptr::from_ref(&front).cast::<u8>();
+        let back_ptr = core::ptr::from_ref(&back).cast::<u8>();
+        let (mut refresher, mut drawer) = swap.split(&mut front, &mut back);
+        let drawer_ptr = |drawer: &Drawer<'_, NoopRawMutex, TestFB>| {
+            core::ptr::from_ref::<TestFB>(drawer).cast::<u8>()
+        };
+
+        // cancelled before the swap: the back buffer stays with the drawer
+        {
+            let mut flush = pin!(drawer.flush());
+            assert!(poll_once(flush.as_mut()).is_pending());
+        }
+        assert_eq!(drawer_ptr(&drawer), back_ptr);
+        let mut output = MockOutput::default();
+        block_on(refresher.refresh(&mut output)).unwrap();
+        block_on(refresher.refresh(&mut output)).unwrap();
+        assert_eq!(output.streamed, [front_ptr, front_ptr]);
+
+        // cancelled after the swap: the drawer gets the old front buffer
+        {
+            let mut flush = pin!(drawer.flush());
+            assert!(poll_once(flush.as_mut()).is_pending());
+            block_on(refresher.refresh(&mut output)).unwrap();
+        }
+        assert_eq!(drawer_ptr(&drawer), front_ptr);
+        block_on(refresher.refresh(&mut output)).unwrap();
+        assert_eq!(output.streamed[3], back_ptr);
+    }
+
+    #[test]
+    fn test_hook_sees_every_frame_and_swap() {
+        let swap = FrameSwap::<NoopRawMutex, TestFB>::new();
+        let mut front = TestFB::new();
+        let mut back = TestFB::new();
+        let (mut refresher, mut drawer) = swap.split(&mut front, &mut back);
+
+        let mut output = MockOutput::default();
+        let mut events = Vec::new();
+        let mut hook = |vsync: Vsync| events.push(vsync);
+        block_on(refresher.refresh_with_hook(&mut output, &mut hook)).unwrap();
+        {
+            let mut flush = pin!(drawer.flush());
+            assert!(poll_once(flush.as_mut()).is_pending());
+            block_on(refresher.refresh_with_hook(&mut output, &mut hook)).unwrap();
+            assert!(poll_once(flush.as_mut()).is_ready());
+        }
+        block_on(refresher.refresh_with_hook(&mut output, &mut hook)).unwrap();
+
+        let swapped: Vec<_> = events.iter().map(|v| v.swapped).collect();
+        let frames: Vec<_> = events.iter().map(|v| v.frame).collect();
+        assert_eq!(swapped, [false, true, false]);
+        assert_eq!(frames, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_run_stops_on_output_error() {
+        let swap = FrameSwap::<NoopRawMutex, TestFB>::new();
+        let mut front = TestFB::new();
+        let mut back = TestFB::new();
+        let (refresher, _drawer) = swap.split(&mut front, &mut back);
+
+        let output = MockOutput {
+            fail_after: Some(3),
+            ..MockOutput::default()
+        };
+        assert_eq!(block_on(refresher.run(output)), Err(3));
+    }
+}
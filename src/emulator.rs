@@ -0,0 +1,313 @@
+//! Software model of a HUB75 panel, fed one clock at a time.
+//!
+//! [`Panel`] models the parts of a panel that a DMA stream drives: the
+//! colour shift registers, the column drivers loaded by `LAT`, the row
+//! address decoder and `OE`. Every clock with the output enabled lights the
+//! selected row with the driver contents, and the panel adds up how long
+//! each LED was lit, which is what the eye sees.
+//!
+//! While scanning it also flags timing that a real panel shows as
+//! artefacts, see [`Issues`]. Use it to validate new formats or user
+//! configurations on the host; [`crate::decode`] reconstructs the BCM frames
+//! instead.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::emulator::{Issues, Panel};
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::plain::{DefaultPins, DmaFrameBuffer};
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! fb.set_pixel(Point::new(3, 3), Color::WHITE);
+//!
+//! let mut panel = Panel::<32, 64, 16>::plain();
+//! panel.scan_plain::<DefaultOrdering, DefaultPins>(fb.as_words());
+//! assert_eq!(panel.issues(), Issues::default());
+//! assert!(panel.pixel(3, 3).r() > 200);
+//! assert_eq!(panel.pixel(4, 3), Color::BLACK);
+//! ```
+
+use crate::decode::{latched_signals, plain_signals, Signals};
+use crate::ordering::{map_byte_index, map_half_word_index, Ordering};
+use crate::{latched, plain, Color};
+
+/// Timing problems found while scanning, as the number of clocks each one
+/// occurred on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Issues {
+    /// Colour data was clocked in while `LAT` had already been high for a
+    /// clock, so the column drivers followed the shifting data.
+    pub clocked_while_latch_open: usize,
+    /// The row address changed while the output was enabled, which shows
+    /// the data on the wrong row (ghosting). Usually missing blanking.
+    pub address_change_while_lit: usize,
+    /// `LAT` was high while the output was enabled, so the lit row changed
+    /// its data while being shown.
+    pub latch_while_lit: usize,
+}
+
+/// A HUB75 panel of `ROWS` by `COLS` pixels with `NROWS` scan rows.
+#[derive(Clone)]
+pub struct Panel<const ROWS: usize, const COLS: usize, const NROWS: usize> {
+    /// Whether the address comes from an external latch that also gates
+    /// the clock while `LAT` is high, as with [`crate::latched`].
+    address_latch: bool,
+    shift: [u8; COLS],
+    head: usize,
+    drivers: [u8; COLS],
+    held_addr: u8,
+    prev: Option<(u8, bool, bool)>,
+    /// Clocks each colour bit of each pixel was lit for.
+    on_clocks: [[[u32; 3]; COLS]; ROWS],
+    clocks: u32,
+    issues: Issues,
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize> core::fmt::Debug
+    for Panel<ROWS, COLS, NROWS>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Panel")
+            .field("address_latch", &self.address_latch)
+            .field("clocks", &self.clocks)
+            .field("issues", &self.issues)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Panel<ROWS, COLS, NROWS> {
+    const fn new(address_latch: bool) -> Self {
+        Self {
+            address_latch,
+            shift: [0; COLS],
+            head: 0,
+            drivers: [0; COLS],
+            held_addr: 0,
+            prev: None,
+            on_clocks: [[[0; 3]; COLS]; ROWS],
+            clocks: 0,
+            issues: Issues {
+                clocked_while_latch_open: 0,
+                address_change_while_lit: 0,
+                latch_while_lit: 0,
+            },
+        }
+    }
+
+    /// A panel wired directly to the data bus, as with [`crate::plain`].
+    #[must_use]
+    pub const fn plain() -> Self {
+        Self::new(false)
+    }
+
+    /// A panel behind the reference latch circuit of [`crate::latched`].
+    #[must_use]
+    pub const fn latched() -> Self {
+        Self::new(true)
+    }
+
+    /// Clock in one [`crate::plain`] word with the signals assigned by `P`.
+    pub fn clock_plain<P: plain::PinMap>(&mut self, word: u16) {
+        self.clock(plain_signals::<P>(word));
+    }
+
+    /// Clock in one [`crate::latched`] word with the signals assigned by
+    /// `P`.
+    pub fn clock_latched<P: latched::PinMap>(&mut self, word: u8) {
+        self.clock(latched_signals::<P>(word));
+    }
+
+    /// Clock in a whole [`crate::plain`] stream, e.g. from `as_words()`.
+    pub fn scan_plain<O: Ordering, P: plain::PinMap>(&mut self, words: &[u16]) {
+        for i in 0..words.len() {
+            self.clock_plain::<P>(words[map_half_word_index::<O>(i)]);
+        }
+    }
+
+    /// Clock in a whole [`crate::latched`] stream, e.g. from `as_bytes()`.
+    pub fn scan_latched<O: Ordering, P: latched::PinMap>(&mut self, bytes: &[u8]) {
+        for i in 0..bytes.len() {
+            self.clock_latched::<P>(bytes[map_byte_index::<O>(i)]);
+        }
+    }
+
+    fn clock(&mut self, s: Signals) {
+        let gated = self.address_latch && s.lat;
+        if s.lat {
+            self.held_addr = s.addr;
+        }
+        let addr = if self.address_latch {
+            self.held_addr
+        } else {
+            s.addr
+        };
+
+        if let Some((prev_addr, prev_lat, prev_oe)) = self.prev {
+            if prev_lat && s.lat && !gated {
+                self.issues.clocked_while_latch_open += 1;
+            }
+            if addr != prev_addr && (s.oe || prev_oe) {
+                self.issues.address_change_while_lit += 1;
+            }
+        }
+        if s.lat && s.oe {
+            self.issues.latch_while_lit += 1;
+        }
+
+        if !gated {
+            self.shift[self.head] = s.colors;
+            self.head = (self.head + 1) % COLS;
+        }
+        // the drivers follow the shift register while the latch is open
+        if s.lat {
+            self.drivers = core::array::from_fn(|x| self.shift[(self.head + x) % COLS]);
+        }
+        if s.oe {
+            self.light(addr);
+        }
+        self.clocks += 1;
+        self.prev = Some((addr, s.lat, s.oe));
+    }
+
+    fn light(&mut self, addr: u8) {
+        let scan_row = usize::from(addr);
+        if scan_row >= NROWS {
+            return;
+        }
+        for (half, shift) in [(0, 0), (1, 3)] {
+            let y = half * NROWS + scan_row;
+            if y < ROWS {
+                for (pixel, colors) in self.on_clocks[y].iter_mut().zip(self.drivers) {
+                    for (channel, on) in pixel.iter_mut().enumerate() {
+                        *on += u32::from(colors >> (shift + channel) & 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Timing problems found so far.
+    #[must_use]
+    pub const fn issues(&self) -> Issues {
+        self.issues
+    }
+
+    /// Number of clocks scanned so far.
+    #[must_use]
+    pub const fn clocks(&self) -> u32 {
+        self.clocks
+    }
+
+    /// Clocks the red, green and blue LEDs at column `x` of row `y` were lit
+    /// for.
+    ///
+    /// # Panics
+    /// Panics if `x` or `y` is out of range.
+    #[must_use]
+    pub fn on_clocks(&self, x: usize, y: usize) -> [u32; 3] {
+        self.on_clocks[y][x]
+    }
+
+    /// Perceived colour at column `x` of row `y`: the time each LED was lit
+    /// relative to its scan row's share of the clocks.
+    ///
+    /// A channel that is lit whenever its row is selected shows as 255;
+    /// blanking and BCM weighting make channels dimmer.
+    ///
+    /// # Panics
+    /// Panics if `x` or `y` is out of range.
+    #[must_use]
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let row_clocks = u64::from(self.clocks) / NROWS as u64;
+        let level = |on: u32| {
+            if row_clocks == 0 {
+                return 0;
+            }
+            (u64::from(on) * 255 / row_clocks).min(255) as u8
+        };
+        let [red, green, blue] = self.on_clocks[y][x];
+        Color::new(level(red), level(green), level(blue))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::DmaFrameBuffer;
+    use crate::latched::LatchedFormat;
+    use crate::ordering::{Esp32, Linear};
+    use crate::plain::PlainFormat;
+    use embedded_graphics::prelude::*;
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = 16;
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = 7;
+
+    type TestPanel = Panel<ROWS, COLS, NROWS>;
+
+    #[test]
+    fn test_plain_scan() {
+        let mut fb =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+        fb.set_pixel(Point::new(2, 5), Color::WHITE);
+        fb.set_pixel(Point::new(3, 5), Color::new(96, 0, 0));
+        fb.set_pixel(Point::new(2, 21), Color::BLUE);
+
+        let mut panel = TestPanel::plain();
+        panel.scan_plain::<Esp32, plain::DefaultPins>(fb.as_words());
+        assert_eq!(panel.issues(), Issues::default());
+        assert_eq!(panel.clocks() as usize, fb.as_words().len());
+
+        // white is lit for the whole lit window of its row in every frame
+        let [r, g, b] = panel.on_clocks(2, 5);
+        assert_eq!((r, g, b), (r, r, r));
+        assert!(r > 0);
+        // 96 is 3 of the 7 frames
+        assert_eq!(panel.on_clocks(3, 5)[0] * 7, r * 3);
+        assert_eq!(panel.on_clocks(2, 21), [0, 0, r]);
+        assert_eq!(panel.pixel(2, 4), Color::BLACK);
+    }
+
+    #[test]
+    fn test_latched_scan() {
+        type Leading = LatchedFormat<4, 0>;
+        let mut fb = DmaFrameBuffer::<Leading, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        fb.set_pixel(Point::new(63, 31), Color::GREEN);
+
+        let mut panel = TestPanel::latched();
+        // twice, so the first row shows the data of the last one
+        for _ in 0..2 {
+            panel.scan_latched::<Linear, latched::DefaultPins>(fb.as_bytes());
+        }
+        assert_eq!(panel.issues(), Issues::default());
+        assert!(panel.pixel(63, 31).g() > 200);
+        assert_eq!(panel.pixel(63, 30), Color::BLACK);
+    }
+
+    #[test]
+    fn test_flags_timing_issues() {
+        let mut fb =
+            DmaFrameBuffer::<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        // enable the output everywhere: no blanking around the latch
+        for word in fb.as_bytes_mut().chunks_mut(2) {
+            word[1] |= 1;
+        }
+        let mut panel = TestPanel::plain();
+        panel.scan_plain::<Linear, plain::DefaultPins>(fb.as_words());
+        let issues = panel.issues();
+        assert_eq!(issues.latch_while_lit, FRAME_COUNT * NROWS);
+        assert_eq!(issues.address_change_while_lit, FRAME_COUNT * NROWS);
+        assert_eq!(issues.clocked_while_latch_open, 0);
+
+        // a latch held open for two clocks
+        let mut panel = TestPanel::plain();
+        panel.clock_plain::<plain::DefaultPins>(1 << 5);
+        panel.clock_plain::<plain::DefaultPins>(1 << 5);
+        assert_eq!(panel.issues().clocked_while_latch_open, 1);
+    }
+}
@@ -0,0 +1,264 @@
+//! Framebuffers behind `dyn`.
+//!
+//! The framebuffer types carry their whole configuration in const generics,
+//! so two panel configurations are two unrelated types that cannot share a
+//! field or be chosen at runtime. [`DynFrameBuffer`] is an object-safe
+//! view of the operations an application needs after startup: drawing,
+//! clearing and handing the buffer to DMA, implemented by every framebuffer
+//! with a single DMA stream. [`ErasedFrameBuffer`] holds one behind a
+//! reference and is a [`DrawTarget`] itself.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::erased::{DynFrameBuffer, ErasedFrameBuffer};
+//! use hub75_framebuffer::{latched, plain, Color};
+//!
+//! let mut small = plain::DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let mut large = latched::DmaFrameBuffer::<64, 64, 32, 3, 7>::new();
+//!
+//! // chosen at startup, e.g. from a configuration pin
+//! let large_panel = true;
+//! let fb: &mut dyn DynFrameBuffer = if large_panel { &mut large } else { &mut small };
+//! let mut fb = ErasedFrameBuffer::new(fb);
+//!
+//! assert_eq!(fb.size(), Size::new(64, 64));
+//! Rectangle::new(Point::new(1, 1), Size::new(8, 4))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::GREEN))
+//!     .draw(&mut fb)
+//!     .unwrap();
+//! let (ptr, len) = unsafe { fb.read_buffer_raw() };
+//! assert!(!ptr.is_null() && len > 0);
+//! ```
+
+use core::convert::Infallible;
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, FrameBufferOperations, WordSize};
+
+/// Object-safe framebuffer operations behind [`ErasedFrameBuffer`].
+///
+/// Implemented for every framebuffer with a single DMA stream of bytes. The
+/// geometry comes from the [`FrameBuffer`] supertrait.
+pub trait DynFrameBuffer: FrameBuffer {
+    /// Set a pixel, ignoring pixels outside the framebuffer.
+    fn set_pixel(&mut self, p: Point, color: Color);
+
+    /// Erase pixel colors while preserving control bits.
+    fn erase(&mut self);
+
+    /// Size of the framebuffer in pixels.
+    fn size(&self) -> Size;
+
+    /// Size of the DMA words.
+    fn word_size(&self) -> WordSize;
+
+    /// Pointer to and length in bytes of the DMA stream.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`ReadBuffer::read_buffer`]: the memory must not be
+    /// written while DMA reads it.
+    unsafe fn read_buffer_raw(&self) -> (*const u8, usize);
+}
+
+impl<T> DynFrameBuffer for T
+where
+    T: FrameBufferOperations + OriginDimensions + ReadBuffer<Word = u8>,
+{
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        FrameBufferOperations::set_pixel(self, p, color);
+    }
+
+    #[inline]
+    fn erase(&mut self) {
+        FrameBufferOperations::erase(self);
+    }
+
+    #[inline]
+    fn size(&self) -> Size {
+        OriginDimensions::size(self)
+    }
+
+    #[inline]
+    fn word_size(&self) -> WordSize {
+        self.get_word_size()
+    }
+
+    unsafe fn read_buffer_raw(&self) -> (*const u8, usize) {
+        self.read_buffer()
+    }
+}
+
+/// A framebuffer of any configuration, borrowed as a [`DynFrameBuffer`].
+///
+/// It is a [`DrawTarget`], so `embedded-graphics` drawables draw on it like
+/// on the framebuffer itself.
+pub struct ErasedFrameBuffer<'a>(&'a mut dyn DynFrameBuffer);
+
+impl<'a> ErasedFrameBuffer<'a> {
+    /// Borrow `fb` as an erased framebuffer.
+    pub fn new(fb: &'a mut dyn DynFrameBuffer) -> Self {
+        Self(fb)
+    }
+
+    /// Set a pixel, ignoring pixels outside the framebuffer.
+    #[inline]
+    pub fn set_pixel(&mut self, p: Point, color: Color) {
+        self.0.set_pixel(p, color);
+    }
+
+    /// Erase pixel colors while preserving control bits.
+    pub fn erase(&mut self) {
+        self.0.erase();
+    }
+
+    /// Size of the DMA words.
+    #[must_use]
+    pub fn word_size(&self) -> WordSize {
+        self.0.word_size()
+    }
+
+    /// Pointer to and length in bytes of the DMA stream.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`ReadBuffer::read_buffer`]: the memory must not be
+    /// written while DMA reads it.
+    #[must_use]
+    pub unsafe fn read_buffer_raw(&self) -> (*const u8, usize) {
+        self.0.read_buffer_raw()
+    }
+}
+
+impl core::fmt::Debug for ErasedFrameBuffer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ErasedFrameBuffer")
+            .field("size", &self.0.size())
+            .field("word_size", &self.0.word_size())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FrameBuffer for ErasedFrameBuffer<'_> {
+    fn get_word_size(&self) -> WordSize {
+        self.0.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.0.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.0.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.0.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.0.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.0.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.0.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.0.buffer_len_bytes()
+    }
+}
+
+impl OriginDimensions for ErasedFrameBuffer<'_> {
+    fn size(&self) -> Size {
+        self.0.size()
+    }
+}
+
+impl DrawTarget for ErasedFrameBuffer<'_> {
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            self.0.set_pixel(p, color);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{latched, plain};
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+    type SmallPlain = plain::DmaFrameBuffer<16, 32, 8, 3, 7>;
+    type SmallLatched = latched::DmaFrameBuffer<16, 32, 8, 3, 7>;
+
+    fn pick<'a>(
+        latched: bool,
+        plain: &'a mut SmallPlain,
+        other: &'a mut SmallLatched,
+    ) -> ErasedFrameBuffer<'a> {
+        if latched {
+            ErasedFrameBuffer::new(other)
+        } else {
+            ErasedFrameBuffer::new(plain)
+        }
+    }
+
+    #[test]
+    fn test_erased_matches_concrete() {
+        let rect = Rectangle::new(Point::new(2, 3), Size::new(5, 4))
+            .into_styled(PrimitiveStyle::with_fill(Color::new(200, 40, 0)));
+        let mut expected_plain = SmallPlain::new();
+        rect.draw(&mut expected_plain).unwrap();
+        let mut expected_latched = SmallLatched::new();
+        rect.draw(&mut expected_latched).unwrap();
+
+        let mut plain = SmallPlain::new();
+        let mut latched = SmallLatched::new();
+        for use_latched in [false, true] {
+            let mut fb = pick(use_latched, &mut plain, &mut latched);
+            assert_eq!(fb.size(), Size::new(32, 16));
+            rect.draw(&mut fb).unwrap();
+            fb.set_pixel(Point::new(40, 0), Color::WHITE);
+        }
+        assert_eq!(plain.as_bytes(), expected_plain.as_bytes());
+        assert_eq!(latched.as_bytes(), expected_latched.as_bytes());
+
+        let mut fb = pick(false, &mut plain, &mut latched);
+        assert_eq!(fb.word_size(), WordSize::Sixteen);
+        let (_, len) = unsafe { fb.read_buffer_raw() };
+        assert_eq!(len, SmallPlain::dma_buffer_size_bytes());
+        assert_eq!(fb.buffer_len_bytes(), len);
+        assert_eq!(
+            (fb.rows(), fb.cols(), fb.bits(), fb.frame_count()),
+            (16, 32, 3, 7)
+        );
+        fb.erase();
+        assert_eq!(plain.as_bytes(), SmallPlain::new().as_bytes());
+
+        let fb = pick(true, &mut plain, &mut latched);
+        assert_eq!(fb.word_size(), WordSize::Eight);
+        let (ptr, len) = unsafe { fb.read_buffer_raw() };
+        assert_eq!(ptr, latched.as_bytes().as_ptr());
+        assert_eq!(len, latched.as_bytes().len());
+    }
+}
@@ -0,0 +1,420 @@
+//! Binary container for captured DMA streams.
+//!
+//! Every framebuffer can write its raw DMA stream, prefixed with a small
+//! header describing its geometry, through `export()`. The resulting blob can
+//! be sent to a host, replayed into an FPGA testbench, stored as a golden
+//! image, or compared between crate versions. [`Container::parse`] reads it
+//! back.
+//!
+//! # Format
+//! All multi-byte fields are little-endian.
+//!
+//! ```text
+//! offset  size  field
+//!      0     8  magic "HUB75FB\0"
+//!      8     2  format version (1)
+//!     10     1  layout (see `Layout`)
+//!     11     1  word size in bits (8, 16 or 32)
+//!     12     2  ROWS
+//!     14     2  COLS
+//!     16     2  NROWS
+//!     18     1  BITS (number of planes for bit-plane layouts)
+//!     19     1  ordering (0 = linear, 1 = ESP32)
+//!     20     2  plane count
+//!     22     2  reserved (0)
+//!     24     4  bytes per plane
+//!     28     …  plane data, back to back, exactly as read by DMA
+//! ```
+//!
+//! 16-bit and 32-bit words are stored little-endian.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::export::{Container, Layout};
+//! use hub75_framebuffer::latched::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! let mut blob = Vec::new();
+//! fb.export(|chunk| blob.extend_from_slice(chunk));
+//!
+//! let container = Container::parse(&blob).unwrap();
+//! assert_eq!(container.header.layout, Layout::Latched);
+//! assert_eq!(container.plane(0), Some(fb.as_bytes()));
+//! ```
+
+use crate::{FrameBuffer, WordSize};
+
+/// Magic bytes at the start of every container.
+pub const MAGIC: [u8; 8] = *b"HUB75FB\0";
+/// Current container format version.
+pub const VERSION: u16 = 1;
+/// Size of the container header in bytes.
+pub const HEADER_LEN: usize = 28;
+
+/// Which framebuffer produced the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum Layout {
+    /// [`crate::plain::DmaFrameBuffer`]
+    Plain = 0,
+    /// [`crate::latched::DmaFrameBuffer`]
+    Latched = 1,
+    /// [`crate::bitplane::plain::DmaFrameBuffer`]
+    BitplanePlain = 2,
+    /// [`crate::bitplane::latched::DmaFrameBuffer`]
+    BitplaneLatched = 3,
+    /// [`crate::latched16::DmaFrameBuffer`]
+    Latched16 = 4,
+    /// [`crate::plain8::DmaFrameBuffer`]
+    Plain8 = 5,
+    /// [`crate::plain32::DmaFrameBuffer`]
+    Plain32 = 6,
+}
+
+impl Layout {
+    const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Plain),
+            1 => Some(Self::Latched),
+            2 => Some(Self::BitplanePlain),
+            3 => Some(Self::BitplaneLatched),
+            4 => Some(Self::Latched16),
+            5 => Some(Self::Plain8),
+            6 => Some(Self::Plain32),
+            _ => None,
+        }
+    }
+}
+
+/// Geometry of an exported framebuffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Header {
+    /// Framebuffer layout
+    pub layout: Layout,
+    /// Size of one DMA word
+    pub word_size: WordSize,
+    /// Total number of rows in the panel
+    pub rows: u16,
+    /// Number of columns in the panel
+    pub cols: u16,
+    /// Number of rows per scan
+    pub nrows: u16,
+    /// Colour depth, or number of planes for bit-plane layouts
+    pub bits: u8,
+    /// `true` if the stream uses the ESP32 byte ordering
+    pub esp32_ordering: bool,
+    /// Number of planes that follow the header
+    pub plane_count: u16,
+    /// Size of each plane in bytes
+    pub plane_bytes: u32,
+}
+
+impl Header {
+    /// Serialize the header.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[0..8].copy_from_slice(&MAGIC);
+        out[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        out[10] = self.layout as u8;
+        out[11] = match self.word_size {
+            WordSize::Eight => 8,
+            WordSize::Sixteen => 16,
+            WordSize::ThirtyTwo => 32,
+        };
+        out[12..14].copy_from_slice(&self.rows.to_le_bytes());
+        out[14..16].copy_from_slice(&self.cols.to_le_bytes());
+        out[16..18].copy_from_slice(&self.nrows.to_le_bytes());
+        out[18] = self.bits;
+        out[19] = u8::from(self.esp32_ordering);
+        out[20..22].copy_from_slice(&self.plane_count.to_le_bytes());
+        out[24..28].copy_from_slice(&self.plane_bytes.to_le_bytes());
+        out
+    }
+
+    /// Parse a header from the start of `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if `bytes` does not start with a valid header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(ParseError::TooShort)?;
+        if header[0..8] != MAGIC {
+            return Err(ParseError::BadMagic);
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([header[i], header[i + 1]]);
+        let version = u16_at(8);
+        if version != VERSION {
+            return Err(ParseError::UnsupportedVersion(version));
+        }
+        let layout = Layout::from_u8(header[10]).ok_or(ParseError::InvalidField)?;
+        let word_size = match header[11] {
+            8 => WordSize::Eight,
+            16 => WordSize::Sixteen,
+            32 => WordSize::ThirtyTwo,
+            _ => return Err(ParseError::InvalidField),
+        };
+        let esp32_ordering = match header[19] {
+            0 => false,
+            1 => true,
+            _ => return Err(ParseError::InvalidField),
+        };
+        Ok(Self {
+            layout,
+            word_size,
+            rows: u16_at(12),
+            cols: u16_at(14),
+            nrows: u16_at(16),
+            bits: header[18],
+            esp32_ordering,
+            plane_count: u16_at(20),
+            plane_bytes: u32::from_le_bytes([header[24], header[25], header[26], header[27]]),
+        })
+    }
+
+    /// Total size of the container described by this header.
+    #[must_use]
+    pub fn container_len(&self) -> usize {
+        HEADER_LEN + usize::from(self.plane_count) * self.plane_bytes as usize
+    }
+}
+
+/// Errors returned by [`Container::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseError {
+    /// The input is shorter than the header claims.
+    TooShort,
+    /// The input does not start with [`MAGIC`].
+    BadMagic,
+    /// The container was written by an unknown format version.
+    UnsupportedVersion(u16),
+    /// A header field holds an unknown value.
+    InvalidField,
+    /// The input is longer than the header claims.
+    TrailingData,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort => f.write_str("container is truncated"),
+            Self::BadMagic => f.write_str("not a framebuffer container"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported container version {v}"),
+            Self::InvalidField => f.write_str("invalid header field"),
+            Self::TrailingData => f.write_str("unexpected data after the last plane"),
+        }
+    }
+}
+
+/// A parsed container borrowing its plane data from the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Container<'a> {
+    /// The container header
+    pub header: Header,
+    data: &'a [u8],
+}
+
+impl<'a> Container<'a> {
+    /// Parse a complete container.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the header is invalid or the input length
+    /// does not match it.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        let header = Header::from_bytes(bytes)?;
+        let len = header.container_len();
+        if bytes.len() < len {
+            return Err(ParseError::TooShort);
+        }
+        if bytes.len() > len {
+            return Err(ParseError::TrailingData);
+        }
+        Ok(Self {
+            header,
+            data: &bytes[HEADER_LEN..],
+        })
+    }
+
+    /// Returns the raw bytes of plane `plane_idx`.
+    #[must_use]
+    pub fn plane(&self, plane_idx: usize) -> Option<&'a [u8]> {
+        if plane_idx >= usize::from(self.header.plane_count) {
+            return None;
+        }
+        let size = self.header.plane_bytes as usize;
+        self.data.get(plane_idx * size..(plane_idx + 1) * size)
+    }
+
+    /// Returns all plane data as one slice.
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Write `header` followed by every plane of `fb` to `sink`.
+pub(crate) fn export<FB: FrameBuffer + ?Sized>(
+    fb: &FB,
+    header: &Header,
+    mut sink: impl FnMut(&[u8]),
+) {
+    sink(&header.to_bytes());
+    for plane_idx in 0..fb.plane_count() {
+        let (ptr, len) = fb.plane_ptr_len(plane_idx);
+        // SAFETY: the plane stays borrowed through `fb` while we read it.
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, len) };
+        write_plane(bytes, header.word_size, &mut sink);
+    }
+}
+
+/// Write `header` followed by the single plane `bytes` to `sink`.
+pub(crate) fn export_bytes(bytes: &[u8], header: &Header, mut sink: impl FnMut(&[u8])) {
+    sink(&header.to_bytes());
+    write_plane(bytes, header.word_size, &mut sink);
+}
+
+/// Write the words of `bytes` little-endian to `sink`.
+fn write_plane(bytes: &[u8], word_size: WordSize, sink: &mut impl FnMut(&[u8])) {
+    let word_bytes = match word_size {
+        WordSize::Eight => 1,
+        WordSize::Sixteen => 2,
+        WordSize::ThirtyTwo => 4,
+    };
+    if cfg!(target_endian = "little") || word_bytes == 1 {
+        sink(bytes);
+    } else {
+        // big-endian words are little-endian with their bytes reversed
+        for word in bytes.chunks_exact(word_bytes) {
+            let mut le = [0; 4];
+            le[..word_bytes].copy_from_slice(word);
+            le[..word_bytes].reverse();
+            sink(&le[..word_bytes]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::Point;
+
+    use super::*;
+    use crate::ordering::{Esp32, Linear};
+    use crate::{compute_frame_count, compute_rows, Color};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>;
+    type BitplaneFB = crate::bitplane::latched::DmaFrameBuffer<NROWS, COLS, 4, Linear>;
+
+    fn capture(export: impl FnOnce(&mut dyn FnMut(&[u8]))) -> Vec<u8> {
+        let mut blob = Vec::new();
+        export(&mut |chunk| blob.extend_from_slice(chunk));
+        blob
+    }
+
+    #[test]
+    fn test_plain_round_trip() {
+        let mut fb = PlainFB::new();
+        fb.set_pixel(Point::new(1, 2), Color::CYAN);
+        let blob = capture(|sink| fb.export(sink));
+
+        let container = Container::parse(&blob).unwrap();
+        assert_eq!(
+            container.header,
+            Header {
+                layout: Layout::Plain,
+                word_size: WordSize::Sixteen,
+                rows: ROWS as u16,
+                cols: COLS as u16,
+                nrows: NROWS as u16,
+                bits: BITS,
+                esp32_ordering: false,
+                plane_count: 1,
+                plane_bytes: (FRAME_COUNT * NROWS * COLS * 2) as u32,
+            }
+        );
+        assert_eq!(container.plane(0), Some(fb.as_bytes()));
+        assert_eq!(container.plane(1), None);
+    }
+
+    #[test]
+    fn test_latched_header_records_ordering() {
+        let fb = LatchedFB::new();
+        let blob = capture(|sink| fb.export(sink));
+
+        let container = Container::parse(&blob).unwrap();
+        assert_eq!(container.header.layout, Layout::Latched);
+        assert_eq!(container.header.word_size, WordSize::Eight);
+        assert!(container.header.esp32_ordering);
+        assert_eq!(container.data(), fb.as_bytes());
+    }
+
+    #[test]
+    fn test_bitplane_planes_are_exported_in_order() {
+        let mut fb = BitplaneFB::new();
+        fb.set_pixel(Point::new(3, 3), Color::new(0x80, 0, 0));
+        let blob = capture(|sink| fb.export(sink));
+
+        let container = Container::parse(&blob).unwrap();
+        assert_eq!(container.header.layout, Layout::BitplaneLatched);
+        assert_eq!(container.header.bits, 4);
+        assert_eq!(container.header.plane_count, 4);
+        for plane_idx in 0..4 {
+            let (ptr, len) = fb.plane_ptr_len(plane_idx);
+            let expected = unsafe { core::slice::from_raw_parts(ptr, len) };
+            assert_eq!(container.plane(plane_idx), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let fb = PlainFB::new();
+        let mut blob = capture(|sink| fb.export(sink));
+
+        assert_eq!(Container::parse(&blob[..10]), Err(ParseError::TooShort));
+        assert_eq!(
+            Container::parse(&blob[..blob.len() - 1]),
+            Err(ParseError::TooShort)
+        );
+
+        blob.push(0);
+        assert_eq!(Container::parse(&blob), Err(ParseError::TrailingData));
+        blob.pop();
+
+        blob[8] = 2;
+        assert_eq!(
+            Container::parse(&blob),
+            Err(ParseError::UnsupportedVersion(2))
+        );
+        blob[8] = 1;
+
+        blob[10] = 9;
+        assert_eq!(Container::parse(&blob), Err(ParseError::InvalidField));
+        blob[10] = 0;
+
+        blob[0] = b'X';
+        assert_eq!(Container::parse(&blob), Err(ParseError::BadMagic));
+    }
+}
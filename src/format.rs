@@ -0,0 +1,2164 @@
+//! Generic threshold-frame framebuffer.
+//!
+//! [`crate::plain`] and [`crate::latched`] differ only in how a row of the
+//! panel is laid out in memory: the word size, where the colour bits sit in
+//! each word, and how the address and control signals are packed. Everything
+//! else (splitting the colour into BCM frames, drawing, DMA access) is shared
+//! and lives here in [`DmaFrameBuffer`], which is generic over an
+//! [`EntryFormat`].
+//!
+//! `plain::DmaFrameBuffer` and `latched::DmaFrameBuffer` are type aliases for
+//! this framebuffer with [`crate::plain::PlainFormat`] and
+//! [`crate::latched::LatchedFormat`]. A new wiring (a 32-bit word, a second
+//! chain, custom pin assignments, ...) is added by implementing
+//! [`EntryFormat`] and [`RowFormat`] instead of copying a whole module.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::format::DmaFrameBuffer;
+//! use hub75_framebuffer::latched::LatchedFormat;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! // the same type as `latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>`
+//! let fb = DmaFrameBuffer::<LatchedFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! ```
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+use embedded_dma::{ReadBuffer, WriteBuffer};
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::Point;
+use embedded_graphics::Pixel;
+
+use crate::align::{Alignment, DefaultAlignment};
+use crate::export::Layout;
+use crate::ordering::{DefaultOrdering, Ordering};
+use crate::orientation::{Normal, Orientation};
+use crate::{Color, FrameBuffer, FrameBufferOperations, Hub75Error, MutableFrameBuffer, WordSize};
+
+/// Memory layout of the words streamed to the panel.
+///
+/// # Safety
+///
+/// [`DmaFrameBuffer`] hands the raw memory of its rows to DMA and exposes it
+/// as bytes, so implementations must guarantee that `Row` and `Pad` contain
+/// no padding bytes and that every bit pattern, including all zeros, is a
+/// valid value of both.
+pub unsafe trait EntryFormat: Copy + core::fmt::Debug {
+    /// Size of one DMA word.
+    const WORD_SIZE: WordSize;
+    /// Layout recorded by `export()`.
+    const LAYOUT: Layout;
+    /// Number of panel chains driven in parallel, each with `2 * NROWS`
+    /// rows.
+    const CHAINS: usize = 1;
+    /// Placed in front of the frames, e.g. to raise the buffer's natural
+    /// alignment. Use `()` for none.
+    type Pad: Copy + Default + core::fmt::Debug;
+    /// Order of the BCM frames in memory.
+    type FrameOrder: FrameOrder;
+    /// One scan row of `COLS` columns, including its control words.
+    type Row<const COLS: usize, O: Ordering>: RowFormat;
+}
+
+/// Order in which the BCM frames are stored and streamed.
+///
+/// A colour value lights a channel in as many frames as its brightness
+/// requires. Filling the lowest frames first concentrates the lit frames
+/// of mid-range values at the start of every refresh, which can show as
+/// low-frequency flicker; other orders spread them out. Drawing is
+/// unaffected by the order.
+pub trait FrameOrder: Copy + core::fmt::Debug {
+    /// Whether the stream holds each scan row of all frames back to back
+    /// instead of whole frames, see [`Interleaved`].
+    const INTERLEAVED: bool = false;
+
+    /// Threshold rank of the frame stored at `position`: the frame lights
+    /// a channel iff the channel needs more than `rank` frames.
+    ///
+    /// Must be a permutation of `0..frame_count` (where `frame_count` is
+    /// `2^BITS - 1`). Orders that override [`Self::lit`] need not provide it.
+    #[inline]
+    #[must_use]
+    fn rank(position: usize, _frame_count: usize) -> usize {
+        position
+    }
+
+    /// Whether the frame stored at `position` lights a channel that needs
+    /// `frames_on` of the `frame_count` frames.
+    ///
+    /// Exactly `frames_on` positions must be lit for every value.
+    #[inline]
+    #[must_use]
+    fn lit(position: usize, frame_count: usize, frames_on: usize) -> bool {
+        Self::rank(position, frame_count) < frames_on
+    }
+}
+
+/// Frames stored from the lowest threshold to the highest (the default).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Sequential;
+
+impl FrameOrder for Sequential {
+    #[inline]
+    fn rank(position: usize, _frame_count: usize) -> usize {
+        position
+    }
+}
+
+/// Frames stored in bit-reversed order, so consecutive thresholds are as
+/// far apart in time as possible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitReversed;
+
+impl FrameOrder for BitReversed {
+    #[inline]
+    fn rank(position: usize, frame_count: usize) -> usize {
+        debug_assert!((frame_count + 1).is_power_of_two());
+        // reversing the bits permutes the non-zero BITS-bit values
+        let bits = (frame_count + 1).trailing_zeros();
+        if bits == 0 {
+            return position;
+        }
+        ((position + 1).reverse_bits() >> (usize::BITS - bits)) - 1
+    }
+}
+
+/// Frames grouped into duplicated bit-planes: plane `n` of the colour value
+/// is stored in `2^n` consecutive frames, plane 0 first.
+///
+/// Every frame is shown for the same time, so drivers that cannot vary the
+/// display time per frame still get true BCM weighting at a constant scan
+/// rate. The buffer holds the same `2^BITS - 1` frames as the other orders,
+/// which is `(2^BITS - 1) / BITS` times the memory of a bit-plane buffer
+/// such as [`crate::bitplane::latched::DmaFrameBuffer`];
+/// [`DmaFrameBuffer::frame_bytes`] and [`DmaFrameBuffer::bcm_chunk_bytes`]
+/// give the cost of one frame and of all of them,
+/// [`DmaFrameBuffer::plane_bytes`] locates the copies of one plane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WeightedPlanes;
+
+impl FrameOrder for WeightedPlanes {
+    #[inline]
+    fn lit(position: usize, _frame_count: usize, frames_on: usize) -> bool {
+        // positions 2^n - 1 .. 2^(n + 1) - 1 hold plane n
+        let plane = (position + 1).ilog2();
+        frames_on & (1 << plane) != 0
+    }
+}
+
+/// Rows stored frame-interleaved: scan row 0 of every frame, then scan
+/// row 1 of every frame, and so on, with the frames in the order of `FO`.
+///
+/// With few frames, whole-frame order lights a dim value's rows in one
+/// short burst per refresh; interleaving spreads the frames of every row
+/// over the refresh and reduces the perceived flicker. The buffer remains
+/// one contiguous stream of the same size, so it is streamed exactly like
+/// the other orders. [`DmaFrameBuffer::frame_bytes`] then covers `NROWS`
+/// consecutive rows of the stream rather than one frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Interleaved<FO: FrameOrder = Sequential>(PhantomData<FO>);
+
+impl<FO: FrameOrder> FrameOrder for Interleaved<FO> {
+    const INTERLEAVED: bool = true;
+
+    #[inline]
+    fn rank(position: usize, frame_count: usize) -> usize {
+        FO::rank(position, frame_count)
+    }
+
+    #[inline]
+    fn lit(position: usize, frame_count: usize, frames_on: usize) -> bool {
+        FO::lit(position, frame_count, frames_on)
+    }
+}
+
+/// Set the colour bits of panel row `y` in `row`, which holds scan row
+/// `y % NROWS`.
+#[inline]
+fn set_row_pixel<R: RowFormat, const NROWS: usize>(
+    row: &mut R,
+    y: usize,
+    x: usize,
+    red: bool,
+    green: bool,
+    blue: bool,
+) {
+    if y < NROWS {
+        row.set_color0(x, red, green, blue);
+    } else if y < 2 * NROWS {
+        row.set_color1(x, red, green, blue);
+    } else {
+        row.set_color(y / NROWS, x, red, green, blue);
+    }
+}
+
+/// Operations [`DmaFrameBuffer`] needs on a row.
+pub trait RowFormat: Copy + Default + core::fmt::Debug {
+    /// Write the address and control signals for scan row `addr`, with
+    /// `prev_addr` being the row shown while this one is shifted in.
+    fn format(&mut self, addr: u8, prev_addr: u8);
+
+    /// Clear all colour bits, keeping the control signals.
+    fn clear_colors(&mut self);
+
+    /// Set the upper-half colour bits of column `col`.
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool);
+
+    /// Set the lower-half colour bits of column `col`.
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool);
+
+    /// Red, green and blue bits of column `col` in half `half` of the
+    /// display, numbered as for [`Self::set_color`].
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool);
+
+    /// Set the colour bits of column `col` in half `half` of the display:
+    /// 0 and 1 are the upper and lower half of the first chain, formats that
+    /// drive several chains number the halves of the next chains on from 2.
+    ///
+    /// Only called with `half >= 2` if the framebuffer has more than
+    /// `2 * NROWS` rows.
+    #[inline]
+    fn set_color(&mut self, half: usize, col: usize, r: bool, g: bool, b: bool) {
+        if half == 0 {
+            self.set_color0(col, r, g, b);
+        } else {
+            self.set_color1(col, r, g, b);
+        }
+    }
+}
+
+/// Summary of a framebuffer configuration, from
+/// [`DmaFrameBuffer::report`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    /// Which format the rows use.
+    pub layout: Layout,
+    /// Width of the words clocked out.
+    pub word_size: WordSize,
+    /// Panel height in pixels.
+    pub rows: usize,
+    /// Panel width in pixels.
+    pub cols: usize,
+    /// Scan rows per frame.
+    pub nrows: usize,
+    /// Colour depth per channel.
+    pub bits: u8,
+    /// BCM frames in the buffer.
+    pub frame_count: usize,
+    /// Size of the framebuffer, including padding for its alignment.
+    pub memory_bytes: usize,
+    /// Length of the DMA stream.
+    pub dma_bytes: usize,
+    /// Bytes of one BCM frame.
+    pub frame_bytes: usize,
+    /// Bytes of one scan row, including its control words.
+    pub row_bytes: usize,
+    /// Word clock the estimates are for.
+    pub clk_hz: u32,
+    /// Estimated complete refreshes per second at `clk_hz`.
+    pub refresh_hz: u32,
+    /// Memory bandwidth the DMA engine needs at `clk_hz`.
+    pub bandwidth_bytes_per_sec: u64,
+    /// Largest DMA descriptor the descriptor count is for.
+    pub max_descriptor_len: usize,
+    /// DMA descriptors needed for the whole stream.
+    pub descriptor_count: usize,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let word_bits = match self.word_size {
+            WordSize::Eight => 8,
+            WordSize::Sixteen => 16,
+            WordSize::ThirtyTwo => 32,
+        };
+        writeln!(
+            f,
+            "{:?} {}x{} 1/{} scan, {} bits, {} frames, {word_bits}-bit words",
+            self.layout, self.cols, self.rows, self.nrows, self.bits, self.frame_count
+        )?;
+        writeln!(f, "  memory:      {} bytes", self.memory_bytes)?;
+        writeln!(f, "  DMA stream:  {} bytes", self.dma_bytes)?;
+        writeln!(f, "  frame:       {} bytes", self.frame_bytes)?;
+        writeln!(f, "  row:         {} bytes", self.row_bytes)?;
+        writeln!(
+            f,
+            "  refresh:     {} Hz at {} Hz clock",
+            self.refresh_hz, self.clk_hz
+        )?;
+        writeln!(f, "  bandwidth:   {} bytes/s", self.bandwidth_bytes_per_sec)?;
+        write!(
+            f,
+            "  descriptors: {} of at most {} bytes",
+            self.descriptor_count, self.max_descriptor_len
+        )
+    }
+}
+
+/// Drawing work counted by a [`DmaFrameBuffer`] with the `instrument`
+/// feature.
+///
+/// Pixels outside the panel, and black pixels skipped by the
+/// `skip-black-pixels` feature, touch no memory and are not counted.
+#[cfg(feature = "instrument")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DrawStats {
+    /// Pixels written, including pixels written more than once.
+    pub pixels: u32,
+    /// Bytes of the DMA stream written to.
+    pub bytes_touched: u32,
+    /// Bit `n` is set if scan row `n` was written to.
+    pub scan_rows: u32,
+}
+
+#[cfg(feature = "instrument")]
+impl DrawStats {
+    const fn new() -> Self {
+        Self {
+            pixels: 0,
+            bytes_touched: 0,
+            scan_rows: 0,
+        }
+    }
+
+    #[inline]
+    fn record<const NROWS: usize>(&mut self, y: usize, bytes: usize) {
+        self.pixels = self.pixels.saturating_add(1);
+        self.bytes_touched = self.bytes_touched.saturating_add(bytes as u32);
+        self.scan_rows |= 1 << (y % NROWS);
+    }
+
+    /// Number of scan rows written to, out of `NROWS`.
+    #[must_use]
+    pub const fn rows_visited(&self) -> u32 {
+        self.scan_rows.count_ones()
+    }
+}
+
+/// Position of one BCM frame in the DMA stream, from
+/// [`DmaFrameBuffer::frame_layout`].
+///
+/// Offsets are in bytes from the start of the buffer returned by
+/// `read_buffer()`. The frame's rows start at `offset` and follow each
+/// other every `row_stride` bytes; with a sequential frame order the
+/// stride is one row and the frame is the contiguous span `offset..offset +
+/// len`, with an interleaved one the rows of all frames alternate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameRegion {
+    /// Offset of the frame's first row.
+    pub offset: usize,
+    /// Bytes of the frame's rows, not counting rows of other frames in
+    /// between.
+    pub len: usize,
+    /// Distance between the starts of two consecutive rows of the frame.
+    pub row_stride: usize,
+}
+
+impl FrameRegion {
+    /// Offset of scan row `row` of the frame.
+    #[must_use]
+    pub const fn row_offset(&self, row: usize) -> usize {
+        self.offset + row * self.row_stride
+    }
+}
+
+/// One BCM frame: every scan row once.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub(crate) struct Frame<
+    F: EntryFormat,
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    O: Ordering = DefaultOrdering,
+> {
+    pub(crate) rows: [F::Row<COLS, O>; NROWS],
+}
+
+impl<F: EntryFormat, const ROWS: usize, const COLS: usize, const NROWS: usize, O: Ordering>
+    Frame<F, ROWS, COLS, NROWS, O>
+{
+    /// Scan rows are selected by the 5 address lines `A` to `E`.
+    const ADDR_OK: () = assert!(
+        NROWS >= 1 && NROWS <= 32,
+        "NROWS must be 1 to 32 to fit the address lines A-E"
+    );
+
+    pub fn new() -> Self {
+        let () = Self::ADDR_OK;
+        Self {
+            rows: [<F::Row<COLS, O>>::default(); NROWS],
+        }
+    }
+
+    pub fn format(&mut self) {
+        let () = Self::ADDR_OK;
+        for (addr, row) in self.rows.iter_mut().enumerate() {
+            let prev_addr = if addr == 0 {
+                NROWS as u8 - 1
+            } else {
+                addr as u8 - 1
+            };
+            row.format(addr as u8, prev_addr);
+        }
+    }
+
+    /// Fast clear method that preserves timing/control bits while clearing pixel data.
+    #[inline]
+    pub fn clear_colors(&mut self) {
+        for row in &mut self.rows {
+            row.clear_colors();
+        }
+    }
+
+    #[inline]
+    pub fn set_pixel(&mut self, y: usize, x: usize, red: bool, green: bool, blue: bool) {
+        set_row_pixel::<_, NROWS>(&mut self.rows[y % NROWS], y, x, red, green, blue);
+    }
+}
+
+impl<F: EntryFormat, const ROWS: usize, const COLS: usize, const NROWS: usize, O: Ordering> Default
+    for Frame<F, ROWS, COLS, NROWS, O>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The checks of [`DmaFrameBuffer::check_config`] for `rows` panel rows
+/// driven as `chains` chains of `nrows` scan rows, shared with the other
+/// framebuffers taking these parameters.
+pub(crate) const fn check_panel_config(
+    rows: usize,
+    nrows: usize,
+    bits: u8,
+    frame_count: usize,
+    chains: usize,
+) -> Result<(), Hub75Error> {
+    if bits == 0 || bits > 8 {
+        return Err(Hub75Error::InvalidBits(bits));
+    }
+    let expected = crate::compute_frame_count(bits);
+    if frame_count != expected {
+        return Err(Hub75Error::FrameCountMismatch {
+            expected,
+            actual: frame_count,
+        });
+    }
+    if nrows == 0 || nrows > 32 || rows == 0 || rows > 2 * nrows * chains {
+        return Err(Hub75Error::RowCountMismatch { rows, nrows });
+    }
+    Ok(())
+}
+
+/// DMA-compatible threshold-frame framebuffer for HUB75 LED panels.
+///
+/// This is a framebuffer implementation that:
+/// - Manages multiple frames for Binary Code Modulation (BCM)
+/// - Provides DMA-compatible memory layout
+/// - Implements the embedded-graphics `DrawTarget` trait
+///
+/// # Type Parameters
+/// - `F`: Row memory layout, see [`EntryFormat`]
+/// - `ROWS`: Total number of rows in the panel
+/// - `COLS`: Number of columns in the panel
+/// - `NROWS`: Number of rows per scan (typically half of ROWS)
+/// - `BITS`: Color depth (1-8 bits)
+/// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA byte [`Ordering`], defaults to [`DefaultOrdering`]
+/// - `A`: minimum buffer [`Alignment`], defaults to [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`], defaults to [`Normal`]
+///
+/// # Memory Layout
+/// The buffer is aligned to `A` (or the natural alignment of `F::Pad` if
+/// larger) and padded to a multiple of its alignment. It contains:
+/// - The format's padding field
+/// - An array of frames, each containing the full panel data
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct DmaFrameBuffer<
+    F: EntryFormat,
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const BITS: u8,
+    const FRAME_COUNT: usize,
+    O: Ordering = DefaultOrdering,
+    A: Alignment = DefaultAlignment,
+    OR: Orientation = Normal,
+> {
+    pub(crate) _alignment: [A; 0],
+    pub(crate) _orientation: PhantomData<OR>,
+    pub(crate) _align: F::Pad,
+    pub(crate) frames: [Frame<F, ROWS, COLS, NROWS, O>; FRAME_COUNT],
+    /// Trailing frames left out of the DMA stream, see
+    /// [`DmaFrameBuffer::set_max_brightness`].
+    pub(crate) dropped_frames: u8,
+    /// Colour bits below `BITS` in use, see
+    /// [`DmaFrameBuffer::set_active_bits`].
+    pub(crate) dropped_bits: u8,
+    /// OE window to restore while the output is blanked, see
+    /// `blank()` on the plain and latched framebuffers.
+    pub(crate) blanked_oe: Option<u16>,
+    #[cfg(feature = "instrument")]
+    pub(crate) stats: DrawStats,
+}
+
+impl<
+        F: EntryFormat<FrameOrder = WeightedPlanes>,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    /// Byte range of the DMA stream holding the `2^plane` copies of
+    /// bit-plane `plane`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `plane` is not below `BITS`.
+    #[must_use]
+    pub const fn plane_bytes(plane: u8) -> core::ops::Range<usize> {
+        assert!(plane < BITS, "plane out of range");
+        let frame = core::mem::size_of::<Frame<F, ROWS, COLS, NROWS, O>>();
+        ((1 << plane) - 1) * frame..((2 << plane) - 1) * frame
+    }
+
+    /// Bytes needed beyond a buffer holding each bit-plane once, the
+    /// memory cost of the constant-rate weighting.
+    #[must_use]
+    pub const fn repetition_overhead_bytes() -> usize {
+        let frame = core::mem::size_of::<Frame<F, ROWS, COLS, NROWS, O>>();
+        (FRAME_COUNT - BITS as usize) * frame
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > Default for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    const WORD_BYTES: usize = match F::WORD_SIZE {
+        WordSize::Eight => 1,
+        WordSize::Sixteen => 2,
+        WordSize::ThirtyTwo => 4,
+    };
+
+    /// Create a new, ready-to-use framebuffer.
+    ///
+    /// This creates a new framebuffer and automatically formats it with proper timing signals.
+    /// The framebuffer is immediately ready for pixel operations and DMA transfers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `BITS` is greater than 8, as only 1-8 bit color depths are supported.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    ///
+    /// const ROWS: usize = 32;
+    /// const COLS: usize = 64;
+    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
+    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
+    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    ///
+    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+    /// // No need to call format() - framebuffer is ready to use!
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        let mut instance = Self::new_unformatted();
+
+        // Pre-format the framebuffer so it's immediately ready for use
+        instance.format();
+        instance
+    }
+
+    /// Create a framebuffer with all words zero, without formatting it.
+    ///
+    /// The buffer is not ready for DMA until [`Self::format`] is called or
+    /// a complete stream, e.g. a restored snapshot, is copied into
+    /// [`Self::as_bytes_mut`]. Use it to move the formatting off the boot
+    /// path or to skip it when the content is replaced anyway; check with
+    /// [`Self::is_formatted`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new_unformatted();
+    /// assert!(!fb.is_formatted());
+    /// // later, before the first transfer
+    /// fb.format();
+    /// assert!(fb.is_formatted());
+    /// ```
+    #[must_use]
+    pub fn new_unformatted() -> Self {
+        debug_assert!(BITS <= 8);
+
+        Self {
+            _alignment: [],
+            _orientation: PhantomData,
+            _align: F::Pad::default(),
+            frames: [Frame::new(); FRAME_COUNT],
+            dropped_frames: 0,
+            dropped_bits: 0,
+            blanked_oe: None,
+            #[cfg(feature = "instrument")]
+            stats: DrawStats::new(),
+        }
+    }
+
+    /// Whether every row of the stream carries control signals, i.e. the
+    /// buffer was formatted or filled with a formatted stream since
+    /// [`Self::new_unformatted`].
+    ///
+    /// Every format sets address, latch or output-enable bits in each row,
+    /// so only rows left all zero count as unformatted. The check reads the
+    /// whole buffer.
+    #[must_use]
+    pub fn is_formatted(&self) -> bool {
+        self.frames
+            .iter()
+            .flat_map(|frame| &frame.rows)
+            .all(|row| Self::row_as_bytes(row).iter().any(|&byte| byte != 0))
+    }
+
+    /// Check the type parameters, for configurations chosen at runtime
+    /// among several framebuffer types.
+    ///
+    /// `BITS` must be in `1..=8`, `FRAME_COUNT` must equal
+    /// [`compute_frame_count(BITS)`](crate::compute_frame_count), `NROWS`
+    /// must be in `1..=32` and `ROWS` must fit the scan rows of the chains
+    /// the format drives.
+    ///
+    /// # Errors
+    /// Returns the first mismatching parameter.
+    pub const fn check_config() -> Result<(), Hub75Error> {
+        check_panel_config(ROWS, NROWS, BITS, FRAME_COUNT, F::CHAINS)
+    }
+
+    /// Create a new framebuffer like [`Self::new`], after checking the type
+    /// parameters with [`Self::check_config`].
+    ///
+    /// # Errors
+    /// Returns the first mismatching parameter.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Hub75Error;
+    ///
+    /// assert!(DmaFrameBuffer::<32, 64, 16, 3, 7>::try_new().is_ok());
+    /// assert_eq!(
+    ///     DmaFrameBuffer::<32, 64, 16, 3, 8>::try_new().unwrap_err(),
+    ///     Hub75Error::FrameCountMismatch { expected: 7, actual: 8 }
+    /// );
+    /// ```
+    pub fn try_new() -> Result<Self, Hub75Error> {
+        Self::check_config()?;
+        Ok(Self::new())
+    }
+
+    /// Initialize a framebuffer in place, without building it on the stack.
+    ///
+    /// This is the building block for placing a buffer in a `static`, for
+    /// example in a DMA-capable RAM section (see the `placement` module). The
+    /// returned buffer is formatted and ready to use, like one from
+    /// [`Self::new`].
+    #[allow(clippy::used_underscore_binding)]
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        let ptr = slot.as_mut_ptr();
+        // SAFETY: every field is written through a raw pointer into `slot`
+        // before it is assumed initialised:
+        // - `frames` and `_align` are zeroed, `EntryFormat` guarantees that
+        //   all-zero rows and padding are valid
+        // - `_alignment` and `_orientation` are zero-sized and need no write
+        // - the brightness, blanking and, with `instrument`, statistics
+        //   fields get the values of `new_unformatted`; zeroing them instead
+        //   would not make `blanked_oe` a valid `None`
+        let fb = unsafe {
+            (&raw mut (*ptr).frames).write_bytes(0, 1);
+            (&raw mut (*ptr)._align).write_bytes(0, 1);
+            (&raw mut (*ptr).dropped_frames).write(0);
+            (&raw mut (*ptr).dropped_bits).write(0);
+            (&raw mut (*ptr).blanked_oe).write(None);
+            #[cfg(feature = "instrument")]
+            (&raw mut (*ptr).stats).write(DrawStats::new());
+            slot.assume_init_mut()
+        };
+        fb.format();
+        fb
+    }
+
+    /// Returns the number of BCM chunks in this framebuffer (always 1 for
+    /// single-plane framebuffers — the entire buffer is one contiguous chunk).
+    #[must_use]
+    pub const fn bcm_chunk_count() -> usize {
+        1
+    }
+
+    /// Returns the byte size of one BCM chunk (for single-plane framebuffers
+    /// this equals the total DMA buffer size, since BCM weighting is baked in).
+    #[must_use]
+    pub const fn bcm_chunk_bytes() -> usize {
+        core::mem::size_of::<[Frame<F, ROWS, COLS, NROWS, O>; FRAME_COUNT]>()
+    }
+
+    /// Returns the byte size of one BCM frame, i.e. all rows shown once.
+    #[must_use]
+    pub const fn frame_bytes() -> usize {
+        core::mem::size_of::<Frame<F, ROWS, COLS, NROWS, O>>()
+    }
+
+    /// Returns the size of the DMA stream in bytes, i.e. the length covered
+    /// by `read_buffer()`.
+    #[must_use]
+    pub const fn dma_buffer_size_bytes() -> usize {
+        Self::bcm_chunk_bytes()
+    }
+
+    /// Returns the byte size of one scan row, including its control words.
+    ///
+    /// Every row starts with the same control sequence, so DMA transfers
+    /// that start on a row boundary can be chained in any granularity of
+    /// whole rows.
+    #[must_use]
+    pub const fn row_bytes() -> usize {
+        core::mem::size_of::<F::Row<COLS, O>>()
+    }
+
+    /// Returns where each BCM frame lies in the whole buffer, in frame order.
+    ///
+    /// Drivers that build descriptor chains or scatter-gather lists per
+    /// frame, or schedule frames with their own weights, use it instead of
+    /// computing offsets from the type sizes.
+    ///
+    /// This describes all `FRAME_COUNT` frames. A buffer limited by
+    /// [`Self::set_max_brightness`] or [`Self::set_active_bits`] streams
+    /// fewer; use [`Self::active_frame_layout`] for the frames that
+    /// `read_buffer()` actually covers.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    ///
+    /// type FBType = DmaFrameBuffer<32, 64, 16, 3, 7>;
+    ///
+    /// let mut offset = 0;
+    /// for frame in FBType::frame_layout() {
+    ///     assert_eq!(frame.offset, offset);
+    ///     assert_eq!(frame.len, FBType::frame_bytes());
+    ///     assert_eq!(frame.row_stride, FBType::row_bytes());
+    ///     offset += frame.len;
+    /// }
+    /// assert_eq!(offset, FBType::dma_buffer_size_bytes());
+    ///
+    /// let mut fb = FBType::new();
+    /// fb.set_max_brightness(127);
+    /// assert_eq!(fb.active_frame_layout().count(), fb.active_frames());
+    /// let last = fb.active_frame_layout().last().unwrap();
+    /// assert_eq!(last.offset + last.len, fb.active_len_bytes());
+    /// ```
+    pub fn frame_layout() -> impl Iterator<Item = FrameRegion> {
+        (0..FRAME_COUNT).map(|frame| {
+            let (offset, row_stride) = if F::FrameOrder::INTERLEAVED {
+                (frame * Self::row_bytes(), FRAME_COUNT * Self::row_bytes())
+            } else {
+                (frame * Self::frame_bytes(), Self::row_bytes())
+            };
+            FrameRegion {
+                offset,
+                len: Self::frame_bytes(),
+                row_stride,
+            }
+        })
+    }
+
+    /// Returns where each of the [`Self::active_frames`] lies in the DMA
+    /// stream returned by `read_buffer()`, in frame order.
+    ///
+    /// Like [`Self::frame_layout`], but leaves out the frames dropped by
+    /// [`Self::set_max_brightness`] or [`Self::set_active_bits`].
+    pub fn active_frame_layout(&self) -> impl Iterator<Item = FrameRegion> {
+        Self::frame_layout().take(self.active_frames())
+    }
+
+    /// Returns the number of whole rows that fit in one DMA descriptor of
+    /// at most `max_len` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is smaller than one row.
+    #[must_use]
+    pub const fn rows_per_descriptor(max_len: usize) -> usize {
+        assert!(
+            max_len >= Self::row_bytes(),
+            "max_len is smaller than a single row"
+        );
+        max_len / Self::row_bytes()
+    }
+
+    /// Returns the number of DMA descriptors of at most `max_len` bytes
+    /// needed for the whole stream, i.e. the number of spans yielded by
+    /// [`Self::chunks`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is smaller than one row.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    ///
+    /// type FBType = DmaFrameBuffer<32, 64, 16, 3, 7>;
+    ///
+    /// // e.g. the 4095-byte limit of an ESP32 GDMA descriptor
+    /// const DESCRIPTORS: usize = FBType::descriptor_count(4095);
+    /// assert_eq!(FBType::rows_per_descriptor(4095), 31);
+    /// assert_eq!(DESCRIPTORS, (16 * 7usize).div_ceil(31));
+    /// ```
+    #[must_use]
+    pub const fn descriptor_count(max_len: usize) -> usize {
+        let step = Self::rows_per_descriptor(max_len) * Self::row_bytes();
+        Self::dma_buffer_size_bytes().div_ceil(step)
+    }
+
+    /// Summary of this configuration: memory use, stream layout, refresh
+    /// rate at `clk_hz` and DMA descriptors of at most `max_descriptor_len`
+    /// bytes.
+    ///
+    /// Compare the reports of candidate `BITS` and panel sizes to pick a
+    /// trade-off; the [`Display`](core::fmt::Display) form is a readable
+    /// table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_descriptor_len` is smaller than one row.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    ///
+    /// let three = DmaFrameBuffer::<32, 64, 16, 3, 7>::report(20_000_000, 4095);
+    /// let five = DmaFrameBuffer::<32, 64, 16, 5, 31>::report(20_000_000, 4095);
+    /// assert!(five.dma_bytes > 4 * three.dma_bytes);
+    /// assert!(five.refresh_hz < three.refresh_hz / 4);
+    /// println!("{five}");
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub const fn report(clk_hz: u32, max_descriptor_len: usize) -> Report {
+        Report {
+            layout: F::LAYOUT,
+            word_size: F::WORD_SIZE,
+            rows: ROWS,
+            cols: COLS,
+            nrows: NROWS,
+            bits: BITS,
+            frame_count: FRAME_COUNT,
+            memory_bytes: core::mem::size_of::<Self>(),
+            dma_bytes: Self::dma_buffer_size_bytes(),
+            frame_bytes: Self::frame_bytes(),
+            row_bytes: Self::row_bytes(),
+            clk_hz,
+            refresh_hz: Self::estimated_refresh_hz(clk_hz),
+            bandwidth_bytes_per_sec: Self::dma_bandwidth_bytes_per_sec(clk_hz),
+            max_descriptor_len,
+            descriptor_count: Self::descriptor_count(max_descriptor_len),
+        }
+    }
+
+    /// Drawing work done since the buffer was created or the statistics
+    /// were last taken.
+    #[cfg(feature = "instrument")]
+    #[must_use]
+    pub const fn draw_stats(&self) -> DrawStats {
+        self.stats
+    }
+
+    /// Returns the drawing statistics and resets them, e.g. once per
+    /// rendered frame.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// Rectangle::new(Point::new(0, 0), Size::new(10, 4))
+    ///     .into_styled(PrimitiveStyle::with_fill(Color::RED))
+    ///     .draw(&mut fb)
+    ///     .unwrap();
+    ///
+    /// let stats = fb.take_draw_stats();
+    /// assert_eq!(stats.pixels, 40);
+    /// // one 16-bit word in each of the 7 frames per pixel
+    /// assert_eq!(stats.bytes_touched, 40 * 7 * 2);
+    /// assert_eq!(stats.rows_visited(), 4);
+    /// assert_eq!(fb.draw_stats().pixels, 0);
+    /// ```
+    #[cfg(feature = "instrument")]
+    pub fn take_draw_stats(&mut self) -> DrawStats {
+        core::mem::take(&mut self.stats)
+    }
+
+    /// Limit the DMA stream to the frames that can light up for colour
+    /// channels of at most `brightness`.
+    ///
+    /// At a low global brightness, e.g. set with
+    /// [`Hub75Display::set_brightness`](crate::display::Hub75Display::set_brightness),
+    /// the frames for the high thresholds stay dark. Leaving them out of
+    /// the stream raises the refresh rate and reduces flicker, at night for
+    /// example. `read_buffer()`, [`Self::chunks`], [`Self::active_bytes`]
+    /// and the [`FrameBuffer`] geometry then cover only the
+    /// [`Self::active_frames`]; [`Self::as_bytes`] still covers them all.
+    /// Restart DMA for the new length to take effect.
+    ///
+    /// Every pixel is then lit for a larger share of the shorter refresh
+    /// and looks brighter; reducing the output-enable duty to
+    /// `active_frames * 100 / FRAME_COUNT` percent keeps the brightness.
+    /// Colours brighter than `brightness` are clipped. Interleaved frame
+    /// orders cannot drop frames and keep streaming them all. Passing
+    /// `u8::MAX` streams every frame again.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::FrameBuffer;
+    ///
+    /// type FBType = DmaFrameBuffer<32, 64, 16, 3, 7>;
+    ///
+    /// let mut fb = FBType::new();
+    /// // channels of 96 light 3 of the 7 frames
+    /// fb.set_max_brightness(96);
+    /// assert_eq!(fb.active_frames(), 3);
+    /// assert_eq!(fb.buffer_len_bytes(), 3 * FBType::frame_bytes());
+    /// fb.set_oe_duty((fb.active_frames() * 100 / 7) as u8);
+    /// ```
+    pub fn set_max_brightness(&mut self, brightness: u8) {
+        if F::FrameOrder::INTERLEAVED {
+            return;
+        }
+        let frames_on = Self::frames_on(brightness) >> self.dropped_bits;
+        let mapped = self.mapped_frames();
+        let mut active = mapped;
+        // drop trailing frames no value up to `brightness` lights
+        while active > 1 && (0..=frames_on).all(|on| !Self::lit(active - 1, mapped, on)) {
+            active -= 1;
+        }
+        self.dropped_frames = (FRAME_COUNT - active) as u8;
+    }
+
+    /// Map colours to `bits` bits from now on, using only the first
+    /// `2^bits - 1` frames, and stream only those frames.
+    ///
+    /// Applications trade colour depth for refresh rate on the fly, e.g.
+    /// during fast animations, without a second framebuffer type. Content
+    /// already drawn keeps its old mapping, so erase and redraw after a
+    /// change. The stream shrinks like with [`Self::set_max_brightness`],
+    /// whose limit this resets; interleaved frame orders keep streaming
+    /// all frames. `set_active_bits(BITS)` restores the full depth.
+    ///
+    /// # Panics
+    /// Panics if `bits` is 0 or greater than `BITS`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::{Color, FrameBuffer};
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 4, 15>::new();
+    /// fb.set_active_bits(2);
+    /// assert_eq!((fb.active_bits(), fb.active_frames()), (2, 3));
+    /// fb.set_pixel(Point::new(1, 2), Color::new(200, 0, 0));
+    /// assert_eq!(fb.get_pixel(Point::new(1, 2)), Some(Color::new(192, 0, 0)));
+    /// ```
+    pub fn set_active_bits(&mut self, bits: u8) {
+        assert!(bits > 0 && bits <= BITS, "active bits must be in 1..=BITS");
+        self.dropped_bits = BITS - bits;
+        self.dropped_frames = if F::FrameOrder::INTERLEAVED {
+            0
+        } else {
+            (FRAME_COUNT - self.mapped_frames()) as u8
+        };
+    }
+
+    /// Limit the colour depth like [`Self::set_active_bits`], but report an
+    /// out-of-range depth instead of panicking.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::InvalidBits`] if `bits` is 0 or greater than
+    /// `BITS`.
+    pub fn try_set_active_bits(&mut self, bits: u8) -> Result<(), Hub75Error> {
+        if bits == 0 || bits > BITS {
+            return Err(Hub75Error::InvalidBits(bits));
+        }
+        self.set_active_bits(bits);
+        Ok(())
+    }
+
+    /// Colour depth in use, `BITS` unless reduced with
+    /// [`Self::set_active_bits`].
+    #[must_use]
+    pub const fn active_bits(&self) -> u8 {
+        BITS - self.dropped_bits
+    }
+
+    /// Number of frames colours are mapped to.
+    #[inline]
+    const fn mapped_frames(&self) -> usize {
+        if self.dropped_bits == 0 {
+            FRAME_COUNT
+        } else {
+            (1 << self.active_bits()) - 1
+        }
+    }
+
+    /// Whether the frame stored at `position` lights a channel that needs
+    /// `frames_on` of the first `mapped` frames.
+    #[inline]
+    fn lit(position: usize, mapped: usize, frames_on: usize) -> bool {
+        position < mapped && F::FrameOrder::lit(position, mapped, frames_on)
+    }
+
+    /// Number of frames in the DMA stream, `FRAME_COUNT` unless limited by
+    /// [`Self::set_max_brightness`].
+    #[must_use]
+    pub const fn active_frames(&self) -> usize {
+        FRAME_COUNT - self.dropped_frames as usize
+    }
+
+    /// Length of the DMA stream returned by `read_buffer()`.
+    #[must_use]
+    pub const fn active_len_bytes(&self) -> usize {
+        self.active_frames() * Self::frame_bytes()
+    }
+
+    /// Returns the bytes of the DMA stream returned by `read_buffer()`, the
+    /// start of [`Self::as_bytes`] holding the [`Self::active_frames`].
+    #[must_use]
+    pub fn active_bytes(&self) -> &[u8] {
+        &self.as_bytes()[..self.active_len_bytes()]
+    }
+
+    /// Returns the DMA stream as bytes.
+    ///
+    /// The slice covers all frames, which is the memory returned by
+    /// `read_buffer()` unless frames are dropped with
+    /// [`Self::set_max_brightness`]. Byte order follows the ordering
+    /// parameter `O`.
+    ///
+    /// # Aliasing
+    ///
+    /// The slice borrows the framebuffer, so safe code cannot draw while it
+    /// is alive. A DMA transfer started from `read_buffer()` is not tracked
+    /// by the borrow: reading the bytes during a transfer is fine, but the
+    /// buffer must not be written, through [`Self::as_bytes_mut`] or
+    /// drawing, until the transfer is finished or the buffer swapped out.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = core::mem::size_of_val(&self.frames);
+        // SAFETY: `EntryFormat` guarantees that rows contain no padding, and
+        // the frames are `repr(C)` arrays of rows.
+        unsafe { core::slice::from_raw_parts((&raw const self.frames).cast::<u8>(), len) }
+    }
+
+    /// Returns the DMA stream as mutable bytes.
+    ///
+    /// Any byte pattern is a valid buffer content, but overwriting control
+    /// bits or address words will corrupt the output until [`Self::format`]
+    /// is called. As with drawing, the buffer must not be written while DMA
+    /// reads it, see [`Self::as_bytes`].
+    #[must_use]
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let len = core::mem::size_of_val(&self.frames);
+        // SAFETY: `EntryFormat` guarantees that rows contain no padding and
+        // that every bit pattern is a valid row.
+        unsafe { core::slice::from_raw_parts_mut((&raw mut self.frames).cast::<u8>(), len) }
+    }
+
+    /// Hash of the colour bits of every frame, ignoring address, latch and
+    /// output-enable bits.
+    ///
+    /// Buffers with the same type parameters hash equal when their colour
+    /// bits are equal, whatever their control bits, e.g. after `set_oe_duty`
+    /// or [`Self::format_with_address_map`]. Use it to skip restarting DMA
+    /// when nothing changed, or to compare a buffer with a stored golden
+    /// hash in tests. The value is a 32-bit FNV-1a over the stream bytes,
+    /// so it differs between orderings and formats.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// let blank = fb.content_hash();
+    /// fb.set_pixel(Point::new(1, 2), Color::RED);
+    /// assert_ne!(fb.content_hash(), blank);
+    /// fb.erase();
+    /// assert_eq!(fb.content_hash(), blank);
+    /// ```
+    #[must_use]
+    pub fn content_hash(&self) -> u32 {
+        const FNV_OFFSET: u32 = 0x811c_9dc5;
+        const FNV_PRIME: u32 = 0x0100_0193;
+
+        let mut hash = FNV_OFFSET;
+        for row in self.frames.iter().flat_map(|frame| &frame.rows) {
+            let mut control = *row;
+            control.clear_colors();
+            for (byte, control) in Self::row_as_bytes(row)
+                .iter()
+                .zip(Self::row_as_bytes(&control))
+            {
+                hash = (hash ^ u32::from(byte ^ control)).wrapping_mul(FNV_PRIME);
+            }
+        }
+        hash
+    }
+
+    /// Copy the colour bits of every pixel from `other`, keeping the
+    /// control bits of `self`.
+    ///
+    /// This is a masked copy of the whole stream, much cheaper than
+    /// redrawing, e.g. to bring a back buffer up to date with the front
+    /// buffer before drawing the next changes, or to start a transition
+    /// from the current picture. Control bits set up with
+    /// [`Self::format_with_address_map`] or an output-enable duty stay as
+    /// they are in `self`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut front = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// front.set_pixel(Point::new(1, 2), Color::RED);
+    ///
+    /// let mut back = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// back.copy_colors_from(&front);
+    /// assert_eq!(back.get_pixel(Point::new(1, 2)), front.get_pixel(Point::new(1, 2)));
+    /// ```
+    pub fn copy_colors_from(&mut self, other: &Self) {
+        let keep = Self::control_mask();
+        let keep = Self::row_as_bytes(&keep);
+        for (dst, src) in self
+            .as_bytes_mut()
+            .chunks_exact_mut(keep.len())
+            .zip(other.as_bytes().chunks_exact(keep.len()))
+        {
+            for ((dst, src), keep) in dst.iter_mut().zip(src).zip(keep) {
+                *dst = (*dst & keep) | (src & !keep);
+            }
+        }
+    }
+
+    /// Whether every pixel has the same colour bits in `self` and `other`,
+    /// ignoring address, latch and output-enable bits.
+    ///
+    /// Unlike comparing [`Self::content_hash`] values, this cannot report a
+    /// false match and stops at the first difference. Use it to skip
+    /// restarting DMA when a render changed nothing.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut shown = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// let mut next = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// next.set_oe_duty(50);
+    /// assert!(next.colors_eq(&shown));
+    /// next.set_pixel(Point::new(1, 2), Color::RED);
+    /// assert!(!next.colors_eq(&shown));
+    /// ```
+    #[must_use]
+    pub fn colors_eq(&self, other: &Self) -> bool {
+        let keep = Self::control_mask();
+        self.frames
+            .iter()
+            .flat_map(|frame| &frame.rows)
+            .zip(other.frames.iter().flat_map(|frame| &frame.rows))
+            .all(|(a, b)| Self::row_colors_eq(a, b, &keep))
+    }
+
+    /// Whether BCM frame `frame` has the same colour bits in `self` and
+    /// `other`, see [`Self::colors_eq`].
+    ///
+    /// # Panics
+    /// Panics if `frame` is not less than `FRAME_COUNT`.
+    #[must_use]
+    pub fn frame_colors_eq(&self, other: &Self, frame: usize) -> bool {
+        assert!(frame < FRAME_COUNT, "frame {frame} out of range");
+        let keep = Self::control_mask();
+        (0..NROWS).all(|row| {
+            let slot = Self::frame_slot(frame, row);
+            Self::row_colors_eq(self.slot(slot), other.slot(slot), &keep)
+        })
+    }
+
+    /// Whether rows `a` and `b` differ only in the bits set in `keep`.
+    fn row_colors_eq(a: &F::Row<COLS, O>, b: &F::Row<COLS, O>, keep: &F::Row<COLS, O>) -> bool {
+        Self::row_as_bytes(a)
+            .iter()
+            .zip(Self::row_as_bytes(b))
+            .zip(Self::row_as_bytes(keep))
+            .all(|((a, b), keep)| (a ^ b) & !keep == 0)
+    }
+
+    /// A row with every bit set except the colour bits.
+    fn control_mask() -> F::Row<COLS, O> {
+        let mut row = F::Row::<COLS, O>::default();
+        let len = core::mem::size_of_val(&row);
+        // SAFETY: `EntryFormat` guarantees that rows contain no padding and
+        // that every bit pattern is a valid row.
+        unsafe {
+            core::ptr::from_mut(&mut row)
+                .cast::<u8>()
+                .write_bytes(0xff, len);
+        }
+        row.clear_colors();
+        row
+    }
+
+    /// The bytes of `row` as clocked out by DMA.
+    fn row_as_bytes(row: &F::Row<COLS, O>) -> &[u8] {
+        let len = core::mem::size_of_val(row);
+        // SAFETY: `EntryFormat` guarantees that rows contain no padding.
+        unsafe { core::slice::from_raw_parts(core::ptr::from_ref(row).cast::<u8>(), len) }
+    }
+
+    /// Estimated complete refreshes per second when the DMA stream is
+    /// clocked out at `clk_hz`, rounded down.
+    ///
+    /// See [`crate::timing::BcmTiming`] for the underlying model.
+    #[must_use]
+    pub const fn estimated_refresh_hz(clk_hz: u32) -> u32 {
+        let row_clocks = core::mem::size_of::<F::Row<COLS, O>>() / Self::WORD_BYTES;
+        crate::timing::BcmTiming::new(clk_hz, row_clocks, NROWS, BITS, 0).refresh_hz()
+    }
+
+    /// Memory bandwidth the DMA engine must sustain to clock out one word
+    /// per cycle at `clk_hz`.
+    #[must_use]
+    pub const fn dma_bandwidth_bytes_per_sec(clk_hz: u32) -> u64 {
+        clk_hz as u64 * Self::WORD_BYTES as u64
+    }
+
+    /// Splits the DMA stream into spans of at most `max_len` bytes for DMA
+    /// engines that limit the size of a single transfer.
+    ///
+    /// Spans end on row boundaries, so every span starts with a complete
+    /// row including its control words. Transfer them in order to output the
+    /// whole buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is smaller than one row.
+    pub fn chunks(&self, max_len: usize) -> impl Iterator<Item = (*const u8, usize)> + '_ {
+        let bytes = self.active_bytes();
+        crate::row_chunks(bytes.as_ptr(), bytes.len(), Self::row_bytes(), max_len)
+    }
+
+    /// Write the DMA stream, prefixed with a container header, to `sink`.
+    ///
+    /// All frames are written, including frames dropped with
+    /// [`Self::set_max_brightness`]. See [`crate::export`] for the format
+    /// and a matching parser.
+    pub fn export(&self, sink: impl FnMut(&[u8])) {
+        let header = crate::export::Header {
+            layout: F::LAYOUT,
+            word_size: F::WORD_SIZE,
+            rows: ROWS as u16,
+            cols: COLS as u16,
+            nrows: NROWS as u16,
+            bits: BITS,
+            esp32_ordering: crate::ordering::is_swapped::<O>(),
+            plane_count: 1,
+            plane_bytes: Self::bcm_chunk_bytes() as u32,
+        };
+        crate::export::export_bytes(self.as_bytes(), &header, sink);
+    }
+
+    /// Perform full formatting of the framebuffer with timing and control signals.
+    ///
+    /// This sets up all the timing and control signals needed for proper HUB75 operation.
+    /// This is automatically called by `new()`, so you typically don't need to call this
+    /// unless you want to completely reinitialize the framebuffer.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    ///
+    /// const ROWS: usize = 32;
+    /// const COLS: usize = 64;
+    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
+    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
+    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    ///
+    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+    /// framebuffer.format(); // Reinitialize if needed
+    /// ```
+    #[inline]
+    pub fn format(&mut self) {
+        self.blanked_oe = None;
+        if !F::FrameOrder::INTERLEAVED {
+            for frame in &mut self.frames {
+                frame.format();
+            }
+            return;
+        }
+        self.format_with_address_map(&core::array::from_fn(|row| row as u8));
+    }
+
+    /// Format the framebuffer like [`Self::format`], but drive address
+    /// `map[row]` on the address lines for scan row `row`.
+    ///
+    /// Panels whose row decoder is wired unusually, e.g. with swapped halves
+    /// or rows offset by a few lines, are driven by mapping the logical rows
+    /// to the addresses they answer to. Drawing still uses logical rows.
+    /// [`Self::erase`] and drawing keep the mapping; [`Self::format`]
+    /// restores the identity mapping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an address does not fit the 5 address lines, i.e. is 32
+    /// or more.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// // the panel's decoder has the two halves of the scan rows swapped
+    /// let map: [u8; 16] = core::array::from_fn(|row| (row as u8 + 8) % 16);
+    /// fb.format_with_address_map(&map);
+    /// ```
+    pub fn format_with_address_map(&mut self, map: &[u8; NROWS]) {
+        let () = Frame::<F, ROWS, COLS, NROWS, O>::ADDR_OK;
+        assert!(map.iter().all(|&addr| addr < 32), "address out of range");
+        self.blanked_oe = None;
+        // every row is shifted in while the one before it in the stream
+        // is shown
+        let slots = FRAME_COUNT * NROWS;
+        for slot in 0..slots {
+            let addr = map[Self::slot_scan_row(slot)];
+            let prev_addr = map[Self::slot_scan_row((slot + slots - 1) % slots)];
+            self.slot_mut(slot).format(addr, prev_addr);
+        }
+    }
+
+    /// Format the framebuffer like [`Self::format_with_address_map`], but
+    /// report an address that does not fit the address lines instead of
+    /// panicking. The buffer is unchanged on error.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::InvalidAddress`] with the first address of 32 or
+    /// more.
+    pub fn try_format_with_address_map(&mut self, map: &[u8; NROWS]) -> Result<(), Hub75Error> {
+        if let Some(&addr) = map.iter().find(|&&addr| addr >= 32) {
+            return Err(Hub75Error::InvalidAddress(addr));
+        }
+        self.format_with_address_map(map);
+        Ok(())
+    }
+
+    /// Scan row held by row `slot` of the stream, counting the rows of all
+    /// frames.
+    #[inline]
+    const fn slot_scan_row(slot: usize) -> usize {
+        if F::FrameOrder::INTERLEAVED {
+            slot / FRAME_COUNT
+        } else {
+            slot % NROWS
+        }
+    }
+
+    /// BCM frame of row `slot` of the stream, counting the rows of all
+    /// frames.
+    #[inline]
+    const fn slot_frame(slot: usize) -> usize {
+        if F::FrameOrder::INTERLEAVED {
+            slot % FRAME_COUNT
+        } else {
+            slot / NROWS
+        }
+    }
+
+    /// Split `words`, the whole DMA stream, into rows tagged with their
+    /// frame and scan row, see [`crate::RowIterExt`].
+    pub(crate) fn split_rows<W>(words: &[W]) -> impl Iterator<Item = (usize, usize, &[W])> {
+        let row_len = Self::row_bytes() / core::mem::size_of::<W>();
+        words
+            .chunks_exact(row_len)
+            .enumerate()
+            .map(|(slot, row)| (Self::slot_frame(slot), Self::slot_scan_row(slot), row))
+    }
+
+    /// `words`, the whole DMA stream, in the order they are clocked out,
+    /// see [`crate::RowIterExt::iter_words`].
+    pub(crate) fn output_words<W: Copy>(words: &[W]) -> impl Iterator<Item = W> + '_ {
+        (0..words.len()).map(move |i| match core::mem::size_of::<W>() {
+            1 => words[crate::ordering::map_byte_index::<O>(i)],
+            2 => words[crate::ordering::map_half_word_index::<O>(i)],
+            _ => words[i],
+        })
+    }
+
+    /// Row of the stream holding scan row `scan_row` of BCM frame `frame`.
+    #[inline]
+    const fn frame_slot(frame: usize, scan_row: usize) -> usize {
+        if F::FrameOrder::INTERLEAVED {
+            scan_row * FRAME_COUNT + frame
+        } else {
+            frame * NROWS + scan_row
+        }
+    }
+
+    /// Row `slot` of the stream, counting the rows of all frames.
+    #[inline]
+    fn slot(&self, slot: usize) -> &F::Row<COLS, O> {
+        &self.frames[slot / NROWS].rows[slot % NROWS]
+    }
+
+    /// Row `slot` of the stream, counting the rows of all frames.
+    #[inline]
+    fn slot_mut(&mut self, slot: usize) -> &mut F::Row<COLS, O> {
+        &mut self.frames[slot / NROWS].rows[slot % NROWS]
+    }
+
+    /// Fast erase operation that clears all pixel data while preserving timing signals.
+    ///
+    /// This is much faster than `format()` when you just want to clear the display
+    /// since it preserves all the timing and control signals that are already set up.
+    /// Use this for clearing between frames or when you want to start drawing fresh content.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    ///
+    /// const ROWS: usize = 32;
+    /// const COLS: usize = 64;
+    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
+    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
+    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    ///
+    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+    /// framebuffer.erase();
+    /// ```
+    #[inline]
+    pub fn erase(&mut self) {
+        for frame in &mut self.frames {
+            frame.clear_colors();
+        }
+    }
+
+    /// Set a pixel in the framebuffer.
+    /// # Example
+    /// ```rust,no_run
+    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    /// use embedded_graphics::prelude::*;
+    ///
+    /// const ROWS: usize = 32;
+    /// const COLS: usize = 64;
+    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
+    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
+    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    ///
+    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+    /// framebuffer.set_pixel(Point::new(10, 10), Color::RED);
+    /// ```
+    pub fn set_pixel(&mut self, p: Point, color: Color) {
+        if p.x < 0 || p.y < 0 {
+            return;
+        }
+        self.set_pixel_internal(p.x as usize, p.y as usize, color);
+    }
+
+    /// Set a pixel like [`Self::set_pixel`], but report a pixel outside the
+    /// framebuffer instead of ignoring it.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::OutOfBounds`] if `p` is outside the framebuffer.
+    pub fn try_set_pixel(&mut self, p: Point, color: Color) -> Result<(), Hub75Error> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= COLS || p.y as usize >= ROWS {
+            return Err(Hub75Error::OutOfBounds { x: p.x, y: p.y });
+        }
+        self.set_pixel_internal(p.x as usize, p.y as usize, color);
+        Ok(())
+    }
+
+    /// Colour of the pixel at `p` as stored in the buffer, or `None` if
+    /// `p` is outside the framebuffer.
+    ///
+    /// Colours are read back from the lit frames, so they come out rounded
+    /// down to the colour depth: the low `8 - BITS` bits of every channel
+    /// are zero.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// fb.set_pixel(Point::new(5, 20), Color::new(0, 128, 255));
+    /// assert_eq!(fb.get_pixel(Point::new(5, 20)), Some(Color::new(0, 128, 224)));
+    /// assert_eq!(fb.get_pixel(Point::new(64, 0)), None);
+    /// ```
+    #[must_use]
+    pub fn get_pixel(&self, p: Point) -> Option<Color> {
+        if p.x < 0 || p.y < 0 || p.x as usize >= COLS || p.y as usize >= ROWS {
+            return None;
+        }
+        Some(self.color_at(p.x as usize, p.y as usize))
+    }
+
+    /// Every pixel of the framebuffer with its colour as returned by
+    /// [`Self::get_pixel`], row by row.
+    ///
+    /// Pass it to
+    /// [`DrawTarget::draw_iter`](embedded_graphics::draw_target::DrawTarget::draw_iter)
+    /// to copy the content into another display, e.g. a panel-sized buffer
+    /// into a region of a tiled wall.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// let mut small = DmaFrameBuffer::<32, 32, 16, 3, 7>::new();
+    /// small.set_pixel(Point::new(1, 2), Color::RED);
+    ///
+    /// let mut wall = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// wall.translated(Point::new(32, 0))
+    ///     .draw_iter(small.pixels())
+    ///     .unwrap();
+    /// assert_eq!(wall.get_pixel(Point::new(33, 2)), small.get_pixel(Point::new(1, 2)));
+    /// ```
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel<Color>> + '_ {
+        (0..).zip(0..ROWS).flat_map(move |(py, y)| {
+            (0..)
+                .zip(0..COLS)
+                .map(move |(px, x)| Pixel(Point::new(px, py), self.color_at(x, y)))
+        })
+    }
+
+    /// Bytes [`Self::save_logical`] writes: three per pixel.
+    #[must_use]
+    pub const fn logical_size_bytes() -> usize {
+        ROWS * COLS * 3
+    }
+
+    /// Write the image as RGB888, row by row, to the start of `buf`, and
+    /// return the number of bytes written.
+    ///
+    /// The image takes [`Self::logical_size_bytes`] bytes, a fraction of the
+    /// `FRAME_COUNT` frames of the DMA stream, so it is the form to keep
+    /// across deep sleep or in flash. Colours are those of
+    /// [`Self::get_pixel`], already rounded to the colour depth, so the low
+    /// `8 - BITS` bits of every byte are zero and compress well.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::BufferTooSmall`] if `buf` is shorter than the
+    /// image.
+    ///
+    /// # Example
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::Color;
+    ///
+    /// type FBType = DmaFrameBuffer<32, 64, 16, 3, 7>;
+    ///
+    /// let mut fb = FBType::new();
+    /// fb.set_pixel(Point::new(5, 20), Color::new(0, 128, 224));
+    /// let mut image = [0u8; FBType::logical_size_bytes()];
+    /// fb.save_logical(&mut image).unwrap();
+    ///
+    /// // after waking up
+    /// let mut fb = FBType::new();
+    /// fb.restore_logical(&image).unwrap();
+    /// assert_eq!(fb.get_pixel(Point::new(5, 20)), Some(Color::new(0, 128, 224)));
+    /// ```
+    pub fn save_logical(&self, buf: &mut [u8]) -> Result<usize, Hub75Error> {
+        let needed = Self::logical_size_bytes();
+        let Some(buf) = buf.get_mut(..needed) else {
+            return Err(Hub75Error::BufferTooSmall {
+                needed,
+                actual: buf.len(),
+            });
+        };
+        for (rgb, Pixel(_, color)) in buf.chunks_exact_mut(3).zip(self.pixels()) {
+            rgb.copy_from_slice(&[color.r(), color.g(), color.b()]);
+        }
+        Ok(needed)
+    }
+
+    /// Replace the image with one written by [`Self::save_logical`], and
+    /// return the number of bytes read.
+    ///
+    /// Control bits are kept, so the buffer must be formatted.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::BufferTooSmall`] if `buf` is shorter than the
+    /// image. The buffer is unchanged on error.
+    pub fn restore_logical(&mut self, buf: &[u8]) -> Result<usize, Hub75Error> {
+        let needed = Self::logical_size_bytes();
+        let Some(buf) = buf.get(..needed) else {
+            return Err(Hub75Error::BufferTooSmall {
+                needed,
+                actual: buf.len(),
+            });
+        };
+        // black pixels may be skipped when drawing, so start from black
+        self.erase();
+        for (i, rgb) in buf.chunks_exact(3).enumerate() {
+            self.set_pixel_internal(i % COLS, i / COLS, Color::new(rgb[0], rgb[1], rgb[2]));
+        }
+        Ok(needed)
+    }
+
+    /// Colour of drawing point `(x, y)`, both in range.
+    fn color_at(&self, x: usize, y: usize) -> Color {
+        let (x, y) = OR::apply::<COLS, ROWS>(x, y);
+        let half = y / NROWS;
+        let mut counts = [0u8; 3];
+        for frame in 0..FRAME_COUNT {
+            let lit = self.slot(Self::frame_slot(frame, y % NROWS)).color(half, x);
+            counts[0] += u8::from(lit.0);
+            counts[1] += u8::from(lit.1);
+            counts[2] += u8::from(lit.2);
+        }
+        let shift = 8 - self.active_bits();
+        let level = |count: u8| count << shift;
+        Color::new(level(counts[0]), level(counts[1]), level(counts[2]))
+    }
+
+    #[inline]
+    fn frames_on(v: u8) -> usize {
+        // v / brightness_step but the compiler resolves the shift at build-time
+        (v as usize) >> (8 - BITS)
+    }
+
+    #[inline]
+    pub(crate) fn set_pixel_internal(&mut self, x: usize, y: usize, color: Color) {
+        if x >= COLS || y >= ROWS {
+            return;
+        }
+        let (x, y) = OR::apply::<COLS, ROWS>(x, y);
+
+        // Early exit for black pixels - common in UI backgrounds
+        // Only enabled when skip-black-pixels feature is active
+        #[cfg(feature = "skip-black-pixels")]
+        if color == Color::BLACK {
+            return;
+        }
+
+        #[cfg(feature = "instrument")]
+        self.stats
+            .record::<NROWS>(y, FRAME_COUNT * Self::WORD_BYTES);
+
+        // Pre-compute how many frames each channel should be on
+        let shift = self.dropped_bits;
+        let red_frames = Self::frames_on(color.r()) >> shift;
+        let green_frames = Self::frames_on(color.g()) >> shift;
+        let blue_frames = Self::frames_on(color.b()) >> shift;
+        let mapped = self.mapped_frames();
+
+        if F::FrameOrder::INTERLEAVED {
+            // the frames of scan row `y % NROWS` are consecutive slots
+            let first = y % NROWS * FRAME_COUNT;
+            for frame_idx in 0..FRAME_COUNT {
+                set_row_pixel::<_, NROWS>(
+                    self.slot_mut(first + frame_idx),
+                    y,
+                    x,
+                    Self::lit(frame_idx, mapped, red_frames),
+                    Self::lit(frame_idx, mapped, green_frames),
+                    Self::lit(frame_idx, mapped, blue_frames),
+                );
+            }
+            return;
+        }
+
+        // Set the pixel in all frames based on pre-computed frame counts
+        for (frame_idx, frame) in self.frames.iter_mut().enumerate() {
+            frame.set_pixel(
+                y,
+                x,
+                Self::lit(frame_idx, mapped, red_frames),
+                Self::lit(frame_idx, mapped, green_frames),
+                Self::lit(frame_idx, mapped, blue_frames),
+            );
+        }
+    }
+
+    fn plane(&self, plane_idx: usize) -> (*const u8, usize) {
+        assert!(plane_idx == 0, "DmaFrameBuffer has only 1 plane");
+        let ptr = (&raw const self.frames).cast::<u8>();
+        (ptr, self.active_len_bytes())
+    }
+
+    fn plane_mut(&mut self) -> (*mut u8, usize) {
+        let ptr = (&raw mut self.frames).cast::<u8>();
+        let len = core::mem::size_of_val(&self.frames);
+        (ptr, len)
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > FrameBufferOperations for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    #[inline]
+    fn erase(&mut self) {
+        DmaFrameBuffer::<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>::erase(self);
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        DmaFrameBuffer::<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>::set_pixel(
+            self, p, color,
+        );
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > FrameBufferOperations
+    for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    #[inline]
+    fn erase(&mut self) {
+        DmaFrameBuffer::<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>::erase(self);
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        DmaFrameBuffer::<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>::set_pixel(
+            self, p, color,
+        );
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > embedded_graphics::prelude::OriginDimensions
+    for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn size(&self) -> embedded_graphics::prelude::Size {
+        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > embedded_graphics::prelude::OriginDimensions
+    for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn size(&self) -> embedded_graphics::prelude::Size {
+        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > embedded_graphics::draw_target::DrawTarget
+    for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Color = Color;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.set_pixel_internal(pixel.0.x as usize, pixel.0.y as usize, pixel.1);
+        }
+        Ok(())
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > embedded_graphics::draw_target::DrawTarget
+    for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Color = Color;
+
+    type Error = Infallible;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+    {
+        (**self).draw_iter(pixels)
+    }
+}
+
+unsafe impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > ReadBuffer for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        self.plane(0)
+    }
+}
+
+unsafe impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > ReadBuffer for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        self.plane(0)
+    }
+}
+
+/// The frame memory as the destination of a DMA transfer, e.g. pre-rendered
+/// frames streamed from an SD card or received over SPI.
+///
+/// The transfer overwrites the control bits and address words along with the
+/// colours, so the source must contain a complete DMA stream of the same
+/// type (as produced by `as_bytes()` or `export()`).
+unsafe impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > WriteBuffer for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Word = u8;
+
+    unsafe fn write_buffer(&mut self) -> (*mut u8, usize) {
+        self.plane_mut()
+    }
+}
+
+unsafe impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > WriteBuffer for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Word = u8;
+
+    unsafe fn write_buffer(&mut self) -> (*mut u8, usize) {
+        self.plane_mut()
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > core::fmt::Debug for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let brightness_step = 1 << (8 - BITS);
+        f.debug_struct("DmaFrameBuffer")
+            .field("size", &core::mem::size_of_val(&self.frames))
+            .field("frame_count", &self.frames.len())
+            .field("frame_size", &core::mem::size_of_val(&self.frames[0]))
+            .field("alignment", &A::BYTES)
+            .field("brightness_step", &&brightness_step)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > defmt::Format for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn format(&self, f: defmt::Formatter) {
+        let brightness_step = 1 << (8 - BITS);
+        defmt::write!(
+            f,
+            "DmaFrameBuffer<{}, {}, {}, {}, {}>",
+            ROWS,
+            COLS,
+            NROWS,
+            BITS,
+            FRAME_COUNT
+        );
+        defmt::write!(f, " size: {}", core::mem::size_of_val(&self.frames));
+        defmt::write!(
+            f,
+            " frame_size: {}",
+            core::mem::size_of_val(&self.frames[0])
+        );
+        defmt::write!(f, " brightness_step: {}", brightness_step);
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > FrameBuffer for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn get_word_size(&self) -> WordSize {
+        F::WORD_SIZE
+    }
+
+    fn plane_count(&self) -> usize {
+        1
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.plane(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        ROWS
+    }
+
+    fn cols(&self) -> usize {
+        COLS
+    }
+
+    fn bits(&self) -> u8 {
+        self.active_bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.active_frames()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.active_len_bytes()
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > FrameBuffer for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    fn get_word_size(&self) -> WordSize {
+        F::WORD_SIZE
+    }
+
+    fn plane_count(&self) -> usize {
+        1
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.plane(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        ROWS
+    }
+
+    fn cols(&self) -> usize {
+        COLS
+    }
+
+    fn bits(&self) -> u8 {
+        self.active_bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.active_frames()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.active_len_bytes()
+    }
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > MutableFrameBuffer for DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+}
+
+impl<
+        F: EntryFormat,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > MutableFrameBuffer
+    for &mut DmaFrameBuffer<F, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+}
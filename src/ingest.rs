@@ -0,0 +1,583 @@
+//! Pixel data received over the network.
+//!
+//! LED walls fed by Art-Net, E1.31 or custom UDP protocols receive packets
+//! that say "put these RGB888 bytes into this rectangle". Once the network
+//! stack has taken the protocol framing off, a [`Region`] describes such a
+//! payload and [`Region::apply`] copies it into any `DrawTarget` for
+//! [`Color`], checking the rectangle and the payload length first so a bad
+//! packet never draws half an image.
+//!
+//! [`DdpPacket`] does the same for the Distributed Display Protocol used by
+//! WLED, xLights and similar senders, which address pixels by their offset
+//! in the whole display rather than by rectangle.
+//!
+//! Payloads with more colour depth than the framebuffer can be [`Dither`]ed
+//! to avoid banding in gradients.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::ingest::{Dither, Region};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//!
+//! // a 2x1 region at (10, 4): one red and one blue pixel
+//! let payload = [255, 0, 0, 0, 0, 255];
+//! Region::new(10, 4, 2, 1, &payload)
+//!     .apply(&mut fb, Dither::Ordered { bits: 3 })
+//!     .unwrap();
+//! ```
+
+use embedded_graphics::prelude::{DrawTarget, Point, RgbColor, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+
+use crate::Color;
+
+/// How colours are rounded to the bit depth of the framebuffer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dither {
+    /// Leave the rounding to the framebuffer, which truncates.
+    #[default]
+    None,
+    /// Ordered dithering with a 4x4 Bayer matrix for a framebuffer with
+    /// `bits` of colour depth, its `BITS` parameter.
+    ///
+    /// The pattern is fixed to the target coordinates, so neighbouring
+    /// regions line up and still areas do not flicker.
+    Ordered {
+        /// Colour depth of the framebuffer.
+        bits: u8,
+    },
+}
+
+/// 4x4 Bayer matrix, thresholds in 16ths.
+pub(crate) const BAYER: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl Dither {
+    /// `color` at `point` rounded to the colour depth.
+    #[must_use]
+    pub fn apply(self, point: Point, color: Color) -> Color {
+        let Self::Ordered { bits } = self else {
+            return color;
+        };
+        let threshold = BAYER[(point.y & 3) as usize][(point.x & 3) as usize];
+        round_to_depth(color, bits, threshold)
+    }
+}
+
+/// `color` rounded down to `bits` of colour depth after adding `threshold`
+/// 16ths of one step.
+pub(crate) fn round_to_depth(color: Color, bits: u8, threshold: u8) -> Color {
+    if bits == 0 || bits >= 8 {
+        return color;
+    }
+    let step = 1u16 << (8 - bits);
+    let offset = u16::from(threshold) * step / 16;
+    let round = |v: u8| {
+        let v = (u16::from(v) + offset).min(255);
+        (v & !(step - 1)) as u8
+    };
+    Color::new(round(color.r()), round(color.g()), round(color.b()))
+}
+
+/// Errors returned when a [`Region`] cannot be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IngestError<E> {
+    /// The region does not lie completely inside the target.
+    OutOfBounds,
+    /// The payload holds fewer than the `expected` bytes of the region.
+    ShortPayload {
+        /// Bytes needed for the region, three per pixel.
+        expected: usize,
+        /// Bytes in the payload.
+        actual: usize,
+    },
+    /// A DDP packet with a data type other than RGB888.
+    UnsupportedType(u8),
+    /// A DDP packet starting or ending in the middle of a pixel.
+    Unaligned,
+    /// The target failed to draw.
+    Target(E),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for IngestError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OutOfBounds => write!(f, "region out of bounds"),
+            Self::ShortPayload { expected, actual } => {
+                write!(f, "payload of {actual} bytes, expected {expected}")
+            }
+            Self::UnsupportedType(t) => write!(f, "unsupported data type {t:#04x}"),
+            Self::Unaligned => write!(f, "pixel data not aligned to pixels"),
+            Self::Target(e) => write!(f, "drawing failed: {e:?}"),
+        }
+    }
+}
+
+/// Fill `area` with the RGB888 pixels in `rgb`.
+fn fill<D>(
+    target: &mut D,
+    area: &Rectangle,
+    rgb: &[u8],
+    dither: Dither,
+) -> Result<(), IngestError<D::Error>>
+where
+    D: DrawTarget<Color = Color>,
+{
+    let colors = area
+        .points()
+        .zip(rgb.chunks_exact(3))
+        .map(|(point, rgb)| dither.apply(point, Color::new(rgb[0], rgb[1], rgb[2])));
+    target
+        .fill_contiguous(area, colors)
+        .map_err(IngestError::Target)
+}
+
+/// A rectangle of RGB888 pixels, row by row from the top left.
+///
+/// Bytes after the last pixel are ignored, so payloads padded to an even
+/// length, as DMX universes are, can be passed unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Region<'a> {
+    /// Left edge in target coordinates.
+    pub x: u16,
+    /// Top edge in target coordinates.
+    pub y: u16,
+    /// Width in pixels.
+    pub width: u16,
+    /// Height in pixels.
+    pub height: u16,
+    /// Red, green and blue bytes of each pixel.
+    pub rgb: &'a [u8],
+}
+
+impl<'a> Region<'a> {
+    /// Describe a `width` by `height` region at `x`, `y` holding `rgb`.
+    #[must_use]
+    pub const fn new(x: u16, y: u16, width: u16, height: u16, rgb: &'a [u8]) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            rgb,
+        }
+    }
+
+    /// The rectangle covered in target coordinates.
+    #[must_use]
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(i32::from(self.x), i32::from(self.y)),
+            Size::new(u32::from(self.width), u32::from(self.height)),
+        )
+    }
+
+    /// Copy the pixels into `target`.
+    ///
+    /// # Errors
+    /// Returns [`IngestError::OutOfBounds`] or
+    /// [`IngestError::ShortPayload`] without drawing anything if the region
+    /// does not fit the target or the payload, and
+    /// [`IngestError::Target`] if drawing fails.
+    pub fn apply<D>(&self, target: &mut D, dither: Dither) -> Result<(), IngestError<D::Error>>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        let area = self.area();
+        let bounds = target.bounding_box();
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        if !bounds.contains(area.top_left)
+            || !bounds.contains(area.bottom_right().unwrap_or_default())
+        {
+            return Err(IngestError::OutOfBounds);
+        }
+        let expected = usize::from(self.width) * usize::from(self.height) * 3;
+        if self.rgb.len() < expected {
+            return Err(IngestError::ShortPayload {
+                expected,
+                actual: self.rgb.len(),
+            });
+        }
+        fill(target, &area, self.rgb, dither)
+    }
+}
+
+/// DDP data type of RGB pixels with 8 bits per channel.
+pub const DDP_RGB888: u8 = 0x0b;
+
+/// Errors returned when a DDP packet cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DdpError {
+    /// The packet is shorter than its header or the data length it claims.
+    TooShort,
+    /// The packet is not DDP version 1.
+    UnsupportedVersion(u8),
+}
+
+impl core::fmt::Display for DdpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "packet too short"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported DDP version {v}"),
+        }
+    }
+}
+
+/// A Distributed Display Protocol data packet.
+///
+/// DDP treats the display as one strip of pixels, row by row from the top
+/// left of the whole display, and every packet carries the byte `offset`
+/// of its first pixel in that strip. Applied to a
+/// [`TiledFrameBuffer`](crate::tiling::TiledFrameBuffer) or any other
+/// target, the pixels land on the target's own coordinates, so packets
+/// spanning several panels need no special handling.
+///
+/// Senders split frames into packets, setting `push` on the last one: show
+/// the frame, e.g. by swapping buffers, once a packet with `push` has been
+/// applied.
+///
+/// # Example
+/// ```rust
+/// use hub75_framebuffer::ingest::{DdpPacket, Dither};
+/// use hub75_framebuffer::plain::DmaFrameBuffer;
+///
+/// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+///
+/// // as received from the UDP socket on port 4048: header, then two
+/// // pixels starting at pixel 64, the first of the second row
+/// let datagram = [
+///     0x41, 0x01, 0x0b, 0x01, 0, 0, 0, 192, 0, 6, 255, 0, 0, 0, 255, 0,
+/// ];
+/// let packet = DdpPacket::parse(&datagram).unwrap();
+/// assert!(packet.push);
+/// packet.apply(&mut fb, Dither::None).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DdpPacket<'a> {
+    /// Sequence number, 1 to 15, or 0 if unused.
+    pub sequence: u8,
+    /// Data type, see [`DDP_RGB888`]. 0 means the default, RGB888.
+    pub data_type: u8,
+    /// Destination ID, 1 for the default output device.
+    pub destination: u8,
+    /// `true` if the frame is complete and should be shown.
+    pub push: bool,
+    /// Byte offset of the payload in the display data.
+    pub offset: u32,
+    /// Pixel data.
+    pub payload: &'a [u8],
+}
+
+impl<'a> DdpPacket<'a> {
+    /// Length of the header without time code.
+    const HEADER: usize = 10;
+
+    /// A packet carrying RGB888 `payload` at byte `offset`, for data whose
+    /// header has already been parsed elsewhere.
+    #[must_use]
+    pub const fn new(offset: u32, payload: &'a [u8]) -> Self {
+        Self {
+            sequence: 0,
+            data_type: DDP_RGB888,
+            destination: 1,
+            push: false,
+            offset,
+            payload,
+        }
+    }
+
+    /// Parse a UDP datagram. Bytes after the data length in the header are
+    /// ignored.
+    ///
+    /// # Errors
+    /// See [`DdpError`].
+    pub fn parse(datagram: &'a [u8]) -> Result<Self, DdpError> {
+        let Some(header) = datagram.get(..Self::HEADER) else {
+            return Err(DdpError::TooShort);
+        };
+        let flags = header[0];
+        let version = flags >> 6;
+        if version != 1 {
+            return Err(DdpError::UnsupportedVersion(version));
+        }
+        // a time code follows the header
+        let start = if flags & 0x10 == 0 {
+            Self::HEADER
+        } else {
+            Self::HEADER + 4
+        };
+        let offset = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        let length = usize::from(u16::from_be_bytes([header[8], header[9]]));
+        let payload = datagram
+            .get(start..start + length)
+            .ok_or(DdpError::TooShort)?;
+        Ok(Self {
+            sequence: header[1] & 0x0f,
+            data_type: header[2],
+            destination: header[3],
+            push: flags & 0x01 != 0,
+            offset,
+            payload,
+        })
+    }
+
+    /// Write the pixels into `target`, wrapping at its right edge.
+    ///
+    /// # Errors
+    /// Returns [`IngestError::UnsupportedType`] for data other than RGB888,
+    /// [`IngestError::Unaligned`] if the offset or length is not a whole
+    /// number of pixels and [`IngestError::OutOfBounds`] if the pixels
+    /// extend past the bottom of the target, all without drawing anything,
+    /// and [`IngestError::Target`] if drawing fails.
+    pub fn apply<D>(&self, target: &mut D, dither: Dither) -> Result<(), IngestError<D::Error>>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        // type bits 0bCRTTTSSS: undefined or RGB, undefined or 8 bit size
+        if !matches!(self.data_type & 0x38, 0x00 | 0x08)
+            || !matches!(self.data_type & 0x07, 0x00 | 0x03)
+            || self.data_type & 0x80 != 0
+        {
+            return Err(IngestError::UnsupportedType(self.data_type));
+        }
+        if self.offset % 3 != 0 || self.payload.len() % 3 != 0 {
+            return Err(IngestError::Unaligned);
+        }
+        let bounds = target.bounding_box();
+        let width = bounds.size.width as usize;
+        let pixels = bounds.size.height as usize * width;
+        let first = self.offset as usize / 3;
+        let count = self.payload.len() / 3;
+        if first.saturating_add(count) > pixels {
+            return Err(IngestError::OutOfBounds);
+        }
+
+        // one region per row
+        let mut rgb = self.payload;
+        let mut index = first;
+        while !rgb.is_empty() {
+            let x = index % width;
+            let y = index / width;
+            let n = (width - x).min(rgb.len() / 3);
+            let (row, rest) = rgb.split_at(n * 3);
+            let area = Rectangle::new(
+                bounds.top_left + Size::new(x as u32, y as u32),
+                Size::new(n as u32, 1),
+            );
+            fill(target, &area, row, dither)?;
+            rgb = rest;
+            index += n;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+
+    #[test]
+    fn test_apply_region() {
+        let mut display = MockDisplay::new();
+        // red, green, blue, then white, black, red and a padding byte
+        let rgb = [
+            255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0xaa,
+        ];
+        Region::new(1, 2, 3, 2, &rgb)
+            .apply(&mut display, Dither::None)
+            .unwrap();
+        display.assert_pattern(&[
+            "    ", //
+            "    ", //
+            " RGB", //
+            " WKR", //
+        ]);
+    }
+
+    #[test]
+    fn test_apply_rejects() {
+        let mut display = MockDisplay::<Color>::new();
+        let rgb = [0; 12];
+
+        let region = Region::new(62, 0, 4, 1, &rgb);
+        assert_eq!(
+            region.apply(&mut display, Dither::None),
+            Err(IngestError::OutOfBounds)
+        );
+        let region = Region::new(0, 0, 2, 3, &rgb);
+        assert_eq!(
+            region.apply(&mut display, Dither::None),
+            Err(IngestError::ShortPayload {
+                expected: 18,
+                actual: 12
+            })
+        );
+        assert_eq!(display.affected_area(), Rectangle::zero());
+
+        // nothing to draw
+        let region = Region::new(100, 100, 0, 0, &[]);
+        assert_eq!(region.apply(&mut display, Dither::None), Ok(()));
+    }
+
+    #[test]
+    fn test_ordered_dither() {
+        let dither = Dither::Ordered { bits: 3 };
+        // a quarter of a 32 step lights a quarter of the pixels
+        let gray = Color::new(8, 8, 8);
+        let lit = (0..4)
+            .flat_map(|y| (0..4).map(move |x| Point::new(x, y)))
+            .filter(|&p| dither.apply(p, gray) == Color::new(32, 32, 32))
+            .count();
+        assert_eq!(lit, 4);
+
+        // levels of the bit depth stay as they are
+        for level in [0, 32, 224] {
+            let color = Color::new(level, level, level);
+            assert_eq!(dither.apply(Point::new(3, 1), color), color);
+        }
+        assert_eq!(
+            dither.apply(Point::zero(), Color::WHITE),
+            Color::new(224, 224, 224)
+        );
+        assert_eq!(
+            Dither::None.apply(Point::zero(), Color::new(1, 2, 3)),
+            Color::new(1, 2, 3)
+        );
+    }
+
+    fn ddp(flags: u8, data_type: u8, offset: u32, payload: &[u8]) -> std::vec::Vec<u8> {
+        let mut datagram = std::vec![flags, 3, data_type, 1];
+        datagram.extend_from_slice(&offset.to_be_bytes());
+        datagram.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        if flags & 0x10 != 0 {
+            datagram.extend_from_slice(&[0; 4]);
+        }
+        datagram.extend_from_slice(payload);
+        datagram
+    }
+
+    #[test]
+    fn test_ddp_parse() {
+        let rgb = [1, 2, 3, 4, 5, 6];
+        let datagram = ddp(0x41, DDP_RGB888, 300, &rgb);
+        let packet = DdpPacket::parse(&datagram).unwrap();
+        assert_eq!(
+            packet,
+            DdpPacket {
+                sequence: 3,
+                push: true,
+                ..DdpPacket::new(300, &rgb)
+            }
+        );
+
+        let datagram = ddp(0x50, 0, 0, &rgb);
+        let packet = DdpPacket::parse(&datagram).unwrap();
+        assert_eq!(packet.payload, &rgb);
+        assert!(!packet.push);
+
+        assert_eq!(DdpPacket::parse(&datagram[..9]), Err(DdpError::TooShort));
+        assert_eq!(
+            DdpPacket::parse(&datagram[..datagram.len() - 1]),
+            Err(DdpError::TooShort)
+        );
+        assert_eq!(
+            DdpPacket::parse(&ddp(0x81, 0, 0, &rgb)),
+            Err(DdpError::UnsupportedVersion(2))
+        );
+    }
+
+    #[test]
+    fn test_ddp_wraps_rows() {
+        let mut display = MockDisplay::new();
+        // from the last two pixels of row 1 to the first pixel of row 3
+        let mut rgb = std::vec::Vec::new();
+        for _ in 0..66 {
+            rgb.extend_from_slice(&[255, 0, 0]);
+        }
+        rgb.extend_from_slice(&[0, 0, 255]);
+        DdpPacket::new(3 * (64 + 62), &rgb)
+            .apply(&mut display, Dither::None)
+            .unwrap();
+
+        assert_eq!(display.get_pixel(Point::new(61, 1)), None);
+        assert_eq!(display.get_pixel(Point::new(62, 1)), Some(Color::RED));
+        assert_eq!(display.get_pixel(Point::new(63, 2)), Some(Color::RED));
+        assert_eq!(display.get_pixel(Point::new(0, 3)), Some(Color::BLUE));
+        assert_eq!(display.get_pixel(Point::new(1, 3)), None);
+        assert_eq!(
+            display.affected_area(),
+            Rectangle::new(Point::new(0, 1), Size::new(64, 3))
+        );
+    }
+
+    #[test]
+    fn test_ddp_tiled() {
+        use crate::plain::DmaFrameBuffer;
+        use crate::tiling::{ChainTopRightDown, TiledFrameBuffer};
+
+        // two 64x32 panels side by side, a 128 pixel wide display
+        let mut fb: TiledFrameBuffer<
+            DmaFrameBuffer<32, 128, 16, 3, 7>,
+            ChainTopRightDown<32, 64, 1, 2>,
+            32,
+            64,
+            16,
+            3,
+            7,
+            1,
+            2,
+            128,
+        > = TiledFrameBuffer::new();
+        let rgb = [255; 3 * 128];
+        DdpPacket::new(3 * 128 * 5, &rgb)
+            .apply(&mut fb, Dither::None)
+            .unwrap();
+        assert_eq!(
+            DdpPacket::new(3 * 128 * 32, &rgb[..3]).apply(&mut fb, Dither::None),
+            Err(IngestError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_ddp_rejects() {
+        let mut display = MockDisplay::<Color>::new();
+        let packet = DdpPacket::new(1, &[0; 3]);
+        assert_eq!(
+            packet.apply(&mut display, Dither::None),
+            Err(IngestError::Unaligned)
+        );
+        let packet = DdpPacket::new(0, &[0; 4]);
+        assert_eq!(
+            packet.apply(&mut display, Dither::None),
+            Err(IngestError::Unaligned)
+        );
+        // RGBW
+        let packet = DdpPacket {
+            data_type: 0x1b,
+            ..DdpPacket::new(0, &[0; 3])
+        };
+        assert_eq!(
+            packet.apply(&mut display, Dither::None),
+            Err(IngestError::UnsupportedType(0x1b))
+        );
+        let packet = DdpPacket::new(3 * 64 * 64, &[0; 3]);
+        assert_eq!(
+            packet.apply(&mut display, Dither::None),
+            Err(IngestError::OutOfBounds)
+        );
+        assert_eq!(display.affected_area(), Rectangle::zero());
+    }
+}
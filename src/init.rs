@@ -0,0 +1,323 @@
+//! Start-up sequences for panels whose driver ICs need configuring.
+//!
+//! Panels built with FM6126A (or FM6124) column drivers stay dark until two
+//! configuration registers have been written. A register is written by
+//! shifting a row of data and holding `LAT` high during the last clocks of
+//! it; the number of clocks with `LAT` high selects the register. Afterwards
+//! the panels display ordinary BCM streams like any other.
+//!
+//! [`Fm6126aInit`] holds that sequence as a small DMA-able buffer in the word
+//! format of a framebuffer. Send it once at start-up, before the first
+//! frame, with the same peripheral and clock used for refreshing.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::init::{Fm6126aConfig, Fm6126aInit};
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//!
+//! const COLS: usize = 64;
+//!
+//! static INIT: Fm6126aInit<u16, COLS> =
+//!     Fm6126aInit::plain::<DefaultOrdering>(Fm6126aConfig::new());
+//!
+//! // two register writes of one row each, then 4 closing words
+//! assert_eq!(INIT.as_bytes().len(), 2 * (2 * COLS + 4));
+//! ```
+
+use embedded_dma::ReadBuffer;
+
+use crate::latched::{color0_mask, color1_mask, inversion_mask, pins_valid, PinMap};
+use crate::ordering::{is_swapped, map_byte_index, map_half_word_index, Ordering};
+use crate::plain;
+
+/// Clocks with `LAT` high that write the first register.
+const REG1_LATCH_CLOCKS: usize = 11;
+/// Clocks with `LAT` high that write the second register.
+const REG2_LATCH_CLOCKS: usize = 12;
+
+/// Words after the register writes that close the latch again.
+const END_WORDS: usize = 4;
+
+/// Register values written by [`Fm6126aInit`].
+///
+/// Each register is 16 bits wide and shifted MSB first, repeated for every
+/// driver IC of the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fm6126aConfig {
+    /// First register, written with 11 latch clocks. The low 15 bits set
+    /// the output current; all set is full brightness.
+    pub reg1: u16,
+    /// Second register, written with 12 latch clocks. Bit 6 turns the
+    /// outputs on.
+    pub reg2: u16,
+}
+
+impl Fm6126aConfig {
+    /// Full brightness with the outputs turned on.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            reg1: 0b0111_1111_1111_1111,
+            reg2: 0b0000_0000_0100_0000,
+        }
+    }
+}
+
+impl Default for Fm6126aConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// FM6126A/FM6124 register writes for a chain of `COLS` columns, as words
+/// of type `W`.
+///
+/// Build it with [`Self::plain`] or [`Self::latched`] to match the
+/// framebuffer in use. The output stays disabled during the whole sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct Fm6126aInit<W: Copy, const COLS: usize> {
+    reg1: [W; COLS],
+    reg2: [W; COLS],
+    end: [W; END_WORDS],
+}
+
+/// Whether column `col` of a register write carries a one bit.
+const fn reg_bit(reg: u16, col: usize) -> bool {
+    reg & (1 << (15 - col % 16)) != 0
+}
+
+/// Whether `LAT` is high at column `col` of a write with `clocks` latch
+/// clocks.
+const fn latched(col: usize, clocks: usize, cols: usize) -> bool {
+    col + clocks >= cols
+}
+
+impl<const COLS: usize> Fm6126aInit<u16, COLS> {
+    /// Sequence in the 16-bit [`crate::plain`] word format, with the
+    /// reference wiring.
+    ///
+    /// # Panics
+    /// At compile time if `COLS` is shorter than a register write, or odd
+    /// with a swapped [`Ordering`].
+    #[must_use]
+    pub const fn plain<O: Ordering>(config: Fm6126aConfig) -> Self {
+        Self::plain_with_pins::<O, plain::DefaultPins>(config)
+    }
+
+    /// Sequence in the 16-bit [`crate::plain`] word format, with the
+    /// signals assigned by `P`.
+    ///
+    /// The colour lines carry the register bits and the address lines stay
+    /// low, so it can be sent with the same [`crate::plain::PinMap`] as the
+    /// frames that follow it.
+    ///
+    /// # Panics
+    /// At compile time if `COLS` is shorter than a register write, odd with
+    /// a swapped [`Ordering`], or if `P` is invalid.
+    #[must_use]
+    pub const fn plain_with_pins<O: Ordering, P: plain::PinMap>(config: Fm6126aConfig) -> Self {
+        assert!(COLS >= REG2_LATCH_CLOCKS, "chain too short for FM6126A");
+        assert!(
+            COLS % 2 == 0 || !is_swapped::<O>(),
+            "column count must be even with a swapped ordering"
+        );
+        assert!(plain::pins_valid::<P>(), "pin map has overlapping signals");
+        let mut init = Self {
+            reg1: [0; COLS],
+            reg2: [0; COLS],
+            end: [0; END_WORDS],
+        };
+        let mut col = 0;
+        while col < COLS {
+            let i = map_half_word_index::<O>(col);
+            init.reg1[i] = plain_word::<P>(
+                reg_bit(config.reg1, col),
+                latched(col, REG1_LATCH_CLOCKS, COLS),
+            );
+            init.reg2[i] = plain_word::<P>(
+                reg_bit(config.reg2, col),
+                latched(col, REG2_LATCH_CLOCKS, COLS),
+            );
+            col += 1;
+        }
+        init
+    }
+}
+
+/// Plain word with the colour bits of `P` set to `bit`, and `LAT` to
+/// `latch`, in the polarity of `P`.
+const fn plain_word<P: plain::PinMap>(bit: bool, latch: bool) -> u16 {
+    let colors = plain::color0_mask::<P>() | plain::color1_mask::<P>();
+    let word = (if bit { colors } else { 0 }) | (if latch { 1 << P::LAT } else { 0 });
+    word ^ plain::inversion_mask::<P>()
+}
+
+/// Latched word with the colour bits of `P` set to `bit`, and `LAT` to
+/// `latch`, in the polarity of `P`.
+const fn latched_word<P: PinMap>(bit: bool, latch: bool) -> u8 {
+    let colors = color0_mask::<P>() | color1_mask::<P>();
+    let word = (if bit { colors } else { 0 }) | (if latch { 1 << P::LAT } else { 0 });
+    word ^ inversion_mask::<P>()
+}
+
+impl<const COLS: usize> Fm6126aInit<u8, COLS> {
+    /// Sequence in the 8-bit [`crate::latched`] word format, with the
+    /// signals assigned by `P`.
+    ///
+    /// The reference latch circuit gates the clock while `LAT` is high, so
+    /// the panel never sees the latch clocks. This sequence only reaches the
+    /// panel through glue logic that passes the clock during `LAT`.
+    ///
+    /// # Panics
+    /// At compile time if `COLS` is shorter than a register write, not a
+    /// multiple of 4 with a swapped [`Ordering`], or if `P` is invalid.
+    #[must_use]
+    pub const fn latched<O: Ordering, P: PinMap>(config: Fm6126aConfig) -> Self {
+        assert!(COLS >= REG2_LATCH_CLOCKS, "chain too short for FM6126A");
+        assert!(
+            COLS % 4 == 0 || !is_swapped::<O>(),
+            "column count must be a multiple of 4 with a swapped ordering"
+        );
+        assert!(pins_valid::<P>(), "pin map has overlapping signals");
+        let mut init = Self {
+            reg1: [0; COLS],
+            reg2: [0; COLS],
+            end: [0; END_WORDS],
+        };
+        let mut col = 0;
+        while col < COLS {
+            let i = map_byte_index::<O>(col);
+            init.reg1[i] = latched_word::<P>(
+                reg_bit(config.reg1, col),
+                latched(col, REG1_LATCH_CLOCKS, COLS),
+            );
+            init.reg2[i] = latched_word::<P>(
+                reg_bit(config.reg2, col),
+                latched(col, REG2_LATCH_CLOCKS, COLS),
+            );
+            col += 1;
+        }
+        init
+    }
+}
+
+impl<W: Copy, const COLS: usize> Fm6126aInit<W, COLS> {
+    /// Returns the sequence as bytes, in the order they are sent.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the struct is `repr(C)` arrays of one integer type, so it
+        // has no padding.
+        unsafe {
+            core::slice::from_raw_parts(
+                core::ptr::from_ref(self).cast::<u8>(),
+                core::mem::size_of::<Self>(),
+            )
+        }
+    }
+}
+
+// SAFETY: the buffer is owned and not moved while the DMA is reading it.
+unsafe impl<W: Copy, const COLS: usize> ReadBuffer for Fm6126aInit<W, COLS> {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        let bytes = self.as_bytes();
+        (bytes.as_ptr(), bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latched::DefaultPins;
+    use crate::ordering::{Esp32, Linear};
+
+    /// Colour bits of both halves in the reference plain wiring.
+    const PLAIN_COLORS: u16 = 0b0111_1110_0000_0000;
+    /// Latch bit in the reference plain wiring.
+    const PLAIN_LAT: u16 = 1 << 5;
+
+    #[test]
+    fn test_plain_register_writes() {
+        let init = Fm6126aInit::<u16, 32>::plain::<Linear>(Fm6126aConfig::new());
+
+        // reg1 is 0x7fff: only the first bit of every 16 is off
+        for (col, word) in init.reg1.iter().enumerate() {
+            assert_eq!(word & PLAIN_COLORS != 0, col % 16 != 0, "col {col}");
+            assert_eq!(word & PLAIN_LAT != 0, col >= 32 - 11, "col {col}");
+            assert_eq!(word & !(PLAIN_COLORS | PLAIN_LAT), 0);
+        }
+        // reg2 is 0x0040: only bit 6, i.e. the tenth column, is on
+        for (col, word) in init.reg2.iter().enumerate() {
+            assert_eq!(word & PLAIN_COLORS != 0, col % 16 == 9, "col {col}");
+            assert_eq!(word & PLAIN_LAT != 0, col >= 32 - 12, "col {col}");
+        }
+        assert_eq!(init.end, [0; END_WORDS]);
+        assert_eq!(init.as_bytes().len(), 2 * (2 * 32 + END_WORDS));
+    }
+
+    #[test]
+    fn test_plain_pin_map() {
+        #[derive(Debug, Clone, Copy)]
+        struct LowColors;
+        impl plain::PinMap for LowColors {
+            const R1: u8 = 0;
+            const G1: u8 = 1;
+            const B1: u8 = 2;
+            const R2: u8 = 3;
+            const G2: u8 = 4;
+            const B2: u8 = 5;
+            const ADDR: [u8; 5] = [9, 10, 11, 12, 13];
+            const LAT: u8 = 14;
+            const OE: u8 = 15;
+        }
+
+        let init =
+            Fm6126aInit::<u16, 32>::plain_with_pins::<Linear, LowColors>(Fm6126aConfig::new());
+        assert_eq!(init.reg1[0], 0);
+        assert_eq!(init.reg1[1], 0b11_1111);
+        assert_eq!(init.reg1[31], (1 << 14) | 0b11_1111);
+        assert_eq!(init.reg2[9], 0b11_1111);
+        assert_eq!(init.reg2[20], 1 << 14);
+    }
+
+    #[test]
+    fn test_latched_register_writes() {
+        let init = Fm6126aInit::<u8, 16>::latched::<Linear, DefaultPins>(Fm6126aConfig {
+            reg1: 0x8001,
+            reg2: 0,
+        });
+
+        assert_eq!(init.reg1[0], 0b0011_1111);
+        assert_eq!(init.reg1[1], 0);
+        assert_eq!(init.reg1[4], 0);
+        assert_eq!(init.reg1[5], 0b0100_0000);
+        assert_eq!(init.reg1[15], 0b0111_1111);
+        assert_eq!(init.reg2[3], 0);
+        assert_eq!(init.reg2[4], 0b0100_0000);
+        // the output stays disabled throughout
+        assert!(init.as_bytes().iter().all(|b| b & 0b1000_0000 == 0));
+    }
+
+    #[test]
+    fn test_swapped_ordering() {
+        let linear = Fm6126aInit::<u8, 16>::latched::<Linear, DefaultPins>(Fm6126aConfig::new());
+        let esp32 = Fm6126aInit::<u8, 16>::latched::<Esp32, DefaultPins>(Fm6126aConfig::new());
+
+        for col in 0..16 {
+            assert_eq!(esp32.reg1[map_byte_index::<Esp32>(col)], linear.reg1[col]);
+            assert_eq!(esp32.reg2[map_byte_index::<Esp32>(col)], linear.reg2[col]);
+        }
+    }
+
+    #[test]
+    fn test_read_buffer() {
+        let init = Fm6126aInit::<u16, 16>::plain::<Linear>(Fm6126aConfig::default());
+        let (ptr, len) = unsafe { init.read_buffer() };
+        assert_eq!(ptr, init.as_bytes().as_ptr());
+        assert_eq!(len, 2 * (2 * 16 + END_WORDS));
+    }
+}
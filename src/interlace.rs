@@ -0,0 +1,338 @@
+//! Updating half of the rows per frame.
+//!
+//! Redrawing a large tiled display every frame can take a slow MCU longer
+//! than the frame itself. [`Interlaced`] wraps a framebuffer and passes
+//! drawing through to the rows of the current [`Field`] only, even rows or
+//! odd rows. Calling [`Interlaced::next_field`] once per redraw alternates
+//! the field, so each redraw costs about half and every row is updated every
+//! second redraw, like interlaced video.
+//!
+//! Clearing is interlaced as well, so the rows of the other field keep
+//! showing the previous redraw instead of going dark. When a change must
+//! show at once, e.g. switching screens,
+//! [`Interlaced::request_full_update`] passes the next redraw through to
+//! all rows.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::interlace::Interlaced;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::Color;
+//!
+//! let mut display = Interlaced::new(DmaFrameBuffer::<32, 64, 16, 3, 7>::new());
+//! for frame in 0..4 {
+//!     display.clear(Color::BLACK).unwrap();
+//!     Rectangle::new(Point::new(frame, 0), Size::new(8, 8))
+//!         .into_styled(PrimitiveStyle::with_fill(Color::RED))
+//!         .draw(&mut display)
+//!         .unwrap();
+//!     display.next_field();
+//! }
+//! ```
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+
+/// The rows [`Interlaced`] updates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Field {
+    /// Rows 0, 2, 4, ...
+    #[default]
+    Even,
+    /// Rows 1, 3, 5, ...
+    Odd,
+}
+
+impl Field {
+    /// The other field.
+    #[must_use]
+    pub const fn other(self) -> Self {
+        match self {
+            Self::Even => Self::Odd,
+            Self::Odd => Self::Even,
+        }
+    }
+
+    /// Whether row `y` belongs to the field.
+    #[must_use]
+    pub const fn contains(self, y: i32) -> bool {
+        (y.rem_euclid(2) == 0) == matches!(self, Self::Even)
+    }
+}
+
+/// A framebuffer updated one field at a time, see the
+/// [module documentation](self).
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Interlaced<FB> {
+    fb: FB,
+    field: Field,
+    enabled: bool,
+    full_update: bool,
+}
+
+impl<FB> Interlaced<FB> {
+    /// Wrap `fb`, starting with the even rows.
+    #[must_use]
+    pub const fn new(fb: FB) -> Self {
+        Self {
+            fb,
+            field: Field::Even,
+            enabled: true,
+            full_update: false,
+        }
+    }
+
+    /// The field drawing goes to, unless a full update is pending.
+    #[must_use]
+    pub const fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Switch to the other field and end a full update, returning the new
+    /// field. Call it once per redraw.
+    pub fn next_field(&mut self) -> Field {
+        self.field = self.field.other();
+        self.full_update = false;
+        self.field
+    }
+
+    /// Let drawing through to all rows until the next
+    /// [`next_field`](Self::next_field).
+    pub fn request_full_update(&mut self) {
+        self.full_update = true;
+    }
+
+    /// Whether drawing goes to all rows until the next
+    /// [`next_field`](Self::next_field).
+    #[must_use]
+    pub const fn is_full_update(&self) -> bool {
+        self.full_update || !self.enabled
+    }
+
+    /// Turn interlacing on or off; while off, drawing goes to all rows.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Whether interlacing is on.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The wrapped framebuffer.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, for drawing to all rows.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// Unwrap the framebuffer.
+    pub fn into_inner(self) -> FB {
+        self.fb
+    }
+
+    /// Whether drawing to row `y` goes through.
+    #[inline]
+    fn updates(&self, y: i32) -> bool {
+        self.is_full_update() || self.field.contains(y)
+    }
+}
+
+impl<FB: OriginDimensions> OriginDimensions for Interlaced<FB> {
+    fn size(&self) -> Size {
+        self.fb.size()
+    }
+}
+
+impl<FB> DrawTarget for Interlaced<FB>
+where
+    FB: DrawTarget<Color = Color> + OriginDimensions,
+{
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if self.is_full_update() {
+            return self.fb.draw_iter(pixels);
+        }
+        let field = self.field;
+        self.fb
+            .draw_iter(pixels.into_iter().filter(|Pixel(p, _)| field.contains(p.y)))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.is_full_update() {
+            return self.fb.fill_solid(area, color);
+        }
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let first = area.top_left.y + i32::from(!self.field.contains(area.top_left.y));
+        for y in (first..=bottom_right.y).step_by(2) {
+            let row = Rectangle::new(
+                Point::new(area.top_left.x, y),
+                Size::new(area.size.width, 1),
+            );
+            self.fb.fill_solid(&row, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl<FB: FrameBufferOperations> FrameBufferOperations for Interlaced<FB> {
+    /// Erase all rows, whatever the field.
+    #[inline]
+    fn erase(&mut self) {
+        self.fb.erase();
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        if self.updates(p.y) {
+            self.fb.set_pixel(p, color);
+        }
+    }
+}
+
+impl<FB: FrameBuffer> FrameBuffer for Interlaced<FB> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+impl<FB: MutableFrameBuffer + OriginDimensions> MutableFrameBuffer for Interlaced<FB> {}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>> ReadBuffer for Interlaced<FB> {
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plain::DmaFrameBuffer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    fn mock() -> Interlaced<MockDisplay<Color>> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        Interlaced::new(mock)
+    }
+
+    #[test]
+    fn test_field() {
+        assert!(Field::Even.contains(-2) && Field::Odd.contains(-1));
+        assert!(!Field::Even.contains(3));
+        assert_eq!(Field::Odd.other(), Field::Even);
+    }
+
+    #[test]
+    fn test_fill_solid_alternates() {
+        let mut display = mock();
+        let area = Rectangle::new(Point::new(0, 1), Size::new(2, 4));
+        display.fill_solid(&area, Color::RED).unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["  ", "  ", "RR", "  ", "RR"]);
+
+        assert_eq!(display.next_field(), Field::Odd);
+        display.fill_solid(&area, Color::GREEN).unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["  ", "GG", "RR", "GG", "RR"]);
+
+        display.request_full_update();
+        display.fill_solid(&area, Color::BLUE).unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["  ", "BB", "BB", "BB", "BB"]);
+        display.next_field();
+        assert!(!display.is_full_update());
+    }
+
+    #[test]
+    fn test_draw_iter_alternates() {
+        let mut display = mock();
+        display.next_field();
+        Line::new(Point::new(0, 0), Point::new(0, 3))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        display.framebuffer().assert_pattern(&[" ", "R", " ", "R"]);
+
+        display.set_enabled(false);
+        assert!(display.is_full_update());
+        Line::new(Point::new(1, 0), Point::new(1, 3))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&[" R", "RR", " R", "RR"]);
+    }
+
+    #[test]
+    fn test_framebuffer() {
+        type SmallFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+        let mut display = Interlaced::new(SmallFB::new());
+        display.set_pixel(Point::new(3, 4), Color::WHITE);
+        display.set_pixel(Point::new(3, 5), Color::WHITE);
+        let mut expected = SmallFB::new();
+        expected.set_pixel(Point::new(3, 4), Color::WHITE);
+        assert_eq!(display.framebuffer().as_bytes(), expected.as_bytes());
+
+        let (ptr, len) = unsafe { display.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { display.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!(display.size(), Size::new(32, 16));
+    }
+}
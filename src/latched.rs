@@ -162,22 +162,27 @@ doc = ::embed_doc_image::embed_image!("latch-circuit", "images/latch-circuit.png
 //!
 //! # Memory Layout
 //! Each row consists of:
-//! - 4 address words (8 bits each) for row selection and timing
 //! - COLS data words (8 bits each) for pixel data
+//! - 4 address words (8 bits each) for row selection and timing
+//!
+//! The latch is open for the first 3 address words, the last one closes it.
+//! The number of address words, and with it the settle clocks with `LAT`
+//! high, and whether they come before or after the pixel data can be changed
+//! with the parameters of [`LatchedFormat`].
 //!
 //! # Safety
 //! This implementation uses unsafe code for DMA operations. The framebuffer
 //! must be properly aligned in memory and the DMA configuration must match the
 //! buffer layout.
-use core::convert::Infallible;
-
-use super::Color;
-use crate::{FrameBufferOperations, MutableFrameBuffer};
+use core::marker::PhantomData;
+
+use crate::align::{Alignment, DefaultAlignment};
+use crate::export::Layout;
+use crate::format::{EntryFormat, FrameOrder, RowFormat, Sequential};
+use crate::ordering::{is_swapped, map_byte_index, DefaultOrdering, Ordering};
+use crate::orientation::{Normal, Orientation};
+use crate::{Hub75Error, WordSize};
 use bitfield::bitfield;
-use embedded_dma::ReadBuffer;
-use embedded_graphics::pixelcolor::Rgb888;
-use embedded_graphics::pixelcolor::RgbColor;
-use embedded_graphics::prelude::Point;
 
 bitfield! {
     /// 8-bit word carrying the row-address and timing control signals that are
@@ -214,19 +219,41 @@ bitfield! {
     /// * Because `CLK` is inhibited during the latch interval, the pixel data
     ///   stream produced from [`Entry`] words is paused until the latch is
     ///   released.
+    ///
+    /// The layout is that of [`DefaultPins`] and is part of the stable API,
+    /// so custom drivers and init-sequence generators can decode and build
+    /// words with it. Framebuffers with another [`PinMap`] place the signals
+    /// on the bits of their pin map instead.
     #[derive(Clone, Copy, Default, PartialEq, Eq)]
     #[repr(transparent)]
-    struct Address(u8);
+    pub struct Address(u8);
     impl Debug;
+    /// Output enable (bit 7).
     pub output_enable, set_output_enable: 7;
+    /// Latch (bit 6).
     pub latch, set_latch: 6;
+    /// Row address (bits 4-0).
     pub addr, set_addr: 4, 0;
 }
 
 impl Address {
+    /// A word with all signals low.
+    #[must_use]
     pub const fn new() -> Self {
         Self(0)
     }
+
+    /// The word with the raw value `bits`, as clocked out.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// The raw value of the word, as clocked out.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
 }
 
 bitfield! {
@@ -246,82 +273,273 @@ bitfield! {
     /// - Bit 2: Blue channel for color0
     /// - Bit 1: Green channel for color0
     /// - Bit 0: Red channel for color0
-    #[derive(Clone, Copy, Default, PartialEq)]
+    ///
+    /// The layout is that of [`DefaultPins`] and is part of the stable API,
+    /// like that of [`Address`]. Pixel words and address words are told apart
+    /// by their position in the row, see [`Row`].
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
     #[repr(transparent)]
-    struct Entry(u8);
+    pub struct Entry(u8);
     impl Debug;
+    /// Output enable (bit 7).
     pub output_enable, set_output_enable: 7;
+    /// Latch (bit 6).
     pub latch, set_latch: 6;
+    /// Blue of the lower half (bit 5).
     pub blu2, set_blu2: 5;
+    /// Green of the lower half (bit 4).
     pub grn2, set_grn2: 4;
+    /// Red of the lower half (bit 3).
     pub red2, set_red2: 3;
+    /// Blue of the upper half (bit 2).
     pub blu1, set_blu1: 2;
+    /// Green of the upper half (bit 1).
     pub grn1, set_grn1: 1;
+    /// Red of the upper half (bit 0).
     pub red1, set_red1: 0;
 }
 
 impl Entry {
+    /// A word with all signals low.
+    #[must_use]
     pub const fn new() -> Self {
         Self(0)
     }
 
-    // Optimized color bit manipulation constants and methods
-    const COLOR0_MASK: u8 = 0b0000_0111; // bits 0-2: R1, G1, B1
-    const COLOR1_MASK: u8 = 0b0011_1000; // bits 3-5: R2, G2, B2
+    /// The word with the raw value `bits`, as clocked out.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// The raw value of the word, as clocked out.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
 
+    /// Set R1/G1/B1 from `bits` (bit 0 red, 1 green, 2 blue) at the positions of `P`.
     #[inline]
-    fn set_color0_bits(&mut self, bits: u8) {
-        self.0 = (self.0 & !Self::COLOR0_MASK) | (bits & Self::COLOR0_MASK);
+    fn set_color0_bits<P: PinMap>(&mut self, bits: u8) {
+        let spread = spread_rgb(bits, P::R1, P::G1, P::B1);
+        self.0 = (self.0 & !color0_mask::<P>()) | spread;
     }
 
+    /// Set R2/G2/B2 from `bits` (bit 0 red, 1 green, 2 blue) at the positions of `P`.
     #[inline]
-    fn set_color1_bits(&mut self, bits: u8) {
-        self.0 = (self.0 & !Self::COLOR1_MASK) | ((bits << 3) & Self::COLOR1_MASK);
+    fn set_color1_bits<P: PinMap>(&mut self, bits: u8) {
+        let spread = spread_rgb(bits, P::R2, P::G2, P::B2);
+        self.0 = (self.0 & !color1_mask::<P>()) | spread;
+    }
+}
+
+/// Data-bus bit of every signal in the latched 8-bit words.
+///
+/// The defaults match the bit mapping in the [module documentation](self)
+/// and the reference latch circuit. Boards that route the signals to other
+/// bits implement this trait on a marker type, overriding only the constants
+/// that differ, and pass it to [`LatchedFormat`]:
+///
+/// ```rust
+/// use hub75_framebuffer::latched::{LatchedFormat, PinMap};
+///
+/// #[derive(Debug, Clone, Copy)]
+/// struct MyBoard;
+///
+/// // OE and LAT on bits 0 and 1, colours above them
+/// impl PinMap for MyBoard {
+///     const OE: u8 = 0;
+///     const LAT: u8 = 1;
+///     const R1: u8 = 2;
+///     const G1: u8 = 3;
+///     const B1: u8 = 4;
+///     const R2: u8 = 5;
+///     const G2: u8 = 6;
+///     const B2: u8 = 7;
+///     const ADDR: [u8; 5] = [2, 3, 4, 5, 6];
+/// }
+///
+/// type Format = LatchedFormat<0, 4, MyBoard>;
+/// ```
+///
+/// The six colour bits, `OE` and `LAT` must be distinct bits of the word,
+/// and the address lines must be distinct and not overlap `OE` or `LAT`;
+/// other maps fail to compile.
+///
+/// Boards that invert `OE` or `LAT`, e.g. with a spare inverting gate, set
+/// [`OE_ACTIVE_LOW`](PinMap::OE_ACTIVE_LOW) or
+/// [`LAT_ACTIVE_LOW`](PinMap::LAT_ACTIVE_LOW) instead of adding another
+/// inverter; the bit is then flipped in every pixel, blanking and address
+/// word.
+///
+/// Latch boards that wire the address lines in a different order only need
+/// to override [`ADDR`](PinMap::ADDR), the data-bus bit of each of `A` to
+/// `E`. The address table is generated from it at compile time, so fixing
+/// swapped lines in software costs nothing at run time:
+///
+/// ```rust
+/// use hub75_framebuffer::latched::{LatchedFormat, PinMap};
+///
+/// #[derive(Debug, Clone, Copy)]
+/// struct SwappedAb;
+///
+/// // A and B are swapped on the board
+/// impl PinMap for SwappedAb {
+///     const ADDR: [u8; 5] = [1, 0, 2, 3, 4];
+/// }
+///
+/// type Format = LatchedFormat<0, 4, SwappedAb>;
+/// ```
+pub trait PinMap: Copy + core::fmt::Debug {
+    /// Output enable.
+    const OE: u8 = 7;
+    /// Latch.
+    const LAT: u8 = 6;
+    /// Red of the upper half.
+    const R1: u8 = 0;
+    /// Green of the upper half.
+    const G1: u8 = 1;
+    /// Blue of the upper half.
+    const B1: u8 = 2;
+    /// Red of the lower half.
+    const R2: u8 = 3;
+    /// Green of the lower half.
+    const G2: u8 = 4;
+    /// Blue of the lower half.
+    const B2: u8 = 5;
+    /// Data-bus bit of each row address line, `A` first. Any permutation of
+    /// the non-control bits is allowed.
+    const ADDR: [u8; 5] = [0, 1, 2, 3, 4];
+    /// `OE` is low while the output is enabled.
+    const OE_ACTIVE_LOW: bool = false;
+    /// `LAT` is low while the latch is open.
+    const LAT_ACTIVE_LOW: bool = false;
+}
+
+/// The pin map of the reference latch circuit, see [`PinMap`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DefaultPins;
+
+impl PinMap for DefaultPins {}
+
+/// Control bits that are active low, to be flipped in every word.
+pub(crate) const fn inversion_mask<P: PinMap>() -> u8 {
+    ((P::OE_ACTIVE_LOW as u8) << P::OE) | ((P::LAT_ACTIVE_LOW as u8) << P::LAT)
+}
+
+#[inline]
+const fn spread_rgb(bits: u8, r: u8, g: u8, b: u8) -> u8 {
+    ((bits & 1) << r) | (((bits >> 1) & 1) << g) | (((bits >> 2) & 1) << b)
+}
+
+pub(crate) const fn color0_mask<P: PinMap>() -> u8 {
+    spread_rgb(0b111, P::R1, P::G1, P::B1)
+}
+
+pub(crate) const fn color1_mask<P: PinMap>() -> u8 {
+    spread_rgb(0b111, P::R2, P::G2, P::B2)
+}
+
+/// Places the 5 row-address bits of `addr` on the address lines of `P`.
+const fn spread_addr<P: PinMap>(addr: u8) -> u8 {
+    let mut word = 0;
+    let mut i = 0;
+    while i < 5 {
+        word |= ((addr >> i) & 1) << P::ADDR[i];
+        i += 1;
     }
+    word
+}
+
+/// Checks that the signals of `P` fit in a byte without colliding.
+pub(crate) const fn pins_valid<P: PinMap>() -> bool {
+    const fn claim(used: &mut u8, bit: u8) -> bool {
+        let free = bit < 8 && *used & (1 << bit) == 0;
+        if free {
+            *used |= 1 << bit;
+        }
+        free
+    }
+
+    let entry = [P::OE, P::LAT, P::R1, P::G1, P::B1, P::R2, P::G2, P::B2];
+    let mut used = 0u8;
+    let mut ok = true;
+    let mut i = 0;
+    while i < entry.len() {
+        ok &= claim(&mut used, entry[i]);
+        i += 1;
+    }
+    if !ok {
+        return false;
+    }
+    // address lines share the colour bits, but not OE and LAT
+    let mut used = (1 << P::OE) | (1 << P::LAT);
+    i = 0;
+    while i < P::ADDR.len() {
+        ok &= claim(&mut used, P::ADDR[i]);
+        i += 1;
+    }
+    ok
 }
 
 /// Represents a single row of pixels with external latch circuit support.
 ///
 /// Each row contains both pixel data and address information:
-/// - 4 address words for row selection and timing
+/// - `ADDR_BEFORE` address words in front of the pixel data
+/// - `BLANK` blanked entries right after the address words
+/// - `DEAD` no-op entries in front of the pixel data
 /// - COLS data words for pixel data
+/// - `ADDR_AFTER` address words after the pixel data (4 by default)
 ///
 /// The address words are arranged to match the external latch circuit's
-/// timing requirements. When the `esp32` feature is enabled, a specific
-/// mapping (2, 3, 0, 1) is applied to correct for the strange byte ordering
-/// required for the ESP32's I2S peripheral.
+/// timing requirements. With the [`Esp32`](crate::ordering::Esp32) ordering, a
+/// specific mapping (2, 3, 0, 1) is applied to correct for the strange byte
+/// ordering required for the ESP32's I2S peripheral.
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[repr(C)]
-struct Row<const COLS: usize> {
+pub struct Row<
+    const COLS: usize,
+    O: Ordering = DefaultOrdering,
+    const ADDR_BEFORE: usize = 0,
+    const ADDR_AFTER: usize = 4,
+    P: PinMap = DefaultPins,
+    const BLANK: usize = 0,
+    const DEAD: usize = 0,
+    const PULSES: usize = 1,
+> {
+    leading: [Address; ADDR_BEFORE],
+    blank: [Entry; BLANK],
+    dead: [Entry; DEAD],
     data: [Entry; COLS],
-    address: [Address; 4],
-}
-
-// bytes are output in the order 2, 3, 0, 1
-#[inline]
-const fn map_index(index: usize) -> usize {
-    #[cfg(feature = "esp32-ordering")]
-    {
-        index ^ 2
-    }
-    #[cfg(not(feature = "esp32-ordering"))]
-    {
-        index
-    }
+    address: [Address; ADDR_AFTER],
+    _ordering: PhantomData<(O, P)>,
 }
 
 /// Pre-computed address table for all possible row addresses (0-31).
-/// Each entry contains the 4 address words needed for that row.
-const fn make_addr_table() -> [[Address; 4]; 32] {
-    let mut tbl = [[Address::new(); 4]; 32];
+/// Each entry contains the `N` address words needed for that row, split into
+/// `PULSES` equal parts; the latch is open for all but the last word of each.
+const fn make_addr_table<O: Ordering, P: PinMap, const N: usize, const PULSES: usize>(
+) -> [[Address; N]; 32] {
+    assert!(
+        N % 4 == 0 || !is_swapped::<O>(),
+        "address word count must be a multiple of 4 with a swapped ordering"
+    );
+    assert!(
+        N == 0 || (PULSES > 0 && N % PULSES == 0 && N / PULSES >= 2),
+        "address words must split into latch pulses of at least 2 words"
+    );
+    let period = if N == 0 { 1 } else { N / PULSES };
+    let mut tbl = [[Address::new(); N]; 32];
     let mut addr = 0;
     while addr < 32 {
         let mut i = 0;
-        while i < 4 {
-            let latch = i != 3;
-            let mapped_i = map_index(i);
-            let latch_bit = if latch { 1u8 << 6 } else { 0u8 };
-            tbl[addr][mapped_i].0 = latch_bit | addr as u8;
+        while i < N {
+            let latch = i % period != period - 1;
+            let mapped_i = map_byte_index::<O>(i);
+            let latch_bit = if latch { 1u8 << P::LAT } else { 0u8 };
+            tbl[addr][mapped_i].0 =
+                (latch_bit | spread_addr::<P>(addr as u8)) ^ inversion_mask::<P>();
             i += 1;
         }
         addr += 1;
@@ -329,118 +547,307 @@ const fn make_addr_table() -> [[Address; 4]; 32] {
     tbl
 }
 
-static ADDR_TABLE: [[Address; 4]; 32] = make_addr_table();
-
 /// Pre-computed data template for a row with the given number of columns.
 /// This template has the correct OE/LAT bits set for each column position.
-const fn make_data_template<const COLS: usize>() -> [Entry; COLS] {
+const fn make_data_template<const COLS: usize, O: Ordering, P: PinMap>() -> [Entry; COLS] {
     let mut data = [Entry::new(); COLS];
     let mut i = 0;
     while i < COLS {
-        let mapped_i = map_index(i);
+        let mapped_i = map_byte_index::<O>(i);
         // Set latch to false and output_enable to true for all except last column
         // Note: Check the logical index (i), not the mapped index (mapped_i)
-        data[mapped_i].0 = if i == COLS - 1 { 0 } else { 0b1000_0000 }; // OE bit
+        let oe = if i == COLS - 1 { 0 } else { 1 << P::OE }; // OE bit
+        data[mapped_i].0 = oe ^ inversion_mask::<P>();
         i += 1;
     }
     data
 }
 
-impl<const COLS: usize> Row<COLS> {
-    pub const fn new() -> Self {
+impl<
+        const COLS: usize,
+        O: Ordering,
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        const PULSES: usize,
+    > Row<COLS, O, ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, PULSES>
+{
+    const LEADING_TABLE: [[Address; ADDR_BEFORE]; 32] =
+        make_addr_table::<O, P, ADDR_BEFORE, PULSES>();
+    const ADDR_TABLE: [[Address; ADDR_AFTER]; 32] = make_addr_table::<O, P, ADDR_AFTER, PULSES>();
+
+    const LAYOUT_OK: () = {
+        assert!(
+            (ADDR_BEFORE == 0) != (ADDR_AFTER == 0),
+            "address words must be either before or after the pixel data"
+        );
+        assert!(pins_valid::<P>(), "pin map has overlapping signals");
+        assert!(
+            BLANK % 4 == 0 || !is_swapped::<O>(),
+            "blanking word count must be a multiple of 4 with a swapped ordering"
+        );
+        assert!(
+            DEAD % 4 == 0 || !is_swapped::<O>(),
+            "dead clock count must be a multiple of 4 with a swapped ordering"
+        );
+    };
+
+    const fn new() -> Self {
+        let () = Self::LAYOUT_OK;
         Self {
-            address: [Address::new(); 4],
+            leading: [Address::new(); ADDR_BEFORE],
+            blank: [Entry::new(); BLANK],
+            dead: [Entry::new(); DEAD],
+            address: [Address::new(); ADDR_AFTER],
             data: [Entry::new(); COLS],
+            _ordering: PhantomData,
         }
     }
 
     #[inline]
-    pub fn format(&mut self, addr: u8) {
+    fn format(&mut self, addr: u8) {
         // Use pre-computed address table
-        self.address.copy_from_slice(&ADDR_TABLE[addr as usize]);
+        self.address
+            .copy_from_slice(&Self::ADDR_TABLE[addr as usize]);
+
+        // Output disabled, latch closed, no colour
+        self.blank = [Entry(inversion_mask::<P>()); BLANK];
+        // Output enabled like the pixels that follow, no colour
+        self.dead = [Entry((1 << P::OE) ^ inversion_mask::<P>()); DEAD];
 
         // Use pre-computed data template - create it each time since we can't use generics in static
-        let data_template = make_data_template::<COLS>();
+        let data_template = make_data_template::<COLS, O, P>();
         self.data.copy_from_slice(&data_template);
     }
 
+    /// Leading address words latch the row whose data was shifted in before
+    /// this one, so the output is the same stream as with trailing words,
+    /// just starting at a different point.
+    #[inline]
+    fn format_leading(&mut self, prev_addr: u8) {
+        let () = Self::LAYOUT_OK;
+        self.leading
+            .copy_from_slice(&Self::LEADING_TABLE[prev_addr as usize]);
+    }
+
+    /// Disable the output for the first `blanked` entries of the lit window
+    /// (the dead clocks and all but the last pixel) and enable it for the rest.
+    fn set_oe_window(&mut self, blanked: usize) {
+        let oe = 1 << P::OE;
+        for i in 0..DEAD + COLS - 1 {
+            let entry = if i < DEAD {
+                &mut self.dead[map_byte_index::<O>(i)]
+            } else {
+                &mut self.data[map_byte_index::<O>(i - DEAD)]
+            };
+            // the bit is set for an enabled output unless OE is active low
+            if (i < blanked) == P::OE_ACTIVE_LOW {
+                entry.0 |= oe;
+            } else {
+                entry.0 &= !oe;
+            }
+        }
+    }
+
+    /// Number of leading entries of the lit window with the output
+    /// disabled, as set by [`Self::set_oe_window`].
+    fn oe_window(&self) -> usize {
+        let oe = 1 << P::OE;
+        (0..DEAD + COLS - 1)
+            .take_while(|&i| {
+                let entry = if i < DEAD {
+                    self.dead[map_byte_index::<O>(i)]
+                } else {
+                    self.data[map_byte_index::<O>(i - DEAD)]
+                };
+                (entry.0 & oe != 0) == P::OE_ACTIVE_LOW
+            })
+            .count()
+    }
+
     /// Fast clear that only zeros the color bits, preserving OE/LAT control bits
     #[inline]
-    pub fn clear_colors(&mut self) {
+    fn clear_colors(&mut self) {
         // Clear color bits while preserving timing and control bits
-        const COLOR_CLEAR_MASK: u8 = !0b0011_1111; // Clear bits 0-5 (R1,G1,B1,R2,G2,B2)
+        let color_clear_mask: u8 = !(color0_mask::<P>() | color1_mask::<P>());
 
         for entry in &mut self.data {
-            entry.0 &= COLOR_CLEAR_MASK;
+            entry.0 &= color_clear_mask;
         }
     }
 
     #[inline]
-    pub fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
         let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
-        let col = map_index(col);
-        self.data[col].set_color0_bits(bits);
+        let col = map_byte_index::<O>(col);
+        self.data[col].set_color0_bits::<P>(bits);
     }
 
     #[inline]
-    pub fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
         let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
-        let col = map_index(col);
-        self.data[col].set_color1_bits(bits);
+        let col = map_byte_index::<O>(col);
+        self.data[col].set_color1_bits::<P>(bits);
+    }
+
+    #[inline]
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        let word = self.data[map_byte_index::<O>(col)].0;
+        let (r, g, b) = if half == 0 {
+            (P::R1, P::G1, P::B1)
+        } else {
+            (P::R2, P::G2, P::B2)
+        };
+        (word >> r & 1 != 0, word >> g & 1 != 0, word >> b & 1 != 0)
     }
 }
 
-impl<const COLS: usize> Default for Row<COLS> {
+impl<
+        const COLS: usize,
+        O: Ordering,
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        const PULSES: usize,
+    > Default for Row<COLS, O, ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, PULSES>
+{
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-#[repr(C)]
-struct Frame<const ROWS: usize, const COLS: usize, const NROWS: usize> {
-    rows: [Row<COLS>; NROWS],
-}
+impl<
+        const COLS: usize,
+        O: Ordering,
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        const PULSES: usize,
+    > RowFormat for Row<COLS, O, ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, PULSES>
+{
+    #[inline]
+    fn format(&mut self, addr: u8, prev_addr: u8) {
+        self.format_leading(prev_addr);
+        Row::format(self, addr);
+    }
 
-impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Frame<ROWS, COLS, NROWS> {
-    pub const fn new() -> Self {
-        Self {
-            rows: [Row::new(); NROWS],
-        }
+    #[inline]
+    fn clear_colors(&mut self) {
+        Row::clear_colors(self);
     }
 
     #[inline]
-    pub fn format(&mut self) {
-        for (addr, row) in self.rows.iter_mut().enumerate() {
-            row.format(addr as u8);
-        }
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        Row::set_color0(self, col, r, g, b);
     }
 
-    /// Fast clear that only zeros the color bits, preserving control bits
     #[inline]
-    pub fn clear_colors(&mut self) {
-        for row in &mut self.rows {
-            row.clear_colors();
-        }
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        Row::set_color1(self, col, r, g, b);
     }
 
     #[inline]
-    pub fn set_pixel(&mut self, y: usize, x: usize, red: bool, green: bool, blue: bool) {
-        let row = &mut self.rows[if y < NROWS { y } else { y - NROWS }];
-        if y < NROWS {
-            row.set_color0(x, red, green, blue);
-        } else {
-            row.set_color1(x, red, green, blue);
-        }
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        Row::color(self, half, col)
     }
 }
 
-impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
-    for Frame<ROWS, COLS, NROWS>
+/// [`EntryFormat`] of the latched framebuffer: 8-bit words, with the row
+/// address sent in extra words per row for the external latch.
+///
+/// By default 4 address words follow the pixel data of each row. Glue logic
+/// that needs a different count, or the words in front of the pixel data,
+/// sets `ADDR_BEFORE` or `ADDR_AFTER` instead; exactly one of them must be
+/// non-zero. With a swapped [`Ordering`] the count must be a multiple of 4.
+///
+/// The latch is open for all but the last address word, which closes it:
+/// a count of `N` gives the latch circuit `N - 1` settle clocks with `LAT`
+/// high, 3 by default. CPLDs or discrete logic that need more gating
+/// cycles raise the count, fast ones can go down to 2 (one settle clock).
+///
+/// `P` assigns the signals to data-bus bits for boards that do not follow
+/// the reference wiring, see [`PinMap`].
+///
+/// `BLANK` adds that many entries with the output disabled right after the
+/// address words of every row, before the pixel data resumes. Slow row
+/// drivers settle during them, which removes ghosting between adjacent rows
+/// at the cost of `BLANK` clocks per row. They are shifted in ahead of the
+/// pixel data, so they fall off the end of the panel's shift register. With
+/// a swapped [`Ordering`] the count must be a multiple of 4.
+///
+/// `DEAD` adds that many no-op clocks in front of the pixel data of every
+/// row, for long cables where the first pixels otherwise arrive garbled.
+/// The output stays enabled during them, so brightness is unchanged. The
+/// same multiple-of-4 rule applies.
+///
+/// `PULSES` splits the address words into that many equal latch pulses,
+/// for panels or driver boards that only latch reliably on a second pulse:
+/// with the default 4 words and `PULSES = 2` the latch is open, closed, open
+/// and closed again. Each part must be at least 2 words long; widen the
+/// pulses by raising the address word count.
+///
+/// `FO` sets the order of the BCM frames in memory, e.g.
+/// [`BitReversed`](crate::format::BitReversed) against low-frequency
+/// flicker, or [`WeightedPlanes`](crate::format::WeightedPlanes) to store
+/// bit-plane `n` in `2^n` frames for drivers that show every frame for the
+/// same time, or [`Interleaved`](crate::format::Interleaved) to send every
+/// scan row of all frames back to back; see [`FrameOrder`].
+///
+/// Use it with [`crate::format::DmaFrameBuffer`]:
+/// ```rust
+/// use hub75_framebuffer::format::DmaFrameBuffer;
+/// use hub75_framebuffer::latched::LatchedFormat;
+/// use hub75_framebuffer::ordering::Linear;
+/// use hub75_framebuffer::{compute_frame_count, compute_rows};
+///
+/// const ROWS: usize = 32;
+/// const COLS: usize = 64;
+/// const BITS: u8 = 3;
+/// const NROWS: usize = compute_rows(ROWS);
+/// const FRAME_COUNT: usize = compute_frame_count(BITS);
+///
+/// // 2 address words in front of the pixel data of each row
+/// type FBType = DmaFrameBuffer<LatchedFormat<2, 0>, ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+///
+/// assert_eq!(FBType::bcm_chunk_bytes(), FRAME_COUNT * NROWS * (COLS + 2));
+/// let fb = FBType::new();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LatchedFormat<
+    const ADDR_BEFORE: usize = 0,
+    const ADDR_AFTER: usize = 4,
+    P: PinMap = DefaultPins,
+    const BLANK: usize = 0,
+    const DEAD: usize = 0,
+    FO: FrameOrder = Sequential,
+    const PULSES: usize = 1,
+>(PhantomData<(P, FO)>);
+
+// SAFETY: rows are `repr(C)` arrays of integer wrappers without padding, and
+// every bit pattern of them is valid.
+unsafe impl<
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        FO: FrameOrder,
+        const PULSES: usize,
+    > EntryFormat for LatchedFormat<ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, FO, PULSES>
 {
-    fn default() -> Self {
-        Self::new()
-    }
+    const WORD_SIZE: WordSize = WordSize::Eight;
+    const LAYOUT: Layout = Layout::Latched;
+    type Pad = ();
+    type FrameOrder = FO;
+    type Row<const COLS: usize, O: Ordering> =
+        Row<COLS, O, ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, PULSES>;
 }
 
 /// DMA-compatible framebuffer for HUB75 LED panels with external latch circuit
@@ -459,6 +866,9 @@ impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
 /// - `NROWS`: Number of rows per scan (typically half of ROWS)
 /// - `BITS`: Color depth (1-8 bits)
 /// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA byte [`Ordering`], defaults to [`DefaultOrdering`]
+/// - `A`: minimum buffer [`Alignment`], defaults to [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`], defaults to [`Normal`]
 ///
 /// # Helper Functions
 /// Use these functions to compute the correct values:
@@ -467,398 +877,193 @@ impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
 /// - `esp_hub75::compute_rows(ROWS)`: Computes the number of rows per scan
 ///
 /// # Memory Layout
-/// The buffer is aligned to ensure efficient DMA transfers and contains:
+/// The buffer is aligned to `A` and padded to a multiple of it. It contains:
 /// - An array of frames, each containing the full panel data
 /// - Each frame contains NROWS rows
 /// - Each row contains both data and address words
-#[derive(Copy, Clone)]
-#[repr(C)]
-#[repr(align(4))]
-pub struct DmaFrameBuffer<
+pub type DmaFrameBuffer<
     const ROWS: usize,
     const COLS: usize,
     const NROWS: usize,
     const BITS: u8,
     const FRAME_COUNT: usize,
-> {
-    frames: [Frame<ROWS, COLS, NROWS>; FRAME_COUNT],
-}
+    O = DefaultOrdering,
+    A = DefaultAlignment,
+    OR = Normal,
+> = crate::format::DmaFrameBuffer<LatchedFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>;
 
 impl<
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        FO: FrameOrder,
+        const PULSES: usize,
         const ROWS: usize,
         const COLS: usize,
         const NROWS: usize,
         const BITS: u8,
         const FRAME_COUNT: usize,
-    > Default for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    >
+    crate::format::DmaFrameBuffer<
+        LatchedFormat<ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, FO, PULSES>,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
 {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    /// Create a new framebuffer with the given number of frames.
-    /// The framebuffer is automatically formatted and ready to use.
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{latched::DmaFrameBuffer,compute_rows,compute_frame_count};
-    ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
-    ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// // Ready to use immediately
-    /// ```
-    #[must_use]
-    pub fn new() -> Self {
-        let mut fb = Self {
-            frames: [Frame::new(); FRAME_COUNT],
-        };
-        fb.format();
-        fb
-    }
-
-    /// Returns the number of BCM chunks in this framebuffer (always 1 for
-    /// single-plane framebuffers — the entire buffer is one contiguous chunk).
-    #[must_use]
-    pub const fn bcm_chunk_count() -> usize {
-        1
-    }
-
-    /// Returns the byte size of one BCM chunk (for single-plane framebuffers
-    /// this equals the total DMA buffer size, since BCM weighting is baked in).
-    #[must_use]
-    pub const fn bcm_chunk_bytes() -> usize {
-        core::mem::size_of::<[Frame<ROWS, COLS, NROWS>; FRAME_COUNT]>()
-    }
-
-    /// Format the framebuffer, setting up all control bits and clearing pixel data.
-    /// This method does a full format of all control bits and clears all pixel data.
-    /// Normally you don't need to call this as `new()` automatically formats the framebuffer.
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,latched::DmaFrameBuffer,compute_rows,compute_frame_count};
+    /// Reduce the overall brightness to `percent` by keeping the output
+    /// disabled for the first part of every row.
     ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    /// The output is normally enabled while a row is shifted in, except for
+    /// the last pixel. This rewrites the OE bits of every row in every frame
+    /// so only the last `percent` of that window is lit; colours are not
+    /// touched. It needs no extra hardware, but the steps are coarse on
+    /// narrow panels (one step per column).
     ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// // framebuffer.format(); // Not needed - new() already calls this
-    /// ```
-    pub fn format(&mut self) {
-        for frame in &mut self.frames {
-            frame.format();
-        }
-    }
-
-    /// Erase pixel colors while preserving control bits.
-    /// This is much faster than `format()` and is the typical way to clear the display.
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,latched::DmaFrameBuffer,compute_rows,compute_frame_count};
+    /// [`erase`](Self::erase) and drawing keep the setting; [`format`](Self::format)
+    /// restores full brightness. While [blanked](Self::blank) the new duty
+    /// takes effect on [`unblank`](Self::unblank).
     ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    /// # Panics
     ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// // ... draw some pixels ...
-    /// framebuffer.erase();
-    /// ```
-    #[inline]
-    pub fn erase(&mut self) {
-        for frame in &mut self.frames {
-            frame.clear_colors();
+    /// Panics if `percent` is greater than 100.
+    pub fn set_oe_duty(&mut self, percent: u8) {
+        assert!(percent <= 100, "percent must be at most 100");
+        let window = DEAD + COLS - 1;
+        let lit = (window * usize::from(percent) + 50) / 100;
+        if let Some(saved) = &mut self.blanked_oe {
+            *saved = (window - lit) as u16;
+            return;
         }
+        self.set_oe_windows(window - lit);
     }
 
-    /// Set a pixel in the framebuffer.
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,latched::DmaFrameBuffer,compute_rows,compute_frame_count};
-    /// use embedded_graphics::prelude::*;
-    ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    /// Turn the display dark by disabling the output in every row of every
+    /// frame, keeping the colours and addresses.
     ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// framebuffer.set_pixel(Point::new(10, 10), Color::RED);
-    /// ```
-    pub fn set_pixel(&mut self, p: Point, color: Color) {
-        if p.x < 0 || p.y < 0 {
+    /// Only the OE bits change, so this is instant compared to erasing and
+    /// redrawing; [`unblank`](Self::unblank) restores the previous pattern,
+    /// including a duty set with [`set_oe_duty`](Self::set_oe_duty).
+    /// Drawing while blanked updates the content shown after unblanking.
+    /// [`format`](Self::format) ends the blanking.
+    pub fn blank(&mut self) {
+        if self.blanked_oe.is_some() {
             return;
         }
-        self.set_pixel_internal(p.x as usize, p.y as usize, color);
+        self.blanked_oe = Some(self.frames[0].rows[0].oe_window() as u16);
+        self.set_oe_windows(DEAD + COLS - 1);
     }
 
-    #[inline]
-    fn frames_on(v: u8) -> usize {
-        // v / brightness_step but the compiler resolves the shift at build-time
-        (v as usize) >> (8 - BITS)
-    }
-
-    #[inline]
-    fn set_pixel_internal(&mut self, x: usize, y: usize, color: Rgb888) {
-        if x >= COLS || y >= ROWS {
-            return;
-        }
-
-        // Early exit for black pixels - common in UI backgrounds
-        // Only enabled when skip-black-pixels feature is active
-        #[cfg(feature = "skip-black-pixels")]
-        if color == Rgb888::BLACK {
-            return;
-        }
-
-        // Pre-compute how many frames each channel should be on
-        let red_frames = Self::frames_on(color.r());
-        let green_frames = Self::frames_on(color.g());
-        let blue_frames = Self::frames_on(color.b());
-
-        // Set the pixel in all frames based on pre-computed frame counts
-        for (frame_idx, frame) in self.frames.iter_mut().enumerate() {
-            frame.set_pixel(
-                y,
-                x,
-                frame_idx < red_frames,
-                frame_idx < green_frames,
-                frame_idx < blue_frames,
-            );
+    /// Restore the output enable pattern in place before
+    /// [`blank`](Self::blank). Does nothing if the display is not blanked.
+    pub fn unblank(&mut self) {
+        if let Some(blanked) = self.blanked_oe.take() {
+            self.set_oe_windows(usize::from(blanked));
         }
     }
-}
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > FrameBufferOperations for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    #[inline]
-    fn erase(&mut self) {
-        DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::erase(self);
-    }
-
-    #[inline]
-    fn set_pixel(&mut self, p: Point, color: Color) {
-        DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::set_pixel(self, p, color);
+    /// Whether the output is disabled by [`blank`](Self::blank).
+    #[must_use]
+    pub const fn is_blanked(&self) -> bool {
+        self.blanked_oe.is_some()
     }
-}
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::prelude::OriginDimensions
-    for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn size(&self) -> embedded_graphics::prelude::Size {
-        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+    fn set_oe_windows(&mut self, blanked: usize) {
+        for frame in &mut self.frames {
+            for row in &mut frame.rows {
+                row.set_oe_window(blanked);
+            }
+        }
     }
-}
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::draw_target::DrawTarget
-    for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Color = Color;
-
-    type Error = Infallible;
-
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            self.set_pixel_internal(pixel.0.x as usize, pixel.0.y as usize, pixel.1);
+    /// Reduce the brightness like [`Self::set_oe_duty`], but report a
+    /// percentage greater than 100 instead of panicking.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::InvalidPercent`] if `percent` is greater than
+    /// 100.
+    pub fn try_set_oe_duty(&mut self, percent: u8) -> Result<(), Hub75Error> {
+        if percent > 100 {
+            return Err(Hub75Error::InvalidPercent(percent));
         }
+        self.set_oe_duty(percent);
         Ok(())
     }
-}
 
-unsafe impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > ReadBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Word = u8;
-
-    unsafe fn read_buffer(&self) -> (*const u8, usize) {
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
-
-unsafe impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > ReadBuffer for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Word = u8;
-
-    unsafe fn read_buffer(&self) -> (*const u8, usize) {
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > core::fmt::Debug for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let brightness_step = 1 << (8 - BITS);
-        f.debug_struct("DmaFrameBuffer")
-            .field("size", &core::mem::size_of_val(&self.frames))
-            .field("frame_count", &self.frames.len())
-            .field("frame_size", &core::mem::size_of_val(&self.frames[0]))
-            .field("brightness_step", &&brightness_step)
-            .finish()
-    }
-}
-
-#[cfg(feature = "defmt")]
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > defmt::Format for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn format(&self, f: defmt::Formatter) {
-        let brightness_step = 1 << (8 - BITS);
-        defmt::write!(
-            f,
-            "DmaFrameBuffer<{}, {}, {}, {}, {}>",
-            ROWS,
-            COLS,
+    /// Check the control signals of every row in every frame, as clocked
+    /// out with the pin map `P` into the reference latch circuit.
+    ///
+    /// Every row must latch an address, keep the output disabled while
+    /// latching and while the latched address changes, and select each
+    /// scan row once per frame. Use it after modifying the stream through
+    /// [`as_bytes_mut`](Self::as_bytes_mut), when suspecting memory
+    /// corruption, or while developing new format parameters.
+    ///
+    /// # Errors
+    /// Returns the first broken invariant, see
+    /// [`ControlBitError`](crate::decode::ControlBitError).
+    pub fn verify_control_bits(&self) -> Result<(), crate::decode::ControlBitError> {
+        let bytes = self.as_bytes();
+        crate::decode::verify_control_signals(
+            bytes.len(),
+            Self::row_bytes(),
             NROWS,
-            BITS,
-            FRAME_COUNT
-        );
-        defmt::write!(f, " size: {}", core::mem::size_of_val(&self.frames));
-        defmt::write!(
-            f,
-            " frame_size: {}",
-            core::mem::size_of_val(&self.frames[0])
-        );
-        defmt::write!(f, " brightness_step: {}", brightness_step);
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > super::FrameBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn get_word_size(&self) -> super::WordSize {
-        super::WordSize::Eight
-    }
-
-    fn plane_count(&self) -> usize {
-        1
-    }
-
-    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
-        assert!(plane_idx == 0, "latched DmaFrameBuffer has only 1 plane");
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::prelude::OriginDimensions
-    for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn size(&self) -> embedded_graphics::prelude::Size {
-        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+            |i| crate::decode::latched_signals::<P>(bytes[map_byte_index::<O>(i)]),
+            true,
+        )
     }
 }
 
 impl<
+        const ADDR_BEFORE: usize,
+        const ADDR_AFTER: usize,
+        P: PinMap,
+        const BLANK: usize,
+        const DEAD: usize,
+        FO: FrameOrder,
+        const PULSES: usize,
         const ROWS: usize,
         const COLS: usize,
         const NROWS: usize,
         const BITS: u8,
         const FRAME_COUNT: usize,
-    > super::FrameBuffer for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > crate::RowIterExt
+    for crate::format::DmaFrameBuffer<
+        LatchedFormat<ADDR_BEFORE, ADDR_AFTER, P, BLANK, DEAD, FO, PULSES>,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
 {
-    fn get_word_size(&self) -> super::WordSize {
-        super::WordSize::Eight
-    }
+    type Word = u8;
 
-    fn plane_count(&self) -> usize {
-        1
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[u8])> + '_ {
+        Self::split_rows(self.active_bytes())
     }
 
-    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
-        assert!(plane_idx == 0, "latched DmaFrameBuffer has only 1 plane");
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
+    fn iter_words(&self) -> impl Iterator<Item = u8> + '_ {
+        Self::output_words(self.active_bytes())
     }
 }
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > MutableFrameBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-}
-
 #[cfg(test)]
 mod tests {
     extern crate std;
@@ -867,11 +1072,20 @@ mod tests {
     use std::vec;
 
     use super::*;
-    use crate::{FrameBuffer, WordSize};
+    use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+    use embedded_dma::{ReadBuffer, WriteBuffer};
+    use embedded_graphics::pixelcolor::Rgb888;
     use embedded_graphics::pixelcolor::RgbColor;
     use embedded_graphics::prelude::*;
     use embedded_graphics::primitives::{Circle, PrimitiveStyle, Rectangle};
 
+    type Frame<const ROWS: usize, const COLS: usize, const NROWS: usize, O = DefaultOrdering> =
+        crate::format::Frame<LatchedFormat, ROWS, COLS, NROWS, O>;
+
+    fn map_index(index: usize) -> usize {
+        map_byte_index::<DefaultOrdering>(index)
+    }
+
     const TEST_ROWS: usize = 32;
     const TEST_COLS: usize = 64;
     const TEST_NROWS: usize = TEST_ROWS / 2;
@@ -889,6 +1103,21 @@ mod tests {
         assert_eq!(addr.addr(), 0);
     }
 
+    #[test]
+    fn test_words_bits() {
+        let addr = Address::from_bits(0b0100_0011);
+        assert!(addr.latch());
+        assert!(!addr.output_enable());
+        assert_eq!(addr.addr(), 3);
+        assert_eq!(addr.bits(), 0b0100_0011);
+
+        let mut entry = Entry::new();
+        entry.set_blu2(true);
+        entry.set_red1(true);
+        assert_eq!(entry.bits(), 0b0010_0001);
+        assert_eq!(Entry::from_bits(entry.bits()), entry);
+    }
+
     #[test]
     fn test_address_setters() {
         let mut addr = Address::new();
@@ -963,7 +1192,7 @@ mod tests {
         let mut entry = Entry::new();
 
         let bits = (u8::from(true) << 2) | (u8::from(false) << 1) | u8::from(true); // b=1, g=0, r=1 = 0b101
-        entry.set_color0_bits(bits);
+        entry.set_color0_bits::<DefaultPins>(bits);
         assert_eq!(entry.red1(), true);
         assert_eq!(entry.grn1(), false);
         assert_eq!(entry.blu1(), true);
@@ -975,7 +1204,7 @@ mod tests {
         let mut entry = Entry::new();
 
         let bits = (u8::from(true) << 2) | (u8::from(true) << 1) | u8::from(false); // b=1, g=1, r=0 = 0b110
-        entry.set_color1_bits(bits);
+        entry.set_color1_bits::<DefaultPins>(bits);
         assert_eq!(entry.red2(), false);
         assert_eq!(entry.grn2(), true);
         assert_eq!(entry.blu2(), true);
@@ -1168,7 +1397,15 @@ mod tests {
     #[test]
     fn test_dma_framebuffer_format() {
         let mut fb = TestFrameBuffer {
+            _alignment: [],
+            _orientation: PhantomData,
+            _align: (),
             frames: [Frame::new(); TEST_FRAME_COUNT],
+            dropped_frames: 0,
+            dropped_bits: 0,
+            blanked_oe: None,
+            #[cfg(feature = "instrument")]
+            stats: crate::format::DrawStats::default(),
         };
         fb.format();
 
@@ -1461,6 +1698,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_buffer_receives_stream() {
+        let mut source = TestFrameBuffer::new();
+        source.set_pixel(Point::new(3, 4), Color::RED);
+        // lower half
+        source.set_pixel(Point::new(10, 18), Color::WHITE);
+
+        let mut fb = TestFrameBuffer::new();
+        let (ptr, len) = unsafe { fb.write_buffer() };
+        assert_eq!(len, source.as_bytes().len());
+        // simulate the DMA transfer
+        unsafe { core::ptr::copy_nonoverlapping(source.as_bytes().as_ptr(), ptr, len) };
+        assert_eq!(fb.as_bytes(), source.as_bytes());
+
+        let mut fb_ref = &mut fb;
+        let (ref_ptr, ref_len) =
+            unsafe { <&mut TestFrameBuffer as WriteBuffer>::write_buffer(&mut fb_ref) };
+        assert_eq!((ref_ptr, ref_len), (ptr, len));
+    }
+
     #[test]
     fn test_framebuffer_trait() {
         let fb = TestFrameBuffer::new();
@@ -1471,6 +1728,33 @@ mod tests {
         assert_eq!(fb_ref.get_word_size(), WordSize::Eight);
     }
 
+    /// Draws through the trait only, as generic application code does.
+    fn draw_generic<FB: MutableFrameBuffer>(fb: &mut FB) {
+        Rectangle::new(Point::new(2, 3), Size::new(4, 2))
+            .into_styled(PrimitiveStyle::with_fill(Color::RED))
+            .draw(fb)
+            .unwrap();
+        assert_eq!(fb.plane_count(), 1);
+    }
+
+    #[test]
+    fn test_mutable_framebuffer_trait() {
+        let mut fb = TestFrameBuffer::new();
+        draw_generic(&mut fb);
+        let mut fb_ref = &mut fb;
+        draw_generic(&mut fb_ref);
+        Pixel(Point::new(9, 4), Color::RED)
+            .draw(&mut fb_ref)
+            .unwrap();
+
+        let mut expected = TestFrameBuffer::new();
+        for p in Rectangle::new(Point::new(2, 3), Size::new(4, 2)).points() {
+            expected.set_pixel(p, Color::RED);
+        }
+        expected.set_pixel(Point::new(9, 4), Color::RED);
+        assert!(fb.as_bytes() == expected.as_bytes());
+    }
+
     #[test]
     fn test_debug_formatting() {
         let fb = TestFrameBuffer::new();
@@ -1502,15 +1786,583 @@ mod tests {
         assert_eq!(map_index(5), 7); // 5 & !0b11 | 3 = 4 | 3 = 7
     }
 
+    #[test]
+    fn test_both_orderings_in_one_build() {
+        use crate::ordering::{Esp32, Linear};
+
+        let mut linear = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new();
+        let mut esp32 = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >::new();
+
+        linear.set_pixel(Point::new(1, 0), Color::RED);
+        esp32.set_pixel(Point::new(1, 0), Color::RED);
+
+        assert!(linear.frames[0].rows[0].data[1].red1());
+        assert!(esp32.frames[0].rows[0].data[3].red1());
+        assert!(!esp32.frames[0].rows[0].data[1].red1());
+
+        // the fourth address word is the all-zero clear cycle
+        assert_eq!(linear.frames[0].rows[0].address[3].0, 0);
+        assert_eq!(esp32.frames[0].rows[0].address[1].0, 0);
+        assert!(esp32.frames[0].rows[0].address[3].latch());
+    }
+
+    #[test]
+    fn test_configurable_address_words() {
+        use crate::ordering::Linear;
+
+        type LeadingFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<4, 0>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type TwoWordFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type TrailingFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+
+        let mut trailing = TrailingFB::new();
+        let mut leading = LeadingFB::new();
+        trailing.set_pixel(Point::new(3, 20), Color::WHITE);
+        leading.set_pixel(Point::new(3, 20), Color::WHITE);
+
+        // leading words latch the previous row, so the stream is the same
+        // one rotated by the address words
+        let rotated: std::vec::Vec<u8> = leading.as_bytes()[4..]
+            .iter()
+            .chain(&leading.as_bytes()[..4])
+            .copied()
+            .collect();
+        assert_eq!(rotated, trailing.as_bytes());
+        assert_eq!(
+            leading.frames[0].rows[0].leading[0].addr(),
+            TEST_NROWS as u8 - 1
+        );
+
+        let two = TwoWordFB::new();
+        assert_eq!(
+            TwoWordFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * TEST_NROWS * (TEST_COLS + 2)
+        );
+        let row = &two.frames[0].rows[5];
+        assert!(row.address[0].latch());
+        assert!(!row.address[1].latch());
+        assert!(row.address.iter().all(|addr| addr.addr() == 5));
+    }
+
+    #[test]
+    fn test_custom_pin_map() {
+        use crate::ordering::Linear;
+
+        // OE/LAT on the two lowest bits, colours and address shifted up
+        #[derive(Debug, Clone, Copy)]
+        struct Swapped;
+        impl PinMap for Swapped {
+            const OE: u8 = 0;
+            const LAT: u8 = 1;
+            const R1: u8 = 2;
+            const G1: u8 = 3;
+            const B1: u8 = 4;
+            const R2: u8 = 5;
+            const G2: u8 = 6;
+            const B2: u8 = 7;
+            const ADDR: [u8; 5] = [2, 3, 4, 5, 6];
+        }
+
+        type SwappedFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, Swapped>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let mut fb = SwappedFB::new();
+        let row = &fb.frames[0].rows[5];
+        assert_eq!(row.data[0].0, 0b0000_0001);
+        assert_eq!(row.data[TEST_COLS - 1].0, 0);
+        assert_eq!(row.address[0].0, 0b0001_0110);
+        assert_eq!(row.address[3].0, 0b0001_0100);
+
+        fb.set_pixel(Point::new(1, 5), Color::RED);
+        // row 21 is the lower half of scan row 5
+        fb.set_pixel(Point::new(1, 21), Color::BLUE);
+        assert_eq!(fb.frames[0].rows[5].data[1].0, 0b1000_0101);
+
+        fb.erase();
+        assert_eq!(fb.frames[0].rows[5].data[1].0, 0b0000_0001);
+    }
+
+    #[test]
+    fn test_scrambled_address_lines() {
+        // E, D, C, B, A on bits 0..5
+        #[derive(Debug, Clone, Copy)]
+        struct Reversed;
+        impl PinMap for Reversed {
+            const ADDR: [u8; 5] = [4, 3, 2, 1, 0];
+        }
+
+        let table = make_addr_table::<crate::ordering::Linear, Reversed, 4, 1>();
+        for (addr, words) in table.iter().enumerate() {
+            let expected = (addr as u8).reverse_bits() >> 3;
+            for word in words {
+                assert_eq!(word.addr(), expected);
+            }
+            assert!(words[0].latch());
+            assert!(!words[3].latch());
+        }
+        assert!(pins_valid::<Reversed>());
+    }
+
+    #[test]
+    fn test_pin_map_validation() {
+        #[derive(Debug, Clone, Copy)]
+        struct ColourOnLatch;
+        impl PinMap for ColourOnLatch {
+            const R1: u8 = 6;
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        struct AddressOnOe;
+        impl PinMap for AddressOnOe {
+            const ADDR: [u8; 5] = [0, 1, 2, 3, 7];
+        }
+
+        #[derive(Debug, Clone, Copy)]
+        struct OutOfRange;
+        impl PinMap for OutOfRange {
+            const OE: u8 = 8;
+        }
+
+        assert!(pins_valid::<DefaultPins>());
+        assert!(!pins_valid::<ColourOnLatch>());
+        assert!(!pins_valid::<AddressOnOe>());
+        assert!(!pins_valid::<OutOfRange>());
+    }
+
+    #[test]
+    fn test_blanking_words_after_latch() {
+        use crate::ordering::Linear;
+
+        type BlankFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 4>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let row_len = 4 + TEST_COLS + 4;
+        assert_eq!(
+            BlankFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * TEST_NROWS * row_len
+        );
+
+        let mut fb = BlankFB::new();
+        fb.fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(TEST_COLS as u32, TEST_ROWS as u32)),
+            Color::WHITE,
+        )
+        .unwrap();
+
+        let bytes = fb.as_bytes();
+        for row in bytes.chunks(row_len) {
+            // blanked entries first, then lit pixels, then the address words
+            assert_eq!(row[..4], [0; 4]);
+            assert_eq!(row[4], 0b1011_1111);
+            assert!(Address(row[row_len - 4]).latch());
+        }
+    }
+
+    #[test]
+    fn test_dead_clock_padding() {
+        use crate::ordering::Linear;
+
+        type PaddedFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 0, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let row_len = 2 + TEST_COLS + 4;
+        assert_eq!(
+            PaddedFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * TEST_NROWS * row_len
+        );
+
+        let mut fb = PaddedFB::new();
+        fb.set_pixel(Point::new(0, 0), Color::RED);
+        fb.erase();
+        fb.set_pixel(Point::new(0, 0), Color::GREEN);
+
+        let row = &fb.as_bytes()[..row_len];
+        // OE on, no colour, then the first pixel
+        assert_eq!(row[..2], [0b1000_0000; 2]);
+        assert_eq!(row[2], 0b1000_0010);
+    }
+
+    #[test]
+    fn test_set_oe_duty() {
+        use crate::ordering::Linear;
+
+        let mut fb = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new();
+        fb.set_pixel(Point::new(0, 0), Color::WHITE);
+
+        // 63 entries are lit at full duty, half of that rounds to 32
+        fb.set_oe_duty(50);
+        for frame in &fb.frames {
+            for row in &frame.rows {
+                let lit: std::vec::Vec<bool> = row.data.iter().map(Entry::output_enable).collect();
+                assert_eq!(lit.iter().filter(|&&on| on).count(), 32);
+                assert!(lit[..31].iter().all(|&on| !on));
+                assert!(lit[31..TEST_COLS - 1].iter().all(|&on| on));
+                assert!(!lit[TEST_COLS - 1]);
+            }
+        }
+        // colours are kept
+        assert!(fb.frames[0].rows[0].data[0].red1());
+
+        fb.erase();
+        assert!(!fb.frames[0].rows[0].data[0].output_enable());
+
+        fb.set_oe_duty(0);
+        assert!(fb
+            .as_bytes()
+            .chunks(TEST_COLS + 4)
+            .all(|row| row[..TEST_COLS]
+                .iter()
+                .all(|&entry| entry & 0b1000_0000 == 0)));
+
+        fb.format();
+        assert_eq!(fb.as_bytes(), DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new().as_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "percent must be at most 100")]
+    fn test_set_oe_duty_out_of_range() {
+        TestFrameBuffer::new().set_oe_duty(101);
+    }
+
+    #[test]
+    fn test_try_set_oe_duty() {
+        let mut fb = TestFrameBuffer::new();
+        let before = fb;
+        assert_eq!(
+            fb.try_set_oe_duty(101),
+            Err(Hub75Error::InvalidPercent(101))
+        );
+        assert_eq!(fb.as_bytes(), before.as_bytes());
+        assert_eq!(fb.try_set_oe_duty(50), Ok(()));
+        let mut expected = before;
+        expected.set_oe_duty(50);
+        assert_eq!(fb.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_blank() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(0, 0), Color::WHITE);
+        fb.set_oe_duty(50);
+        let shown = fb;
+
+        fb.blank();
+        assert!(fb.is_blanked());
+        assert!(fb.as_bytes().iter().all(|&entry| entry & 0b1000_0000 == 0));
+        assert!(fb.verify_control_bits().is_ok());
+        fb.blank();
+        fb.unblank();
+        assert!(!fb.is_blanked());
+        assert_eq!(fb.as_bytes(), shown.as_bytes());
+
+        // drawing and a new duty while blanked show after unblanking
+        fb.blank();
+        fb.set_pixel(Point::new(1, 0), Color::WHITE);
+        fb.set_oe_duty(100);
+        assert!(!fb.frames[0].rows[0].data[0].output_enable());
+        fb.unblank();
+        let mut expected = TestFrameBuffer::new();
+        expected.set_pixel(Point::new(0, 0), Color::WHITE);
+        expected.set_pixel(Point::new(1, 0), Color::WHITE);
+        assert_eq!(fb.as_bytes(), expected.as_bytes());
+
+        fb.blank();
+        fb.format();
+        assert!(!fb.is_blanked());
+        fb.unblank();
+        assert_eq!(fb.as_bytes(), TestFrameBuffer::new().as_bytes());
+    }
+
+    #[test]
+    fn test_bit_reversed_frame_order() {
+        use crate::format::BitReversed;
+        use crate::ordering::Linear;
+
+        type ReversedFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 0, 0, BitReversed>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        // positions 0..7 hold thresholds 3, 1, 5, 0, 4, 2, 6
+        let ranks: std::vec::Vec<usize> = (0..TEST_FRAME_COUNT)
+            .map(|pos| BitReversed::rank(pos, TEST_FRAME_COUNT))
+            .collect();
+        assert_eq!(ranks, [3, 1, 5, 0, 4, 2, 6]);
+
+        let mut fb = ReversedFB::new();
+        // 3 of 7 frames lit
+        fb.set_pixel(Point::new(2, 1), Color::new(96, 0, 0));
+        let lit: std::vec::Vec<bool> = fb
+            .frames
+            .iter()
+            .map(|frame| frame.rows[1].data[2].red1())
+            .collect();
+        assert_eq!(lit, [false, true, false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_weighted_planes_frame_order() {
+        use crate::format::WeightedPlanes;
+        use crate::ordering::Linear;
+
+        type WeightedFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 0, 0, WeightedPlanes>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        assert_eq!(
+            WeightedFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * WeightedFB::frame_bytes()
+        );
+
+        let mut fb = WeightedFB::new();
+        // 5 of 7 frames lit: plane 0 once and plane 2 four times
+        fb.set_pixel(Point::new(2, 1), Color::new(160, 0, 0));
+        // 2 of 7 frames lit: plane 1 twice
+        fb.set_pixel(Point::new(3, 1), Color::new(0, 64, 0));
+        let red: std::vec::Vec<bool> = fb
+            .frames
+            .iter()
+            .map(|frame| frame.rows[1].data[2].red1())
+            .collect();
+        assert_eq!(red, [true, false, false, true, true, true, true]);
+        let green: std::vec::Vec<bool> = fb
+            .frames
+            .iter()
+            .map(|frame| frame.rows[1].data[3].grn1())
+            .collect();
+        assert_eq!(green, [false, true, true, false, false, false, false]);
+    }
+
     #[test]
     fn test_memory_alignment() {
         let fb = TestFrameBuffer::new();
         let ptr = &fb as *const _ as usize;
 
-        // Should be 4-byte aligned as specified in repr(align(4))
+        // Should be 4-byte aligned by the default alignment
         assert_eq!(ptr % 4, 0);
     }
 
+    #[test]
+    fn test_slice_views_match_read_buffer() {
+        let mut fb = TestFrameBuffer::new();
+        let (ptr, len) = unsafe { fb.read_buffer() };
+
+        assert_eq!(fb.as_bytes().as_ptr(), ptr);
+        assert_eq!(fb.as_bytes().len(), len);
+        assert_eq!(fb.as_bytes().as_ptr() as usize % 4, 0);
+
+        fb.set_pixel(Point::new(0, 0), Color::RED);
+        let byte = fb.as_bytes()[map_index(0)];
+        assert_eq!(byte, fb.frames[0].rows[0].data[map_index(0)].0);
+        assert_ne!(byte & 1, 0);
+
+        fb.as_bytes_mut().fill(0);
+        assert!(fb.as_bytes().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_refresh_and_bandwidth_estimates() {
+        assert_eq!(TestFrameBuffer::estimated_refresh_hz(10_000_000), 1313);
+        assert_eq!(
+            TestFrameBuffer::dma_bandwidth_bytes_per_sec(10_000_000),
+            10_000_000
+        );
+    }
+
+    #[test]
+    fn test_chunks_split_on_row_boundaries() {
+        let fb = TestFrameBuffer::new();
+        let row_bytes = TEST_COLS + 4;
+        let bytes = fb.as_bytes();
+
+        let mut offset = 0;
+        for (ptr, len) in fb.chunks(row_bytes * 3 + 1) {
+            assert_eq!(ptr, bytes[offset..].as_ptr());
+            assert_eq!(len % row_bytes, 0);
+            assert!(len <= row_bytes * 3);
+            offset += len;
+        }
+        assert_eq!(offset, bytes.len());
+        assert_eq!(fb.chunks(bytes.len()).count(), 1);
+    }
+
+    #[test]
+    fn test_rows_in_stream_order() {
+        use crate::RowIterExt;
+
+        let fb = TestFrameBuffer::new();
+        let row_bytes = TEST_COLS + 4;
+        let mut bytes = fb.as_bytes();
+        for (i, (frame, row, data)) in fb.iter_rows().enumerate() {
+            assert_eq!((frame, row), (i / TEST_NROWS, i % TEST_NROWS));
+            assert_eq!(data, &bytes[..row_bytes]);
+            bytes = &bytes[row_bytes..];
+        }
+        assert!(bytes.is_empty());
+        assert_eq!(fb.iter_rows().count(), TEST_FRAME_COUNT * TEST_NROWS);
+    }
+
+    #[test]
+    fn test_copy_colors_from() {
+        let mut front = TestFrameBuffer::new();
+        front.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        let mut back = TestFrameBuffer::new();
+        back.set_oe_duty(50);
+        back.set_pixel(Point::new(10, 10), Color::BLUE);
+
+        let mut expected = back;
+        expected.erase();
+        expected.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        back.copy_colors_from(&front);
+        assert_eq!(back.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_new_unformatted() {
+        let mut fb = TestFrameBuffer::new_unformatted();
+        assert!(!fb.is_formatted());
+        fb.format();
+        assert!(fb.is_formatted());
+        assert_eq!(fb.as_bytes(), TestFrameBuffer::new().as_bytes());
+    }
+
+    #[test]
+    fn test_get_pixel() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(3, 5), Color::new(255, 128, 31));
+        fb.set_pixel(Point::new(63, 31), Color::new(32, 0, 200));
+
+        assert_eq!(
+            fb.get_pixel(Point::new(3, 5)),
+            Some(Color::new(224, 128, 0))
+        );
+        assert_eq!(
+            fb.get_pixel(Point::new(63, 31)),
+            Some(Color::new(32, 0, 192))
+        );
+        assert_eq!(fb.get_pixel(Point::new(64, 31)), None);
+        assert_eq!(
+            fb.pixels()
+                .filter(|Pixel(_, color)| *color != Color::BLACK)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_words_in_output_order() {
+        use crate::ordering::{Esp32, Linear};
+        use crate::RowIterExt;
+
+        let mut linear = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new();
+        let mut esp32 = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >::new();
+        for (x, color) in (0..).zip([Color::RED, Color::GREEN, Color::BLUE]) {
+            linear.set_pixel(Point::new(x, 20), color);
+            esp32.set_pixel(Point::new(x, 20), color);
+        }
+
+        assert!(linear.iter_words().eq(linear.as_bytes().iter().copied()));
+        assert!(esp32.iter_words().eq(linear.as_bytes().iter().copied()));
+        assert_ne!(esp32.as_bytes(), linear.as_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "smaller than a single row")]
+    fn test_chunks_smaller_than_row() {
+        let fb = TestFrameBuffer::new();
+        let _ = fb.chunks(TEST_COLS + 4 - 1);
+    }
+
     #[test]
     fn test_color_values() {
         let mut fb = TestFrameBuffer::new();
@@ -1651,6 +2503,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verify_control_bits() {
+        use crate::decode::ControlBitError;
+        use crate::ordering::Linear;
+
+        type LinearFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+        type Leading = crate::format::DmaFrameBuffer<
+            LatchedFormat<4, 0>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >;
+
+        let mut fb = LinearFB::new();
+        fb.set_pixel(Point::new(5, 5), Color::WHITE);
+        fb.set_oe_duty(60);
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+        assert_eq!(Leading::new().verify_control_bits(), Ok(()));
+
+        // the address words of row 3
+        let row_bytes = LinearFB::row_bytes();
+        let address = 3 * row_bytes + TEST_COLS..4 * row_bytes;
+        let mut broken = fb;
+        for byte in &mut broken.as_bytes_mut()[address.clone()] {
+            *byte &= !(1 << 6);
+        }
+        assert_eq!(
+            broken.verify_control_bits(),
+            Err(ControlBitError::MissingLatch { frame: 0, row: 3 })
+        );
+        let mut broken = fb;
+        for byte in &mut broken.as_bytes_mut()[address] {
+            *byte |= 1 << 7;
+        }
+        assert_eq!(
+            broken.verify_control_bits(),
+            Err(ControlBitError::LatchWhileLit { frame: 0, row: 3 })
+        );
+    }
+
     #[test]
     fn test_erase() {
         let mut fb = TestFrameBuffer::new();
@@ -1736,10 +2631,125 @@ mod tests {
         assert_eq!(row.data[mapped_col_1].latch(), original_latch_1);
     }
 
+    #[test]
+    fn test_settle_word_count() {
+        use crate::ordering::Esp32;
+
+        // 7 settle clocks with the latch open, then one to close it
+        type SlowLatchFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 8>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >;
+
+        let fb = SlowLatchFB::new();
+        assert_eq!(
+            SlowLatchFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * TEST_NROWS * (TEST_COLS + 8)
+        );
+        for (addr, row) in fb.frames[0].rows.iter().enumerate() {
+            let latches: std::vec::Vec<bool> = (0..8)
+                .map(|i| row.address[map_byte_index::<Esp32>(i)].latch())
+                .collect();
+            assert_eq!(latches, [true, true, true, true, true, true, true, false]);
+            assert!(row.address.iter().all(|a| a.addr() as usize == addr));
+            assert!(row.address.iter().all(|a| !a.output_enable()));
+        }
+    }
+
+    #[test]
+    fn test_inverted_control_polarity() {
+        use crate::ordering::Linear;
+
+        #[derive(Debug, Clone, Copy)]
+        struct Inverted;
+        impl PinMap for Inverted {
+            const OE_ACTIVE_LOW: bool = true;
+            const LAT_ACTIVE_LOW: bool = true;
+        }
+
+        type InvertedFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, Inverted, 4, 4>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type NormalFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 4, 4>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let mut inverted = InvertedFB::new();
+        let mut normal = NormalFB::new();
+        for fb in [&mut inverted as &mut dyn FrameBufferOperations, &mut normal] {
+            fb.set_pixel(Point::new(5, 3), Color::WHITE);
+            fb.set_pixel(Point::new(6, 20), Color::new(128, 64, 0));
+        }
+        inverted.set_oe_duty(40);
+        normal.set_oe_duty(40);
+
+        // only OE and LAT differ, in every word
+        for (inv, norm) in inverted.as_bytes().iter().zip(normal.as_bytes()) {
+            assert_eq!(inv ^ 0b1100_0000, *norm);
+        }
+    }
+
+    #[test]
+    fn test_double_latch_pulses() {
+        use crate::format::Sequential;
+        use crate::ordering::Linear;
+
+        type DoubleFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<0, 4, DefaultPins, 0, 0, Sequential, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type WideFB = crate::format::DmaFrameBuffer<
+            LatchedFormat<8, 0, DefaultPins, 0, 0, Sequential, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let double = DoubleFB::new();
+        for (addr, row) in double.frames[0].rows.iter().enumerate() {
+            let latches: std::vec::Vec<bool> = row.address.iter().map(Address::latch).collect();
+            assert_eq!(latches, [true, false, true, false]);
+            assert!(row.address.iter().all(|a| a.addr() as usize == addr));
+        }
+
+        let wide = WideFB::new();
+        let latches: std::vec::Vec<bool> = wide.frames[0].rows[3]
+            .leading
+            .iter()
+            .map(Address::latch)
+            .collect();
+        assert_eq!(latches, [true, true, true, false, true, true, true, false]);
+    }
+
     #[test]
     fn test_make_addr_table_function() {
         // Test the make_addr_table function directly to ensure code coverage
-        let table = make_addr_table();
+        let table = make_addr_table::<DefaultOrdering, DefaultPins, 4, 1>();
 
         // Verify basic properties of the generated table
         assert_eq!(table.len(), 32); // Should have 32 address entries (0-31)
@@ -1771,7 +2781,7 @@ mod tests {
     #[test]
     fn test_make_data_template_function() {
         // Test the make_data_template function directly to ensure code coverage
-        let template = make_data_template::<TEST_COLS>();
+        let template = make_data_template::<TEST_COLS, DefaultOrdering, DefaultPins>();
 
         // Verify basic properties
         assert_eq!(template.len(), TEST_COLS);
@@ -1789,7 +2799,7 @@ mod tests {
         assert_eq!(oe_false_count, 1);
 
         // Test with a small template size to verify edge cases
-        let small_template = make_data_template::<4>();
+        let small_template = make_data_template::<4, DefaultOrdering, DefaultPins>();
         assert_eq!(small_template.len(), 4);
 
         let oe_false_count = small_template
@@ -1802,7 +2812,7 @@ mod tests {
         // because the mapping function assumes at least 4 columns for proper mapping
         #[cfg(not(feature = "esp32-ordering"))]
         {
-            let single_template = make_data_template::<1>();
+            let single_template = make_data_template::<1, DefaultOrdering, DefaultPins>();
             assert_eq!(single_template.len(), 1);
             assert_eq!(single_template[0].output_enable(), false); // Single column should have OE=false
             assert_eq!(single_template[0].latch(), false);
@@ -1828,7 +2838,7 @@ mod tests {
             }
 
             // Compare with table
-            let table_addresses = &ADDR_TABLE[addr as usize];
+            let table_addresses = &Row::<TEST_COLS>::ADDR_TABLE[addr as usize];
             for i in 0..4 {
                 assert_eq!(table_addresses[i].0, expected_addresses[i].0);
             }
@@ -1941,10 +2951,30 @@ mod tests {
 
         // Address words should remain precomputed table values
         for (i, addr) in row0.address.iter().enumerate() {
-            assert_eq!(addr.0, ADDR_TABLE[0][i].0);
+            assert_eq!(addr.0, Row::<TEST_COLS>::ADDR_TABLE[0][i].0);
         }
     }
 
+    /// Sets and erases pixels through the trait only.
+    fn operate_generic<FB: FrameBufferOperations>(fb: &mut FB) {
+        fb.erase();
+        fb.set_pixel(Point::new(8, 3), Color::BLUE);
+    }
+
+    #[test]
+    fn test_framebuffer_operations_trait_generic() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(1, 1), Color::RED);
+        operate_generic(&mut fb);
+        let mut expected = TestFrameBuffer::new();
+        expected.set_pixel(Point::new(8, 3), Color::BLUE);
+        assert!(fb.as_bytes() == expected.as_bytes());
+
+        let mut fb_ref = &mut fb;
+        operate_generic(&mut fb_ref);
+        assert!(fb.as_bytes() == expected.as_bytes());
+    }
+
     #[test]
     fn test_framebuffer_operations_trait_set_pixel() {
         let mut fb = TestFrameBuffer::new();
@@ -0,0 +1,403 @@
+//! 16-bit latched framebuffer driving two chains through one latch circuit.
+//!
+//! This is the [`crate::latched`] format widened to 16-bit words for
+//! microcontrollers with a 16-bit parallel bus. The low byte is exactly the
+//! 8-bit latched word of the first chain; the high byte carries the colour
+//! bits of a second chain that shares the latch circuit, `CLK`, `OE` and the
+//! row address. Twice the panels are driven at the same refresh rate, for
+//! twice the memory of one 8-bit chain.
+//!
+//! # Display Layout
+//! The second chain continues below the first: with panels of `PANEL_ROWS`
+//! rows, `NROWS = compute_rows(PANEL_ROWS)` and `ROWS = 2 * PANEL_ROWS`, so
+//! rows `0..PANEL_ROWS` are drawn on the first chain and the rest on the
+//! second. Use [`crate::tiling::TiledFrameBuffer`] to arrange the panels
+//! differently.
+//!
+//! # HUB75 Signal Bit Mapping (16-bit words)
+//! ```text
+//! Entry word
+//! ┌─15─┬─14─┬─13─┬─12─┬─11─┬─10─┬─9──┬─8──┬─7──┬──6──┬─5──┬─4──┬─3──┬─2──┬─1──┬─0──┐
+//! │    │    │ B4 │ G4 │ R4 │ B3 │ G3 │ R3 │ OE │ LAT │ B2 │ G2 │ R2 │ B1 │ G1 │ R1 │
+//! └────┴────┴────┴────┴────┴────┴────┴────┴────┴─────┴────┴────┴────┴────┴────┴────┘
+//!   chain 2 (R3-B4) ──┘                      └── same bits as the 8-bit format
+//!
+//! Address word
+//! ┌─15..8─┬─7──┬──6──┬─5─┬─4─┬─3─┬─2─┬─1─┬─0─┐
+//! │   0   │ OE │ LAT │   │ E │ D │ C │ B │ A │
+//! └───────┴────┴─────┴───┴───┴───┴───┴───┴───┘
+//! ```
+//!
+//! Each row is `COLS` entries followed by 4 address words, with the same
+//! timing as the 8-bit format.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::latched16::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows, Color};
+//!
+//! // two chains of 64x32 panels
+//! const PANEL_ROWS: usize = 32;
+//! const ROWS: usize = 2 * PANEL_ROWS;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(PANEL_ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let mut fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! assert_eq!(fb.size(), Size::new(64, 64));
+//!
+//! // spans both chains
+//! Rectangle::new(Point::new(0, 24), Size::new(8, 16))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::GREEN))
+//!     .draw(&mut fb)
+//!     .unwrap();
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::align::DefaultAlignment;
+use crate::export::Layout;
+use crate::format::{EntryFormat, RowFormat, Sequential};
+use crate::ordering::{map_half_word_index, DefaultOrdering, Ordering};
+use crate::orientation::Normal;
+use crate::WordSize;
+use bitfield::bitfield;
+
+bitfield! {
+    /// 16-bit address word; the low byte matches the 8-bit latched format.
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    struct Address(u16);
+    impl Debug;
+    pub output_enable, set_output_enable: 7;
+    pub latch, set_latch: 6;
+    pub addr, set_addr: 4, 0;
+}
+
+bitfield! {
+    /// 16-bit pixel word carrying both halves of two chains.
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    struct Entry(u16);
+    impl Debug;
+    pub blu4, set_blu4: 13;
+    pub grn4, set_grn4: 12;
+    pub red4, set_red4: 11;
+    pub blu3, set_blu3: 10;
+    pub grn3, set_grn3: 9;
+    pub red3, set_red3: 8;
+    pub output_enable, set_output_enable: 7;
+    pub latch, set_latch: 6;
+    pub blu2, set_blu2: 5;
+    pub grn2, set_grn2: 4;
+    pub red2, set_red2: 3;
+    pub blu1, set_blu1: 2;
+    pub grn1, set_grn1: 1;
+    pub red1, set_red1: 0;
+}
+
+/// Position of the red bit of each display half, see [`RowFormat::set_color`].
+const HALF_SHIFT: [u32; 4] = [0, 3, 8, 11];
+const COLOR_MASK: u16 = 0b0011_1111_0011_1111;
+const OE_BIT: u16 = 1 << 7;
+const LAT_BIT: u16 = 1 << 6;
+
+/// Pre-computed address words for all possible row addresses (0-31).
+const fn make_addr_table<O: Ordering>() -> [[Address; 4]; 32] {
+    let mut tbl = [[Address(0); 4]; 32];
+    let mut addr = 0;
+    while addr < 32 {
+        let mut i = 0;
+        while i < 4 {
+            let latch_bit = if i == 3 { 0 } else { LAT_BIT };
+            tbl[addr][map_half_word_index::<O>(i)].0 = latch_bit | addr as u16;
+            i += 1;
+        }
+        addr += 1;
+    }
+    tbl
+}
+
+/// Pre-computed pixel words: output enabled for all but the last column.
+const fn make_data_template<const COLS: usize, O: Ordering>() -> [Entry; COLS] {
+    let mut data = [Entry(0); COLS];
+    let mut i = 0;
+    while i < COLS {
+        data[map_half_word_index::<O>(i)].0 = if i == COLS - 1 { 0 } else { OE_BIT };
+        i += 1;
+    }
+    data
+}
+
+/// One row of both chains: pixel data followed by 4 address words.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct Row<const COLS: usize, O: Ordering = DefaultOrdering> {
+    data: [Entry; COLS],
+    address: [Address; 4],
+    _ordering: PhantomData<O>,
+}
+
+impl<const COLS: usize, O: Ordering> Row<COLS, O> {
+    const ADDR_TABLE: [[Address; 4]; 32] = make_addr_table::<O>();
+
+    const fn new() -> Self {
+        Self {
+            data: [Entry(0); COLS],
+            address: [Address(0); 4],
+            _ordering: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn set_half(&mut self, half: usize, col: usize, r: bool, g: bool, b: bool) {
+        let shift = HALF_SHIFT[half];
+        let bits = (u16::from(b) << 2) | (u16::from(g) << 1) | u16::from(r);
+        let entry = &mut self.data[map_half_word_index::<O>(col)];
+        entry.0 = (entry.0 & !(0b111 << shift)) | (bits << shift);
+    }
+}
+
+impl<const COLS: usize, O: Ordering> Default for Row<COLS, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: usize, O: Ordering> RowFormat for Row<COLS, O> {
+    #[inline]
+    fn format(&mut self, addr: u8, _prev_addr: u8) {
+        self.address
+            .copy_from_slice(&Self::ADDR_TABLE[addr as usize]);
+        self.data.copy_from_slice(&make_data_template::<COLS, O>());
+    }
+
+    #[inline]
+    fn clear_colors(&mut self) {
+        for entry in &mut self.data {
+            entry.0 &= !COLOR_MASK;
+        }
+    }
+
+    #[inline]
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(0, col, r, g, b);
+    }
+
+    #[inline]
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(1, col, r, g, b);
+    }
+
+    #[inline]
+    fn set_color(&mut self, half: usize, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(half, col, r, g, b);
+    }
+
+    #[inline]
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        let bits = self.data[map_half_word_index::<O>(col)].0 >> HALF_SHIFT[half];
+        (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0)
+    }
+}
+
+/// [`EntryFormat`] of the 16-bit latched framebuffer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Latched16Format;
+
+// SAFETY: rows are `repr(C)` arrays of integer wrappers without padding, and
+// every bit pattern of them is valid.
+unsafe impl EntryFormat for Latched16Format {
+    const WORD_SIZE: WordSize = WordSize::Sixteen;
+    const LAYOUT: Layout = Layout::Latched16;
+    const CHAINS: usize = 2;
+    type Pad = ();
+    type FrameOrder = Sequential;
+    type Row<const COLS: usize, O: Ordering> = Row<COLS, O>;
+}
+
+/// DMA-compatible 16-bit framebuffer for two chains of HUB75 panels sharing
+/// one external latch circuit.
+///
+/// # Type Parameters
+/// - `ROWS`: Total number of rows of both chains (twice the panel height)
+/// - `COLS`: Number of columns of each chain
+/// - `NROWS`: Number of rows per scan of one panel (`compute_rows` of the
+///   panel height)
+/// - `BITS`: Color depth (1-8 bits)
+/// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA word [`Ordering`], defaults to [`DefaultOrdering`]
+/// - `A`: minimum buffer [`Alignment`](crate::align::Alignment), defaults to
+///   [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`](crate::orientation::Orientation),
+///   defaults to [`Normal`]
+///
+/// `ROWS` must be at most `4 * NROWS`.
+pub type DmaFrameBuffer<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const BITS: u8,
+    const FRAME_COUNT: usize,
+    O = DefaultOrdering,
+    A = DefaultAlignment,
+    OR = Normal,
+> = crate::format::DmaFrameBuffer<Latched16Format, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>;
+
+impl<
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: crate::align::Alignment,
+        OR: crate::orientation::Orientation,
+    > crate::RowIterExt
+    for crate::format::DmaFrameBuffer<
+        Latched16Format,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
+{
+    type Word = u16;
+
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[u16])> + '_ {
+        Self::split_rows(stream_words(self))
+    }
+
+    fn iter_words(&self) -> impl Iterator<Item = u16> + '_ {
+        Self::output_words(stream_words(self))
+    }
+}
+
+/// The DMA stream of `fb`, as returned by `read_buffer()`, as 16-bit words
+/// in memory order.
+fn stream_words<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const BITS: u8,
+    const FRAME_COUNT: usize,
+    O: Ordering,
+    A: crate::align::Alignment,
+    OR: crate::orientation::Orientation,
+>(
+    fb: &crate::format::DmaFrameBuffer<
+        Latched16Format,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >,
+) -> &[u16] {
+    let len = fb.active_len_bytes() / 2;
+    // SAFETY: rows are `repr(C)` arrays of `u16` wrappers without padding.
+    unsafe { core::slice::from_raw_parts((&raw const fb.frames).cast::<u16>(), len) }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::ordering::{Esp32, Linear};
+    use crate::{compute_frame_count, compute_rows, Color, FrameBuffer};
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::*;
+
+    const PANEL_ROWS: usize = 32;
+    const ROWS: usize = 2 * PANEL_ROWS;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(PANEL_ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type TestFrameBuffer = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+
+    #[test]
+    fn test_format_matches_8_bit_latched() {
+        let fb = TestFrameBuffer::new();
+        let narrow = crate::latched::DmaFrameBuffer::<
+            PANEL_ROWS,
+            COLS,
+            NROWS,
+            BITS,
+            FRAME_COUNT,
+            Linear,
+        >::new();
+
+        assert_eq!(fb.get_word_size(), WordSize::Sixteen);
+        assert_eq!(fb.as_bytes().len(), 2 * narrow.as_bytes().len());
+        // the low bytes are the 8-bit stream, the high bytes are empty
+        for (word, byte) in fb.as_bytes().chunks(2).zip(narrow.as_bytes()) {
+            assert_eq!(u16::from_le_bytes([word[0], word[1]]), u16::from(*byte));
+        }
+    }
+
+    #[test]
+    fn test_set_pixel_reaches_every_half() {
+        let mut fb = TestFrameBuffer::new();
+        assert!(fb.is_formatted());
+        assert!(!TestFrameBuffer::new_unformatted().is_formatted());
+        fb.set_pixel(Point::new(1, 2), Color::RED);
+        fb.set_pixel(Point::new(1, 18), Color::GREEN);
+        fb.set_pixel(Point::new(1, 34), Color::BLUE);
+        fb.set_pixel(Point::new(1, 50), Color::WHITE);
+
+        let entry = fb.frames[0].rows[2].data[1];
+        assert!(entry.red1() && !entry.grn1() && !entry.blu1());
+        assert!(!entry.red2() && entry.grn2() && !entry.blu2());
+        assert!(!entry.red3() && !entry.grn3() && entry.blu3());
+        assert!(entry.red4() && entry.grn4() && entry.blu4());
+        assert!(entry.output_enable());
+        assert_eq!(fb.get_pixel(Point::new(1, 34)), Some(Color::new(0, 0, 224)));
+        assert_eq!(
+            fb.get_pixel(Point::new(1, 50)),
+            Some(Color::new(224, 224, 224))
+        );
+
+        fb.erase();
+        assert_eq!(fb.frames[0].rows[2].data[1].0, OE_BIT);
+    }
+
+    #[test]
+    fn test_address_words() {
+        let fb = TestFrameBuffer::new();
+        let row = &fb.frames[0].rows[5];
+        for (i, word) in row.address.iter().enumerate() {
+            assert_eq!(word.addr(), 5);
+            assert_eq!(word.latch(), i != 3);
+            assert!(!word.output_enable());
+        }
+        assert!(!row.data[COLS - 1].output_enable());
+
+        let esp32 = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+        assert!(!esp32.frames[0].rows[5].address[2].latch());
+        assert!(!esp32.frames[0].rows[5].data[COLS - 2].output_enable());
+    }
+
+    #[test]
+    fn test_export_layout() {
+        let fb = TestFrameBuffer::new();
+        let mut out = std::vec::Vec::new();
+        fb.export(|bytes| out.extend_from_slice(bytes));
+        let container = crate::export::Container::parse(&out).unwrap();
+        assert_eq!(container.header.layout, Layout::Latched16);
+        assert_eq!(container.header.word_size, WordSize::Sixteen);
+    }
+}
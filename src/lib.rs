@@ -34,21 +34,31 @@
 //!
 //! ## Framebuffer Implementations
 //!
-//! Four framebuffer layouts are provided, covering two hardware variants and
-//! two BCM strategies:
+//! Seven framebuffer layouts are provided, covering three hardware variants
+//! and two BCM strategies:
 //!
 //! | Module | Word size | External latch? | BCM strategy |
 //! |--------|-----------|-----------------|--------------|
 //! | [`plain`] | 16-bit | No | Threshold frames |
+//! | [`plain8`] | 8-bit, address shares colour bits | No | Threshold frames |
+//! | [`plain32`] | 32-bit, two columns per word | No | Threshold frames |
 //! | [`latched`] | 8-bit | Yes | Threshold frames |
+//! | [`latched16`] | 16-bit, two chains | Yes | Threshold frames |
 //! | [`bitplane::plain`] | 16-bit | No | True bitplane |
 //! | [`bitplane::latched`] | 8-bit | Yes | True bitplane |
 //!
 //! ### Plain vs. Latched
 //! - **Plain** packs all HUB75 signals (address, latch, OE, colour) into each
 //!   16-bit word. No extra hardware beyond a parallel output peripheral.
+//! - **Plain8** fits plain wiring on an 8-bit bus by sharing the address lines
+//!   with colour bits; rows only light during extra display words after the
+//!   pixels, trading brightness for the missing latch circuit.
+//! - **Plain32** packs two adjacent columns into every 32-bit word, halving
+//!   the words per row for peripherals that demultiplex them onto the panel.
 //! - **Latched** uses 8-bit words with a separate external latch circuit to
 //!   hold the row address and gate the pixel clock, halving per-entry memory.
+//! - **Latched16** widens the latched words to 16 bits to drive a second chain
+//!   of panels through the same latch circuit, on MCUs with a 16-bit bus.
 //!
 //! ### Threshold Frames vs. True Bitplane
 //! The two BCM strategies differ in how they store colour data and how the DMA
@@ -67,7 +77,7 @@
 //! scanned 128 times, plane 7 = LSB is scanned once). Memory scales linearly
 //! with the number of planes.
 //!
-//! All four variants have configurable row and column dimensions, support
+//! All variants have configurable row and column dimensions, support
 //! `embedded-graphics` via the `DrawTarget` trait, and expose per-plane
 //! pointers for DMA setup through the [`FrameBuffer`] trait.
 //!
@@ -78,9 +88,119 @@
 //! The wrapper exposes a single `embedded-graphics` canvas, so for example a
 //! 3 × 3 stack of 64 × 32 panels simply looks like a 192 × 96 screen while
 //! all coordinate translation happens transparently.
+//! [`tiling::ColumnInterleaved`] wraps a layout for 64 × 32 panels that are
+//! driven like 128 × 16 panels with their halves interleaved column by
+//! column.
+//!
+//! [`split::ColumnSplit`] drives the left and right half of a very wide
+//! display from two framebuffers of the same type, streamed by two I2S or
+//! LCD peripherals in lockstep for twice the pixel clock budget.
+//!
+//! ## Display Settings
+//! [`display::Hub75Display`] wraps any of the framebuffers above, tiled or
+//! not, with runtime brightness, rotation, black transparency and a
+//! [`vsync::VsyncHook`], so applications configure the display in one place.
+//! [`display::Dimming::Temporal`] spreads the remainder of dimmed colours
+//! over successive redraws for fades without visible steps.
+//! Panels fixed upside down or viewed from behind instead set the trailing
+//! [`orientation::Orientation`] parameter of the framebuffer, e.g.
+//! [`orientation::Rotate180`], which flips at no runtime cost.
+//! [`display::MultiDisplay`] coordinates several independent displays, e.g.
+//! both sides of a double-sided sign, with shared brightness.
+//!
+//! [`viewport::Translated`] draws into a fixed sub-rectangle of the panel,
+//! for enclosures that hide its border.
+//!
+//! [`canvas::VirtualCanvas`] keeps content larger than the panel in a
+//! shadow buffer and shows a movable window of it, for panning over maps
+//! or long text.
+//!
+//! [`interlace::Interlaced`] passes drawing through to every other row,
+//! alternating per redraw, to halve the drawing time on slow MCUs.
+//!
+//! [`scale::Downscale2x`] accepts drawing at twice the panel resolution and
+//! shows the average of every 2 by 2 block, for sources with a fixed larger
+//! resolution.
+//!
+//! [`mask::DeadPixelMask`] keeps defective LEDs dark or moves their
+//! colour to a neighbour, for panels with damaged pixels.
+//!
+//! ## Runtime Configuration
+//! [`erased::ErasedFrameBuffer`] holds any single-stream framebuffer behind a
+//! `dyn` reference, so the panel configuration can be chosen at startup and
+//! kept in one field. `try_new` constructors report mismatching parameters as
+//! [`Hub75Error`] instead of producing a broken stream.
+//!
+//! ## Widgets
+//! [`widgets`] has seven-segment digits, VU-style bargraphs, progress bars,
+//! radial gauges and battery and signal strength icons for clocks and
+//! dashboards, drawn with the framebuffers' rectangle fills where possible.
+//!
+//! ## Bring-up
+//! [`pattern::draw_test_pattern`] draws colour bars, single address rows, a
+//! walking pixel or one colour channel, to find wiring and configuration
+//! problems on a new panel one signal group at a time.
+//!
+//! ## Network Input
+//! [`ingest::Region`] copies RGB888 payloads from Art-Net, E1.31 or similar
+//! packets into a rectangle of the display after checking the bounds and
+//! length, optionally with ordered dithering to the framebuffer's colour
+//! depth. [`ingest::DdpPacket`] parses and applies Distributed Display
+//! Protocol packets, e.g. from WLED, across tiled displays.
+//!
+//! ## Slideshows
+//! [`playlist::Playlist`] steps through images with per-slide durations and
+//! cut, fade or wipe transitions, advanced by the application's tick, for
+//! menu boards and signs.
+//!
+//! ## Refresh Timing
+//! [`timing::BcmTiming`] computes row shift times, per-bit display times for
+//! hardware-timed BCM and the resulting refresh rate from the shift clock and
+//! panel geometry, so configurations that would flicker can be rejected at
+//! compile time.
+//!
+//! ## Frame Boundaries
+//! Refresh loops report every completed frame to a [`vsync::VsyncHook`], the
+//! tear-free point for swapping buffers and a steady clock for FPS counters
+//! ([`vsync::FrameCounter`]) and animation timing. [`vsync::FramePacer`]
+//! paces animations at a target frame rate on top of it, telling the drawing
+//! loop when to render and swap and counting late frames.
+//!
+//! ## Decoding Streams
+//! [`decode::Decoded`] replays a plain or latched DMA stream the way a panel
+//! sees it and returns the lit pixels of every BCM frame, for black-box tests
+//! and for checking captured streams. [`emulator::Panel`] models the panel
+//! itself, adding up how long each LED is lit and flagging timing that shows
+//! as artefacts on real hardware. [`test_util`] builds on the decoder to
+//! compare drawing on a framebuffer with `embedded-graphics`' `MockDisplay`.
+//!
+//! ## Driver IC Initialisation
+//! Panels with FM6126A or FM6124 drivers stay dark until their configuration
+//! registers are written. [`init::Fm6126aInit`] builds that register-write
+//! sequence in the word format of a framebuffer, to be sent once at start-up.
+//!
+//! ## S-PWM Driver ICs
+//! Panels with ICN2053, FM6353 or MBI5153 drivers generate the PWM
+//! themselves and take a frame of 16-bit grey values instead of BCM frames.
+//! [`spwm::DmaFrameBuffer`] lays out that stream with the latch and vertical
+//! sync commands, and [`spwm::ConfigWrite`] programs their registers.
+//!
+//! ## Buffer Alignment
+//! On targets with a data cache (Cortex-M7, ESP32-S3 PSRAM) DMA buffers must
+//! occupy whole cache lines. Every framebuffer takes a trailing
+//! [`align::Alignment`] parameter, e.g. [`align::Align32`], that raises its
+//! alignment and pads its size to match, so cache maintenance never touches
+//! neighbouring data.
 //!
 //! ## Available Feature Flags
 //!
+//! There is no feature for selecting a DMA backend. Every framebuffer
+//! implements [`embedded_dma::ReadBuffer`]; HAL-specific requirements are met
+//! by wrapper types such as `stm32::HalfWordBuffer`, so buffers for several
+//! HALs and host-side tests can coexist in one build. The plain and latched
+//! framebuffers also implement [`embedded_dma::WriteBuffer`], so pre-rendered
+//! frames can be received straight into them by DMA.
+//!
 //! ### `skip-black-pixels` Feature (disabled by default)
 //! When enabled, calls to `set_pixel()` with `Color::BLACK` return early without
 //! writing to the framebuffer. This provides a significant performance boost for
@@ -103,15 +223,149 @@
 //! byte ordering requirements compared to other ESP32 variants (S2, S3, C3, etc.),
 //! which do **not** need this feature.
 //!
+//! The feature only changes the [`ordering::DefaultOrdering`] used when the
+//! ordering parameter of a framebuffer is left out. To build buffers for both
+//! kinds of chips in one binary (or to test both paths on the host), name
+//! [`ordering::Esp32`] or [`ordering::Linear`] explicitly instead.
+//!
 //! ```toml
 //! [dependencies]
 //! hub75-framebuffer = { version = "0.8.1", features = ["esp32-ordering"] }
 //! ```
 //!
+//! ### `stm32` Feature
+//! Adds the `stm32` module with a `ReadBuffer<Word = u16>` adapter so
+//! 16-bit framebuffers can be handed straight to the DMA `Transfer` APIs of
+//! `stm32f4xx-hal`/`stm32h7xx-hal` for timer-triggered GPIO output. 8-bit
+//! framebuffers work with those HALs without this feature.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["stm32"] }
+//! ```
+//!
+//! ### `nrf` Feature
+//! Adds the `nrf` module, which splits the DMA stream into `EasyDMA`-sized,
+//! linked chunks and checks at compile time that the framebuffer fits into
+//! the target's data RAM, for driving panels through the nRF52 I2S or PWM
+//! peripherals.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["nrf"] }
+//! ```
+//!
+//! ### `embassy` Feature
+//! Adds the `embassy` module: an async, double-buffered refresh loop built
+//! on `embassy-sync`. The HAL only has to implement `embassy::DmaOutput`;
+//! the refresh task keeps streaming the front buffer and swaps in the back
+//! buffer at a frame boundary when the application calls
+//! `embassy::Drawer::flush`.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["embassy"] }
+//! ```
+//!
+//! ### `queue` Feature
+//! Adds the `queue` module: a bounded queue of drawing commands, built on
+//! `embassy-sync` mutexes, that interrupts and other tasks fill and the
+//! rendering task applies to the framebuffer in one call, so nobody holds a
+//! lock on the framebuffer while drawing.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["queue"] }
+//! ```
+//!
+//! ### `bitbang` Feature
+//! Adds the `bitbang` module, a slow reference driver that clocks any
+//! framebuffer out of `embedded-hal` `OutputPin`s. Useful for validating
+//! wiring and buffer content before setting up DMA.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["bitbang"] }
+//! ```
+//!
+//! ### `spi` Feature
+//! Adds the `spi` module, which re-serializes a framebuffer into the bit
+//! stream expected by shift-register based HUB75 adapters, so MCUs with only
+//! an SPI peripheral can drive a panel.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["spi"] }
+//! ```
+//!
+//! ### `placement` Feature
+//! Adds the `placement` module and the `place_framebuffer!` macro, which
+//! place a formatted framebuffer in a `static` in a named linker section
+//! (e.g. `.sram1`) for targets that only DMA from specific memories.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["placement"] }
+//! ```
+//!
+//! ### `std` Feature
+//! Adds `write_ppm()`, `write_png()` and `save_png()` to
+//! [`decode::Decoded`], which store the decoded image as a picture file for
+//! golden-image tests and bug reports, and `report()` to the framebuffers in
+//! [`format`], which summarises the memory use, row layout, refresh rate and
+//! DMA descriptors of a configuration. `decode::SimulatorBridge` decodes a
+//! buffer every frame and draws it into another display, e.g. the window of
+//! `embedded-graphics-simulator`. Host-side only.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["std"] }
+//! ```
+//!
+//! ### `instrument` Feature
+//! Counts the pixels, DMA bytes and scan rows written by drawing into the
+//! framebuffers in [`format`], readable through `draw_stats()` and
+//! `take_draw_stats()`. Use it to check that rendering fits the frame
+//! budget without a profiler on the target; it costs a few instructions
+//! per pixel and 12 bytes per framebuffer.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["instrument"] }
+//! ```
+//!
+//! ### `text` Feature
+//! Adds the `text` module: centred, right-aligned and word-wrapped text in
+//! `embedded-graphics` monospaced fonts for any of the framebuffers and
+//! wrappers, through the `TextExt` extension trait, and the scrolling
+//! `Ticker`.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["text"] }
+//! ```
+//!
+//! ### `effects` Feature
+//! Adds the `effects` module: plasma, fire, gradient sweep and sparkle
+//! animations behind the `Effect` trait, rendered over a whole target in
+//! integer arithmetic. Useful as screensavers and refresh stress tests.
+//!
+//! ```toml
+//! [dependencies]
+//! hub75-framebuffer = { version = "0.8.1", features = ["effects"] }
+//! ```
+//!
 //! ### `defmt` Feature
 //! Implements `defmt::Format` for framebuffer types so they can be emitted with
 //! the `defmt` logging framework. No functional changes; purely adds a trait impl.
 //!
+//! ### `serde` Feature
+//! Implements `Serialize` and `Deserialize` for configuration types such as
+//! `WordSize`, `display::Rotation`, `display::BrightnessCurve`,
+//! `init::Fm6126aConfig`, the tiling layouts and `mask::Repair`, so setups
+//! can be stored in flash or sent over the network. Deserializing a `BrightnessCurve` checks
+//! the same invariants as its constructor.
+//!
 //! ### `doc-images` Feature
 //! Embeds documentation images when building docs on docs.rs. Not needed for
 //! normal usage.
@@ -122,25 +376,174 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::pixelcolor::Rgb888;
 use embedded_graphics::prelude::Point;
 
+pub mod align;
+#[cfg(feature = "bitbang")]
+pub mod bitbang;
 pub mod bitplane;
+pub mod canvas;
+pub mod decode;
+pub mod display;
+#[cfg(feature = "effects")]
+pub mod effects;
+#[cfg(feature = "embassy")]
+pub mod embassy;
+pub mod emulator;
+pub mod erased;
+pub mod export;
+pub mod format;
+pub mod ingest;
+pub mod init;
+pub mod interlace;
 pub mod latched;
+pub mod latched16;
+pub mod mask;
+#[cfg(feature = "nrf")]
+pub mod nrf;
+pub mod ordering;
+pub mod orientation;
+pub mod pattern;
+#[cfg(feature = "placement")]
+pub mod placement;
 pub mod plain;
+pub mod plain32;
+pub mod plain8;
+pub mod playlist;
+#[cfg(feature = "queue")]
+pub mod queue;
+pub mod scale;
+#[cfg(feature = "spi")]
+pub mod spi;
+pub mod split;
+pub mod spwm;
+#[cfg(feature = "stm32")]
+pub mod stm32;
+pub mod test_util;
+#[cfg(feature = "text")]
+pub mod text;
 pub mod tiling;
+pub mod timing;
+pub mod viewport;
+pub mod vsync;
+pub mod widgets;
 
 /// Color type used in the framebuffer
 pub type Color = Rgb888;
 
 /// Word size configuration for the framebuffer
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WordSize {
     /// 8-bit word size
     Eight,
     /// 16-bit word size
     Sixteen,
+    /// 32-bit word size
+    ThirtyTwo,
+}
+
+/// Error returned by the fallible constructors and setters, e.g.
+/// [`format::DmaFrameBuffer::try_new`], for configurations chosen at
+/// runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Hub75Error {
+    /// The colour depth is outside `1..=8`.
+    InvalidBits(u8),
+    /// The number of BCM frames does not match the colour depth.
+    FrameCountMismatch {
+        /// `compute_frame_count(BITS)`.
+        expected: usize,
+        /// The configured number of frames.
+        actual: usize,
+    },
+    /// The panel rows do not fit the scan rows, or there are more scan
+    /// rows than the 5 address lines can select.
+    RowCountMismatch {
+        /// The configured number of panel rows.
+        rows: usize,
+        /// The configured number of scan rows.
+        nrows: usize,
+    },
+    /// The framebuffer width does not match the tile layout.
+    TileMismatch {
+        /// Columns the tile layout needs.
+        expected: usize,
+        /// The configured number of columns.
+        actual: usize,
+    },
+    /// The panel size does not match the tile layout.
+    PanelMismatch {
+        /// Panel rows and columns the tile layout needs.
+        expected: (usize, usize),
+        /// The configured panel rows and columns.
+        actual: (usize, usize),
+    },
+    /// A pixel lies outside the framebuffer.
+    OutOfBounds {
+        /// Column of the pixel.
+        x: i32,
+        /// Row of the pixel.
+        y: i32,
+    },
+    /// A row address does not fit the 5 address lines.
+    InvalidAddress(u8),
+    /// A percentage is greater than 100.
+    InvalidPercent(u8),
+    /// A caller-provided buffer is too short for the data.
+    BufferTooSmall {
+        /// Bytes the data needs.
+        needed: usize,
+        /// Length of the buffer.
+        actual: usize,
+    },
+    /// A fixed-capacity table has no room for another entry.
+    CapacityExceeded(usize),
+    /// A defective pixel would be moved onto a pixel that is defective
+    /// itself.
+    DeadNeighbour {
+        /// Column of the neighbour.
+        x: i32,
+        /// Row of the neighbour.
+        y: i32,
+    },
+}
+
+impl core::fmt::Display for Hub75Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidBits(bits) => write!(f, "{bits} bits is outside 1..=8"),
+            Self::FrameCountMismatch { expected, actual } => {
+                write!(f, "{actual} frames, expected {expected}")
+            }
+            Self::RowCountMismatch { rows, nrows } => {
+                write!(f, "{rows} rows do not fit {nrows} scan rows")
+            }
+            Self::TileMismatch { expected, actual } => {
+                write!(f, "{actual} columns, tile layout needs {expected}")
+            }
+            Self::PanelMismatch { expected, actual } => write!(
+                f,
+                "{}x{} panels, tile layout needs {}x{}",
+                actual.1, actual.0, expected.1, expected.0
+            ),
+            Self::OutOfBounds { x, y } => write!(f, "pixel ({x}, {y}) out of bounds"),
+            Self::InvalidAddress(addr) => write!(f, "address {addr} out of range"),
+            Self::InvalidPercent(percent) => write!(f, "{percent}% is greater than 100%"),
+            Self::BufferTooSmall { needed, actual } => {
+                write!(f, "buffer of {actual} bytes, needs {needed}")
+            }
+            Self::CapacityExceeded(capacity) => write!(f, "all {capacity} entries in use"),
+            Self::DeadNeighbour { x, y } => write!(f, "neighbour ({x}, {y}) is defective"),
+        }
+    }
 }
 
 /// Computes the NROWS value from ROWS for `DmaFrameBuffer`
@@ -174,6 +577,26 @@ pub const fn compute_frame_count(bits: u8) -> usize {
     (1usize << bits) - 1
 }
 
+/// Splits `len` bytes at `ptr` into spans of at most `max_len` bytes, each a
+/// whole number of `unit`-byte rows.
+///
+/// # Panics
+///
+/// Panics if `max_len` is smaller than `unit`.
+pub(crate) fn row_chunks(
+    ptr: *const u8,
+    len: usize,
+    unit: usize,
+    max_len: usize,
+) -> impl Iterator<Item = (*const u8, usize)> {
+    assert!(max_len >= unit, "max_len is smaller than a single row");
+    let step = max_len - max_len % unit;
+    (0..len.div_ceil(step)).map(move |i| {
+        let offset = i * step;
+        (ptr.wrapping_add(offset), step.min(len - offset))
+    })
+}
+
 /// Trait for read-only framebuffers.
 pub trait FrameBuffer {
     /// Returns the word size configuration for this framebuffer
@@ -196,6 +619,31 @@ pub trait FrameBuffer {
     ///
     /// May panic if `plane_idx >= plane_count()`.
     fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize);
+
+    /// Number of panel rows driven by the DMA stream.
+    ///
+    /// Like the other geometry getters, this describes the stream, before
+    /// any tiling or rotation applied by a wrapper.
+    fn rows(&self) -> usize;
+
+    /// Number of columns shifted in per row.
+    fn cols(&self) -> usize;
+
+    /// Colour depth per channel in bits.
+    fn bits(&self) -> u8;
+
+    /// Number of BCM frames, or bit-planes, held in the buffer.
+    fn frame_count(&self) -> usize;
+
+    /// Total length in bytes of all planes, the memory read by DMA.
+    ///
+    /// Defaults to the sum of the lengths returned by
+    /// [`Self::plane_ptr_len`].
+    fn buffer_len_bytes(&self) -> usize {
+        (0..self.plane_count())
+            .map(|plane_idx| self.plane_ptr_len(plane_idx).1)
+            .sum()
+    }
 }
 
 /// Trait for mutable framebuffers
@@ -217,6 +665,31 @@ pub trait FrameBufferOperations: FrameBuffer {
     fn set_pixel(&mut self, p: Point, color: Color);
 }
 
+/// Row-by-row and word-by-word access to the DMA stream of a framebuffer.
+///
+/// Drivers that clock out one row at a time, e.g. from a timer interrupt
+/// or by writing GPIO registers from the CPU, use it to walk the stream in
+/// output order without knowing how frames and rows are stored.
+pub trait RowIterExt {
+    /// Word clocked out per cycle, `u8`, `u16` or `u32`.
+    type Word: Copy;
+
+    /// Returns `(frame, row, words)` for every row of the stream, in the
+    /// order the rows are clocked out.
+    ///
+    /// `frame` is the index of the BCM frame the row belongs to and `row` is
+    /// the scan row it drives. `words` includes the row's control words; its
+    /// word order follows the ordering parameter of the framebuffer.
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[Self::Word])> + '_;
+
+    /// Returns every word of the stream in the order it is clocked out.
+    ///
+    /// Unlike [`Self::iter_rows`], the words are in output order whatever
+    /// the ordering parameter of the framebuffer, so an MCU without a
+    /// suitable DMA engine can feed them to GPIOs or a FIFO one by one.
+    fn iter_words(&self) -> impl Iterator<Item = Self::Word> + '_;
+}
+
 #[cfg(test)]
 mod tests {
     extern crate std;
@@ -0,0 +1,506 @@
+//! Masking defective pixels.
+//!
+//! LEDs fail: some stay dark, others are stuck bright or flicker, and a
+//! damaged LED driven at full current can get worse. [`DeadPixelMask`]
+//! wraps a framebuffer with a table of up to `N` defective pixels and
+//! applies a [`Repair`] whenever one of them is drawn: the pixel is kept
+//! off, or its colour is drawn at a neighbour instead, so thin lines and
+//! text keep their shape.
+//!
+//! Coordinates are those of the wrapped framebuffer, i.e. of the panel.
+//! To combine the mask with rotation or brightness, wrap the mask in a
+//! [`Hub75Display`](crate::display::Hub75Display), not the other way
+//! round. Like the display wrapper it passes `ReadBuffer` and
+//! [`FrameBuffer`] through to the framebuffer it owns.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::display::Hub75Display;
+//! use hub75_framebuffer::mask::{DeadPixelMask, Repair};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::Color;
+//!
+//! let mut mask = DeadPixelMask::<_, 4>::new(DmaFrameBuffer::<32, 64, 16, 3, 7>::new());
+//! mask.add(Point::new(10, 5), Repair::Off).unwrap();
+//! let mut display = Hub75Display::new(mask);
+//! display.set_brightness(128);
+//!
+//! Rectangle::new(Point::new(0, 0), Size::new(64, 8))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::WHITE))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! let fb = display.framebuffer().framebuffer();
+//! assert_eq!(fb.get_pixel(Point::new(10, 5)), Some(Color::BLACK));
+//! ```
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, FrameBufferOperations, Hub75Error, MutableFrameBuffer, WordSize};
+
+/// What [`DeadPixelMask`] does with a defective pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Repair {
+    /// Keep the pixel black, whatever is drawn there.
+    Off,
+    /// Keep the pixel black and draw its colour at this pixel instead,
+    /// replacing what was drawn there before.
+    Neighbour(
+        #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+        #[cfg_attr(feature = "serde", serde(with = "point"))]
+        Point,
+    ),
+}
+
+/// `Point` as an `(x, y)` tuple, it has no serde support of its own.
+#[cfg(feature = "serde")]
+mod point {
+    use embedded_graphics::prelude::Point;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature required by serde
+    pub fn serialize<S: Serializer>(p: &Point, serializer: S) -> Result<S::Ok, S::Error> {
+        (p.x, p.y).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Point, D::Error> {
+        let (x, y) = <(i32, i32)>::deserialize(deserializer)?;
+        Ok(Point::new(x, y))
+    }
+}
+
+/// A framebuffer whose defective pixels are kept off or moved to a
+/// neighbour, see the [module documentation](self).
+///
+/// Lookups search the table, so keep `N` to the handful of pixels that
+/// actually failed.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeadPixelMask<FB, const N: usize> {
+    fb: FB,
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pixels: [(Point, Repair); N],
+    len: usize,
+}
+
+impl<FB, const N: usize> DeadPixelMask<FB, N> {
+    /// Wrap `fb` without any defective pixels.
+    #[must_use]
+    pub const fn new(fb: FB) -> Self {
+        Self {
+            fb,
+            pixels: [(Point::zero(), Repair::Off); N],
+            len: 0,
+        }
+    }
+
+    /// Apply `repair` whenever `p` is drawn from now on, replacing the
+    /// repair of `p` if it is already in the table.
+    ///
+    /// Content already drawn is not changed, so add the pixels before
+    /// drawing or redraw afterwards.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::CapacityExceeded`] if `N` pixels are already
+    /// in the table, and [`Hub75Error::DeadNeighbour`] if a defective pixel
+    /// would be moved onto another defective pixel: onto `p` itself, onto a
+    /// pixel in the table, or `p` being the neighbour of one.
+    pub fn add(&mut self, p: Point, repair: Repair) -> Result<(), Hub75Error> {
+        if let Repair::Neighbour(q) = repair {
+            if q == p || self.dead_pixels().iter().any(|&(r, _)| r == q) {
+                return Err(Hub75Error::DeadNeighbour { x: q.x, y: q.y });
+            }
+        }
+        if self
+            .dead_pixels()
+            .iter()
+            .any(|&(r, repair)| r != p && repair == Repair::Neighbour(p))
+        {
+            return Err(Hub75Error::DeadNeighbour { x: p.x, y: p.y });
+        }
+        if let Some(entry) = self.pixels[..self.len].iter_mut().find(|(q, _)| *q == p) {
+            entry.1 = repair;
+            return Ok(());
+        }
+        let entry = self
+            .pixels
+            .get_mut(self.len)
+            .ok_or(Hub75Error::CapacityExceeded(N))?;
+        *entry = (p, repair);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove `p` from the table, returning whether it was in it.
+    pub fn remove(&mut self, p: Point) -> bool {
+        let Some(i) = self.pixels[..self.len].iter().position(|(q, _)| *q == p) else {
+            return false;
+        };
+        self.pixels.copy_within(i + 1..self.len, i);
+        self.len -= 1;
+        true
+    }
+
+    /// Remove all pixels from the table.
+    pub fn clear_table(&mut self) {
+        self.len = 0;
+    }
+
+    /// The defective pixels and their repairs, in the order they were
+    /// added.
+    #[must_use]
+    pub fn dead_pixels(&self) -> &[(Point, Repair)] {
+        &self.pixels[..self.len]
+    }
+
+    /// The repair applied when `p` is drawn, if it is defective.
+    #[must_use]
+    pub fn repair(&self, p: Point) -> Option<Repair> {
+        find(self.dead_pixels(), p)
+    }
+
+    /// The wrapped framebuffer.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, for drawing without the mask.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// Unwrap the framebuffer.
+    pub fn into_inner(self) -> FB {
+        self.fb
+    }
+}
+
+/// The repair of `p` in `table`.
+fn find(table: &[(Point, Repair)], p: Point) -> Option<Repair> {
+    table
+        .iter()
+        .find(|(q, _)| *q == p)
+        .map(|&(_, repair)| repair)
+}
+
+/// `pixel` as drawn with the defective pixels in `table`, or `None` if it
+/// is dropped.
+fn map(table: &[(Point, Repair)], Pixel(p, color): Pixel<Color>) -> Option<Pixel<Color>> {
+    match find(table, p) {
+        None => Some(Pixel(p, color)),
+        Some(Repair::Off) => None,
+        Some(Repair::Neighbour(q)) => Some(Pixel(q, color)),
+    }
+}
+
+impl<FB: OriginDimensions, const N: usize> OriginDimensions for DeadPixelMask<FB, N> {
+    fn size(&self) -> Size {
+        self.fb.size()
+    }
+}
+
+impl<FB, const N: usize> DrawTarget for DeadPixelMask<FB, N>
+where
+    FB: DrawTarget<Color = Color> + OriginDimensions,
+{
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let table = &self.pixels[..self.len];
+        if table.is_empty() {
+            return self.fb.draw_iter(pixels);
+        }
+        self.fb
+            .draw_iter(pixels.into_iter().filter_map(|pixel| map(table, pixel)))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.fb.size()));
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        // fill around the defective pixels rather than painting them black
+        // afterwards, which `skip-black-pixels` would skip
+        let table = &self.pixels[..self.len];
+        let dead_in_row = |y: i32, from: i32| {
+            table
+                .iter()
+                .map(|&(p, _)| p)
+                .filter(|p| p.y == y && (from..=bottom_right.x).contains(&p.x))
+                .map(|p| p.x)
+                .min()
+        };
+        let left = area.top_left.x;
+        let mut clean_from = area.top_left.y;
+        for y in area.top_left.y..=bottom_right.y {
+            if dead_in_row(y, left).is_none() {
+                continue;
+            }
+            if y > clean_from {
+                let rows = Size::new(area.size.width, (y - clean_from).unsigned_abs());
+                self.fb
+                    .fill_solid(&Rectangle::new(Point::new(left, clean_from), rows), color)?;
+            }
+            clean_from = y + 1;
+            let mut x = left;
+            while x <= bottom_right.x {
+                let end = dead_in_row(y, x).unwrap_or(bottom_right.x + 1);
+                if end > x {
+                    let span = Size::new((end - x).unsigned_abs(), 1);
+                    self.fb
+                        .fill_solid(&Rectangle::new(Point::new(x, y), span), color)?;
+                }
+                x = end + 1;
+            }
+        }
+        if clean_from <= bottom_right.y {
+            let rows = Size::new(
+                area.size.width,
+                (bottom_right.y + 1 - clean_from).unsigned_abs(),
+            );
+            self.fb
+                .fill_solid(&Rectangle::new(Point::new(left, clean_from), rows), color)?;
+        }
+        self.fb
+            .draw_iter(table.iter().filter(|(p, _)| area.contains(*p)).filter_map(
+                |&(_, repair)| match repair {
+                    Repair::Neighbour(q) => Some(Pixel(q, color)),
+                    Repair::Off => None,
+                },
+            ))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.fill_solid(&self.fb.bounding_box(), color)
+    }
+}
+
+impl<FB, const N: usize> FrameBufferOperations for DeadPixelMask<FB, N>
+where
+    FB: FrameBufferOperations + OriginDimensions,
+{
+    #[inline]
+    fn erase(&mut self) {
+        self.fb.erase();
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        if let Some(Pixel(p, color)) = map(self.dead_pixels(), Pixel(p, color)) {
+            self.fb.set_pixel(p, color);
+        }
+    }
+}
+
+impl<FB: FrameBuffer, const N: usize> FrameBuffer for DeadPixelMask<FB, N> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+impl<FB: MutableFrameBuffer + OriginDimensions, const N: usize> MutableFrameBuffer
+    for DeadPixelMask<FB, N>
+{
+}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>, const N: usize> ReadBuffer for DeadPixelMask<FB, N> {
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plain::DmaFrameBuffer;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    type SmallFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+
+    const WHITE: Color = Color::new(224, 224, 224);
+
+    fn masked() -> DeadPixelMask<SmallFB, 2> {
+        let mut mask = DeadPixelMask::new(SmallFB::new());
+        mask.add(Point::new(3, 1), Repair::Off).unwrap();
+        mask.add(Point::new(5, 1), Repair::Neighbour(Point::new(5, 2)))
+            .unwrap();
+        mask
+    }
+
+    #[test]
+    fn test_table() {
+        let mut mask = masked();
+        assert_eq!(
+            mask.add(Point::new(0, 0), Repair::Off),
+            Err(Hub75Error::CapacityExceeded(2))
+        );
+        // replacing an entry needs no room
+        mask.add(Point::new(5, 1), Repair::Off).unwrap();
+        assert_eq!(mask.repair(Point::new(5, 1)), Some(Repair::Off));
+        assert_eq!(mask.repair(Point::new(0, 0)), None);
+
+        // chains of defective pixels are rejected
+        let dead = Err(Hub75Error::DeadNeighbour { x: 3, y: 1 });
+        assert_eq!(
+            mask.add(Point::new(5, 1), Repair::Neighbour(Point::new(3, 1))),
+            dead
+        );
+        assert_eq!(
+            mask.add(Point::new(3, 1), Repair::Neighbour(Point::new(3, 1))),
+            dead
+        );
+        mask.add(Point::new(3, 1), Repair::Neighbour(Point::new(3, 2)))
+            .unwrap();
+        assert_eq!(
+            mask.add(Point::new(3, 2), Repair::Off),
+            Err(Hub75Error::DeadNeighbour { x: 3, y: 2 })
+        );
+        mask.add(Point::new(3, 1), Repair::Off).unwrap();
+
+        assert!(mask.remove(Point::new(3, 1)));
+        assert!(!mask.remove(Point::new(3, 1)));
+        assert_eq!(mask.dead_pixels(), &[(Point::new(5, 1), Repair::Off)]);
+        mask.clear_table();
+        assert!(mask.dead_pixels().is_empty());
+    }
+
+    #[test]
+    fn test_draw_iter() {
+        let mut mask = masked();
+        Line::new(Point::new(0, 1), Point::new(7, 1))
+            .into_styled(PrimitiveStyle::with_stroke(Color::WHITE, 1))
+            .draw(&mut mask)
+            .unwrap();
+        let fb = mask.framebuffer();
+        assert_eq!(fb.get_pixel(Point::new(2, 1)), Some(WHITE));
+        assert_eq!(fb.get_pixel(Point::new(3, 1)), Some(Color::BLACK));
+        assert_eq!(fb.get_pixel(Point::new(5, 1)), Some(Color::BLACK));
+        assert_eq!(fb.get_pixel(Point::new(5, 2)), Some(WHITE));
+
+        FrameBufferOperations::set_pixel(&mut mask, Point::new(3, 1), Color::RED);
+        assert_eq!(
+            mask.framebuffer().get_pixel(Point::new(3, 1)),
+            Some(Color::BLACK)
+        );
+    }
+
+    #[test]
+    fn test_fill_solid() {
+        let mut mask = masked();
+        let area = Rectangle::new(Point::new(0, 0), Size::new(6, 2));
+        mask.fill_solid(&area, Color::WHITE).unwrap();
+        let fb = mask.framebuffer();
+        assert_eq!(fb.get_pixel(Point::new(4, 1)), Some(WHITE));
+        assert_eq!(fb.get_pixel(Point::new(3, 1)), Some(Color::BLACK));
+        assert_eq!(fb.get_pixel(Point::new(5, 1)), Some(Color::BLACK));
+        assert_eq!(fb.get_pixel(Point::new(5, 2)), Some(WHITE));
+
+        // filling around the defective pixels leaves their neighbours alone
+        let mut mask = masked();
+        mask.fill_solid(
+            &Rectangle::new(Point::zero(), Size::new(2, 2)),
+            Color::WHITE,
+        )
+        .unwrap();
+        assert_eq!(
+            mask.framebuffer().get_pixel(Point::new(5, 2)),
+            Some(Color::BLACK)
+        );
+
+        mask.clear(Color::WHITE).unwrap();
+        assert_eq!(
+            mask.framebuffer().get_pixel(Point::new(3, 1)),
+            Some(Color::BLACK)
+        );
+        assert_eq!(mask.framebuffer().get_pixel(Point::new(0, 15)), Some(WHITE));
+
+        // fills are clipped to the framebuffer before walking the rows
+        let mut mask = masked();
+        let huge = Rectangle::new(Point::new(-100_000, -100_000), Size::new(400_000, 400_000));
+        mask.fill_solid(&huge, Color::WHITE).unwrap();
+        let fb = mask.framebuffer();
+        assert_eq!(fb.get_pixel(Point::new(31, 15)), Some(WHITE));
+        assert_eq!(fb.get_pixel(Point::new(3, 1)), Some(Color::BLACK));
+        assert_eq!(fb.get_pixel(Point::new(5, 2)), Some(WHITE));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_repair_serde() {
+        use serde_test::{assert_tokens, Token};
+
+        assert_tokens(
+            &Repair::Neighbour(Point::new(5, -2)),
+            &[
+                Token::NewtypeVariant {
+                    name: "Repair",
+                    variant: "Neighbour",
+                },
+                Token::Tuple { len: 2 },
+                Token::I32(5),
+                Token::I32(-2),
+                Token::TupleEnd,
+            ],
+        );
+        assert_tokens(
+            &Repair::Off,
+            &[Token::UnitVariant {
+                name: "Repair",
+                variant: "Off",
+            }],
+        );
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let mask = masked();
+        let (ptr, len) = unsafe { mask.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { mask.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!((mask.rows(), mask.cols(), mask.bits()), (16, 32, 3));
+        assert_eq!(mask.size(), Size::new(32, 16));
+        assert_eq!(mask.into_inner().as_bytes(), SmallFB::new().as_bytes());
+    }
+}
@@ -0,0 +1,270 @@
+//! `EasyDMA` chunking for nRF52 peripherals.
+//!
+//! The nRF52 `EasyDMA` engine has two restrictions that regularly trip up
+//! users streaming a framebuffer through the I2S or PWM peripherals:
+//!
+//! - A single transfer is limited by the width of the peripheral's `MAXCNT`
+//!   (or `CNT`) register, which is far smaller than a typical BCM buffer.
+//! - The source buffer must live in data RAM; `EasyDMA` cannot read from flash.
+//!
+//! [`EasyDmaChunks`] splits the DMA stream of any framebuffer into
+//! consecutive, word-aligned chunks that each fit a single transfer. Every
+//! [`EasyDmaChunk`] records the index of the chunk that follows it (wrapping
+//! back to the first one), so an interrupt handler reacting to the
+//! peripheral's pointer-update event can simply program the `next` chunk to
+//! keep the panel refreshing continuously.
+//!
+//! The `RAM_SIZE` parameter is checked at compile time: instantiating
+//! [`EasyDmaChunks`] for a framebuffer larger than the target's data RAM is a
+//! build error rather than a silent runtime failure.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::latched::DmaFrameBuffer;
+//! use hub75_framebuffer::nrf::{EasyDmaChunks, I2S_MAX_TRANSFER_BYTES, NRF52840_RAM_SIZE};
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! let chunks = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, I2S_MAX_TRANSFER_BYTES);
+//!
+//! for chunk in chunks.iter() {
+//!     // program TXD.PTR = chunk.ptr, RXTXD.MAXCNT = chunk.len / 4
+//!     let _next = chunks.chunk(chunk.next);
+//! }
+//! ```
+
+use core::mem::size_of;
+
+use embedded_dma::ReadBuffer;
+
+/// Start address of the nRF52 data RAM region reachable by `EasyDMA`.
+pub const RAM_START: usize = 0x2000_0000;
+
+/// Size of the data RAM on the nRF52832.
+pub const NRF52832_RAM_SIZE: usize = 64 * 1024;
+/// Size of the data RAM on the nRF52833.
+pub const NRF52833_RAM_SIZE: usize = 128 * 1024;
+/// Size of the data RAM on the nRF52840.
+pub const NRF52840_RAM_SIZE: usize = 256 * 1024;
+
+/// Largest single I2S transfer in bytes (14-bit `MAXCNT` counting 32-bit words).
+pub const I2S_MAX_TRANSFER_BYTES: usize = ((1 << 14) - 1) * 4;
+/// Largest single PWM sequence in bytes (15-bit `CNT` counting 16-bit values).
+pub const PWM_MAX_TRANSFER_BYTES: usize = ((1 << 15) - 1) * 2;
+
+/// `EasyDMA` moves whole 32-bit words; every chunk is a multiple of this.
+const CHUNK_ALIGN: usize = 4;
+
+/// One `EasyDMA` transfer worth of framebuffer data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EasyDmaChunk {
+    /// Position of this chunk in the stream.
+    pub index: usize,
+    /// Start of the chunk.
+    pub ptr: *const u8,
+    /// Length of the chunk in bytes.
+    pub len: usize,
+    /// Index of the chunk to transfer after this one (wraps to `0`).
+    pub next: usize,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for EasyDmaChunk {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "EasyDmaChunk {{ index: {}, ptr: {=usize:#x}, len: {}, next: {} }}",
+            self.index,
+            self.ptr as usize,
+            self.len,
+            self.next
+        );
+    }
+}
+
+/// Splits a framebuffer's DMA stream into `EasyDMA`-sized chunks.
+///
+/// # Type Parameters
+/// - `FB`: The framebuffer type
+/// - `RAM_SIZE`: Size of the target's data RAM in bytes, see
+///   [`NRF52832_RAM_SIZE`] and friends
+#[derive(Debug)]
+pub struct EasyDmaChunks<'a, FB, const RAM_SIZE: usize> {
+    _fb: &'a FB,
+    ptr: *const u8,
+    len: usize,
+    chunk_bytes: usize,
+}
+
+impl<'a, FB: ReadBuffer<Word = u8>, const RAM_SIZE: usize> EasyDmaChunks<'a, FB, RAM_SIZE> {
+    const FITS_IN_RAM: () = assert!(
+        size_of::<FB>() <= RAM_SIZE,
+        "framebuffer does not fit in the target's data RAM"
+    );
+
+    /// Split `fb` into chunks of at most `max_chunk_bytes` bytes.
+    ///
+    /// The chunk size is rounded down to a whole number of 32-bit words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_chunk_bytes` is smaller than one 32-bit word, or if
+    /// the DMA stream of `fb` is not a whole number of 32-bit words, which
+    /// would leave a partial word in the last chunk.
+    #[must_use]
+    pub fn new(fb: &'a FB, max_chunk_bytes: usize) -> Self {
+        let () = Self::FITS_IN_RAM;
+        assert!(
+            max_chunk_bytes >= CHUNK_ALIGN,
+            "max_chunk_bytes must hold at least one 32-bit word"
+        );
+        // SAFETY: we only hold a shared reference, so no `&mut self` method
+        // can be called on the framebuffer while the chunks are alive.
+        let (ptr, len) = unsafe { fb.read_buffer() };
+        assert!(
+            len % CHUNK_ALIGN == 0,
+            "the DMA stream must be a multiple of 4 bytes long"
+        );
+        Self {
+            _fb: fb,
+            ptr,
+            len,
+            chunk_bytes: max_chunk_bytes - max_chunk_bytes % CHUNK_ALIGN,
+        }
+    }
+
+    /// Number of chunks needed to transfer the whole buffer.
+    #[must_use]
+    pub fn chunk_count(&self) -> usize {
+        self.len.div_ceil(self.chunk_bytes)
+    }
+
+    /// Returns the chunk at `index`, or `None` if it is out of range.
+    #[must_use]
+    pub fn chunk(&self, index: usize) -> Option<EasyDmaChunk> {
+        let count = self.chunk_count();
+        if index >= count {
+            return None;
+        }
+        let offset = index * self.chunk_bytes;
+        Some(EasyDmaChunk {
+            index,
+            ptr: self.ptr.wrapping_add(offset),
+            len: self.chunk_bytes.min(self.len - offset),
+            next: (index + 1) % count,
+        })
+    }
+
+    /// Iterate over all chunks in stream order.
+    pub fn iter(&self) -> impl Iterator<Item = EasyDmaChunk> + '_ {
+        (0..self.chunk_count()).filter_map(|index| self.chunk(index))
+    }
+
+    /// Returns `true` if the buffer lies entirely within data RAM.
+    ///
+    /// `EasyDMA` silently transfers garbage when pointed at flash, so call this
+    /// once on target before starting the first transfer.
+    #[must_use]
+    pub fn in_data_ram(&self) -> bool {
+        let start = self.ptr as usize;
+        start >= RAM_START && start + self.len <= RAM_START + RAM_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::{compute_frame_count, compute_rows};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+
+    #[test]
+    fn test_chunks_cover_whole_buffer() {
+        let fb = PlainFB::new();
+        let (ptr, len) = unsafe { fb.read_buffer() };
+        let chunks = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, 1000);
+
+        let all: Vec<_> = chunks.iter().collect();
+        assert_eq!(all.len(), chunks.chunk_count());
+        assert_eq!(all.len(), len.div_ceil(1000));
+
+        let mut expected = ptr;
+        for chunk in &all {
+            assert_eq!(chunk.ptr, expected);
+            assert_eq!(chunk.len % 4, 0);
+            expected = expected.wrapping_add(chunk.len);
+        }
+        assert_eq!(expected, ptr.wrapping_add(len));
+    }
+
+    #[test]
+    fn test_chunk_size_rounded_to_words() {
+        let fb = LatchedFB::new();
+        let chunks = EasyDmaChunks::<_, NRF52832_RAM_SIZE>::new(&fb, 1023);
+        assert_eq!(chunks.chunk(0).unwrap().len, 1020);
+    }
+
+    #[test]
+    fn test_chunks_are_linked_in_a_ring() {
+        let fb = LatchedFB::new();
+        let chunks = EasyDmaChunks::<_, NRF52832_RAM_SIZE>::new(&fb, 512);
+        let count = chunks.chunk_count();
+        assert!(count > 1);
+        for chunk in chunks.iter() {
+            assert_eq!(chunk.next, (chunk.index + 1) % count);
+        }
+        assert_eq!(chunks.chunk(count - 1).unwrap().next, 0);
+        assert!(chunks.chunk(count).is_none());
+    }
+
+    #[test]
+    fn test_single_chunk_when_buffer_fits() {
+        let fb = LatchedFB::new();
+        let (_, len) = unsafe { fb.read_buffer() };
+        let chunks = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, I2S_MAX_TRANSFER_BYTES);
+        assert_eq!(chunks.chunk_count(), 1);
+        let chunk = chunks.chunk(0).unwrap();
+        assert_eq!(chunk.len, len);
+        assert_eq!(chunk.next, 0);
+    }
+
+    #[test]
+    fn test_host_memory_is_not_data_ram() {
+        let fb = LatchedFB::new();
+        let chunks = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, PWM_MAX_TRANSFER_BYTES);
+        // Host allocations never live in the nRF52 RAM window
+        assert!(!chunks.in_data_ram());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one 32-bit word")]
+    fn test_chunk_size_too_small() {
+        let fb = LatchedFB::new();
+        let _ = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 4 bytes")]
+    fn test_partial_last_word() {
+        // one 5-column row plus 16 display words is 21 bytes
+        let fb = crate::plain8::DmaFrameBuffer::<2, 5, 1, 1, 1>::new();
+        let _ = EasyDmaChunks::<_, NRF52840_RAM_SIZE>::new(&fb, I2S_MAX_TRANSFER_BYTES);
+    }
+}
@@ -0,0 +1,109 @@
+//! Byte ordering of the DMA stream.
+//!
+//! The I²S peripheral of the original ESP32 swaps the two 16-bit halves of
+//! every 32-bit word it reads in 8-bit and 16-bit parallel modes, so the
+//! framebuffer has to store its entries pre-swapped. All other targets read
+//! memory linearly.
+//!
+//! The ordering is a type parameter of every framebuffer, so buffers for
+//! both kinds of targets can live in one build and be tested side by side.
+//! It defaults to [`DefaultOrdering`], which is [`Esp32`] when the
+//! `esp32-ordering` feature is enabled and [`Linear`] otherwise.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::ordering::{Esp32, Linear};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let esp32 = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+//! let esp32s3 = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+//! ```
+
+/// Maps logical entry indices to their position in DMA memory.
+pub trait Ordering: Copy + core::fmt::Debug {
+    /// XOR mask applied to the index of an 8-bit entry.
+    const BYTE_INDEX_MASK: usize;
+    /// XOR mask applied to the index of a 16-bit entry.
+    const HALF_WORD_INDEX_MASK: usize;
+}
+
+/// Entries are stored in the order they are shifted out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Linear;
+
+impl Ordering for Linear {
+    const BYTE_INDEX_MASK: usize = 0;
+    const HALF_WORD_INDEX_MASK: usize = 0;
+}
+
+/// Entries are stored with the 16-bit halves of each 32-bit word swapped, as
+/// required by the original ESP32's I²S peripheral.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Esp32;
+
+impl Ordering for Esp32 {
+    // bytes are output in the order 2, 3, 0, 1
+    const BYTE_INDEX_MASK: usize = 2;
+    // half-words are output in the order 1, 0
+    const HALF_WORD_INDEX_MASK: usize = 1;
+}
+
+/// Ordering used when a framebuffer's ordering parameter is left out.
+#[cfg(feature = "esp32-ordering")]
+pub type DefaultOrdering = Esp32;
+
+/// Ordering used when a framebuffer's ordering parameter is left out.
+#[cfg(not(feature = "esp32-ordering"))]
+pub type DefaultOrdering = Linear;
+
+/// Position of the 8-bit entry `index` in DMA memory.
+#[inline]
+pub(crate) const fn map_byte_index<O: Ordering>(index: usize) -> usize {
+    index ^ O::BYTE_INDEX_MASK
+}
+
+/// Position of the 16-bit entry `index` in DMA memory.
+#[inline]
+pub(crate) const fn map_half_word_index<O: Ordering>(index: usize) -> usize {
+    index ^ O::HALF_WORD_INDEX_MASK
+}
+
+/// Returns `true` if `O` stores entries out of their logical order.
+#[inline]
+pub(crate) const fn is_swapped<O: Ordering>() -> bool {
+    O::BYTE_INDEX_MASK != 0 || O::HALF_WORD_INDEX_MASK != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_is_identity() {
+        for i in 0..8 {
+            assert_eq!(map_byte_index::<Linear>(i), i);
+            assert_eq!(map_half_word_index::<Linear>(i), i);
+        }
+    }
+
+    #[test]
+    fn test_esp32_swaps_half_words_of_each_word() {
+        let bytes: [usize; 8] = core::array::from_fn(map_byte_index::<Esp32>);
+        assert_eq!(bytes, [2, 3, 0, 1, 6, 7, 4, 5]);
+        let half_words: [usize; 4] = core::array::from_fn(map_half_word_index::<Esp32>);
+        assert_eq!(half_words, [1, 0, 3, 2]);
+        assert!(is_swapped::<Esp32>());
+        assert!(!is_swapped::<Linear>());
+    }
+}
@@ -0,0 +1,155 @@
+//! Fixed panel orientation.
+//!
+//! Panels in fixed installations are often mounted upside down or viewed
+//! from behind, e.g. to route the cables. [`Hub75Display`] can flip the
+//! drawing at runtime, but that costs a coordinate mapping per pixel and
+//! one more wrapper for something that never changes.
+//!
+//! The plain and latched framebuffers, including the 8-bit plain and 16-bit
+//! latched variants, take a trailing [`Orientation`] type parameter
+//! instead. It flips every pixel inside `set_pixel_internal`, and reading
+//! pixels back flips the same way, so the rest of the crate sees the
+//! drawing coordinates. The markers are zero-sized and resolved at compile
+//! time, so [`Normal`], the default, costs nothing. On top of this,
+//! runtime rotation and tiling work as before.
+//!
+//! [`Hub75Display`]: crate::display::Hub75Display
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::align::Align4;
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::orientation::Rotate180;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::Color;
+//!
+//! // the panel is mounted upside down
+//! type FBType = DmaFrameBuffer<32, 64, 16, 3, 7, DefaultOrdering, Align4, Rotate180>;
+//!
+//! let mut fb = FBType::new();
+//! let red = Color::new(224, 0, 0);
+//! fb.set_pixel(Point::new(0, 0), red);
+//! // drawn at the bottom right of the panel, read back where it was drawn
+//! assert_eq!(fb.get_pixel(Point::new(0, 0)), Some(red));
+//! ```
+
+/// Fixed flipping of the drawing coordinates relative to the panel.
+///
+/// Implemented by zero-sized marker types.
+pub trait Orientation: Copy + core::fmt::Debug {
+    /// Whether columns are mirrored left to right.
+    const FLIP_X: bool;
+    /// Whether rows are mirrored top to bottom.
+    const FLIP_Y: bool;
+
+    /// Panel column and row of drawing point `(x, y)` on a `COLS` by `ROWS`
+    /// panel. Both must be in range. Flipping is its own inverse, so this
+    /// also maps panel positions back to drawing points.
+    #[inline]
+    #[must_use]
+    fn apply<const COLS: usize, const ROWS: usize>(x: usize, y: usize) -> (usize, usize) {
+        (
+            if Self::FLIP_X { COLS - 1 - x } else { x },
+            if Self::FLIP_Y { ROWS - 1 - y } else { y },
+        )
+    }
+}
+
+macro_rules! orientation {
+    ($(#[$meta:meta])* $name:ident, $flip_x:literal, $flip_y:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $name;
+
+        impl Orientation for $name {
+            const FLIP_X: bool = $flip_x;
+            const FLIP_Y: bool = $flip_y;
+        }
+    };
+}
+
+orientation!(
+    /// Drawing coordinates are panel coordinates (the default).
+    Normal,
+    false,
+    false
+);
+orientation!(
+    /// Columns mirrored left to right, e.g. for a panel viewed from behind.
+    FlipX,
+    true,
+    false
+);
+orientation!(
+    /// Rows mirrored top to bottom.
+    FlipY,
+    false,
+    true
+);
+orientation!(
+    /// Both mirrored, for a panel mounted upside down.
+    Rotate180,
+    true,
+    true
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latched;
+    use crate::ordering::DefaultOrdering;
+    use crate::plain::DmaFrameBuffer;
+    use crate::{align::Align4, Color};
+    use embedded_graphics::prelude::*;
+
+    type Oriented<OR> = DmaFrameBuffer<16, 32, 8, 3, 7, DefaultOrdering, Align4, OR>;
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Normal::apply::<32, 16>(3, 4), (3, 4));
+        assert_eq!(FlipX::apply::<32, 16>(3, 4), (28, 4));
+        assert_eq!(FlipY::apply::<32, 16>(3, 4), (3, 11));
+        assert_eq!(Rotate180::apply::<32, 16>(0, 0), (31, 15));
+    }
+
+    fn check<OR: Orientation>(expected: Point) {
+        let mut fb = Oriented::<OR>::new();
+        let white = Color::new(224, 224, 224);
+        fb.set_pixel(Point::new(3, 4), Color::WHITE);
+        assert_eq!(
+            fb.get_pixel(Point::new(3, 4)),
+            Some(white),
+            "{}",
+            core::any::type_name::<OR>()
+        );
+
+        let mut panel = Oriented::<Normal>::new();
+        panel.set_pixel(expected, Color::WHITE);
+        assert_eq!(
+            fb.as_bytes(),
+            panel.as_bytes(),
+            "{}",
+            core::any::type_name::<OR>()
+        );
+    }
+
+    #[test]
+    fn test_framebuffer_orientation() {
+        check::<Normal>(Point::new(3, 4));
+        check::<FlipX>(Point::new(28, 4));
+        check::<FlipY>(Point::new(3, 11));
+        check::<Rotate180>(Point::new(28, 11));
+    }
+
+    #[test]
+    fn test_latched_orientation() {
+        type FBType = latched::DmaFrameBuffer<16, 32, 8, 3, 7, DefaultOrdering, Align4, Rotate180>;
+        let mut fb = FBType::new();
+        fb.set_pixel(Point::new(31, 15), Color::WHITE);
+        let mut panel = latched::DmaFrameBuffer::<16, 32, 8, 3, 7>::new();
+        panel.set_pixel(Point::new(0, 0), Color::WHITE);
+        assert_eq!(fb.as_bytes(), panel.as_bytes());
+    }
+}
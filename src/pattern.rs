@@ -0,0 +1,215 @@
+//! Test patterns for bringing up a panel.
+//!
+//! A panel that shows garbage is usually miswired or driven with the wrong
+//! parameters. [`draw_test_pattern`] draws a [`TestPattern`] that narrows
+//! the problem down to one group of signals:
+//!
+//! * [`TestPattern::ColorBars`]: vertical bars in white, yellow, cyan,
+//!   green, magenta, red, blue and black. Swapped or dead colour lines
+//!   change the colours; a wrong width or word ordering repeats or
+//!   scrambles the bars.
+//! * [`TestPattern::Address`]: only the rows driven with one address are
+//!   lit, red in the upper and green in the lower half. Stepping through the
+//!   addresses shows dead or swapped address lines (the wrong rows light up
+//!   or several addresses light the same rows) and a wrong scan rate.
+//! * [`TestPattern::PixelWalk`]: a single white pixel, advancing row by row
+//!   with the step. Lost or extra clocks and a wrong column order make it
+//!   jump.
+//! * [`TestPattern::Channel`]: the whole panel in one primary colour, to
+//!   check each colour line and the LEDs on their own.
+//!
+//! Patterns cover the whole target, so they work on tiled and erased
+//! framebuffers as well, and clear what was drawn before.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::pattern::{draw_test_pattern, TestPattern};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! draw_test_pattern(&mut fb, TestPattern::ColorBars).unwrap();
+//!
+//! // light rows 5 and 21, the two rows of address 5 on a 1/16 scan panel
+//! draw_test_pattern(&mut fb, TestPattern::Address { address: 5, nrows: 16 }).unwrap();
+//! ```
+
+use embedded_graphics::prelude::{DrawTarget, RgbColor, Size};
+use embedded_graphics::primitives::Rectangle;
+
+use crate::Color;
+
+/// Colours of [`TestPattern::ColorBars`], from left to right.
+pub const COLOR_BARS: [Color; 8] = [
+    Color::WHITE,
+    Color::YELLOW,
+    Color::CYAN,
+    Color::GREEN,
+    Color::MAGENTA,
+    Color::RED,
+    Color::BLUE,
+    Color::BLACK,
+];
+
+/// A primary colour, see [`TestPattern::Channel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Channel {
+    /// The R1/R2 lines.
+    Red,
+    /// The G1/G2 lines.
+    Green,
+    /// The B1/B2 lines.
+    Blue,
+}
+
+impl Channel {
+    /// Full brightness in this channel only.
+    #[must_use]
+    pub const fn color(self) -> Color {
+        match self {
+            Self::Red => Color::RED,
+            Self::Green => Color::GREEN,
+            Self::Blue => Color::BLUE,
+        }
+    }
+}
+
+/// A pattern drawn by [`draw_test_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TestPattern {
+    /// Eight vertical bars in the colours of [`COLOR_BARS`].
+    ColorBars,
+    /// Only the rows shown while `address` is on the address lines: every
+    /// row `y` with `y % nrows == address`, red in the upper half of the
+    /// panel and green below. Nothing is lit if `address` is not below
+    /// `nrows`.
+    Address {
+        /// Scan row to light.
+        address: u32,
+        /// Scan rows of the panel, e.g. 16 for a 1/16 scan panel.
+        nrows: u32,
+    },
+    /// A single white pixel at `step`, counting row by row from the top
+    /// left and wrapping around after the last pixel.
+    PixelWalk(u32),
+    /// The whole panel in one primary colour.
+    Channel(Channel),
+}
+
+/// Clear `target` and draw `kind` over all of it.
+///
+/// # Errors
+/// Returns the error of the target.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::prelude::*;
+/// use hub75_framebuffer::pattern::{draw_test_pattern, TestPattern};
+/// use hub75_framebuffer::plain::DmaFrameBuffer;
+/// use hub75_framebuffer::Color;
+///
+/// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+/// // step 70 is the 7th pixel of the second row
+/// draw_test_pattern(&mut fb, TestPattern::PixelWalk(70)).unwrap();
+/// assert_eq!(fb.get_pixel(Point::new(6, 1)), Some(Color::new(224, 224, 224)));
+/// assert_eq!(fb.get_pixel(Point::new(5, 1)), Some(Color::BLACK));
+/// ```
+pub fn draw_test_pattern<D>(target: &mut D, kind: TestPattern) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Color>,
+{
+    let area = target.bounding_box();
+    let Size { width, height } = area.size;
+    let origin = area.top_left;
+    target.clear(Color::BLACK)?;
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+    match kind {
+        TestPattern::ColorBars => {
+            let bars = COLOR_BARS.len() as u32;
+            for (i, color) in (0..).zip(COLOR_BARS) {
+                let left = width * i / bars;
+                let right = width * (i + 1) / bars;
+                let bar =
+                    Rectangle::new(origin + Size::new(left, 0), Size::new(right - left, height));
+                target.fill_solid(&bar, color)?;
+            }
+        }
+        TestPattern::Address { address, nrows } => {
+            if nrows == 0 || address >= nrows {
+                return Ok(());
+            }
+            for y in (address..height).step_by(nrows as usize) {
+                let color = if y < height / 2 {
+                    Color::RED
+                } else {
+                    Color::GREEN
+                };
+                let row = Rectangle::new(origin + Size::new(0, y), Size::new(width, 1));
+                target.fill_solid(&row, color)?;
+            }
+        }
+        TestPattern::PixelWalk(step) => {
+            let index = u64::from(step) % (u64::from(width) * u64::from(height));
+            let (x, y) = (index % u64::from(width), index / u64::from(width));
+            let pixel = origin + Size::new(x as u32, y as u32);
+            target.fill_solid(&Rectangle::new(pixel, Size::new(1, 1)), Color::WHITE)?;
+        }
+        TestPattern::Channel(channel) => {
+            target.fill_solid(&area, channel.color())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::{DrawTargetExt, Point};
+
+    fn draw(size: Size, kind: TestPattern) -> MockDisplay<Color> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        let area = Rectangle::new(Point::zero(), size);
+        draw_test_pattern(&mut display.clipped(&area), kind).unwrap();
+        display
+    }
+
+    #[test]
+    fn test_color_bars() {
+        draw(Size::new(16, 2), TestPattern::ColorBars)
+            .assert_pattern(&["WWYYCCGGMMRRBBKK", "WWYYCCGGMMRRBBKK"]);
+        // narrower than the number of bars
+        draw(Size::new(4, 1), TestPattern::ColorBars).assert_pattern(&["YGRK"]);
+    }
+
+    #[test]
+    fn test_address() {
+        let kind = TestPattern::Address {
+            address: 1,
+            nrows: 3,
+        };
+        draw(Size::new(2, 6), kind).assert_pattern(&["KK", "RR", "KK", "KK", "GG", "KK"]);
+
+        let out_of_range = TestPattern::Address {
+            address: 3,
+            nrows: 3,
+        };
+        draw(Size::new(2, 2), out_of_range).assert_pattern(&["KK", "KK"]);
+    }
+
+    #[test]
+    fn test_pixel_walk() {
+        draw(Size::new(3, 2), TestPattern::PixelWalk(4)).assert_pattern(&["KKK", "KWK"]);
+        draw(Size::new(3, 2), TestPattern::PixelWalk(6)).assert_pattern(&["WKK", "KKK"]);
+    }
+
+    #[test]
+    fn test_channel() {
+        draw(Size::new(2, 1), TestPattern::Channel(Channel::Blue)).assert_pattern(&["BB"]);
+        assert_eq!(Channel::Green.color(), Color::GREEN);
+    }
+}
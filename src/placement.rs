@@ -0,0 +1,109 @@
+//! Placing framebuffers in DMA-capable RAM sections.
+//!
+//! Many targets can only DMA from some of their memories: the STM32H7 DMA1/2
+//! cannot reach DTCM, the ESP32-S3 needs internal SRAM or a DMA-enabled PSRAM
+//! region, and so on. Framebuffers are also too large to build on the stack,
+//! so they usually end up in a `static` anyway.
+//!
+//! [`place_framebuffer!`](crate::place_framebuffer) declares such a `static`
+//! in a named linker section, initializes it in place with
+//! `init_in_place()` and returns a `&'static mut` reference. Each expansion
+//! can be evaluated once; a second evaluation panics. The guard flag is a
+//! [`static_cell::StaticCell`] kept outside the named section, so the section
+//! may be `NOLOAD` (uninitialized) memory.
+//!
+//! The section must exist in the target's linker script (e.g. `memory.x`).
+//! Leave the section out to place the buffer in the default `.bss`.
+//!
+//! # Example
+//! ```rust,no_run
+//! use hub75_framebuffer::latched::DmaFrameBuffer;
+//! use hub75_framebuffer::place_framebuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//! type FBType = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+//!
+//! // in `.sram1`, which the DMA controller can reach
+//! let fb: &'static mut FBType = place_framebuffer!(".sram1", FBType);
+//! // in `.bss`
+//! let back: &'static mut FBType = place_framebuffer!(FBType);
+//! ```
+
+#[doc(hidden)]
+pub use static_cell::StaticCell as __StaticCell;
+
+/// Place a formatted framebuffer of type `$ty` in a `static`, optionally in
+/// the linker section `$section`, and return a `&'static mut` to it.
+///
+/// See the [`placement`](crate::placement) module for details.
+///
+/// # Panics
+///
+/// Panics if the same expansion is evaluated more than once.
+#[macro_export]
+macro_rules! place_framebuffer {
+    ($section:literal, $ty:ty) => {{
+        #[link_section = $section]
+        static mut BUFFER: ::core::mem::MaybeUninit<$ty> = ::core::mem::MaybeUninit::uninit();
+        $crate::place_framebuffer!(@init BUFFER, $ty)
+    }};
+    ($ty:ty) => {{
+        static mut BUFFER: ::core::mem::MaybeUninit<$ty> = ::core::mem::MaybeUninit::uninit();
+        $crate::place_framebuffer!(@init BUFFER, $ty)
+    }};
+    (@init $buffer:ident, $ty:ty) => {{
+        static TAKEN: $crate::placement::__StaticCell<()> = $crate::placement::__StaticCell::new();
+        TAKEN.init(());
+        // SAFETY: `TAKEN` panics on a second evaluation, so this is the only
+        // reference to the buffer.
+        let slot = unsafe { &mut *::core::ptr::addr_of_mut!($buffer) };
+        <$ty>::init_in_place(slot)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compute_frame_count, compute_rows};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+    type BitplaneFB = crate::bitplane::plain::DmaFrameBuffer<NROWS, COLS, 4>;
+
+    #[test]
+    fn test_placed_buffers_match_new() {
+        let plain: &'static mut PlainFB = place_framebuffer!(".data.hub75", PlainFB);
+        assert_eq!(plain.as_bytes(), PlainFB::new().as_bytes());
+
+        let latched: &'static mut LatchedFB = place_framebuffer!(LatchedFB);
+        assert_eq!(latched.as_bytes(), LatchedFB::new().as_bytes());
+
+        let bitplane: &'static mut BitplaneFB = place_framebuffer!(BitplaneFB);
+        let reference = BitplaneFB::new();
+        for plane_idx in 0..4 {
+            let (ptr, len) = crate::FrameBuffer::plane_ptr_len(bitplane, plane_idx);
+            let (ref_ptr, ref_len) = crate::FrameBuffer::plane_ptr_len(&reference, plane_idx);
+            let placed = unsafe { core::slice::from_raw_parts(ptr, len) };
+            let expected = unsafe { core::slice::from_raw_parts(ref_ptr, ref_len) };
+            assert_eq!(placed, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "already full")]
+    fn test_second_evaluation_panics() {
+        for _ in 0..2 {
+            let _: &'static mut LatchedFB = place_framebuffer!(LatchedFB);
+        }
+    }
+}
@@ -103,6 +103,18 @@
 //! The pixel clock is generated by the peripheral that owns the DMA stream and
 //! is therefore **not** part of the 16-bit word stored in the framebuffer.
 //!
+//! Boards that wire the signals to other bits of the bus can remap them with
+//! a [`PinMap`] instead of an adapter board.
+//!
+//! The address always drives all five lines, so 1/32-scan panels such as
+//! 64×64 or 128×64 (`NROWS = 32`) get the `E` line set for scan rows 16 to
+//! 31. On panels with fewer scan rows `E` simply stays low. More than 32
+//! scan rows do not fit the address lines and fail to compile.
+//!
+//! Two adjacent pixels do not fit one 16-bit word: their 12 colour bits,
+//! the 5 address lines, `OE` and `LAT` need 19 bits. [`crate::plain32`]
+//! packs them into 32-bit words instead, two of these entries per word.
+//!
 //! # Binary Code Modulation (BCM) Frames
 //! Brightness is achieved with Binary-Code-Modulation as outlined in
 //! <https://www.batsocks.co.uk/readme/art_bcm_1.htm>. For a colour depth of
@@ -129,26 +141,26 @@
 //! must be properly aligned in memory and the DMA configuration must match the
 //! buffer layout.
 
-use core::convert::Infallible;
+use core::marker::PhantomData;
 
-use crate::{FrameBufferOperations, MutableFrameBuffer};
+use crate::align::{Alignment, DefaultAlignment};
+use crate::export::Layout;
+use crate::format::{EntryFormat, FrameOrder, RowFormat, Sequential};
+use crate::ordering::{is_swapped, map_half_word_index, DefaultOrdering, Ordering};
+use crate::orientation::{Normal, Orientation};
+use crate::Hub75Error;
 use bitfield::bitfield;
-use embedded_dma::ReadBuffer;
-use embedded_graphics::pixelcolor::RgbColor;
-use embedded_graphics::prelude::Point;
 
-use super::Color;
-use super::FrameBuffer;
 use super::WordSize;
 
 #[cfg(feature = "blank-delay-1")]
-const BLANKING_DELAY: usize = 1;
+pub(crate) const BLANKING_DELAY: usize = 1;
 #[cfg(feature = "blank-delay-2")]
-const BLANKING_DELAY: usize = 2;
+pub(crate) const BLANKING_DELAY: usize = 2;
 #[cfg(feature = "blank-delay-4")]
-const BLANKING_DELAY: usize = 4;
+pub(crate) const BLANKING_DELAY: usize = 4;
 #[cfg(feature = "blank-delay-8")]
-const BLANKING_DELAY: usize = 8;
+pub(crate) const BLANKING_DELAY: usize = 8;
 
 // Default to 1 if no blanking delay feature is enabled
 #[cfg(not(any(
@@ -157,32 +169,182 @@ const BLANKING_DELAY: usize = 8;
     feature = "blank-delay-4",
     feature = "blank-delay-8"
 )))]
-const BLANKING_DELAY: usize = 1;
+pub(crate) const BLANKING_DELAY: usize = 1;
+
+/// Assignment of the HUB75 signals to the bits of the 16-bit word.
+///
+/// The defaults are the mapping shown in the [module documentation](self).
+/// Boards that route the signals to other bits of the bus implement this
+/// trait on a marker type, overriding only the constants that differ, and
+/// pass it to [`PlainFormat`]. The data template is generated from it at
+/// compile time, so there is no run-time cost:
+///
+/// ```rust
+/// use hub75_framebuffer::format::DmaFrameBuffer;
+/// use hub75_framebuffer::plain::{PinMap, PlainFormat};
+/// use hub75_framebuffer::{compute_frame_count, compute_rows};
+///
+/// #[derive(Debug, Clone, Copy)]
+/// struct MyBoard;
+///
+/// // colours on the low byte, control and address lines above them
+/// impl PinMap for MyBoard {
+///     const R1: u8 = 0;
+///     const G1: u8 = 1;
+///     const B1: u8 = 2;
+///     const R2: u8 = 3;
+///     const G2: u8 = 4;
+///     const B2: u8 = 5;
+///     const OE: u8 = 6;
+///     const LAT: u8 = 7;
+///     const ADDR: [u8; 5] = [8, 9, 10, 11, 12];
+/// }
+///
+/// const ROWS: usize = 32;
+/// const COLS: usize = 64;
+/// const BITS: u8 = 3;
+/// const NROWS: usize = compute_rows(ROWS);
+/// const FRAME_COUNT: usize = compute_frame_count(BITS);
+///
+/// type FBType = DmaFrameBuffer<PlainFormat<MyBoard>, ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+/// let fb = FBType::new();
+/// ```
+///
+/// All 13 signals must be distinct bits of the word; other maps fail to
+/// compile.
+///
+/// Boards with inverting level shifters on `OE` or `LAT` set
+/// [`OE_ACTIVE_LOW`](PinMap::OE_ACTIVE_LOW) or
+/// [`LAT_ACTIVE_LOW`](PinMap::LAT_ACTIVE_LOW); the bit is then flipped in
+/// every word. `CLK` is not part of the word, so its polarity is set on the
+/// peripheral that generates it.
+pub trait PinMap: Copy + core::fmt::Debug {
+    /// Output enable.
+    const OE: u8 = 8;
+    /// Latch.
+    const LAT: u8 = 5;
+    /// Red of the upper half.
+    const R1: u8 = 9;
+    /// Green of the upper half.
+    const G1: u8 = 10;
+    /// Blue of the upper half.
+    const B1: u8 = 11;
+    /// Red of the lower half.
+    const R2: u8 = 12;
+    /// Green of the lower half.
+    const G2: u8 = 13;
+    /// Blue of the lower half.
+    const B2: u8 = 14;
+    /// Data-bus bit of each row address line, `A` first.
+    const ADDR: [u8; 5] = [0, 1, 2, 3, 4];
+    /// `OE` is low while the output is enabled.
+    const OE_ACTIVE_LOW: bool = false;
+    /// `LAT` is low while the latch is open.
+    const LAT_ACTIVE_LOW: bool = false;
+}
+
+/// The pin map of the reference wiring, see [`PinMap`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DefaultPins;
+
+impl PinMap for DefaultPins {}
+
+/// Control bits that are active low, to be flipped in every word.
+pub(crate) const fn inversion_mask<P: PinMap>() -> u16 {
+    ((P::OE_ACTIVE_LOW as u16) << P::OE) | ((P::LAT_ACTIVE_LOW as u16) << P::LAT)
+}
+
+#[inline]
+const fn spread_rgb(bits: u8, r: u8, g: u8, b: u8) -> u16 {
+    ((bits as u16 & 1) << r) | (((bits as u16 >> 1) & 1) << g) | (((bits as u16 >> 2) & 1) << b)
+}
+
+pub(crate) const fn color0_mask<P: PinMap>() -> u16 {
+    spread_rgb(0b111, P::R1, P::G1, P::B1)
+}
+
+pub(crate) const fn color1_mask<P: PinMap>() -> u16 {
+    spread_rgb(0b111, P::R2, P::G2, P::B2)
+}
+
+/// Row address `addr` spread onto the address lines of `P`.
+const fn spread_addr<P: PinMap>(addr: u8) -> u16 {
+    let mut word = 0;
+    let mut i = 0;
+    while i < 5 {
+        word |= ((addr as u16 >> i) & 1) << P::ADDR[i];
+        i += 1;
+    }
+    word
+}
+
+/// Checks that the signals of `P` are distinct bits of a 16-bit word.
+pub(crate) const fn pins_valid<P: PinMap>() -> bool {
+    let pins = [
+        P::OE,
+        P::LAT,
+        P::R1,
+        P::G1,
+        P::B1,
+        P::R2,
+        P::G2,
+        P::B2,
+        P::ADDR[0],
+        P::ADDR[1],
+        P::ADDR[2],
+        P::ADDR[3],
+        P::ADDR[4],
+    ];
+    let mut used = 0u16;
+    let mut i = 0;
+    while i < pins.len() {
+        if pins[i] >= 16 || used & (1 << pins[i]) != 0 {
+            return false;
+        }
+        used |= 1 << pins[i];
+        i += 1;
+    }
+    true
+}
 
 /// Creates a pre-computed data template for a row with the specified addresses.
 /// This template contains all the timing and control signals but no pixel data.
+///
+/// The output is disabled `BLANKING_DELAY + EARLY` entries before the
+/// address changes, and the new address is held for `SETTLE` entries before
+/// the latch.
 #[inline]
-const fn make_data_template<const COLS: usize>(addr: u8, prev_addr: u8) -> [Entry; COLS] {
+const fn make_data_template<
+    const COLS: usize,
+    O: Ordering,
+    P: PinMap,
+    const SETTLE: usize,
+    const EARLY: usize,
+>(
+    addr: u8,
+    prev_addr: u8,
+) -> [Entry; COLS] {
     let mut data = [Entry::new(); COLS];
     let mut i = 0;
 
     while i < COLS {
         let mut entry = Entry::new();
-        entry.0 = prev_addr as u16;
+        entry.0 = spread_addr::<P>(prev_addr);
 
         // Apply timing control based on position
-        if i == 1 {
-            entry.0 |= 0b1_0000_0000; // set output_enable bit
-        } else if i == COLS - BLANKING_DELAY - 1 {
-            // output_enable already false from initialization
-        } else if i == COLS - 1 {
-            entry.0 |= 0b0010_0000; // set latch bit
-            entry.0 = (entry.0 & !0b0001_1111) | (addr as u16); // set new address
-        } else if i > 1 && i < COLS - BLANKING_DELAY - 1 {
-            entry.0 |= 0b1_0000_0000; // set output_enable bit
+        if i == COLS - 1 {
+            entry.0 |= 1 << P::LAT; // set latch bit
+        }
+        if i >= COLS - SETTLE - 1 {
+            // set new address
+            entry.0 = (entry.0 & !spread_addr::<P>(0b1_1111)) | spread_addr::<P>(addr);
+        } else if i >= 1 && i < COLS - SETTLE - BLANKING_DELAY - EARLY - 1 {
+            entry.0 |= 1 << P::OE; // set output_enable bit
         }
 
-        data[map_index(i)] = entry;
+        entry.0 ^= inversion_mask::<P>();
+        data[map_half_word_index::<O>(i)] = entry;
         i += 1;
     }
 
@@ -210,21 +372,39 @@ bitfield! {
     /// - Bit 6: Dummy bit 0
     /// - Bit 5: Latch signal
     /// - Bits 4-0: Row address
-    #[derive(Clone, Copy, Default, PartialEq)]
+    ///
+    /// The layout is that of [`DefaultPins`] and is part of the stable API,
+    /// so custom drivers and init-sequence generators can decode and build
+    /// words with it, e.g. from [`as_words`](crate::format::DmaFrameBuffer::as_words).
+    /// Framebuffers with another [`PinMap`] place the signals on the bits of
+    /// their pin map instead, and the accessors do not apply to their words.
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
     #[repr(transparent)]
-    struct Entry(u16);
-    dummy2, set_dummy2: 15;
-    blu2, set_blu2: 14;
-    grn2, set_grn2: 13;
-    red2, set_red2: 12;
-    blu1, set_blu1: 11;
-    grn1, set_grn1: 10;
-    red1, set_red1: 9;
-    output_enable, set_output_enable: 8;
-    dummy1, set_dummy1: 7;
-    dummy0, set_dummy0: 6;
-    latch, set_latch: 5;
-    addr, set_addr: 4, 0;
+    pub struct Entry(u16);
+    /// Dummy bit 2 (bit 15).
+    pub dummy2, set_dummy2: 15;
+    /// Blue of the lower half (bit 14).
+    pub blu2, set_blu2: 14;
+    /// Green of the lower half (bit 13).
+    pub grn2, set_grn2: 13;
+    /// Red of the lower half (bit 12).
+    pub red2, set_red2: 12;
+    /// Blue of the upper half (bit 11).
+    pub blu1, set_blu1: 11;
+    /// Green of the upper half (bit 10).
+    pub grn1, set_grn1: 10;
+    /// Red of the upper half (bit 9).
+    pub red1, set_red1: 9;
+    /// Output enable (bit 8).
+    pub output_enable, set_output_enable: 8;
+    /// Dummy bit 1 (bit 7).
+    pub dummy1, set_dummy1: 7;
+    /// Dummy bit 0 (bit 6).
+    pub dummy0, set_dummy0: 6;
+    /// Latch (bit 5).
+    pub latch, set_latch: 5;
+    /// Row address (bits 4-0).
+    pub addr, set_addr: 4, 0;
 }
 
 impl core::fmt::Debug for Entry {
@@ -243,24 +423,36 @@ impl defmt::Format for Entry {
 }
 
 impl Entry {
-    const fn new() -> Self {
+    /// A word with all signals low.
+    #[must_use]
+    pub const fn new() -> Self {
         Self(0)
     }
 
-    // Optimized color bit manipulation constants and methods
-    const COLOR0_MASK: u16 = 0b0000_1110_0000_0000; // bits 9-11: R1, G1, B1
-    const COLOR1_MASK: u16 = 0b0111_0000_0000_0000; // bits 12-14: R2, G2, B2
+    /// The word with the raw value `bits`, as clocked out.
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// The raw value of the word, as clocked out.
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
 
+    /// Set R1/G1/B1 from `bits` (bit 0 red, 1 green, 2 blue) at the positions of `P`.
     #[inline]
-    fn set_color0_bits(&mut self, bits: u8) {
-        let bits16 = u16::from(bits) << 9;
-        self.0 = (self.0 & !Self::COLOR0_MASK) | (bits16 & Self::COLOR0_MASK);
+    fn set_color0_bits<P: PinMap>(&mut self, bits: u8) {
+        let spread = spread_rgb(bits, P::R1, P::G1, P::B1);
+        self.0 = (self.0 & !color0_mask::<P>()) | spread;
     }
 
+    /// Set R2/G2/B2 from `bits` (bit 0 red, 1 green, 2 blue) at the positions of `P`.
     #[inline]
-    fn set_color1_bits(&mut self, bits: u8) {
-        let bits16 = u16::from(bits) << 12;
-        self.0 = (self.0 & !Self::COLOR1_MASK) | (bits16 & Self::COLOR1_MASK);
+    fn set_color1_bits<P: PinMap>(&mut self, bits: u8) {
+        let spread = spread_rgb(bits, P::R2, P::G2, P::B2);
+        self.0 = (self.0 & !color1_mask::<P>()) | spread;
     }
 }
 
@@ -272,118 +464,260 @@ impl Entry {
 /// - Latch signal generation for row updates
 /// - Row address management
 /// - Color data for both sub-pixels
+///
+/// `BLANK` entries with the output disabled come in front of the pixel data,
+/// the output is disabled `EARLY` entries before the blanking delay, and the
+/// last `SETTLE` entries before the latch already carry the new address.
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[repr(C)]
-struct Row<const COLS: usize> {
+pub struct Row<
+    const COLS: usize,
+    O: Ordering = DefaultOrdering,
+    P: PinMap = DefaultPins,
+    const BLANK: usize = 0,
+    const SETTLE: usize = 0,
+    const EARLY: usize = 0,
+> {
+    blank: [Entry; BLANK],
     data: [Entry; COLS],
+    _ordering: PhantomData<(O, P)>,
 }
 
-const fn map_index(i: usize) -> usize {
-    #[cfg(feature = "esp32-ordering")]
-    {
-        i ^ 1
-    }
-    #[cfg(not(feature = "esp32-ordering"))]
-    {
-        i
-    }
-}
-
-impl<const COLS: usize> Default for Row<COLS> {
+impl<
+        const COLS: usize,
+        O: Ordering,
+        P: PinMap,
+        const BLANK: usize,
+        const SETTLE: usize,
+        const EARLY: usize,
+    > Default for Row<COLS, O, P, BLANK, SETTLE, EARLY>
+{
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const COLS: usize> Row<COLS> {
-    pub const fn new() -> Self {
-        Self {
-            data: [Entry::new(); COLS],
-        }
+impl<
+        const COLS: usize,
+        O: Ordering,
+        P: PinMap,
+        const BLANK: usize,
+        const SETTLE: usize,
+        const EARLY: usize,
+    > RowFormat for Row<COLS, O, P, BLANK, SETTLE, EARLY>
+{
+    #[inline]
+    fn format(&mut self, addr: u8, prev_addr: u8) {
+        Row::format(self, addr, prev_addr);
     }
 
-    pub fn format(&mut self, addr: u8, prev_addr: u8) {
-        // Use pre-computed template and bulk copy for maximum performance
-        let template = make_data_template::<COLS>(addr, prev_addr);
-        self.data.copy_from_slice(&template);
+    #[inline]
+    fn clear_colors(&mut self) {
+        Row::clear_colors(self);
     }
 
-    /// Fast clear method that preserves timing/control bits while clearing pixel data.
-    /// Uses bulk memory operations for maximum performance.
     #[inline]
-    pub fn clear_colors(&mut self) {
-        // Clear color bits while preserving timing and control bits
-        const COLOR_CLEAR_MASK: u16 = !0b0111_1110_0000_0000; // Clear bits 9-14 (R1,G1,B1,R2,G2,B2)
-
-        for entry in &mut self.data {
-            entry.0 &= COLOR_CLEAR_MASK;
-        }
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        Row::set_color0(self, col, r, g, b);
     }
 
     #[inline]
-    pub fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
-        let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
-        let col = map_index(col);
-        self.data[col].set_color0_bits(bits);
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        Row::set_color1(self, col, r, g, b);
     }
 
     #[inline]
-    pub fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
-        let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
-        let col = map_index(col);
-        self.data[col].set_color1_bits(bits);
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        Row::color(self, half, col)
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-#[repr(C)]
-struct Frame<const ROWS: usize, const COLS: usize, const NROWS: usize> {
-    rows: [Row<COLS>; NROWS],
-}
+impl<
+        const COLS: usize,
+        O: Ordering,
+        P: PinMap,
+        const BLANK: usize,
+        const SETTLE: usize,
+        const EARLY: usize,
+    > Row<COLS, O, P, BLANK, SETTLE, EARLY>
+{
+    /// First entry of the blanking window before the address change.
+    const OE_END: usize = COLS - SETTLE - BLANKING_DELAY - EARLY - 1;
+
+    const LAYOUT_OK: () = {
+        assert!(pins_valid::<P>(), "pin map has overlapping signals");
+        assert!(
+            BLANK % 2 == 0 || !is_swapped::<O>(),
+            "blanking word count must be even with a swapped ordering"
+        );
+        assert!(
+            COLS > SETTLE + BLANKING_DELAY + EARLY + 2,
+            "row too short for the blanking delay and settle words"
+        );
+    };
 
-impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Frame<ROWS, COLS, NROWS> {
-    pub const fn new() -> Self {
+    const fn new() -> Self {
+        let () = Self::LAYOUT_OK;
         Self {
-            rows: [Row::new(); NROWS],
+            blank: [Entry::new(); BLANK],
+            data: [Entry::new(); COLS],
+            _ordering: PhantomData,
         }
     }
 
-    pub fn format(&mut self) {
-        for (addr, row) in self.rows.iter_mut().enumerate() {
-            let prev_addr = if addr == 0 {
-                NROWS as u8 - 1
+    fn format(&mut self, addr: u8, prev_addr: u8) {
+        // Output disabled, still showing the row latched at the end of the
+        // previous one
+        self.blank = [Entry(spread_addr::<P>(prev_addr) ^ inversion_mask::<P>()); BLANK];
+
+        // Use pre-computed template and bulk copy for maximum performance
+        let template = make_data_template::<COLS, O, P, SETTLE, EARLY>(addr, prev_addr);
+        self.data.copy_from_slice(&template);
+    }
+
+    /// Disable the output for the first `blanked` entries of the lit window
+    /// (from the second column up to the blanking delay) and enable it for
+    /// the rest.
+    fn set_oe_window(&mut self, blanked: usize) {
+        let oe = 1 << P::OE;
+        for i in 1..Self::OE_END {
+            let entry = &mut self.data[map_half_word_index::<O>(i)];
+            // the bit is set for an enabled output unless OE is active low
+            if (i - 1 < blanked) == P::OE_ACTIVE_LOW {
+                entry.0 |= oe;
             } else {
-                addr as u8 - 1
-            };
-            row.format(addr as u8, prev_addr);
+                entry.0 &= !oe;
+            }
         }
     }
 
+    /// Number of leading entries of the lit window with the output
+    /// disabled, as set by [`Self::set_oe_window`].
+    fn oe_window(&self) -> usize {
+        let oe = 1 << P::OE;
+        (1..Self::OE_END)
+            .take_while(|&i| {
+                (self.data[map_half_word_index::<O>(i)].0 & oe != 0) == P::OE_ACTIVE_LOW
+            })
+            .count()
+    }
+
     /// Fast clear method that preserves timing/control bits while clearing pixel data.
+    /// Uses bulk memory operations for maximum performance.
     #[inline]
-    pub fn clear_colors(&mut self) {
-        for row in &mut self.rows {
-            row.clear_colors();
+    fn clear_colors(&mut self) {
+        // Clear color bits while preserving timing and control bits
+        let color_clear_mask: u16 = !(color0_mask::<P>() | color1_mask::<P>());
+
+        for entry in &mut self.data {
+            entry.0 &= color_clear_mask;
         }
     }
 
     #[inline]
-    pub fn set_pixel(&mut self, y: usize, x: usize, red: bool, green: bool, blue: bool) {
-        let row = &mut self.rows[if y < NROWS { y } else { y - NROWS }];
-        if y < NROWS {
-            row.set_color0(x, red, green, blue);
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
+        let col = map_half_word_index::<O>(col);
+        self.data[col].set_color0_bits::<P>(bits);
+    }
+
+    #[inline]
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
+        let col = map_half_word_index::<O>(col);
+        self.data[col].set_color1_bits::<P>(bits);
+    }
+
+    #[inline]
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        let word = self.data[map_half_word_index::<O>(col)].0;
+        let (r, g, b) = if half == 0 {
+            (P::R1, P::G1, P::B1)
         } else {
-            row.set_color1(x, red, green, blue);
-        }
+            (P::R2, P::G2, P::B2)
+        };
+        (word >> r & 1 != 0, word >> g & 1 != 0, word >> b & 1 != 0)
     }
 }
 
-impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
-    for Frame<ROWS, COLS, NROWS>
+/// [`EntryFormat`] of the plain framebuffer: 16-bit words with the row
+/// address, latch and output enable in every word.
+///
+/// `P` assigns the signals to data-bus bits for boards that do not follow
+/// the reference wiring, see [`PinMap`].
+///
+/// `BLANK` adds that many words with the output disabled in front of the
+/// pixel data of every row, right after the previous row was latched. Fast
+/// panels that show the previous row bleeding into the next at high clock
+/// rates settle during them, at the cost of `BLANK` clocks per row. Unlike
+/// the `blank-delay-*` features, which blank columns before the latch, they
+/// are shifted in ahead of the pixels and fall off the end of the panel's
+/// shift register. With a swapped [`Ordering`] the count must be even.
+///
+/// `SETTLE` drives the next row's address for that many words before the
+/// latch, with the output still disabled. Panels with slow address decoders
+/// that show faint ghost rows need a wider settle window than the single
+/// latch word; each word of it costs one lit column. The `blank-delay-*`
+/// features set the words between disabling the output and changing the
+/// address.
+///
+/// `EARLY` disables the output that many words earlier before the address
+/// changes, on top of the `blank-delay-*` features, for row drivers that
+/// are still switching off when the address changes. Each word of it costs
+/// one lit column. [`GhostCancel`] combines `BLANK`, `SETTLE` and `EARLY`
+/// into one preset.
+///
+/// `FO` sets the order of the BCM frames in memory, see [`FrameOrder`].
+/// [`Interleaved`](crate::format::Interleaved) sends every scan row of all
+/// frames back to back, which reduces flicker at low frame counts.
+/// [`WeightedPlanes`](crate::format::WeightedPlanes) stores bit-plane `n`
+/// in `2^n` consecutive frames, so a stream clocked out at a constant rate
+/// still has true binary weighting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PlainFormat<
+    P: PinMap = DefaultPins,
+    const BLANK: usize = 0,
+    FO: FrameOrder = Sequential,
+    const SETTLE: usize = 0,
+    const EARLY: usize = 0,
+>(PhantomData<(P, FO)>);
+
+/// Anti-ghosting preset of [`PlainFormat`], tuned by a single `LEVEL`.
+///
+/// Every row gets `LEVEL` dark clocks with the output disabled in front of
+/// the pixel data (`BLANK`), the output switches off `LEVEL` words earlier
+/// before the address changes (`EARLY`), and the new address settles for
+/// `LEVEL` words before the latch (`SETTLE`). That covers the three usual
+/// causes of ghost rows, drivers still lit from the previous row, slow row
+/// drivers and slow address decoders, without tuning each knob on its own.
+///
+/// Start at 1 and raise the level until the ghosting is gone. Each level
+/// costs one clock and two lit columns per row. With a swapped
+/// [`Ordering`], as on the original ESP32, the level must be even.
+///
+/// ```rust
+/// use hub75_framebuffer::format::DmaFrameBuffer;
+/// use hub75_framebuffer::plain::GhostCancel;
+///
+/// type FBType = DmaFrameBuffer<GhostCancel<2>, 32, 64, 16, 3, 7>;
+///
+/// let fb = FBType::new();
+/// assert_eq!(fb.verify_control_bits(), Ok(()));
+/// ```
+pub type GhostCancel<const LEVEL: usize, P = DefaultPins> =
+    PlainFormat<P, LEVEL, Sequential, LEVEL, LEVEL>;
+
+// SAFETY: rows are `repr(C)` arrays of integer wrappers without padding, and
+// every bit pattern of them is valid.
+unsafe impl<P: PinMap, const BLANK: usize, FO: FrameOrder, const SETTLE: usize, const EARLY: usize>
+    EntryFormat for PlainFormat<P, BLANK, FO, SETTLE, EARLY>
 {
-    fn default() -> Self {
-        Self::new()
-    }
+    const WORD_SIZE: WordSize = WordSize::Sixteen;
+    const LAYOUT: Layout = Layout::Plain;
+    type Pad = u64;
+    type FrameOrder = FO;
+    type Row<const COLS: usize, O: Ordering> = Row<COLS, O, P, BLANK, SETTLE, EARLY>;
 }
 
 /// DMA-compatible framebuffer for HUB75 LED panels.
@@ -399,6 +733,9 @@ impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
 /// - `NROWS`: Number of rows per scan (typically half of ROWS)
 /// - `BITS`: Color depth (1-8 bits)
 /// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA byte [`Ordering`], defaults to [`DefaultOrdering`]
+/// - `A`: minimum buffer [`Alignment`], defaults to [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`], defaults to [`Normal`]
 ///
 /// # Helper Functions
 /// Use these functions to compute the correct values:
@@ -407,441 +744,265 @@ impl<const ROWS: usize, const COLS: usize, const NROWS: usize> Default
 /// - `esp_hub75::compute_rows(ROWS)`: Computes the number of rows per scan
 ///
 /// # Memory Layout
-/// The buffer is aligned to ensure efficient DMA transfers and contains:
+/// The buffer is aligned to at least 8 bytes, or `A` if larger, and padded
+/// to a multiple of its alignment. It contains:
 /// - A 64-bit alignment field
 /// - An array of frames, each containing the full panel data
-#[derive(Copy, Clone)]
-#[repr(C)]
-pub struct DmaFrameBuffer<
+pub type DmaFrameBuffer<
     const ROWS: usize,
     const COLS: usize,
     const NROWS: usize,
     const BITS: u8,
     const FRAME_COUNT: usize,
-> {
-    _align: u64,
-    frames: [Frame<ROWS, COLS, NROWS>; FRAME_COUNT],
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > Default for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    O = DefaultOrdering,
+    A = DefaultAlignment,
+    OR = Normal,
+> = crate::format::DmaFrameBuffer<PlainFormat, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>;
 
 impl<
+        P: PinMap,
+        const BLANK: usize,
+        FO: FrameOrder,
+        const SETTLE: usize,
+        const EARLY: usize,
         const ROWS: usize,
         const COLS: usize,
         const NROWS: usize,
         const BITS: u8,
         const FRAME_COUNT: usize,
-    > DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    >
+    crate::format::DmaFrameBuffer<
+        PlainFormat<P, BLANK, FO, SETTLE, EARLY>,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
 {
-    /// Create a new, ready-to-use framebuffer.
+    /// Returns the DMA stream as 16-bit words, in the order they are clocked
+    /// out.
     ///
-    /// This creates a new framebuffer and automatically formats it with proper timing signals.
-    /// The framebuffer is immediately ready for pixel operations and DMA transfers.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `BITS` is greater than 8, as only 1-8 bit color depths are supported.
-    ///
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
-    ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
-    ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// // No need to call format() - framebuffer is ready to use!
-    /// ```
+    /// The slice starts on an 8-byte boundary and covers all frames, like
+    /// [`Self::as_bytes`]. Word order follows the ordering parameter `O`.
     #[must_use]
-    pub fn new() -> Self {
-        debug_assert!(BITS <= 8);
-
-        let mut instance = Self {
-            _align: 0,
-            frames: [Frame::new(); FRAME_COUNT],
-        };
-
-        // Pre-format the framebuffer so it's immediately ready for use
-        instance.format();
-        instance
+    pub fn as_words(&self) -> &[u16] {
+        let len = core::mem::size_of_val(&self.frames) / 2;
+        // SAFETY: `Entry` is a `repr(transparent)` `u16` and the frames are
+        // `repr(C)` arrays of entries without padding.
+        unsafe { core::slice::from_raw_parts((&raw const self.frames).cast::<u16>(), len) }
     }
 
-    /// Returns the number of BCM chunks in this framebuffer (always 1 for
-    /// single-plane framebuffers — the entire buffer is one contiguous chunk).
+    /// Returns the words of the DMA stream returned by `read_buffer()`, see
+    /// [`Self::active_bytes`].
     #[must_use]
-    pub const fn bcm_chunk_count() -> usize {
-        1
+    pub fn active_words(&self) -> &[u16] {
+        &self.as_words()[..self.active_len_bytes() / 2]
     }
 
-    /// Returns the byte size of one BCM chunk (for single-plane framebuffers
-    /// this equals the total DMA buffer size, since BCM weighting is baked in).
-    #[must_use]
-    pub const fn bcm_chunk_bytes() -> usize {
-        core::mem::size_of::<[Frame<ROWS, COLS, NROWS>; FRAME_COUNT]>()
+    /// Check the control signals of every row in every frame, as clocked
+    /// out with the pin map `P`.
+    ///
+    /// Every row must raise `LAT` for exactly one clock, keep the output
+    /// disabled while latching and while the address changes, and select
+    /// each scan row once per frame. Use it after modifying the stream
+    /// through [`as_bytes_mut`](Self::as_bytes_mut), when suspecting memory
+    /// corruption, or while developing new format parameters.
+    ///
+    /// # Errors
+    /// Returns the first broken invariant, see
+    /// [`ControlBitError`](crate::decode::ControlBitError).
+    pub fn verify_control_bits(&self) -> Result<(), crate::decode::ControlBitError> {
+        let words = self.as_words();
+        crate::decode::verify_control_signals(
+            words.len(),
+            Self::row_bytes() / 2,
+            NROWS,
+            |i| crate::decode::plain_signals::<P>(words[map_half_word_index::<O>(i)]),
+            false,
+        )
     }
 
-    /// Perform full formatting of the framebuffer with timing and control signals.
+    /// Reduce the overall brightness to `percent` by keeping the output
+    /// disabled for the first part of every row.
     ///
-    /// This sets up all the timing and control signals needed for proper HUB75 operation.
-    /// This is automatically called by `new()`, so you typically don't need to call this
-    /// unless you want to completely reinitialize the framebuffer.
+    /// The output is normally enabled while a row is shifted in, except
+    /// around the latch. This rewrites the OE bits of every row in every
+    /// frame so only the last `percent` of that window is lit; colours are
+    /// not touched. It needs no extra hardware, but the steps are coarse on
+    /// narrow panels (one step per column).
     ///
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    /// [`erase`](Self::erase) and drawing keep the setting; [`format`](Self::format)
+    /// restores full brightness. While [blanked](Self::blank) the new duty
+    /// takes effect on [`unblank`](Self::unblank).
     ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    /// # Panics
     ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// framebuffer.format(); // Reinitialize if needed
-    /// ```
-    #[inline]
-    pub fn format(&mut self) {
-        for frame in &mut self.frames {
-            frame.format();
+    /// Panics if `percent` is greater than 100.
+    pub fn set_oe_duty(&mut self, percent: u8) {
+        assert!(percent <= 100, "percent must be at most 100");
+        let window = Self::oe_window_len();
+        let lit = (window * usize::from(percent) + 50) / 100;
+        if let Some(saved) = &mut self.blanked_oe {
+            *saved = (window - lit) as u16;
+            return;
         }
+        self.set_oe_windows(window - lit);
     }
 
-    /// Fast erase operation that clears all pixel data while preserving timing signals.
+    /// Turn the display dark by disabling the output in every row of every
+    /// frame, keeping the colours.
     ///
-    /// This is much faster than `format()` when you just want to clear the display
-    /// since it preserves all the timing and control signals that are already set up.
-    /// Use this for clearing between frames or when you want to start drawing fresh content.
+    /// Much faster than erasing and redrawing: only the OE bits change, and
+    /// [`unblank`](Self::unblank) restores the previous pattern, including
+    /// a duty set with [`set_oe_duty`](Self::set_oe_duty). Drawing while
+    /// blanked updates the content shown after unblanking. Blanking twice
+    /// has no further effect; [`format`](Self::format) ends the blanking.
     ///
     /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
+    /// ```rust
+    /// use embedded_graphics::prelude::*;
+    /// use hub75_framebuffer::plain::DmaFrameBuffer;
+    /// use hub75_framebuffer::{Color, FrameBuffer};
     ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
+    /// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+    /// let red = Color::new(224, 0, 0);
+    /// fb.set_pixel(Point::new(1, 2), red);
+    /// let shown = fb.as_bytes().to_vec();
     ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// framebuffer.erase();
+    /// fb.blank();
+    /// assert!(fb.is_blanked());
+    /// assert_eq!(fb.get_pixel(Point::new(1, 2)), Some(red));
+    /// fb.unblank();
+    /// assert_eq!(fb.as_bytes(), &shown[..]);
     /// ```
-    #[inline]
-    pub fn erase(&mut self) {
-        for frame in &mut self.frames {
-            frame.clear_colors();
+    pub fn blank(&mut self) {
+        if self.blanked_oe.is_some() {
+            return;
         }
+        self.blanked_oe = Some(self.frames[0].rows[0].oe_window() as u16);
+        self.set_oe_windows(Self::oe_window_len());
     }
 
-    /// Set a pixel in the framebuffer.
-    /// # Example
-    /// ```rust,no_run
-    /// use hub75_framebuffer::{Color,plain::DmaFrameBuffer,compute_rows,compute_frame_count};
-    /// use embedded_graphics::prelude::*;
-    ///
-    /// const ROWS: usize = 32;
-    /// const COLS: usize = 64;
-    /// const BITS: u8 = 3; // Color depth (8 brightness levels, 7 frames)
-    /// const NROWS: usize = compute_rows(ROWS); // Number of rows per scan
-    /// const FRAME_COUNT: usize = compute_frame_count(BITS); // Number of frames for BCM
-    ///
-    /// let mut framebuffer = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
-    /// framebuffer.set_pixel(Point::new(10, 10), Color::RED);
-    /// ```
-    pub fn set_pixel(&mut self, p: Point, color: Color) {
-        if p.x < 0 || p.y < 0 {
-            return;
+    /// Restore the output enable pattern in place before
+    /// [`blank`](Self::blank). Does nothing if the display is not blanked.
+    pub fn unblank(&mut self) {
+        if let Some(blanked) = self.blanked_oe.take() {
+            self.set_oe_windows(usize::from(blanked));
         }
-        self.set_pixel_internal(p.x as usize, p.y as usize, color);
     }
 
-    #[inline]
-    fn frames_on(v: u8) -> usize {
-        // v / brightness_step but the compiler resolves the shift at build-time
-        (v as usize) >> (8 - BITS)
+    /// Whether the output is disabled by [`blank`](Self::blank).
+    #[must_use]
+    pub const fn is_blanked(&self) -> bool {
+        self.blanked_oe.is_some()
     }
 
-    #[inline]
-    fn set_pixel_internal(&mut self, x: usize, y: usize, color: Color) {
-        if x >= COLS || y >= ROWS {
-            return;
-        }
+    /// Entries per row whose output enable [`Self::set_oe_duty`] controls.
+    const fn oe_window_len() -> usize {
+        COLS.saturating_sub(SETTLE + BLANKING_DELAY + EARLY + 2)
+    }
 
-        // Early exit for black pixels - common in UI backgrounds
-        // Only enabled when skip-black-pixels feature is active
-        #[cfg(feature = "skip-black-pixels")]
-        if color == Color::BLACK {
-            return;
+    fn set_oe_windows(&mut self, blanked: usize) {
+        for frame in &mut self.frames {
+            for row in &mut frame.rows {
+                row.set_oe_window(blanked);
+            }
         }
+    }
 
-        // Pre-compute how many frames each channel should be on
-        let red_frames = Self::frames_on(color.r());
-        let green_frames = Self::frames_on(color.g());
-        let blue_frames = Self::frames_on(color.b());
-
-        // Set the pixel in all frames based on pre-computed frame counts
-        for (frame_idx, frame) in self.frames.iter_mut().enumerate() {
-            frame.set_pixel(
-                y,
-                x,
-                frame_idx < red_frames,
-                frame_idx < green_frames,
-                frame_idx < blue_frames,
-            );
+    /// Reduce the brightness like [`Self::set_oe_duty`], but report a
+    /// percentage greater than 100 instead of panicking.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::InvalidPercent`] if `percent` is greater than
+    /// 100.
+    pub fn try_set_oe_duty(&mut self, percent: u8) -> Result<(), Hub75Error> {
+        if percent > 100 {
+            return Err(Hub75Error::InvalidPercent(percent));
         }
+        self.set_oe_duty(percent);
+        Ok(())
     }
 }
 
 impl<
+        P: PinMap,
+        const BLANK: usize,
+        FO: FrameOrder,
+        const SETTLE: usize,
+        const EARLY: usize,
         const ROWS: usize,
         const COLS: usize,
         const NROWS: usize,
         const BITS: u8,
         const FRAME_COUNT: usize,
-    > FrameBufferOperations for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > crate::RowIterExt
+    for crate::format::DmaFrameBuffer<
+        PlainFormat<P, BLANK, FO, SETTLE, EARLY>,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
 {
-    #[inline]
-    fn erase(&mut self) {
-        DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::erase(self);
-    }
-
-    #[inline]
-    fn set_pixel(&mut self, p: Point, color: Color) {
-        DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::set_pixel(self, p, color);
-    }
-}
+    type Word = u16;
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::prelude::OriginDimensions
-    for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn size(&self) -> embedded_graphics::prelude::Size {
-        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[u16])> + '_ {
+        Self::split_rows(self.active_words())
     }
-}
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::prelude::OriginDimensions
-    for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn size(&self) -> embedded_graphics::prelude::Size {
-        embedded_graphics::prelude::Size::new(COLS as u32, ROWS as u32)
+    fn iter_words(&self) -> impl Iterator<Item = u16> + '_ {
+        Self::output_words(self.active_words())
     }
 }
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > embedded_graphics::draw_target::DrawTarget
-    for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Color = Color;
+#[cfg(test)]
+mod tests {
+    extern crate std;
 
-    type Error = Infallible;
+    use std::format;
+    use std::vec;
 
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            self.set_pixel_internal(pixel.0.x as usize, pixel.0.y as usize, pixel.1);
-        }
-        Ok(())
-    }
-}
+    use super::*;
+    use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+    use embedded_dma::{ReadBuffer, WriteBuffer};
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Circle, PrimitiveStyle, Rectangle};
 
-unsafe impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > ReadBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Word = u8;
+    type Frame<const ROWS: usize, const COLS: usize, const NROWS: usize, O = DefaultOrdering> =
+        crate::format::Frame<PlainFormat, ROWS, COLS, NROWS, O>;
 
-    unsafe fn read_buffer(&self) -> (*const u8, usize) {
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
+    fn map_index(index: usize) -> usize {
+        map_half_word_index::<DefaultOrdering>(index)
     }
-}
 
-unsafe impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > ReadBuffer for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    type Word = u8;
+    const TEST_ROWS: usize = 32;
+    const TEST_COLS: usize = 64;
+    const TEST_NROWS: usize = TEST_ROWS / 2;
+    const TEST_BITS: u8 = 3;
+    const TEST_FRAME_COUNT: usize = (1 << TEST_BITS) - 1; // 7 frames for 3-bit depth
 
-    unsafe fn read_buffer(&self) -> (*const u8, usize) {
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
+    type TestFrameBuffer =
+        DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT>;
 
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > core::fmt::Debug for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let brightness_step = 1 << (8 - BITS);
-        f.debug_struct("DmaFrameBuffer")
-            .field("size", &core::mem::size_of_val(&self.frames))
-            .field("frame_count", &self.frames.len())
-            .field("frame_size", &core::mem::size_of_val(&self.frames[0]))
-            .field("brightness_step", &&brightness_step)
-            .finish_non_exhaustive()
-    }
-}
-
-#[cfg(feature = "defmt")]
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > defmt::Format for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn format(&self, f: defmt::Formatter) {
-        let brightness_step = 1 << (8 - BITS);
-        defmt::write!(
-            f,
-            "DmaFrameBuffer<{}, {}, {}, {}, {}>",
-            ROWS,
-            COLS,
-            NROWS,
-            BITS,
-            FRAME_COUNT
-        );
-        defmt::write!(f, " size: {}", core::mem::size_of_val(&self.frames));
-        defmt::write!(
-            f,
-            " frame_size: {}",
-            core::mem::size_of_val(&self.frames[0])
-        );
-        defmt::write!(f, " brightness_step: {}", brightness_step);
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > FrameBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn get_word_size(&self) -> WordSize {
-        WordSize::Sixteen
-    }
-
-    fn plane_count(&self) -> usize {
-        1
-    }
-
-    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
-        assert!(plane_idx == 0, "plain DmaFrameBuffer has only 1 plane");
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > FrameBuffer for &mut DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-    fn get_word_size(&self) -> WordSize {
-        WordSize::Sixteen
-    }
-
-    fn plane_count(&self) -> usize {
-        1
-    }
-
-    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
-        assert!(plane_idx == 0, "plain DmaFrameBuffer has only 1 plane");
-        let ptr = (&raw const self.frames).cast::<u8>();
-        let len = core::mem::size_of_val(&self.frames);
-        (ptr, len)
-    }
-}
-
-impl<
-        const ROWS: usize,
-        const COLS: usize,
-        const NROWS: usize,
-        const BITS: u8,
-        const FRAME_COUNT: usize,
-    > MutableFrameBuffer for DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>
-{
-}
-
-#[cfg(test)]
-mod tests {
-    extern crate std;
-
-    use std::format;
-    use std::vec;
-
-    use super::*;
-    use crate::{FrameBuffer, WordSize};
-    use embedded_graphics::pixelcolor::RgbColor;
-    use embedded_graphics::prelude::*;
-    use embedded_graphics::primitives::{Circle, PrimitiveStyle, Rectangle};
-
-    const TEST_ROWS: usize = 32;
-    const TEST_COLS: usize = 64;
-    const TEST_NROWS: usize = TEST_ROWS / 2;
-    const TEST_BITS: u8 = 3;
-    const TEST_FRAME_COUNT: usize = (1 << TEST_BITS) - 1; // 7 frames for 3-bit depth
-
-    type TestFrameBuffer =
-        DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT>;
-
-    // Helper function to get mapped index for ESP32
-    fn get_mapped_index(index: usize) -> usize {
-        map_index(index)
+    // Helper function to get mapped index for ESP32
+    fn get_mapped_index(index: usize) -> usize {
+        map_index(index)
     }
 
     #[test]
@@ -862,6 +1023,21 @@ mod tests {
         assert_eq!(entry.addr(), 0);
     }
 
+    #[test]
+    fn test_entry_bits() {
+        let mut entry = Entry::from_bits(1 << 9);
+        assert!(entry.red1());
+        entry.set_latch(true);
+        assert_eq!(entry.bits(), (1 << 9) | (1 << 5));
+
+        // the last word of a row latches it
+        let fb = TestFrameBuffer::new();
+        let last = Entry::from_bits(fb.as_words()[map_index(TEST_COLS - 1)]);
+        assert!(last.latch());
+        assert!(!last.output_enable());
+        assert_eq!(last.addr(), 0);
+    }
+
     #[test]
     fn test_entry_setters() {
         let mut entry = Entry::new();
@@ -942,7 +1118,7 @@ mod tests {
         let mut entry = Entry::new();
 
         let bits = (u8::from(true) << 2) | (u8::from(false) << 1) | u8::from(true); // b=1, g=0, r=1 = 0b101
-        entry.set_color0_bits(bits);
+        entry.set_color0_bits::<DefaultPins>(bits);
         assert_eq!(entry.red1(), true);
         assert_eq!(entry.grn1(), false);
         assert_eq!(entry.blu1(), true);
@@ -955,7 +1131,7 @@ mod tests {
         let mut entry = Entry::new();
 
         let bits = (u8::from(true) << 2) | (u8::from(true) << 1) | u8::from(false); // b=1, g=1, r=0 = 0b110
-        entry.set_color1_bits(bits);
+        entry.set_color1_bits::<DefaultPins>(bits);
         assert_eq!(entry.red2(), false);
         assert_eq!(entry.grn2(), true);
         assert_eq!(entry.blu2(), true);
@@ -1417,169 +1593,1209 @@ mod tests {
             Color::BLUE.g() >= first_frame_threshold
         );
         assert_eq!(
-            first_frame.rows[12].data[col_idx].blu2(),
-            Color::BLUE.b() >= first_frame_threshold
+            first_frame.rows[12].data[col_idx].blu2(),
+            Color::BLUE.b() >= first_frame_threshold
+        );
+
+        // Test black pixel - should not be visible in any frame
+        let col_idx = get_mapped_index(40);
+        assert_eq!(first_frame.rows[1].data[col_idx].red1(), false);
+        assert_eq!(first_frame.rows[1].data[col_idx].grn1(), false);
+        assert_eq!(first_frame.rows[1].data[col_idx].blu1(), false);
+    }
+
+    #[test]
+    fn test_embedded_graphics_integration() {
+        let mut fb = TestFrameBuffer::new();
+
+        // Draw a rectangle
+        let result = Rectangle::new(Point::new(5, 5), Size::new(10, 8))
+            .into_styled(PrimitiveStyle::with_fill(Color::RED))
+            .draw(&mut fb);
+        assert!(result.is_ok());
+
+        // Draw a circle
+        let result = Circle::new(Point::new(30, 15), 8)
+            .into_styled(PrimitiveStyle::with_fill(Color::BLUE))
+            .draw(&mut fb);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "skip-black-pixels")]
+    fn test_skip_black_pixels_enabled() {
+        let mut fb = TestFrameBuffer::new();
+
+        // Set a red pixel first
+        fb.set_pixel_internal(10, 5, Color::RED);
+
+        // Verify it's red in the first frame
+        let mapped_col_10 = get_mapped_index(10);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+
+        // Now set it to black - with skip-black-pixels enabled, this should be ignored
+        fb.set_pixel_internal(10, 5, Color::BLACK);
+
+        // The pixel should still be red (black write was skipped)
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+    }
+
+    #[test]
+    #[cfg(not(feature = "skip-black-pixels"))]
+    fn test_skip_black_pixels_disabled() {
+        let mut fb = TestFrameBuffer::new();
+
+        // Set a red pixel first
+        fb.set_pixel_internal(10, 5, Color::RED);
+
+        // Verify it's red in the first frame
+        let mapped_col_10 = get_mapped_index(10);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+
+        // Now set it to black - with skip-black-pixels disabled, this should overwrite
+        fb.set_pixel_internal(10, 5, Color::BLACK);
+
+        // The pixel should now be black (all bits false)
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), false);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
+        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+    }
+
+    #[test]
+    fn test_bcm_frame_overwrite() {
+        let mut fb = TestFrameBuffer::new();
+
+        // First write a white pixel (255, 255, 255)
+        fb.set_pixel_internal(10, 5, Color::WHITE);
+
+        let mapped_col_10 = get_mapped_index(10);
+
+        // Verify white pixel is lit in all frames (255 >= all thresholds)
+        for frame in fb.frames.iter() {
+            // White (255) should be active in all frames since it's >= all thresholds
+            assert_eq!(frame.rows[5].data[mapped_col_10].red1(), true);
+            assert_eq!(frame.rows[5].data[mapped_col_10].grn1(), true);
+            assert_eq!(frame.rows[5].data[mapped_col_10].blu1(), true);
+        }
+
+        // Now overwrite with 50% white (128, 128, 128)
+        let half_white = Color::from(embedded_graphics::pixelcolor::Rgb888::new(128, 128, 128));
+        fb.set_pixel_internal(10, 5, half_white);
+
+        // Verify only the correct frames are lit for 50% white
+        // With 3-bit depth: thresholds are 32, 64, 96, 128, 160, 192, 224
+        // 128 should activate frames 0, 1, 2, 3 (thresholds 32, 64, 96, 128)
+        // but not frames 4, 5, 6 (thresholds 160, 192, 224)
+        let brightness_step = 1 << (8 - TEST_BITS); // 32 for 3-bit
+        for (frame_idx, frame) in fb.frames.iter().enumerate() {
+            let frame_threshold = (frame_idx as u8 + 1) * brightness_step;
+            let should_be_active = 128 >= frame_threshold;
+
+            assert_eq!(frame.rows[5].data[mapped_col_10].red1(), should_be_active);
+            assert_eq!(frame.rows[5].data[mapped_col_10].grn1(), should_be_active);
+            assert_eq!(frame.rows[5].data[mapped_col_10].blu1(), should_be_active);
+        }
+
+        // Specifically verify the expected pattern for 3-bit depth
+        // Frames 0-3 should be active (thresholds 32, 64, 96, 128)
+        for frame_idx in 0..4 {
+            assert_eq!(
+                fb.frames[frame_idx].rows[5].data[mapped_col_10].red1(),
+                true
+            );
+        }
+        // Frames 4-6 should be inactive (thresholds 160, 192, 224)
+        for frame_idx in 4..TEST_FRAME_COUNT {
+            assert_eq!(
+                fb.frames[frame_idx].rows[5].data[mapped_col_10].red1(),
+                false
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_buffer_implementation() {
+        // Test owned implementation - explicitly move the framebuffer to ensure we're testing the owned impl
+        let fb = TestFrameBuffer::new();
+        let expected_size = core::mem::size_of_val(&fb.frames);
+
+        // Test owned ReadBuffer implementation by calling ReadBuffer::read_buffer explicitly
+        unsafe {
+            let (ptr, len) = <TestFrameBuffer as ReadBuffer>::read_buffer(&fb);
+            assert!(!ptr.is_null());
+            assert_eq!(len, expected_size);
+        }
+
+        // Test direct method call on owned value
+        unsafe {
+            let (ptr, len) = fb.read_buffer();
+            assert!(!ptr.is_null());
+            assert_eq!(len, expected_size);
+        }
+
+        // Test reference implementation
+        let fb = TestFrameBuffer::new();
+        let fb_ref = &fb;
+        unsafe {
+            let (ptr, len) = fb_ref.read_buffer();
+            assert!(!ptr.is_null());
+            assert_eq!(len, core::mem::size_of_val(&fb.frames));
+        }
+
+        // Test mutable reference implementation
+        let mut fb = TestFrameBuffer::new();
+        let fb_ref = &mut fb;
+        unsafe {
+            let (ptr, len) = fb_ref.read_buffer();
+            assert!(!ptr.is_null());
+            assert_eq!(len, core::mem::size_of_val(&fb.frames));
+        }
+    }
+
+    #[test]
+    fn test_set_oe_duty() {
+        use crate::ordering::Linear;
+
+        type LinearFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+
+        let mut fb = LinearFB::new();
+        fb.set_pixel(Point::new(0, 0), Color::WHITE);
+
+        let end = TEST_COLS - BLANKING_DELAY - 1;
+        let window = end - 1;
+        let lit = (window * 50 + 50) / 100;
+        fb.set_oe_duty(50);
+        for frame in &fb.frames {
+            for row in &frame.rows {
+                let on: std::vec::Vec<bool> = row.data.iter().map(Entry::output_enable).collect();
+                assert_eq!(on.iter().filter(|&&on| on).count(), lit);
+                assert!(on[end - lit..end].iter().all(|&on| on));
+                // latch and address bits are untouched
+                assert!(row.data[TEST_COLS - 1].latch());
+            }
+        }
+        // colours are kept
+        assert!(fb.frames[0].rows[0].data[0].red1());
+
+        fb.erase();
+        assert!(!fb.frames[0].rows[0].data[1].output_enable());
+
+        fb.set_oe_duty(0);
+        assert!(fb.as_words().iter().all(|&word| word & (1 << 8) == 0));
+
+        fb.format();
+        assert_eq!(fb.as_words(), LinearFB::new().as_words());
+    }
+
+    #[test]
+    #[should_panic(expected = "percent must be at most 100")]
+    fn test_set_oe_duty_out_of_range() {
+        TestFrameBuffer::new().set_oe_duty(101);
+    }
+
+    #[test]
+    fn test_try_set_oe_duty() {
+        let mut fb = TestFrameBuffer::new();
+        let before = fb;
+        assert_eq!(
+            fb.try_set_oe_duty(101),
+            Err(Hub75Error::InvalidPercent(101))
+        );
+        assert_eq!(fb.as_bytes(), before.as_bytes());
+        assert_eq!(fb.try_set_oe_duty(50), Ok(()));
+        let mut expected = before;
+        expected.set_oe_duty(50);
+        assert_eq!(fb.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_init_in_place_over_garbage() {
+        let mut slot = core::mem::MaybeUninit::<TestFrameBuffer>::uninit();
+        // SAFETY: any byte pattern may be written to uninitialised memory
+        unsafe { slot.as_mut_ptr().write_bytes(0xff, 1) };
+        let fb = TestFrameBuffer::init_in_place(&mut slot);
+        assert!(!fb.is_blanked());
+        assert_eq!(fb.active_frames(), TEST_FRAME_COUNT);
+        assert_eq!(fb.as_bytes(), TestFrameBuffer::new().as_bytes());
+    }
+
+    #[test]
+    fn test_blank() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(0, 0), Color::WHITE);
+        fb.set_oe_duty(50);
+        let shown = fb;
+
+        fb.blank();
+        assert!(fb.is_blanked());
+        assert!(fb.as_words().iter().all(|&word| word & (1 << 8) == 0));
+        assert!(fb.verify_control_bits().is_ok());
+        fb.blank();
+        fb.unblank();
+        assert!(!fb.is_blanked());
+        assert_eq!(fb.as_bytes(), shown.as_bytes());
+
+        // drawing and a new duty while blanked show after unblanking
+        fb.blank();
+        fb.set_pixel(Point::new(1, 0), Color::WHITE);
+        fb.set_oe_duty(100);
+        assert!(!fb.frames[0].rows[0].data[1].output_enable());
+        fb.unblank();
+        let mut expected = TestFrameBuffer::new();
+        expected.set_pixel(Point::new(0, 0), Color::WHITE);
+        expected.set_pixel(Point::new(1, 0), Color::WHITE);
+        assert_eq!(fb.as_bytes(), expected.as_bytes());
+
+        fb.blank();
+        fb.format();
+        assert!(!fb.is_blanked());
+        fb.unblank();
+        assert_eq!(fb.as_bytes(), TestFrameBuffer::new().as_bytes());
+    }
+
+    #[test]
+    fn test_fallible_construction() {
+        assert!(TestFrameBuffer::try_new().is_ok());
+        assert_eq!(
+            DmaFrameBuffer::<TEST_ROWS, TEST_COLS, TEST_NROWS, 0, 0>::try_new().err(),
+            Some(Hub75Error::InvalidBits(0))
+        );
+        assert_eq!(
+            DmaFrameBuffer::<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, 8>::try_new().err(),
+            Some(Hub75Error::FrameCountMismatch {
+                expected: TEST_FRAME_COUNT,
+                actual: 8
+            })
+        );
+        assert_eq!(
+            DmaFrameBuffer::<TEST_ROWS, TEST_COLS, 8, TEST_BITS, TEST_FRAME_COUNT>::check_config(),
+            Err(Hub75Error::RowCountMismatch {
+                rows: TEST_ROWS,
+                nrows: 8
+            })
+        );
+        assert_eq!(
+            crate::latched16::DmaFrameBuffer::<64, TEST_COLS, 16, TEST_BITS, TEST_FRAME_COUNT>::check_config(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_try_set_pixel() {
+        let mut fb = TestFrameBuffer::new();
+        let mut expected = TestFrameBuffer::new();
+        expected.set_pixel(Point::new(63, 31), Color::RED);
+
+        assert_eq!(fb.try_set_pixel(Point::new(63, 31), Color::RED), Ok(()));
+        for p in [Point::new(64, 0), Point::new(0, 32), Point::new(-1, 3)] {
+            assert_eq!(
+                fb.try_set_pixel(p, Color::RED),
+                Err(Hub75Error::OutOfBounds { x: p.x, y: p.y })
+            );
+        }
+        assert_eq!(fb.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_new_unformatted() {
+        let mut fb = TestFrameBuffer::new_unformatted();
+        assert!(!fb.is_formatted());
+        assert!(fb.as_bytes().iter().all(|&byte| byte == 0));
+
+        // a restored snapshot formats it as well
+        let mut snapshot = TestFrameBuffer::new();
+        snapshot.set_pixel(Point::new(1, 1), Color::RED);
+        assert!(snapshot.is_formatted());
+        fb.as_bytes_mut().copy_from_slice(snapshot.as_bytes());
+        assert!(fb.is_formatted());
+
+        // one row left unformatted is enough
+        let row_bytes = TestFrameBuffer::row_bytes();
+        fb.as_bytes_mut()[row_bytes..2 * row_bytes].fill(0);
+        assert!(!fb.is_formatted());
+        fb.format();
+        assert!(fb.is_formatted());
+    }
+
+    #[test]
+    fn test_save_restore_logical() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(3, 5), Color::new(255, 128, 31));
+        fb.set_pixel(Point::new(63, 31), Color::new(32, 0, 200));
+
+        let mut image = vec![0u8; TestFrameBuffer::logical_size_bytes() + 1];
+        assert_eq!(fb.save_logical(&mut image), Ok(TEST_ROWS * TEST_COLS * 3));
+        let offset = (5 * TEST_COLS + 3) * 3;
+        assert_eq!(image[offset..offset + 3], [224, 128, 0]);
+
+        let mut restored = TestFrameBuffer::new();
+        restored.set_pixel(Point::new(0, 0), Color::WHITE);
+        assert_eq!(
+            restored.restore_logical(&image),
+            Ok(TestFrameBuffer::logical_size_bytes())
+        );
+        assert_eq!(restored.as_bytes(), fb.as_bytes());
+
+        let short = &image[..10];
+        assert_eq!(
+            restored.restore_logical(short),
+            Err(Hub75Error::BufferTooSmall {
+                needed: TestFrameBuffer::logical_size_bytes(),
+                actual: 10,
+            })
+        );
+        assert_eq!(
+            fb.save_logical(&mut image[..10]),
+            Err(Hub75Error::BufferTooSmall {
+                needed: TestFrameBuffer::logical_size_bytes(),
+                actual: 10,
+            })
+        );
+        assert_eq!(restored.as_bytes(), fb.as_bytes());
+    }
+
+    #[test]
+    fn test_get_pixel() {
+        use crate::format::{Interleaved, WeightedPlanes};
+
+        fn check<F: crate::format::EntryFormat>() {
+            let mut fb = crate::format::DmaFrameBuffer::<
+                F,
+                TEST_ROWS,
+                TEST_COLS,
+                TEST_NROWS,
+                TEST_BITS,
+                TEST_FRAME_COUNT,
+            >::new();
+            let colors = [Color::new(255, 128, 31), Color::new(32, 0, 200)];
+            fb.set_pixel(Point::new(3, 5), colors[0]);
+            fb.set_pixel(Point::new(63, 31), colors[1]);
+
+            assert_eq!(
+                fb.get_pixel(Point::new(3, 5)),
+                Some(Color::new(224, 128, 0))
+            );
+            assert_eq!(
+                fb.get_pixel(Point::new(63, 31)),
+                Some(Color::new(32, 0, 192))
+            );
+            assert_eq!(fb.get_pixel(Point::new(3, 21)), Some(Color::BLACK));
+            for p in [Point::new(64, 0), Point::new(0, 32), Point::new(-1, 3)] {
+                assert_eq!(fb.get_pixel(p), None);
+            }
+
+            let pixels: std::vec::Vec<_> = fb.pixels().collect();
+            assert_eq!(pixels.len(), TEST_ROWS * TEST_COLS);
+            assert_eq!(pixels[5 * TEST_COLS + 3].0, Point::new(3, 5));
+            for Pixel(p, color) in pixels {
+                assert_eq!(Some(color), fb.get_pixel(p));
+            }
+        }
+
+        check::<PlainFormat>();
+        check::<PlainFormat<DefaultPins, 0, Interleaved>>();
+        check::<PlainFormat<DefaultPins, 0, WeightedPlanes>>();
+    }
+
+    #[test]
+    fn test_try_format_with_address_map() {
+        let mut fb = TestFrameBuffer::new();
+        let before = fb;
+        let mut map: [u8; TEST_NROWS] = core::array::from_fn(|row| row as u8);
+        map[3] = 32;
+        assert_eq!(
+            fb.try_format_with_address_map(&map),
+            Err(Hub75Error::InvalidAddress(32))
+        );
+        assert_eq!(fb.as_bytes(), before.as_bytes());
+
+        map[3] = 3;
+        assert_eq!(fb.try_format_with_address_map(&map), Ok(()));
+        assert_eq!(fb.as_bytes(), before.as_bytes());
+    }
+
+    #[test]
+    fn test_copy_colors_from() {
+        let mut front = TestFrameBuffer::new();
+        front.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        front.set_pixel(Point::new(63, 0), Color::WHITE);
+
+        // the back buffer has its own control bits and stale content
+        let map: [u8; TEST_NROWS] = core::array::from_fn(|row| (TEST_NROWS - 1 - row) as u8);
+        let mut back = TestFrameBuffer::new();
+        back.set_oe_duty(30);
+        back.format_with_address_map(&map);
+        back.set_pixel(Point::new(10, 10), Color::BLUE);
+
+        let mut expected = back;
+        expected.erase();
+        expected.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        expected.set_pixel(Point::new(63, 0), Color::WHITE);
+
+        back.copy_colors_from(&front);
+        assert_eq!(back.as_bytes(), expected.as_bytes());
+        assert_eq!(back.content_hash(), front.content_hash());
+    }
+
+    #[test]
+    fn test_set_max_brightness() {
+        use crate::format::{Interleaved, WeightedPlanes};
+        use crate::RowIterExt;
+
+        let mut fb = TestFrameBuffer::new();
+        let frame_bytes = TestFrameBuffer::frame_bytes();
+        fb.set_max_brightness(96);
+        assert_eq!(fb.active_frames(), 3);
+        let (ptr, len) = unsafe { fb.read_buffer() };
+        assert_eq!((ptr, len), (fb.as_bytes().as_ptr(), 3 * frame_bytes));
+        assert_eq!(fb.active_bytes(), &fb.as_bytes()[..len]);
+        assert_eq!(fb.active_words().len(), len / 2);
+        assert_eq!((fb.frame_count(), fb.buffer_len_bytes()), (3, len));
+        assert_eq!(fb.chunks(frame_bytes).count(), 3);
+        assert_eq!(fb.iter_rows().count(), 3 * TEST_NROWS);
+        assert_eq!(fb.iter_words().count(), len / 2);
+
+        // colours up to the limit look the same in the streamed frames
+        fb.set_pixel(Point::new(3, 20), Color::new(96, 64, 0));
+        assert_eq!(fb.get_pixel(Point::new(3, 20)), Some(Color::new(96, 64, 0)));
+        let mut exported = vec![];
+        fb.export(|bytes| exported.extend_from_slice(bytes));
+        assert!(exported.ends_with(fb.as_bytes()));
+
+        fb.set_max_brightness(0);
+        assert_eq!(fb.active_frames(), 1);
+        fb.set_max_brightness(u8::MAX);
+        assert_eq!(fb.active_frames(), TEST_FRAME_COUNT);
+        assert_eq!(
+            fb.buffer_len_bytes(),
+            TestFrameBuffer::dma_buffer_size_bytes()
+        );
+
+        let mut weighted = crate::format::DmaFrameBuffer::<
+            PlainFormat<DefaultPins, 0, WeightedPlanes>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >::new();
+        // planes 0 and 1 take 1 + 2 frames
+        weighted.set_max_brightness(96);
+        assert_eq!(weighted.active_frames(), 3);
+        weighted.set_max_brightness(128);
+        assert_eq!(weighted.active_frames(), TEST_FRAME_COUNT);
+
+        let mut interleaved = crate::format::DmaFrameBuffer::<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >::new();
+        interleaved.set_max_brightness(32);
+        assert_eq!(interleaved.active_frames(), TEST_FRAME_COUNT);
+    }
+
+    #[test]
+    fn test_set_active_bits() {
+        use crate::format::Interleaved;
+
+        let mut fb = TestFrameBuffer::new();
+        let frame_bytes = TestFrameBuffer::frame_bytes();
+        fb.set_active_bits(2);
+        assert_eq!((fb.active_bits(), fb.bits()), (2, 2));
+        assert_eq!(fb.active_frames(), 3);
+        assert_eq!(
+            (fb.frame_count(), fb.buffer_len_bytes()),
+            (3, 3 * frame_bytes)
+        );
+
+        // colours map to the first three frames only
+        fb.set_pixel(Point::new(3, 20), Color::new(200, 100, 0));
+        assert_eq!(
+            fb.get_pixel(Point::new(3, 20)),
+            Some(Color::new(192, 64, 0))
+        );
+        let x = map_index(3);
+        for (frame, lit) in [(0, (true, true)), (1, (true, false)), (2, (true, false))] {
+            let row = &fb.frames[frame].rows[4].data[x];
+            assert_eq!((row.red2(), row.grn2()), lit);
+        }
+        assert!(fb.frames[3..].iter().all(|f| !f.rows[4].data[x].red2()));
+
+        fb.set_max_brightness(64);
+        assert_eq!(fb.active_frames(), 1);
+
+        assert_eq!(fb.try_set_active_bits(0), Err(Hub75Error::InvalidBits(0)));
+        assert_eq!(fb.try_set_active_bits(4), Err(Hub75Error::InvalidBits(4)));
+        assert_eq!(fb.active_bits(), 2);
+        fb.try_set_active_bits(TEST_BITS).unwrap();
+        assert_eq!(fb.active_frames(), TEST_FRAME_COUNT);
+        fb.erase();
+        fb.set_pixel(Point::new(3, 20), Color::new(200, 100, 0));
+        assert_eq!(
+            fb.get_pixel(Point::new(3, 20)),
+            Some(Color::new(192, 96, 0))
+        );
+
+        let mut interleaved = crate::format::DmaFrameBuffer::<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >::new();
+        interleaved.set_active_bits(1);
+        assert_eq!(interleaved.active_frames(), TEST_FRAME_COUNT);
+        interleaved.set_pixel(Point::new(0, 0), Color::WHITE);
+        assert_eq!(
+            interleaved.get_pixel(Point::new(0, 0)),
+            Some(Color::new(128, 128, 128))
+        );
+    }
+
+    #[test]
+    fn test_colors_eq() {
+        use crate::format::Interleaved;
+
+        let mut a = TestFrameBuffer::new();
+        let mut b = TestFrameBuffer::new();
+        b.set_oe_duty(30);
+        b.format_with_address_map(&core::array::from_fn(|row| (TEST_NROWS - 1 - row) as u8));
+        assert!(a.colors_eq(&b));
+
+        // one level of green lights only the first frame
+        a.set_pixel(Point::new(3, 20), Color::new(0, 32, 0));
+        assert!(!a.colors_eq(&b));
+        assert!(!a.frame_colors_eq(&b, 0));
+        assert!(a.frame_colors_eq(&b, 1));
+        b.set_pixel(Point::new(3, 20), Color::new(0, 32, 0));
+        assert!(a.colors_eq(&b));
+
+        let mut a = crate::format::DmaFrameBuffer::<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >::new();
+        let b = a;
+        a.set_pixel(Point::new(3, 20), Color::new(0, 64, 0));
+        assert!(!a.frame_colors_eq(&b, 1));
+        assert!(a.frame_colors_eq(&b, 2));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        use crate::ordering::Linear;
+
+        type LinearFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+
+        let mut fb = LinearFB::new();
+        let blank = fb.content_hash();
+        fb.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        let drawn = fb.content_hash();
+        assert_ne!(drawn, blank);
+        // golden value: changes only if the buffer layout changes
+        assert_eq!(drawn, 0x9ba0_9a95);
+
+        // control bits do not count
+        fb.set_oe_duty(30);
+        let mut map = [0; TEST_NROWS];
+        for (addr, entry) in map.iter_mut().enumerate() {
+            *entry = (TEST_NROWS - 1 - addr) as u8;
+        }
+        fb.format_with_address_map(&map);
+        assert_eq!(fb.content_hash(), blank);
+        fb.set_pixel(Point::new(3, 20), Color::new(255, 96, 0));
+        assert_eq!(fb.content_hash(), drawn);
+
+        // a colour one level different hashes differently
+        let mut other = LinearFB::new();
+        other.set_pixel(Point::new(3, 20), Color::new(255, 64, 0));
+        assert_ne!(other.content_hash(), drawn);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_report() {
+        let report = TestFrameBuffer::report(10_000_000, 4095);
+        assert_eq!(report.layout, Layout::Plain);
+        assert_eq!(report.word_size, WordSize::Sixteen);
+        assert_eq!(report.frame_count, TEST_FRAME_COUNT);
+        assert_eq!(report.row_bytes, TEST_COLS * 2);
+        assert_eq!(report.frame_bytes, TEST_NROWS * TEST_COLS * 2);
+        assert_eq!(report.dma_bytes, TEST_FRAME_COUNT * report.frame_bytes);
+        assert!(report.memory_bytes >= report.dma_bytes);
+        assert_eq!(
+            report.refresh_hz,
+            TestFrameBuffer::estimated_refresh_hz(10_000_000)
+        );
+        assert_eq!(
+            report.descriptor_count,
+            TestFrameBuffer::descriptor_count(4095)
+        );
+
+        let text = std::format!("{report}");
+        assert!(text.starts_with("Plain 64x32 1/16 scan, 3 bits, 7 frames, 16-bit words\n"));
+        assert!(text.contains(&std::format!("  DMA stream:  {} bytes\n", report.dma_bytes)));
+    }
+
+    #[test]
+    #[cfg(feature = "instrument")]
+    fn test_draw_stats() {
+        let mut fb = TestFrameBuffer::new();
+        assert_eq!(fb.draw_stats(), crate::format::DrawStats::default());
+
+        fb.set_pixel(Point::new(1, 2), Color::RED);
+        fb.set_pixel(Point::new(1, 2), Color::GREEN);
+        // y = 18 is scan row 2 again, in the lower half
+        fb.set_pixel(Point::new(5, 18), Color::BLUE);
+        fb.set_pixel(Point::new(0, 7), Color::WHITE);
+        // out of bounds: nothing touched
+        fb.set_pixel(Point::new(-1, 0), Color::WHITE);
+        fb.set_pixel(Point::new(0, 32), Color::WHITE);
+
+        let stats = fb.take_draw_stats();
+        assert_eq!(stats.pixels, 4);
+        assert_eq!(stats.bytes_touched, 4 * TEST_FRAME_COUNT as u32 * 2);
+        assert_eq!(stats.scan_rows, (1 << 2) | (1 << 7));
+        assert_eq!(stats.rows_visited(), 2);
+        assert_eq!(fb.draw_stats(), crate::format::DrawStats::default());
+
+        // erasing writes the control words directly, not pixels
+        fb.erase();
+        assert_eq!(fb.draw_stats().pixels, 0);
+    }
+
+    #[test]
+    fn test_verify_control_bits() {
+        use crate::decode::ControlBitError;
+        use crate::ordering::{Esp32, Linear};
+
+        type LinearFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+        type Variant = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 2, crate::format::Interleaved, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >;
+
+        let mut fb = LinearFB::new();
+        fb.set_pixel(Point::new(5, 5), Color::WHITE);
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+        fb.set_oe_duty(40);
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+        assert_eq!(Variant::new().verify_control_bits(), Ok(()));
+
+        // low byte of the latch word of row 3
+        let latch = 2 * (4 * TEST_COLS - 1);
+        let mut broken = fb;
+        broken.as_bytes_mut()[latch] &= !(1 << 5);
+        assert_eq!(
+            broken.verify_control_bits(),
+            Err(ControlBitError::MissingLatch { frame: 0, row: 3 })
+        );
+        let mut broken = fb;
+        broken.as_bytes_mut()[latch - 2] |= 1 << 5;
+        assert_eq!(
+            broken.verify_control_bits(),
+            Err(ControlBitError::LatchHeldOpen { frame: 0, row: 3 })
+        );
+        let mut broken = fb;
+        broken.as_bytes_mut()[latch + 1] |= 1;
+        assert_eq!(
+            broken.verify_control_bits(),
+            Err(ControlBitError::LatchWhileLit { frame: 0, row: 3 })
+        );
+
+        let mut map = [0; TEST_NROWS];
+        for (addr, entry) in map.iter_mut().enumerate() {
+            *entry = addr as u8;
+        }
+        map.swap(2, 9);
+        fb.format_with_address_map(&map);
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+        // found once address 4 is latched more often than there are frames
+        map[5] = 4;
+        fb.format_with_address_map(&map);
+        assert_eq!(
+            fb.verify_control_bits(),
+            Err(ControlBitError::RepeatedAddress {
+                frame: 3,
+                row: 5,
+                addr: 4
+            })
         );
+    }
 
-        // Test black pixel - should not be visible in any frame
-        let col_idx = get_mapped_index(40);
-        assert_eq!(first_frame.rows[1].data[col_idx].red1(), false);
-        assert_eq!(first_frame.rows[1].data[col_idx].grn1(), false);
-        assert_eq!(first_frame.rows[1].data[col_idx].blu1(), false);
+    #[test]
+    fn test_e_line_64x64() {
+        use crate::ordering::Linear;
+
+        // 1/32 scan: the fifth address line E selects rows 16-31
+        type FB64 = DmaFrameBuffer<64, 64, 32, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+
+        let mut fb = FB64::new();
+        for (addr, row) in fb.frames[0].rows.iter().enumerate() {
+            let last = row.data[63];
+            assert!(last.latch());
+            assert_eq!(last.addr() as usize, addr);
+            // all other words still select the previous row
+            assert_eq!(row.data[0].addr() as usize, (addr + 31) % 32);
+        }
+        assert_eq!(fb.frames[0].rows[16].data[63].0 & 0b1_0000, 0b1_0000);
+        assert_eq!(fb.frames[0].rows[15].data[63].0 & 0b1_0000, 0);
+
+        // the bottom row is the lower half of scan row 31
+        fb.set_pixel(Point::new(7, 63), Color::BLUE);
+        fb.set_pixel(Point::new(7, 31), Color::RED);
+        let entry = fb.frames[0].rows[31].data[7];
+        assert!(entry.blu2() && !entry.red2());
+        assert!(entry.red1() && !entry.blu1());
+        assert_eq!(fb.size(), Size::new(64, 64));
     }
 
     #[test]
-    fn test_embedded_graphics_integration() {
-        let mut fb = TestFrameBuffer::new();
+    fn test_e_line_128x64() {
+        use crate::ordering::Linear;
 
-        // Draw a rectangle
-        let result = Rectangle::new(Point::new(5, 5), Size::new(10, 8))
-            .into_styled(PrimitiveStyle::with_fill(Color::RED))
-            .draw(&mut fb);
-        assert!(result.is_ok());
+        type FB128 = DmaFrameBuffer<64, 128, 32, TEST_BITS, TEST_FRAME_COUNT, Linear>;
 
-        // Draw a circle
-        let result = Circle::new(Point::new(30, 15), 8)
-            .into_styled(PrimitiveStyle::with_fill(Color::BLUE))
-            .draw(&mut fb);
-        assert!(result.is_ok());
+        let mut fb = FB128::new();
+        fb.set_pixel(Point::new(127, 47), Color::GREEN);
+        for frame in &fb.frames {
+            assert!(frame.rows[15].data[127].grn2());
+            assert_eq!(frame.rows[31].data[127].addr(), 31);
+        }
+        assert_eq!(fb.as_words().len(), TEST_FRAME_COUNT * 32 * 128);
+        assert_eq!(fb.size(), Size::new(128, 64));
     }
 
     #[test]
-    #[cfg(feature = "skip-black-pixels")]
-    fn test_skip_black_pixels_enabled() {
-        let mut fb = TestFrameBuffer::new();
-
-        // Set a red pixel first
-        fb.set_pixel_internal(10, 5, Color::RED);
+    fn test_frame_interleaved_order() {
+        use crate::format::Interleaved;
+        use crate::ordering::Linear;
+
+        type Sequential =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+        type InterleavedFB = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        const COLORS: u16 = 0b0111_1110_0000_0000;
+
+        let mut seq = Sequential::new();
+        let mut fb = InterleavedFB::new();
+        for (p, color) in [
+            (Point::new(3, 2), Color::new(96, 0, 0)),
+            (Point::new(5, 20), Color::new(0, 200, 32)),
+            (Point::new(63, 31), Color::WHITE),
+        ] {
+            seq.set_pixel(p, color);
+            fb.set_pixel(p, color);
+        }
+        assert_eq!(fb.as_words().len(), seq.as_words().len());
+
+        let rows: std::vec::Vec<_> = fb.frames.iter().flat_map(|f| f.rows.iter()).collect();
+        for (slot, row) in rows.iter().enumerate() {
+            let (addr, frame) = (slot / TEST_FRAME_COUNT, slot % TEST_FRAME_COUNT);
+            let prev = rows[(slot + rows.len() - 1) % rows.len()];
+            // each row latches its own address while the previous one in
+            // the stream is shown
+            assert_eq!(row.data[TEST_COLS - 1].addr() as usize, addr);
+            assert_eq!(row.data[0].addr(), prev.data[TEST_COLS - 1].addr());
+            for col in 0..TEST_COLS {
+                assert_eq!(
+                    row.data[col].0 & COLORS,
+                    seq.frames[frame].rows[addr].data[col].0 & COLORS,
+                    "slot {slot} col {col}"
+                );
+            }
+        }
+        // row 2 lights red in its first three frames only
+        assert!(rows[2 * TEST_FRAME_COUNT + 2].data[3].red1());
+        assert!(!rows[2 * TEST_FRAME_COUNT + 3].data[3].red1());
+    }
 
-        // Verify it's red in the first frame
-        let mapped_col_10 = get_mapped_index(10);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+    #[test]
+    fn test_weighted_planes_stream() {
+        use crate::format::WeightedPlanes;
+        use crate::ordering::Linear;
+
+        type WeightedFB = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 0, WeightedPlanes>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let frame_words = WeightedFB::frame_bytes() / 2;
+        assert_eq!(WeightedFB::plane_bytes(0), 0..2 * frame_words);
+        assert_eq!(
+            WeightedFB::plane_bytes(2).end,
+            WeightedFB::bcm_chunk_bytes()
+        );
+        assert_eq!(
+            WeightedFB::repetition_overhead_bytes(),
+            (TEST_FRAME_COUNT - TEST_BITS as usize) * WeightedFB::frame_bytes()
+        );
 
-        // Now set it to black - with skip-black-pixels enabled, this should be ignored
-        fb.set_pixel_internal(10, 5, Color::BLACK);
+        let mut fb = WeightedFB::new();
+        for level in 0..8u8 {
+            fb.set_pixel(
+                Point::new(i32::from(level), 3),
+                Color::new(level * 32, 0, 0),
+            );
+        }
 
-        // The pixel should still be red (black write was skipped)
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+        // decode column x of scan row 3 from the raw stream
+        let words = fb.as_words();
+        for x in 0..8 {
+            let mut level = 0;
+            for plane in 0..TEST_BITS {
+                let range = WeightedFB::plane_bytes(plane);
+                let copies: std::vec::Vec<bool> = words[range.start / 2..range.end / 2]
+                    .chunks(frame_words)
+                    .map(|frame| frame[3 * TEST_COLS + x] & (1 << 9) != 0)
+                    .collect();
+                assert_eq!(copies.len(), 1 << plane);
+                assert!(copies.iter().all(|&bit| bit == copies[0]));
+                level |= usize::from(copies[0]) << plane;
+            }
+            assert_eq!(level, x);
+        }
     }
 
     #[test]
-    #[cfg(not(feature = "skip-black-pixels"))]
-    fn test_skip_black_pixels_disabled() {
-        let mut fb = TestFrameBuffer::new();
+    fn test_address_settle_words() {
+        use crate::format::Sequential;
+        use crate::ordering::Linear;
+
+        type SettleFB = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 0, Sequential, 3>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let mut fb = SettleFB::new();
+        let row = &fb.frames[0].rows[5];
+        let addr_change = TEST_COLS - 4;
+        let oe_end = addr_change - BLANKING_DELAY;
+        for (i, entry) in row.data.iter().enumerate() {
+            assert_eq!(entry.latch(), i == TEST_COLS - 1, "col {i}");
+            assert_eq!(entry.addr(), if i < addr_change { 4 } else { 5 }, "col {i}");
+            assert_eq!(entry.output_enable(), i >= 1 && i < oe_end, "col {i}");
+        }
 
-        // Set a red pixel first
-        fb.set_pixel_internal(10, 5, Color::RED);
+        // the brightness window ends where the blanking starts
+        fb.set_oe_duty(0);
+        assert!(fb.frames[0].rows[5].data.iter().all(|e| !e.output_enable()));
+        fb.set_oe_duty(100);
+        assert!(fb.frames[0].rows[5].data[oe_end - 1].output_enable());
+        assert!(!fb.frames[0].rows[5].data[oe_end].output_enable());
+    }
 
-        // Verify it's red in the first frame
-        let mapped_col_10 = get_mapped_index(10);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), true);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+    #[test]
+    fn test_ghost_cancel() {
+        use crate::decode::Decoded;
+        use crate::ordering::Linear;
+
+        type GhostFB<const LEVEL: usize> = crate::format::DmaFrameBuffer<
+            GhostCancel<LEVEL>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let mut fb = GhostFB::<2>::new();
+        let row = &fb.frames[0].rows[5];
+        assert!(row
+            .blank
+            .iter()
+            .all(|e| !e.output_enable() && e.addr() == 4));
+        assert_eq!(row.blank.len(), 2);
+        let addr_change = TEST_COLS - 3;
+        let oe_end = addr_change - BLANKING_DELAY - 2;
+        for (i, entry) in row.data.iter().enumerate() {
+            assert_eq!(entry.latch(), i == TEST_COLS - 1, "col {i}");
+            assert_eq!(entry.addr(), if i < addr_change { 4 } else { 5 }, "col {i}");
+            assert_eq!(entry.output_enable(), i >= 1 && i < oe_end, "col {i}");
+        }
 
-        // Now set it to black - with skip-black-pixels disabled, this should overwrite
-        fb.set_pixel_internal(10, 5, Color::BLACK);
+        // the picture is the same as without the preset
+        let mut plain = TestFrameBuffer::new();
+        for x in 0..64u8 {
+            let color = Color::new(x * 4, 255 - x * 4, 128);
+            let point = Point::new(i32::from(x), i32::from(x % 32));
+            fb.set_pixel(point, color);
+            plain.set_pixel(point, color);
+        }
+        assert_eq!(fb.verify_control_bits(), Ok(()));
+        let ghost = Decoded::<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_FRAME_COUNT>::plain::<
+            Linear,
+            DefaultPins,
+        >(fb.as_words())
+        .unwrap();
+        let reference = Decoded::<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_FRAME_COUNT>::plain::<
+            DefaultOrdering,
+            DefaultPins,
+        >(plain.as_words())
+        .unwrap();
+        for y in 0..TEST_ROWS {
+            for x in 0..TEST_COLS {
+                assert_eq!(ghost.pixel(x, y), reference.pixel(x, y), "{x},{y}");
+            }
+        }
 
-        // The pixel should now be black (all bits false)
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].red1(), false);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].grn1(), false);
-        assert_eq!(fb.frames[0].rows[5].data[mapped_col_10].blu1(), false);
+        // the brightness window ends where the early blanking starts
+        fb.set_oe_duty(100);
+        assert!(fb.frames[0].rows[5].data[oe_end - 1].output_enable());
+        assert!(!fb.frames[0].rows[5].data[oe_end].output_enable());
+        assert_eq!(GhostFB::<1>::new().verify_control_bits(), Ok(()));
+        assert_eq!(GhostFB::<4>::new().verify_control_bits(), Ok(()));
     }
 
     #[test]
-    fn test_bcm_frame_overwrite() {
+    fn test_address_map() {
+        let map: [u8; TEST_NROWS] = core::array::from_fn(|row| (row as u8 + 8) % 16);
         let mut fb = TestFrameBuffer::new();
+        fb.format_with_address_map(&map);
+        fb.set_pixel(Point::new(4, 1), Color::RED);
 
-        // First write a white pixel (255, 255, 255)
-        fb.set_pixel_internal(10, 5, Color::WHITE);
-
-        let mapped_col_10 = get_mapped_index(10);
-
-        // Verify white pixel is lit in all frames (255 >= all thresholds)
-        for frame in fb.frames.iter() {
-            // White (255) should be active in all frames since it's >= all thresholds
-            assert_eq!(frame.rows[5].data[mapped_col_10].red1(), true);
-            assert_eq!(frame.rows[5].data[mapped_col_10].grn1(), true);
-            assert_eq!(frame.rows[5].data[mapped_col_10].blu1(), true);
+        for frame in &fb.frames {
+            for (row, entries) in frame.rows.iter().enumerate() {
+                let last = map_half_word_index::<DefaultOrdering>(TEST_COLS - 1);
+                let first = map_half_word_index::<DefaultOrdering>(0);
+                assert_eq!(entries.data[last].addr(), u16::from(map[row]));
+                assert_eq!(entries.data[first].addr(), u16::from(map[(row + 15) % 16]));
+            }
         }
+        // drawing still targets the logical row
+        let col = map_half_word_index::<DefaultOrdering>(4);
+        assert!(fb.frames[0].rows[1].data[col].red1());
 
-        // Now overwrite with 50% white (128, 128, 128)
-        let half_white = Color::from(embedded_graphics::pixelcolor::Rgb888::new(128, 128, 128));
-        fb.set_pixel_internal(10, 5, half_white);
+        fb.format();
+        let last = map_half_word_index::<DefaultOrdering>(TEST_COLS - 1);
+        assert_eq!(fb.frames[0].rows[1].data[last].addr(), 1);
+    }
 
-        // Verify only the correct frames are lit for 50% white
-        // With 3-bit depth: thresholds are 32, 64, 96, 128, 160, 192, 224
-        // 128 should activate frames 0, 1, 2, 3 (thresholds 32, 64, 96, 128)
-        // but not frames 4, 5, 6 (thresholds 160, 192, 224)
-        let brightness_step = 1 << (8 - TEST_BITS); // 32 for 3-bit
-        for (frame_idx, frame) in fb.frames.iter().enumerate() {
-            let frame_threshold = (frame_idx as u8 + 1) * brightness_step;
-            let should_be_active = 128 >= frame_threshold;
+    #[test]
+    #[should_panic(expected = "address out of range")]
+    fn test_address_map_out_of_range() {
+        let mut map = [0; TEST_NROWS];
+        map[3] = 32;
+        TestFrameBuffer::new().format_with_address_map(&map);
+    }
 
-            assert_eq!(frame.rows[5].data[mapped_col_10].red1(), should_be_active);
-            assert_eq!(frame.rows[5].data[mapped_col_10].grn1(), should_be_active);
-            assert_eq!(frame.rows[5].data[mapped_col_10].blu1(), should_be_active);
+    #[test]
+    fn test_inverted_control_polarity() {
+        use crate::format::Sequential;
+        use crate::ordering::Linear;
+
+        #[derive(Debug, Clone, Copy)]
+        struct Inverted;
+        impl PinMap for Inverted {
+            const OE_ACTIVE_LOW: bool = true;
+            const LAT_ACTIVE_LOW: bool = true;
         }
 
-        // Specifically verify the expected pattern for 3-bit depth
-        // Frames 0-3 should be active (thresholds 32, 64, 96, 128)
-        for frame_idx in 0..4 {
-            assert_eq!(
-                fb.frames[frame_idx].rows[5].data[mapped_col_10].red1(),
-                true
-            );
+        type Normal = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 2>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type InvertedFB = crate::format::DmaFrameBuffer<
+            PlainFormat<Inverted, 2, Sequential>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        const CONTROL: u16 = (1 << 8) | (1 << 5);
+
+        let mut normal = Normal::new();
+        let mut inverted = InvertedFB::new();
+        for duty in [100, 40] {
+            normal.set_oe_duty(duty);
+            inverted.set_oe_duty(duty);
+            normal.set_pixel(Point::new(3, 3), Color::CYAN);
+            inverted.set_pixel(Point::new(3, 3), Color::CYAN);
+            // only OE and LAT differ, and they differ in every word
+            for (n, i) in normal.as_words().iter().zip(inverted.as_words()) {
+                assert_eq!(n ^ i, CONTROL);
+            }
         }
-        // Frames 4-6 should be inactive (thresholds 160, 192, 224)
-        for frame_idx in 4..TEST_FRAME_COUNT {
-            assert_eq!(
-                fb.frames[frame_idx].rows[5].data[mapped_col_10].red1(),
-                false
-            );
+    }
+
+    #[test]
+    fn test_blanking_words_after_latch() {
+        use crate::ordering::Linear;
+
+        type BlankFB = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 4>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+        type LinearFB =
+            DmaFrameBuffer<TEST_ROWS, TEST_COLS, TEST_NROWS, TEST_BITS, TEST_FRAME_COUNT, Linear>;
+
+        let mut fb = BlankFB::new();
+        let mut plain = LinearFB::new();
+        fb.set_pixel(Point::new(3, 20), Color::WHITE);
+        plain.set_pixel(Point::new(3, 20), Color::WHITE);
+        assert_eq!(
+            BlankFB::bcm_chunk_bytes(),
+            TEST_FRAME_COUNT * TEST_NROWS * (TEST_COLS + 4) * 2
+        );
+
+        // every row is the plain row behind 4 blanked words on the address
+        // latched before it
+        for (blank_row, plain_row) in fb
+            .as_words()
+            .chunks(TEST_COLS + 4)
+            .zip(plain.as_words().chunks(TEST_COLS))
+        {
+            let prev_addr = plain_row[0] & 0b1_1111;
+            assert_eq!(blank_row[..4], [prev_addr; 4]);
+            assert_eq!(blank_row[4..], *plain_row);
         }
+
+        fb.erase();
+        assert_eq!(fb.frames[0].rows[1].blank, [Entry(0); 4]);
     }
 
     #[test]
-    fn test_read_buffer_implementation() {
-        // Test owned implementation - explicitly move the framebuffer to ensure we're testing the owned impl
-        let fb = TestFrameBuffer::new();
-        let expected_size = core::mem::size_of_val(&fb.frames);
+    fn test_custom_pin_map() {
+        use crate::ordering::Linear;
+
+        // colours on the low byte, control and address lines above them
+        #[derive(Debug, Clone, Copy)]
+        struct Moved;
+        impl PinMap for Moved {
+            const R1: u8 = 0;
+            const G1: u8 = 1;
+            const B1: u8 = 2;
+            const R2: u8 = 3;
+            const G2: u8 = 4;
+            const B2: u8 = 5;
+            const OE: u8 = 6;
+            const LAT: u8 = 7;
+            const ADDR: [u8; 5] = [8, 9, 10, 11, 12];
+        }
 
-        // Test owned ReadBuffer implementation by calling ReadBuffer::read_buffer explicitly
-        unsafe {
-            let (ptr, len) = <TestFrameBuffer as ReadBuffer>::read_buffer(&fb);
-            assert!(!ptr.is_null());
-            assert_eq!(len, expected_size);
+        type MovedFB = crate::format::DmaFrameBuffer<
+            PlainFormat<Moved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >;
+
+        let mut fb = MovedFB::new();
+        let row = &fb.frames[0].rows[5];
+        // previous address 4 while shifting, OE from the second column
+        assert_eq!(row.data[0].0, 0x0400);
+        assert_eq!(row.data[1].0, 0x0440);
+        // the last column latches address 5
+        assert_eq!(row.data[TEST_COLS - 1].0, 0x0580);
+
+        fb.set_pixel(Point::new(1, 5), Color::RED);
+        // row 21 is the lower half of scan row 5
+        fb.set_pixel(Point::new(1, 21), Color::BLUE);
+        assert_eq!(fb.frames[0].rows[5].data[1].0, 0x0461);
+        assert_eq!(fb.as_words()[5 * TEST_COLS + 1], 0x0461);
+
+        fb.erase();
+        assert_eq!(fb.frames[0].rows[5].data[1].0, 0x0440);
+    }
+
+    #[test]
+    fn test_pin_map_validation() {
+        #[derive(Debug, Clone, Copy)]
+        struct ColourOnLatch;
+        impl PinMap for ColourOnLatch {
+            const R1: u8 = 5;
         }
 
-        // Test direct method call on owned value
-        unsafe {
-            let (ptr, len) = fb.read_buffer();
-            assert!(!ptr.is_null());
-            assert_eq!(len, expected_size);
+        #[derive(Debug, Clone, Copy)]
+        struct AddressOnOe;
+        impl PinMap for AddressOnOe {
+            const ADDR: [u8; 5] = [0, 1, 2, 3, 8];
         }
 
-        // Test reference implementation
-        let fb = TestFrameBuffer::new();
-        let fb_ref = &fb;
-        unsafe {
-            let (ptr, len) = fb_ref.read_buffer();
-            assert!(!ptr.is_null());
-            assert_eq!(len, core::mem::size_of_val(&fb.frames));
+        #[derive(Debug, Clone, Copy)]
+        struct OutOfRange;
+        impl PinMap for OutOfRange {
+            const B2: u8 = 16;
         }
 
-        // Test mutable reference implementation
+        assert!(pins_valid::<DefaultPins>());
+        assert!(!pins_valid::<ColourOnLatch>());
+        assert!(!pins_valid::<AddressOnOe>());
+        assert!(!pins_valid::<OutOfRange>());
+    }
+
+    #[test]
+    fn test_write_buffer_receives_stream() {
+        let mut source = TestFrameBuffer::new();
+        source.set_pixel(Point::new(3, 4), Color::RED);
+        // lower half
+        source.set_pixel(Point::new(10, 18), Color::WHITE);
+
         let mut fb = TestFrameBuffer::new();
-        let fb_ref = &mut fb;
-        unsafe {
-            let (ptr, len) = fb_ref.read_buffer();
-            assert!(!ptr.is_null());
-            assert_eq!(len, core::mem::size_of_val(&fb.frames));
-        }
+        let (ptr, len) = unsafe { fb.write_buffer() };
+        assert_eq!(len, source.as_bytes().len());
+        // simulate the DMA transfer
+        unsafe { core::ptr::copy_nonoverlapping(source.as_bytes().as_ptr(), ptr, len) };
+        assert_eq!(fb.as_bytes(), source.as_bytes());
+
+        let mut fb_ref = &mut fb;
+        let (ref_ptr, ref_len) =
+            unsafe { <&mut TestFrameBuffer as WriteBuffer>::write_buffer(&mut fb_ref) };
+        assert_eq!((ref_ptr, ref_len), (ptr, len));
     }
 
     #[test]
@@ -1612,6 +2828,37 @@ mod tests {
         let mut fb = TestFrameBuffer::new();
         let fb_ref = &mut fb;
         assert_eq!(fb_ref.get_word_size(), WordSize::Sixteen);
+        assert_eq!((fb_ref.rows(), fb_ref.cols()), (TEST_ROWS, TEST_COLS));
+        assert_eq!(fb_ref.bits(), TEST_BITS);
+        assert_eq!(fb_ref.frame_count(), TEST_FRAME_COUNT);
+        assert_eq!(fb_ref.buffer_len_bytes(), fb_ref.as_bytes().len());
+    }
+
+    /// Draws through the trait only, as generic application code does.
+    fn draw_generic<FB: MutableFrameBuffer>(fb: &mut FB) {
+        Rectangle::new(Point::new(2, 3), Size::new(4, 2))
+            .into_styled(PrimitiveStyle::with_fill(Color::RED))
+            .draw(fb)
+            .unwrap();
+        assert_eq!(fb.plane_count(), 1);
+    }
+
+    #[test]
+    fn test_mutable_framebuffer_trait() {
+        let mut fb = TestFrameBuffer::new();
+        draw_generic(&mut fb);
+        let mut fb_ref = &mut fb;
+        draw_generic(&mut fb_ref);
+        Pixel(Point::new(9, 4), Color::RED)
+            .draw(&mut fb_ref)
+            .unwrap();
+
+        let mut expected = TestFrameBuffer::new();
+        for p in Rectangle::new(Point::new(2, 3), Size::new(4, 2)).points() {
+            expected.set_pixel(p, Color::RED);
+        }
+        expected.set_pixel(Point::new(9, 4), Color::RED);
+        assert!(fb.as_bytes() == expected.as_bytes());
     }
 
     #[test]
@@ -1643,6 +2890,202 @@ mod tests {
         assert_eq!(ptr % 8, 0);
     }
 
+    #[test]
+    fn test_slice_views_match_read_buffer() {
+        let mut fb = TestFrameBuffer::new();
+        let (ptr, len) = unsafe { fb.read_buffer() };
+
+        assert_eq!(fb.as_bytes().as_ptr(), ptr);
+        assert_eq!(fb.as_bytes().len(), len);
+        assert_eq!(fb.as_words().as_ptr().cast::<u8>(), ptr);
+        assert_eq!(fb.as_words().len(), len / 2);
+        assert_eq!(fb.as_words().as_ptr() as usize % 8, 0);
+
+        fb.set_pixel(Point::new(0, 0), Color::RED);
+        let word = fb.as_words()[get_mapped_index(0)];
+        assert_eq!(word, fb.frames[0].rows[0].data[get_mapped_index(0)].0);
+        assert_ne!(word & (1 << 9), 0);
+
+        fb.as_bytes_mut().fill(0);
+        assert!(fb.as_words().iter().all(|&w| w == 0));
+    }
+
+    #[test]
+    fn test_refresh_and_bandwidth_estimates() {
+        assert_eq!(TestFrameBuffer::estimated_refresh_hz(10_000_000), 1395);
+        assert_eq!(
+            TestFrameBuffer::dma_bandwidth_bytes_per_sec(10_000_000),
+            20_000_000
+        );
+    }
+
+    #[test]
+    fn test_chunks_split_on_row_boundaries() {
+        let fb = TestFrameBuffer::new();
+        let row_bytes = TEST_COLS * 2;
+        let bytes = fb.as_bytes();
+
+        let mut offset = 0;
+        for (ptr, len) in fb.chunks(row_bytes * 3 + 1) {
+            assert_eq!(ptr, bytes[offset..].as_ptr());
+            assert_eq!(len % row_bytes, 0);
+            assert!(len <= row_bytes * 3);
+            offset += len;
+        }
+        assert_eq!(offset, bytes.len());
+        assert_eq!(fb.chunks(bytes.len()).count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "smaller than a single row")]
+    fn test_chunks_smaller_than_row() {
+        let fb = TestFrameBuffer::new();
+        let _ = fb.chunks(TEST_COLS * 2 - 1);
+    }
+
+    #[test]
+    fn test_rows_in_stream_order() {
+        use crate::format::Interleaved;
+        use crate::RowIterExt;
+
+        let fb = TestFrameBuffer::new();
+        let mut words = fb.as_words();
+        for (i, (frame, row, data)) in fb.iter_rows().enumerate() {
+            assert_eq!((frame, row), (i / TEST_NROWS, i % TEST_NROWS));
+            assert_eq!(data.len(), TEST_COLS);
+            assert_eq!(data, &words[..TEST_COLS]);
+            words = &words[TEST_COLS..];
+        }
+        assert!(words.is_empty());
+
+        let mut fb = crate::format::DmaFrameBuffer::<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >::new();
+        fb.set_pixel(Point::new(3, 2), Color::new(96, 0, 0));
+        let rows: std::vec::Vec<_> = fb.iter_rows().collect();
+        assert_eq!(rows.len(), TEST_FRAME_COUNT * TEST_NROWS);
+        assert_eq!(rows[1], (1, 0, &fb.as_words()[TEST_COLS..2 * TEST_COLS]));
+        for (frame, row, data) in rows {
+            let lit = row == 2 && frame < 3;
+            assert_eq!(
+                Entry(data[map_index(3)]).red1(),
+                lit,
+                "frame {frame} row {row}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        use crate::format::{FrameRegion, Interleaved};
+        use crate::RowIterExt;
+
+        type InterleavedFB = crate::format::DmaFrameBuffer<
+            PlainFormat<DefaultPins, 0, Interleaved>,
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+        >;
+        let row_bytes = TestFrameBuffer::row_bytes();
+
+        let layout: std::vec::Vec<FrameRegion> = TestFrameBuffer::frame_layout().collect();
+        assert_eq!(layout.len(), TEST_FRAME_COUNT);
+        assert_eq!(
+            layout[2],
+            FrameRegion {
+                offset: 2 * TestFrameBuffer::frame_bytes(),
+                len: TEST_NROWS * row_bytes,
+                row_stride: row_bytes,
+            }
+        );
+
+        let mut fb = TestFrameBuffer::new();
+        assert!(fb.active_frame_layout().eq(layout.iter().copied()));
+        fb.set_max_brightness(63);
+        assert_eq!(fb.active_frame_layout().count(), fb.active_frames());
+        assert!(fb.active_frame_layout().count() < TEST_FRAME_COUNT);
+
+        // every row of the stream is where the layout of its frame says
+        let fb = InterleavedFB::new();
+        let layout: std::vec::Vec<FrameRegion> = InterleavedFB::frame_layout().collect();
+        assert_eq!(layout[1].offset, row_bytes);
+        assert_eq!(layout[1].row_stride, TEST_FRAME_COUNT * row_bytes);
+        for (i, (frame, row, _)) in fb.iter_rows().enumerate() {
+            assert_eq!(layout[frame].row_offset(row), i * row_bytes);
+        }
+    }
+
+    #[test]
+    fn test_words_in_output_order() {
+        use crate::ordering::{Esp32, Linear};
+        use crate::RowIterExt;
+
+        let mut linear = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new();
+        let mut esp32 = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >::new();
+        for (x, color) in (0..).zip([Color::RED, Color::GREEN, Color::BLUE]) {
+            linear.set_pixel(Point::new(x, 20), color);
+            esp32.set_pixel(Point::new(x, 20), color);
+        }
+
+        assert!(linear.iter_words().eq(linear.as_words().iter().copied()));
+        assert!(esp32.iter_words().eq(linear.as_words().iter().copied()));
+        assert_ne!(esp32.as_words(), linear.as_words());
+    }
+
+    #[test]
+    fn test_dma_sizing_helpers() {
+        let fb = TestFrameBuffer::new();
+        let row_bytes = TEST_COLS * 2;
+
+        assert_eq!(TestFrameBuffer::row_bytes(), row_bytes);
+        assert_eq!(
+            TestFrameBuffer::dma_buffer_size_bytes(),
+            fb.as_bytes().len()
+        );
+        assert_eq!(
+            TestFrameBuffer::dma_buffer_size_bytes(),
+            core::mem::size_of::<[Frame<TEST_ROWS, TEST_COLS, TEST_NROWS>; TEST_FRAME_COUNT]>()
+        );
+        let (_, len) = unsafe { fb.read_buffer() };
+        assert_eq!(len, TestFrameBuffer::dma_buffer_size_bytes());
+
+        assert_eq!(TestFrameBuffer::rows_per_descriptor(row_bytes), 1);
+        assert_eq!(TestFrameBuffer::rows_per_descriptor(row_bytes * 3 + 1), 3);
+        for max_len in [row_bytes, row_bytes * 3 + 1, 4095, fb.as_bytes().len()] {
+            assert_eq!(
+                TestFrameBuffer::descriptor_count(max_len),
+                fb.chunks(max_len).count()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "smaller than a single row")]
+    fn test_rows_per_descriptor_smaller_than_row() {
+        let _ = TestFrameBuffer::rows_per_descriptor(TEST_COLS * 2 - 1);
+    }
+
     #[test]
     fn test_color_values() {
         let mut fb = TestFrameBuffer::new();
@@ -1702,6 +3145,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_both_orderings_in_one_build() {
+        use crate::ordering::{Esp32, Linear};
+
+        let mut linear = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Linear,
+        >::new();
+        let mut esp32 = DmaFrameBuffer::<
+            TEST_ROWS,
+            TEST_COLS,
+            TEST_NROWS,
+            TEST_BITS,
+            TEST_FRAME_COUNT,
+            Esp32,
+        >::new();
+
+        linear.set_pixel(Point::new(0, 0), Color::RED);
+        esp32.set_pixel(Point::new(0, 0), Color::RED);
+
+        assert!(linear.frames[0].rows[0].data[0].red1());
+        assert!(!linear.frames[0].rows[0].data[1].red1());
+        assert!(esp32.frames[0].rows[0].data[1].red1());
+        assert!(!esp32.frames[0].rows[0].data[0].red1());
+
+        // the control template is swapped the same way
+        assert!(linear.frames[0].rows[0].data[1].output_enable());
+        assert!(esp32.frames[0].rows[0].data[0].output_enable());
+    }
+
     #[test]
     fn test_bits_assertion() {
         // Test that BITS <= 8 assertion is enforced at compile time
@@ -1830,6 +3307,26 @@ mod tests {
         assert!(fb.frames[0].rows[0].data[last_col].latch());
     }
 
+    /// Sets and erases pixels through the trait only.
+    fn operate_generic<FB: FrameBufferOperations>(fb: &mut FB) {
+        fb.erase();
+        fb.set_pixel(Point::new(8, 3), Color::BLUE);
+    }
+
+    #[test]
+    fn test_framebuffer_operations_trait_generic() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(1, 1), Color::RED);
+        operate_generic(&mut fb);
+        let mut expected = TestFrameBuffer::new();
+        expected.set_pixel(Point::new(8, 3), Color::BLUE);
+        assert!(fb.as_bytes() == expected.as_bytes());
+
+        let mut fb_ref = &mut fb;
+        operate_generic(&mut fb_ref);
+        assert!(fb.as_bytes() == expected.as_bytes());
+    }
+
     #[test]
     fn test_framebuffer_operations_trait_set_pixel() {
         let mut fb = TestFrameBuffer::new();
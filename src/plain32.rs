@@ -0,0 +1,364 @@
+//! 32-bit plain framebuffer clocking two adjacent columns per word.
+//!
+//! [`crate::plain`] sends one column per word, so the word rate of the
+//! parallel peripheral caps the refresh rate. This format packs two
+//! horizontally adjacent columns into every 32-bit word, halving the words
+//! per row for the same memory. It is meant for peripherals that
+//! demultiplex each word onto the panel in two pixel clocks, e.g. a PIO
+//! program or a CPLD, and for panels with doubled data lines that shift in
+//! two columns per clock.
+//!
+//! A word is two [`Entry`] halves in the reference wiring of
+//! [`crate::plain`]. The low half carries the even column together with
+//! the row address, `OE` and `LAT`; the high half carries only the colour
+//! bits of the odd column, at the same positions. A demultiplexer drives
+//! the low half on the first pixel clock and the colours of the high half
+//! on the second, holding the control signals of the low half for both.
+//!
+//! # HUB75 Signal Bit Mapping (32-bit words)
+//! ```text
+//! Bit    Signal
+//! 30-25  R1 G1 B1 R2 G2 B2 of the odd column
+//! 14-9   R1 G1 B1 R2 G2 B2 of the even column
+//!  8     OE
+//!  5     LAT
+//! 4-0    A..E
+//! ```
+//!
+//! All other bits stay low. The timing is that of the plain format counted
+//! in words: the output is enabled from the second word until the
+//! `blank-delay-*` words before the last one, which drives the new address
+//! and latches the row.
+//!
+//! `COLS` must be even. Peripherals read the 32-bit words whole, so the
+//! ordering parameter defaults to [`Linear`], and [`Esp32`] fails to
+//! compile.
+//!
+//! [`Esp32`]: crate::ordering::Esp32
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::plain32::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows, Color};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let mut fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! Rectangle::new(Point::new(8, 8), Size::new(16, 16))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::RED))
+//!     .draw(&mut fb)
+//!     .unwrap();
+//!
+//! // 32 words per row, the memory of the 16-bit plain format
+//! assert_eq!(fb.as_words().len(), FRAME_COUNT * NROWS * COLS / 2);
+//! assert_eq!(fb.as_bytes().len(), FRAME_COUNT * NROWS * COLS * 2);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::align::{Alignment, DefaultAlignment};
+use crate::export::Layout;
+use crate::format::{EntryFormat, RowFormat, Sequential};
+use crate::ordering::{is_swapped, Linear, Ordering};
+use crate::orientation::{Normal, Orientation};
+use crate::plain::{Entry, BLANKING_DELAY};
+use crate::WordSize;
+
+const OE_BIT: u16 = 1 << 8;
+const LAT_BIT: u16 = 1 << 5;
+const COLOR_MASK: u16 = 0b0111_1110_0000_0000;
+/// Position of the red bit of the upper and lower display half.
+const HALF_SHIFT: [u32; 2] = [9, 12];
+
+/// Index of the half of column `col` in a row; the low half of a word is
+/// the first in memory on little-endian targets.
+#[inline]
+const fn half_index(col: usize) -> usize {
+    if cfg!(target_endian = "big") {
+        col ^ 1
+    } else {
+        col
+    }
+}
+
+/// Pre-computed data template: the control signals in the low half of
+/// every word, no colours.
+const fn make_data_template<const COLS: usize>(addr: u8, prev_addr: u8) -> [Entry; COLS] {
+    let words = COLS / 2;
+    let mut data = [Entry::new(); COLS];
+    let mut i = 0;
+    while i < words {
+        let bits = if i == words - 1 {
+            LAT_BIT | addr as u16
+        } else if i >= 1 && i < words - BLANKING_DELAY - 1 {
+            OE_BIT | prev_addr as u16
+        } else {
+            prev_addr as u16
+        };
+        data[half_index(2 * i)] = Entry::from_bits(bits);
+        i += 1;
+    }
+    data
+}
+
+/// One row: `COLS / 2` words of two columns, stored as 16-bit halves.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct Row<const COLS: usize, O: Ordering = Linear> {
+    data: [Entry; COLS],
+    _ordering: PhantomData<O>,
+}
+
+impl<const COLS: usize, O: Ordering> Row<COLS, O> {
+    /// Checked once per instantiation, see [`Plain32Format`].
+    const LAYOUT_OK: () = {
+        assert!(COLS % 2 == 0, "COLS must be even to pair the columns");
+        assert!(
+            COLS / 2 > BLANKING_DELAY + 2,
+            "row too short for the blanking delay"
+        );
+        assert!(
+            !is_swapped::<O>(),
+            "32-bit words are read whole, use the Linear ordering"
+        );
+    };
+
+    const fn new() -> Self {
+        let () = Self::LAYOUT_OK;
+        Self {
+            data: [Entry::new(); COLS],
+            _ordering: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn set_half(&mut self, half: usize, col: usize, r: bool, g: bool, b: bool) {
+        let shift = HALF_SHIFT[half];
+        let bits = (u16::from(b) << 2) | (u16::from(g) << 1) | u16::from(r);
+        let entry = &mut self.data[half_index(col)];
+        *entry = Entry::from_bits((entry.bits() & !(0b111 << shift)) | (bits << shift));
+    }
+}
+
+impl<const COLS: usize, O: Ordering> Default for Row<COLS, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: usize, O: Ordering> RowFormat for Row<COLS, O> {
+    #[inline]
+    fn format(&mut self, addr: u8, prev_addr: u8) {
+        let () = Self::LAYOUT_OK;
+        self.data
+            .copy_from_slice(&make_data_template::<COLS>(addr, prev_addr));
+    }
+
+    #[inline]
+    fn clear_colors(&mut self) {
+        for entry in &mut self.data {
+            *entry = Entry::from_bits(entry.bits() & !COLOR_MASK);
+        }
+    }
+
+    #[inline]
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(0, col, r, g, b);
+    }
+
+    #[inline]
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(1, col, r, g, b);
+    }
+
+    #[inline]
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        let bits = self.data[half_index(col)].bits() >> HALF_SHIFT[half];
+        (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0)
+    }
+}
+
+/// [`EntryFormat`] of the 32-bit plain framebuffer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Plain32Format;
+
+// SAFETY: rows are `repr(C)` arrays of integer wrappers without padding, and
+// every bit pattern of them is valid.
+unsafe impl EntryFormat for Plain32Format {
+    const WORD_SIZE: WordSize = WordSize::ThirtyTwo;
+    const LAYOUT: Layout = Layout::Plain32;
+    type Pad = u64;
+    type FrameOrder = Sequential;
+    type Row<const COLS: usize, O: Ordering> = Row<COLS, O>;
+}
+
+/// DMA-compatible 32-bit framebuffer for HUB75 panels, two columns per
+/// word.
+///
+/// # Type Parameters
+/// - `ROWS`: Total number of rows in the panel
+/// - `COLS`: Number of columns in the panel, must be even
+/// - `NROWS`: Number of rows per scan (typically half of ROWS)
+/// - `BITS`: Color depth (1-8 bits)
+/// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA word [`Ordering`], defaults to [`Linear`], the only one
+///   allowed
+/// - `A`: minimum buffer [`Alignment`], defaults to [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`], defaults to [`Normal`]
+pub type DmaFrameBuffer<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const BITS: u8,
+    const FRAME_COUNT: usize,
+    O = Linear,
+    A = DefaultAlignment,
+    OR = Normal,
+> = crate::format::DmaFrameBuffer<Plain32Format, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>;
+
+impl<
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > crate::format::DmaFrameBuffer<Plain32Format, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    /// Returns the DMA stream as 32-bit words, each carrying two columns.
+    ///
+    /// The slice starts on an 8-byte boundary and covers all frames, like
+    /// [`Self::as_bytes`].
+    #[must_use]
+    pub fn as_words(&self) -> &[u32] {
+        let len = core::mem::size_of_val(&self.frames) / 4;
+        // SAFETY: the frames follow the 8-byte `Pad`, every row is an even
+        // number of `repr(transparent)` `u16` entries without padding, and
+        // every bit pattern is a valid `u32`.
+        unsafe { core::slice::from_raw_parts((&raw const self.frames).cast::<u32>(), len) }
+    }
+
+    /// Returns the words of the DMA stream returned by `read_buffer()`, see
+    /// [`Self::active_bytes`].
+    #[must_use]
+    pub fn active_words(&self) -> &[u32] {
+        &self.as_words()[..self.active_len_bytes() / 4]
+    }
+}
+
+impl<
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: Alignment,
+        OR: Orientation,
+    > crate::RowIterExt
+    for crate::format::DmaFrameBuffer<Plain32Format, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>
+{
+    type Word = u32;
+
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[u32])> + '_ {
+        Self::split_rows(self.active_words())
+    }
+
+    fn iter_words(&self) -> impl Iterator<Item = u32> + '_ {
+        Self::output_words(self.active_words())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{compute_frame_count, compute_rows, Color, FrameBuffer, RowIterExt};
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::*;
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+    const WORDS: usize = COLS / 2;
+
+    type TestFrameBuffer = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+
+    fn word(fb: &TestFrameBuffer, frame: usize, row: usize, i: usize) -> u32 {
+        fb.as_words()[(frame * NROWS + row) * WORDS + i]
+    }
+
+    #[test]
+    fn test_row_timing() {
+        let fb = TestFrameBuffer::new();
+        for i in 0..WORDS {
+            let word = word(&fb, 0, 5, i);
+            // the odd column carries no control signals
+            assert_eq!(word >> 16, 0);
+            let low = Entry::from_bits(word as u16);
+            assert_eq!(low.latch(), i == WORDS - 1);
+            assert_eq!(low.addr(), if i == WORDS - 1 { 5 } else { 4 });
+            assert_eq!(
+                low.output_enable(),
+                (1..WORDS - BLANKING_DELAY - 1).contains(&i)
+            );
+        }
+        assert_eq!(fb.get_word_size(), WordSize::ThirtyTwo);
+        assert_eq!(TestFrameBuffer::row_bytes(), WORDS * 4);
+    }
+
+    #[test]
+    fn test_set_pixel() {
+        let mut fb = TestFrameBuffer::new();
+        // scan row 3 of both halves
+        fb.set_pixel(Point::new(2, 3), Color::RED);
+        fb.set_pixel(Point::new(3, 19), Color::BLUE);
+
+        let word = word(&fb, 0, 3, 1);
+        let (even, odd) = (
+            Entry::from_bits(word as u16),
+            Entry::from_bits((word >> 16) as u16),
+        );
+        assert!(even.red1() && !even.blu2());
+        assert!(odd.blu2() && !odd.red1());
+        assert!(even.output_enable());
+        assert_eq!(fb.get_pixel(Point::new(2, 3)), Some(Color::new(224, 0, 0)));
+        assert_eq!(fb.get_pixel(Point::new(3, 19)), Some(Color::new(0, 0, 224)));
+        assert_eq!(fb.get_pixel(Point::new(3, 3)), Some(Color::BLACK));
+
+        fb.erase();
+        assert_eq!(fb.as_words(), TestFrameBuffer::new().as_words());
+    }
+
+    #[test]
+    fn test_iter_words() {
+        let mut fb = TestFrameBuffer::new();
+        fb.set_pixel(Point::new(63, 31), Color::GREEN);
+        assert!(fb.iter_words().eq(fb.as_words().iter().copied()));
+        let (frame, row, words) = fb.iter_rows().nth(NROWS + 2).unwrap();
+        assert_eq!((frame, row, words.len()), (1, 2, WORDS));
+    }
+
+    #[test]
+    fn test_export_layout() {
+        let fb = TestFrameBuffer::new();
+        let mut out = std::vec::Vec::new();
+        fb.export(|bytes| out.extend_from_slice(bytes));
+        let container = crate::export::Container::parse(&out).unwrap();
+        assert_eq!(container.header.layout, Layout::Plain32);
+        assert_eq!(container.header.word_size, WordSize::ThirtyTwo);
+        assert_eq!(container.plane(0), Some(fb.as_bytes()));
+    }
+}
@@ -0,0 +1,364 @@
+//! 8-bit framebuffer for plain wiring, without an external latch circuit.
+//!
+//! Parallel peripherals that are only 8 bits wide cannot hold the row
+//! address next to the colour bits, and [`crate::latched`] solves that with
+//! a latch circuit. This format needs no extra hardware: the address lines
+//! `A` to `E` are wired to the same data-bus bits as the first five colour
+//! signals, and every row is sent in two phases.
+//!
+//! 1. `COLS` pixel words are shifted in with the output disabled, since the
+//!    address lines follow the colour bits. `LAT` is high on the last one.
+//! 2. `LIT` display words drive the row address on the shared bits and
+//!    enable the output, so the row just latched lights up. The first and
+//!    last of them keep the output disabled while the address settles.
+//!
+//! The panel only lights during the display words, so brightness and
+//! refresh rate trade off through `LIT`: each row takes `COLS + LIT` clocks
+//! and is lit for `LIT - 2` of them. The display words are shifted into the
+//! panel as well, but the next row's pixels push them out before its latch.
+//!
+//! # HUB75 Signal Bit Mapping (8-bit words)
+//! ```text
+//! Bit  Pixel word     Display word
+//!  7   OE (low)       OE
+//!  6   LAT            LAT (low)
+//!  5   B2             -
+//!  4   G2             E
+//!  3   R2             D
+//!  2   B1             C
+//!  1   G1             B
+//!  0   R1             A
+//! ```
+//!
+//! Each data-bus bit from 0 to 4 drives both of its panel inputs, e.g. bit 0
+//! is wired to `R1` and `A`.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::plain8::DmaFrameBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows, Color};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! let mut fb = DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::new();
+//! Rectangle::new(Point::new(8, 8), Size::new(16, 16))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::RED))
+//!     .draw(&mut fb)
+//!     .unwrap();
+//!
+//! // every row is 64 pixel words and 16 display words
+//! assert_eq!(DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT>::frame_bytes(), NROWS * 80);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::align::DefaultAlignment;
+use crate::export::Layout;
+use crate::format::{EntryFormat, RowFormat, Sequential};
+use crate::ordering::{is_swapped, map_byte_index, DefaultOrdering, Ordering};
+use crate::orientation::Normal;
+use crate::WordSize;
+use bitfield::bitfield;
+
+bitfield! {
+    /// 8-bit word; the address lines share bits 0-4 with the colours.
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
+    #[repr(transparent)]
+    struct Entry(u8);
+    impl Debug;
+    pub output_enable, set_output_enable: 7;
+    pub latch, set_latch: 6;
+    pub blu2, set_blu2: 5;
+    pub grn2, set_grn2: 4;
+    pub red2, set_red2: 3;
+    pub blu1, set_blu1: 2;
+    pub grn1, set_grn1: 1;
+    pub red1, set_red1: 0;
+    pub addr, set_addr: 4, 0;
+}
+
+const COLOR_MASK: u8 = 0b0011_1111;
+const OE_BIT: u8 = 1 << 7;
+const LAT_BIT: u8 = 1 << 6;
+
+/// Pre-computed display words for all possible row addresses (0-31).
+const fn make_lit_table<O: Ordering, const LIT: usize>() -> [[Entry; LIT]; 32] {
+    let mut tbl = [[Entry(0); LIT]; 32];
+    let mut addr = 0;
+    while addr < 32 {
+        let mut i = 0;
+        while i < LIT {
+            let oe = if i == 0 || i == LIT - 1 { 0 } else { OE_BIT };
+            tbl[addr][map_byte_index::<O>(i)].0 = oe | addr as u8;
+            i += 1;
+        }
+        addr += 1;
+    }
+    tbl
+}
+
+/// Pre-computed pixel words: output disabled, latch on the last column.
+const fn make_data_template<const COLS: usize, O: Ordering>() -> [Entry; COLS] {
+    let mut data = [Entry(0); COLS];
+    data[map_byte_index::<O>(COLS - 1)].0 = LAT_BIT;
+    data
+}
+
+/// One row: pixel data followed by `LIT` display words.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct Row<const COLS: usize, O: Ordering = DefaultOrdering, const LIT: usize = 16> {
+    data: [Entry; COLS],
+    lit: [Entry; LIT],
+    _ordering: PhantomData<O>,
+}
+
+impl<const COLS: usize, O: Ordering, const LIT: usize> Row<COLS, O, LIT> {
+    const LIT_TABLE: [[Entry; LIT]; 32] = make_lit_table::<O, LIT>();
+
+    /// Checked once per instantiation, see [`Plain8Format`].
+    const LAYOUT_OK: () = {
+        assert!(LIT >= 3, "LIT needs at least 3 display words");
+        assert!(
+            (COLS % 4 == 0 && LIT % 4 == 0) || !is_swapped::<O>(),
+            "COLS and LIT must be multiples of 4 with a swapped ordering"
+        );
+    };
+
+    const fn new() -> Self {
+        let () = Self::LAYOUT_OK;
+        Self {
+            data: [Entry(0); COLS],
+            lit: [Entry(0); LIT],
+            _ordering: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn set_half(&mut self, shift: u32, col: usize, r: bool, g: bool, b: bool) {
+        let bits = (u8::from(b) << 2) | (u8::from(g) << 1) | u8::from(r);
+        let entry = &mut self.data[map_byte_index::<O>(col)];
+        entry.0 = (entry.0 & !(0b111 << shift)) | (bits << shift);
+    }
+}
+
+impl<const COLS: usize, O: Ordering, const LIT: usize> Default for Row<COLS, O, LIT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: usize, O: Ordering, const LIT: usize> RowFormat for Row<COLS, O, LIT> {
+    #[inline]
+    fn format(&mut self, addr: u8, _prev_addr: u8) {
+        let () = Self::LAYOUT_OK;
+        self.lit.copy_from_slice(&Self::LIT_TABLE[addr as usize]);
+        self.data.copy_from_slice(&make_data_template::<COLS, O>());
+    }
+
+    #[inline]
+    fn clear_colors(&mut self) {
+        for entry in &mut self.data {
+            entry.0 &= !COLOR_MASK;
+        }
+    }
+
+    #[inline]
+    fn set_color0(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(0, col, r, g, b);
+    }
+
+    #[inline]
+    fn set_color1(&mut self, col: usize, r: bool, g: bool, b: bool) {
+        self.set_half(3, col, r, g, b);
+    }
+
+    #[inline]
+    fn color(&self, half: usize, col: usize) -> (bool, bool, bool) {
+        let shift = if half == 0 { 0 } else { 3 };
+        let bits = self.data[map_byte_index::<O>(col)].0 >> shift;
+        (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0)
+    }
+}
+
+/// [`EntryFormat`] of the 8-bit plain framebuffer.
+///
+/// `LIT` is the number of display words per row, including the two that
+/// keep the output disabled around the address change. It must be at least
+/// 3, and a multiple of 4 with a swapped [`Ordering`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Plain8Format<const LIT: usize = 16>;
+
+// SAFETY: rows are `repr(C)` arrays of integer wrappers without padding, and
+// every bit pattern of them is valid.
+unsafe impl<const LIT: usize> EntryFormat for Plain8Format<LIT> {
+    const WORD_SIZE: WordSize = WordSize::Eight;
+    const LAYOUT: Layout = Layout::Plain8;
+    type Pad = ();
+    type FrameOrder = Sequential;
+    type Row<const COLS: usize, O: Ordering> = Row<COLS, O, LIT>;
+}
+
+/// DMA-compatible 8-bit framebuffer for HUB75 panels wired without a latch
+/// circuit.
+///
+/// # Type Parameters
+/// - `ROWS`: Total number of rows in the panel
+/// - `COLS`: Number of columns in the panel
+/// - `NROWS`: Number of rows per scan (typically half of ROWS)
+/// - `BITS`: Color depth (1-8 bits)
+/// - `FRAME_COUNT`: Number of frames used for Binary Code Modulation
+/// - `O`: DMA byte [`Ordering`], defaults to [`DefaultOrdering`]
+/// - `A`: minimum buffer [`Alignment`](crate::align::Alignment), defaults to
+///   [`DefaultAlignment`]
+/// - `OR`: fixed panel [`Orientation`](crate::orientation::Orientation),
+///   defaults to [`Normal`]
+///
+/// Use [`crate::format::DmaFrameBuffer`] with [`Plain8Format`] directly to
+/// choose a different number of display words per row.
+pub type DmaFrameBuffer<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const BITS: u8,
+    const FRAME_COUNT: usize,
+    O = DefaultOrdering,
+    A = DefaultAlignment,
+    OR = Normal,
+> = crate::format::DmaFrameBuffer<Plain8Format, ROWS, COLS, NROWS, BITS, FRAME_COUNT, O, A, OR>;
+
+impl<
+        const LIT: usize,
+        const ROWS: usize,
+        const COLS: usize,
+        const NROWS: usize,
+        const BITS: u8,
+        const FRAME_COUNT: usize,
+        O: Ordering,
+        A: crate::align::Alignment,
+        OR: crate::orientation::Orientation,
+    > crate::RowIterExt
+    for crate::format::DmaFrameBuffer<
+        Plain8Format<LIT>,
+        ROWS,
+        COLS,
+        NROWS,
+        BITS,
+        FRAME_COUNT,
+        O,
+        A,
+        OR,
+    >
+{
+    type Word = u8;
+
+    fn iter_rows(&self) -> impl Iterator<Item = (usize, usize, &[u8])> + '_ {
+        Self::split_rows(self.active_bytes())
+    }
+
+    fn iter_words(&self) -> impl Iterator<Item = u8> + '_ {
+        Self::output_words(self.active_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::ordering::{Esp32, Linear};
+    use crate::{compute_frame_count, compute_rows, Color, FrameBuffer};
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::*;
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type TestFrameBuffer = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+
+    #[test]
+    fn test_row_phases() {
+        let fb = TestFrameBuffer::new();
+        let row = &fb.frames[0].rows[5];
+
+        // pixels are shifted in dark, latched on the last one
+        for (col, word) in row.data.iter().enumerate() {
+            assert!(!word.output_enable());
+            assert_eq!(word.latch(), col == COLS - 1);
+        }
+        // then the row address is driven and the output enabled
+        for (i, word) in row.lit.iter().enumerate() {
+            assert_eq!(word.addr(), 5);
+            assert!(!word.latch() && !word.blu2());
+            assert_eq!(word.output_enable(), i != 0 && i != 15);
+        }
+        assert_eq!(fb.get_word_size(), WordSize::Eight);
+        assert_eq!(fb.as_bytes().len(), FRAME_COUNT * NROWS * (COLS + 16));
+    }
+
+    #[test]
+    fn test_set_pixel() {
+        let mut fb = TestFrameBuffer::new();
+        assert!(fb.is_formatted());
+        assert!(!TestFrameBuffer::new_unformatted().is_formatted());
+        fb.set_pixel(Point::new(3, 2), Color::RED);
+        fb.set_pixel(Point::new(3, 18), Color::new(0, 255, 255));
+
+        let entry = fb.frames[0].rows[2].data[3];
+        assert!(entry.red1() && !entry.grn1() && !entry.blu1());
+        assert!(!entry.red2() && entry.grn2() && entry.blu2());
+        assert!(!entry.output_enable());
+        assert_eq!(
+            fb.get_pixel(Point::new(3, 18)),
+            Some(Color::new(0, 224, 224))
+        );
+        // the display words are left alone
+        assert_eq!(fb.frames[0].rows[2].lit[1].0, OE_BIT | 2);
+
+        fb.erase();
+        assert_eq!(fb.frames[0].rows[2].data[3].0, 0);
+        assert_eq!(fb.frames[0].rows[2].data[COLS - 1].0, LAT_BIT);
+    }
+
+    #[test]
+    fn test_lit_words_and_ordering() {
+        type Short = crate::format::DmaFrameBuffer<
+            Plain8Format<4>,
+            ROWS,
+            COLS,
+            NROWS,
+            BITS,
+            FRAME_COUNT,
+            Esp32,
+        >;
+
+        let fb = Short::new();
+        let row = &fb.frames[0].rows[7];
+        let lit: std::vec::Vec<bool> = (0..4)
+            .map(|i| row.lit[map_byte_index::<Esp32>(i)].output_enable())
+            .collect();
+        assert_eq!(lit, [false, true, true, false]);
+        assert!(row.data[map_byte_index::<Esp32>(COLS - 1)].latch());
+        assert_eq!(Short::frame_bytes(), NROWS * (COLS + 4));
+    }
+
+    #[test]
+    fn test_export_layout() {
+        let fb = TestFrameBuffer::new();
+        let mut out = std::vec::Vec::new();
+        fb.export(|bytes| out.extend_from_slice(bytes));
+        let container = crate::export::Container::parse(&out).unwrap();
+        assert_eq!(container.header.layout, Layout::Plain8);
+        assert_eq!(container.header.word_size, WordSize::Eight);
+    }
+}
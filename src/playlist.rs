@@ -0,0 +1,420 @@
+//! Slideshows for menu boards and signs.
+//!
+//! A [`Playlist`] steps through a list of [`Slide`]s, each showing an image
+//! for a number of ticks and entering with a [`Transition`]. Images are
+//! anything implementing `embedded-graphics`' `ImageDrawable` for
+//! [`Color`]: pre-converted `ImageRaw` data, or decoded BMP or GIF images
+//! from crates such as `tinybmp` and `tinygif`.
+//!
+//! The application calls [`Playlist::tick`] at a steady rate, e.g. from a
+//! [`FramePacer`](crate::vsync::FramePacer), and redraws whenever it
+//! returns `true`. Drawing clears the target first, so with
+//! double-buffered framebuffers draw into the back buffer and swap.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::image::ImageRaw;
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::playlist::{Playlist, Slide, Transition};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::Color;
+//!
+//! // 2x1 pixel RGB888 images, converted at build time in practice
+//! const BREAKFAST: ImageRaw<Color> = ImageRaw::new(&[255, 128, 0, 255, 128, 0], 2);
+//! const LUNCH: ImageRaw<Color> = ImageRaw::new(&[0, 128, 255, 0, 128, 255], 2);
+//!
+//! let slides = [
+//!     Slide::new(&BREAKFAST, 600),
+//!     Slide::new(&LUNCH, 600).with_transition(Transition::Fade(60)),
+//! ];
+//! let mut playlist = Playlist::new(&slides);
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//!
+//! playlist.draw(&mut fb).unwrap();
+//! for _ in 0..1000 {
+//!     if playlist.tick() {
+//!         playlist.draw(&mut fb).unwrap();
+//!     }
+//! }
+//! ```
+
+use embedded_graphics::image::{Image, ImageDrawable};
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{Dimensions, DrawTarget, DrawTargetExt, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::{Drawable, Pixel};
+
+use crate::display::scale;
+use crate::Color;
+
+/// How a slide replaces the one before.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transition {
+    /// Show the slide at once.
+    #[default]
+    Cut,
+    /// Fade the previous slide out to black and this one in, taking the
+    /// given number of ticks.
+    Fade(u32),
+    /// Uncover the slide from left to right over the given number of ticks.
+    Wipe(u32),
+}
+
+impl Transition {
+    /// Ticks the transition takes.
+    #[must_use]
+    pub const fn ticks(self) -> u32 {
+        match self {
+            Self::Cut => 0,
+            Self::Fade(ticks) | Self::Wipe(ticks) => ticks,
+        }
+    }
+}
+
+/// An image shown by a [`Playlist`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Slide<'a, I> {
+    /// The image, drawn at the top left of the target.
+    pub image: &'a I,
+    /// Ticks the slide is shown, including its transition.
+    pub duration: u32,
+    /// How the slide replaces the previous one.
+    pub transition: Transition,
+}
+
+impl<I> Clone for Slide<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for Slide<'_, I> {}
+
+impl<'a, I> Slide<'a, I> {
+    /// Show `image` for `duration` ticks, cutting to it.
+    #[must_use]
+    pub const fn new(image: &'a I, duration: u32) -> Self {
+        Self {
+            image,
+            duration,
+            transition: Transition::Cut,
+        }
+    }
+
+    /// Enter the slide with `transition`.
+    #[must_use]
+    pub const fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+}
+
+/// Steps through slides, advanced by [`Playlist::tick`].
+///
+/// The playlist starts at the first slide and by default starts over after
+/// the last one. The first slide's transition starts from black.
+#[derive(Debug)]
+pub struct Playlist<'a, I> {
+    slides: &'a [Slide<'a, I>],
+    index: usize,
+    previous: Option<usize>,
+    elapsed: u32,
+    repeat: bool,
+    finished: bool,
+    background: Color,
+}
+
+impl<'a, I: ImageDrawable<Color = Color>> Playlist<'a, I> {
+    /// Play `slides` in order, over and over.
+    #[must_use]
+    pub const fn new(slides: &'a [Slide<'a, I>]) -> Self {
+        Self {
+            slides,
+            index: 0,
+            previous: None,
+            elapsed: 0,
+            repeat: true,
+            finished: slides.is_empty(),
+            background: Color::BLACK,
+        }
+    }
+
+    /// Stop on the last slide instead of starting over.
+    #[must_use]
+    pub const fn once(mut self) -> Self {
+        self.repeat = false;
+        self
+    }
+
+    /// Clear the target to `background` instead of black.
+    #[must_use]
+    pub const fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Index of the slide shown.
+    #[must_use]
+    pub const fn current(&self) -> usize {
+        self.index
+    }
+
+    /// `true` once a playlist playing [`once`](Self::once) has shown its
+    /// last slide for its full duration, or if it has no slides.
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advance by one tick. Returns `true` if the picture changed and the
+    /// playlist should be drawn again.
+    pub fn tick(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.elapsed += 1;
+        if self.elapsed >= self.slides[self.index].duration.max(1) {
+            let next = if self.index + 1 < self.slides.len() {
+                self.index + 1
+            } else if self.repeat {
+                0
+            } else {
+                self.finished = true;
+                return false;
+            };
+            self.previous = Some(self.index);
+            self.index = next;
+            self.elapsed = 0;
+            return true;
+        }
+        self.elapsed <= self.slides[self.index].transition.ticks()
+    }
+
+    /// Draw the current picture, clearing the target first.
+    ///
+    /// # Errors
+    /// Returns the error of the target.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        target.clear(self.background)?;
+        let Some(slide) = self.slides.get(self.index) else {
+            return Ok(());
+        };
+        let area = target.bounding_box();
+        let position = area.top_left;
+        let previous = self.previous.map(|index| self.slides[index].image);
+        let ticks = slide.transition.ticks();
+        if self.elapsed >= ticks {
+            return draw_image(slide.image, target, position, u8::MAX);
+        }
+
+        match slide.transition {
+            Transition::Cut => draw_image(slide.image, target, position, u8::MAX),
+            Transition::Fade(ticks) => {
+                // out in the first half, in in the second
+                let half = ticks / 2;
+                if self.elapsed < half {
+                    match previous {
+                        Some(image) => {
+                            let level = level(half - self.elapsed, half);
+                            draw_image(image, target, position, level)
+                        }
+                        None => Ok(()),
+                    }
+                } else {
+                    let level = level(self.elapsed - half, ticks - half);
+                    draw_image(slide.image, target, position, level)
+                }
+            }
+            Transition::Wipe(ticks) => {
+                let edge = u32::try_from(
+                    u64::from(area.size.width) * u64::from(self.elapsed) / u64::from(ticks),
+                )
+                .unwrap_or(area.size.width);
+                let uncovered = Rectangle::new(area.top_left, Size::new(edge, area.size.height));
+                let covered = Rectangle::new(
+                    area.top_left + Size::new(edge, 0),
+                    Size::new(area.size.width - edge, area.size.height),
+                );
+                if let Some(image) = previous {
+                    draw_image(image, &mut target.clipped(&covered), position, u8::MAX)?;
+                }
+                draw_image(
+                    slide.image,
+                    &mut target.clipped(&uncovered),
+                    position,
+                    u8::MAX,
+                )
+            }
+        }
+    }
+}
+
+/// Brightness for `step` of `steps`.
+fn level(step: u32, steps: u32) -> u8 {
+    u8::try_from(u64::from(step) * 255 / u64::from(steps.max(1))).unwrap_or(u8::MAX)
+}
+
+/// Draw `image` at `position`, scaled by `brightness`.
+fn draw_image<I, D>(
+    image: &I,
+    target: &mut D,
+    position: Point,
+    brightness: u8,
+) -> Result<(), D::Error>
+where
+    I: ImageDrawable<Color = Color>,
+    D: DrawTarget<Color = Color>,
+{
+    if brightness == u8::MAX {
+        Image::new(image, position).draw(target)
+    } else {
+        Image::new(image, position).draw(&mut Dimmed { target, brightness })
+    }
+}
+
+/// Scales the colours drawn into the wrapped target.
+struct Dimmed<'t, D> {
+    target: &'t mut D,
+    brightness: u8,
+}
+
+impl<D: DrawTarget<Color = Color>> Dimensions for Dimmed<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget<Color = Color>> DrawTarget for Dimmed<'_, D> {
+    type Color = Color;
+    type Error = D::Error;
+
+    fn draw_iter<P>(&mut self, pixels: P) -> Result<(), Self::Error>
+    where
+        P: IntoIterator<Item = Pixel<Color>>,
+    {
+        let brightness = self.brightness;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, scale(color, brightness))),
+        )
+    }
+
+    fn fill_contiguous<C>(&mut self, area: &Rectangle, colors: C) -> Result<(), Self::Error>
+    where
+        C: IntoIterator<Item = Color>,
+    {
+        let brightness = self.brightness;
+        self.target.fill_contiguous(
+            area,
+            colors.into_iter().map(|color| scale(color, brightness)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::image::ImageRaw;
+    use embedded_graphics::mock_display::MockDisplay;
+
+    const RED: ImageRaw<Color> = ImageRaw::new(&[255, 0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0], 4);
+    const BLUE: ImageRaw<Color> = ImageRaw::new(&[0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0, 255], 4);
+
+    fn drawn(playlist: &Playlist<'_, ImageRaw<'_, Color>>) -> MockDisplay<Color> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        playlist
+            .draw(&mut display.clipped(&Rectangle::new(Point::zero(), Size::new(4, 1))))
+            .unwrap();
+        display
+    }
+
+    #[test]
+    fn test_cut() {
+        let slides = [Slide::new(&RED, 3), Slide::new(&BLUE, 2)];
+        let mut playlist = Playlist::new(&slides);
+        drawn(&playlist).assert_pattern(&["RRRR"]);
+
+        assert!(!playlist.tick());
+        assert!(!playlist.tick());
+        assert!(playlist.tick());
+        assert_eq!(playlist.current(), 1);
+        drawn(&playlist).assert_pattern(&["BBBB"]);
+
+        assert!(!playlist.tick());
+        assert!(playlist.tick());
+        assert_eq!(playlist.current(), 0);
+        assert!(!playlist.is_finished());
+    }
+
+    #[test]
+    fn test_once() {
+        let slides = [Slide::new(&RED, 1), Slide::new(&BLUE, 2)];
+        let mut playlist = Playlist::new(&slides).once();
+        assert!(playlist.tick());
+        assert!(!playlist.tick());
+        assert!(!playlist.tick());
+        assert!(playlist.is_finished());
+        assert_eq!(playlist.current(), 1);
+        assert!(!playlist.tick());
+
+        let empty: [Slide<'_, ImageRaw<'_, Color>>; 0] = [];
+        let mut playlist = Playlist::new(&empty);
+        assert!(playlist.is_finished());
+        assert!(!playlist.tick());
+        drawn(&playlist).assert_pattern(&["KKKK"]);
+    }
+
+    #[test]
+    fn test_fade() {
+        let slides = [
+            Slide::new(&RED, 1),
+            Slide::new(&BLUE, 10).with_transition(Transition::Fade(4)),
+        ];
+        let mut playlist = Playlist::new(&slides);
+        assert!(playlist.tick());
+        // red at full brightness, then half, then black and blue fading in
+        drawn(&playlist).assert_pattern(&["RRRR"]);
+        assert!(playlist.tick());
+        let display = drawn(&playlist);
+        assert_eq!(
+            display.get_pixel(Point::zero()),
+            Some(Color::new(127, 0, 0))
+        );
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["KKKK"]);
+        assert!(playlist.tick());
+        let display = drawn(&playlist);
+        assert_eq!(
+            display.get_pixel(Point::zero()),
+            Some(Color::new(0, 0, 127))
+        );
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["BBBB"]);
+        assert!(!playlist.tick());
+    }
+
+    #[test]
+    fn test_wipe() {
+        let slides = [
+            Slide::new(&RED, 1),
+            Slide::new(&BLUE, 10).with_transition(Transition::Wipe(4)),
+        ];
+        let mut playlist = Playlist::new(&slides);
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["RRRR"]);
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["BRRR"]);
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["BBRR"]);
+        assert!(playlist.tick());
+        assert!(playlist.tick());
+        drawn(&playlist).assert_pattern(&["BBBB"]);
+    }
+}
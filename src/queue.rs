@@ -0,0 +1,271 @@
+//! Deferred drawing from other contexts.
+//!
+//! Small updates often come from somewhere else than the rendering task: a
+//! button interrupt lights a status pixel, a network callback fills in a
+//! bar. Sharing the whole framebuffer behind a mutex means holding the lock
+//! while drawing and stalling the renderer. A [`CommandQueue`] instead
+//! collects up to `N` [`Command`]s from any context, each push taking the
+//! lock only to copy one command, and the rendering task applies them all
+//! with [`CommandQueue::apply`].
+//!
+//! `M` is an `embassy-sync` raw mutex: `CriticalSectionRawMutex` for pushes
+//! from interrupts or other cores, `ThreadModeRawMutex` or `NoopRawMutex`
+//! when all contexts run in thread mode.
+//!
+//! # Example
+//! ```rust
+//! use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::Rectangle;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::queue::CommandQueue;
+//! use hub75_framebuffer::Color;
+//!
+//! // on the target usually a `static` with `CriticalSectionRawMutex`
+//! let queue = CommandQueue::<NoopRawMutex, 16>::new();
+//!
+//! // e.g. in an interrupt handler
+//! queue.set_pixel(Point::new(63, 0), Color::GREEN).unwrap();
+//! queue
+//!     .fill_rect(Rectangle::new(Point::new(0, 30), Size::new(20, 2)), Color::BLUE)
+//!     .unwrap();
+//!
+//! // in the rendering task
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! assert_eq!(queue.apply(&mut fb), Ok(2));
+//! assert!(queue.is_empty());
+//! ```
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, Point};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use crate::{Color, Hub75Error};
+
+/// A drawing operation queued in a [`CommandQueue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<'a> {
+    /// Set one pixel.
+    SetPixel(Point, Color),
+    /// Fill a rectangle with one colour.
+    FillRect(Rectangle, Color),
+    /// Copy `colors`, row by row, into `area`.
+    Blit {
+        /// Destination of the pixels.
+        area: Rectangle,
+        /// One colour per pixel of `area`, row by row.
+        colors: &'a [Color],
+    },
+}
+
+impl Command<'_> {
+    /// Draw the command on `target`.
+    ///
+    /// # Errors
+    /// Returns the error of the target.
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        match *self {
+            Self::SetPixel(p, color) => target.draw_iter(core::iter::once(Pixel(p, color))),
+            Self::FillRect(area, color) => target.fill_solid(&area, color),
+            Self::Blit { area, colors } => target.fill_contiguous(&area, colors.iter().copied()),
+        }
+    }
+}
+
+/// Commands pushed but not applied yet.
+struct Pending<'a, const N: usize> {
+    commands: [Command<'a>; N],
+    len: usize,
+}
+
+/// A bounded queue of drawing commands shared between contexts, see the
+/// [module documentation](self).
+pub struct CommandQueue<'a, M: RawMutex, const N: usize> {
+    pending: Mutex<M, RefCell<Pending<'a, N>>>,
+}
+
+impl<M: RawMutex, const N: usize> Default for CommandQueue<'_, M, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, M: RawMutex, const N: usize> CommandQueue<'a, M, N> {
+    /// Create an empty queue, e.g. in a `static`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pending: Mutex::new(RefCell::new(Pending {
+                commands: [Command::SetPixel(Point::zero(), Color::BLACK); N],
+                len: 0,
+            })),
+        }
+    }
+
+    /// Queue `command`.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::CapacityExceeded`] if `N` commands are already
+    /// queued, and [`Hub75Error::BufferTooSmall`] for a
+    /// [`Command::Blit`] with fewer colours than pixels.
+    pub fn push(&self, command: Command<'a>) -> Result<(), Hub75Error> {
+        if let Command::Blit { area, colors } = command {
+            let needed = area.size.width as usize * area.size.height as usize;
+            if colors.len() < needed {
+                return Err(Hub75Error::BufferTooSmall {
+                    needed,
+                    actual: colors.len(),
+                });
+            }
+        }
+        self.pending.lock(|pending| {
+            let mut pending = pending.borrow_mut();
+            let len = pending.len;
+            let slot = pending
+                .commands
+                .get_mut(len)
+                .ok_or(Hub75Error::CapacityExceeded(N))?;
+            *slot = command;
+            pending.len += 1;
+            Ok(())
+        })
+    }
+
+    /// Queue a [`Command::SetPixel`].
+    ///
+    /// # Errors
+    /// See [`Self::push`].
+    pub fn set_pixel(&self, p: Point, color: Color) -> Result<(), Hub75Error> {
+        self.push(Command::SetPixel(p, color))
+    }
+
+    /// Queue a [`Command::FillRect`].
+    ///
+    /// # Errors
+    /// See [`Self::push`].
+    pub fn fill_rect(&self, area: Rectangle, color: Color) -> Result<(), Hub75Error> {
+        self.push(Command::FillRect(area, color))
+    }
+
+    /// Queue a [`Command::Blit`].
+    ///
+    /// # Errors
+    /// See [`Self::push`].
+    pub fn blit(&self, area: Rectangle, colors: &'a [Color]) -> Result<(), Hub75Error> {
+        self.push(Command::Blit { area, colors })
+    }
+
+    /// Number of queued commands.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pending.lock(|pending| pending.borrow().len)
+    }
+
+    /// Whether no commands are queued.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all queued commands.
+    pub fn clear(&self) {
+        self.pending.lock(|pending| pending.borrow_mut().len = 0);
+    }
+
+    /// Draw all queued commands on `target` in the order they were pushed
+    /// and empty the queue, returning the number of commands drawn.
+    ///
+    /// The commands are taken out of the queue first, so the lock is not
+    /// held while drawing and commands pushed meanwhile wait for the next
+    /// call.
+    ///
+    /// # Errors
+    /// Returns the first error of the target; the commands after the failing
+    /// one are dropped.
+    pub fn apply<D>(&self, target: &mut D) -> Result<usize, D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        let (commands, len) = self.pending.lock(|pending| {
+            let mut pending = pending.borrow_mut();
+            let len = core::mem::take(&mut pending.len);
+            (pending.commands, len)
+        });
+        for command in &commands[..len] {
+            command.draw(target)?;
+        }
+        Ok(len)
+    }
+}
+
+impl<M: RawMutex, const N: usize> core::fmt::Debug for CommandQueue<'_, M, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CommandQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::Size;
+
+    fn mock() -> MockDisplay<Color> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        mock
+    }
+
+    #[test]
+    fn test_apply_in_order() {
+        let queue = CommandQueue::<NoopRawMutex, 4>::new();
+        let colors = [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE];
+        queue
+            .fill_rect(Rectangle::new(Point::zero(), Size::new(3, 2)), Color::BLUE)
+            .unwrap();
+        queue
+            .blit(Rectangle::new(Point::new(1, 0), Size::new(2, 2)), &colors)
+            .unwrap();
+        queue.set_pixel(Point::new(0, 2), Color::RED).unwrap();
+        assert_eq!(queue.len(), 3);
+
+        let mut display = mock();
+        assert_eq!(queue.apply(&mut display), Ok(3));
+        display.assert_pattern(&["BRG", "BBW", "R  "]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.apply(&mut display), Ok(0));
+    }
+
+    #[test]
+    fn test_push_errors() {
+        let queue = CommandQueue::<NoopRawMutex, 1>::new();
+        let colors = [Color::RED; 3];
+        assert_eq!(
+            queue.blit(Rectangle::new(Point::zero(), Size::new(2, 2)), &colors),
+            Err(Hub75Error::BufferTooSmall {
+                needed: 4,
+                actual: 3
+            })
+        );
+        queue.set_pixel(Point::zero(), Color::RED).unwrap();
+        assert_eq!(
+            queue.set_pixel(Point::zero(), Color::RED),
+            Err(Hub75Error::CapacityExceeded(1))
+        );
+        queue.clear();
+        assert!(queue.is_empty());
+        queue.set_pixel(Point::zero(), Color::RED).unwrap();
+    }
+}
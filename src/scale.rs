@@ -0,0 +1,371 @@
+//! Drawing at twice the panel resolution.
+//!
+//! Some sources only produce fixed, larger resolutions: a video decoder
+//! outputs 128 by 64 frames, an existing UI layout was made for a bigger
+//! display. [`Downscale2x`] wraps a `W` by `H` pixel framebuffer and
+//! accepts drawing at `2 * W` by `2 * H` pixels; every panel pixel shows the
+//! average colour of the 2 by 2 block of source pixels it covers.
+//!
+//! To average blocks drawn piecewise, e.g. by separate primitives, the
+//! adapter keeps the four source colours of every panel pixel, `12 * W * H`
+//! bytes: a 64 by 32 pixel panel takes 24 KiB.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::scale::Downscale2x;
+//! use hub75_framebuffer::Color;
+//!
+//! let fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let mut display = Downscale2x::<_, 64, 32>::new(fb);
+//! assert_eq!(display.size(), Size::new(128, 64));
+//!
+//! // a one pixel wide line shows at half the brightness
+//! Rectangle::new(Point::new(10, 0), Size::new(1, 64))
+//!     .into_styled(PrimitiveStyle::with_fill(Color::WHITE))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! assert_eq!(display.pixel(Point::new(5, 0)), Some(Color::new(128, 128, 128)));
+//! ```
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+
+/// A `W` by `H` pixel framebuffer drawn at twice its resolution, see the
+/// [module documentation](self).
+pub struct Downscale2x<FB, const W: usize, const H: usize> {
+    fb: FB,
+    samples: [[[Color; 4]; W]; H],
+}
+
+impl<FB: OriginDimensions, const W: usize, const H: usize> Downscale2x<FB, W, H> {
+    /// Wrap `fb` with all source pixels black. The framebuffer is not
+    /// changed until something is drawn.
+    ///
+    /// # Panics
+    /// Panics if `fb` is not `W` by `H` pixels.
+    #[must_use]
+    pub fn new(fb: FB) -> Self {
+        assert_eq!(
+            fb.size(),
+            Size::new(W as u32, H as u32),
+            "framebuffer size does not match Downscale2x<_, {W}, {H}>"
+        );
+        Self {
+            fb,
+            samples: [[[Color::BLACK; 4]; W]; H],
+        }
+    }
+}
+
+impl<FB, const W: usize, const H: usize> Downscale2x<FB, W, H> {
+    /// Colour shown at panel pixel `p`, or `None` if it is outside the
+    /// panel.
+    #[must_use]
+    pub fn pixel(&self, p: Point) -> Option<Color> {
+        let x = usize::try_from(p.x).ok().filter(|&x| x < W)?;
+        let y = usize::try_from(p.y).ok().filter(|&y| y < H)?;
+        Some(average(&self.samples[y][x]))
+    }
+
+    /// The wrapped framebuffer.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, for drawing at panel resolution. Drawing on
+    /// it directly is overwritten when the source pixels of a panel pixel
+    /// change.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// Unwrap the framebuffer.
+    pub fn into_inner(self) -> FB {
+        self.fb
+    }
+
+    /// Store source pixel `p` and return the panel pixel it belongs to with
+    /// its new colour, or `None` if `p` is outside the source area.
+    fn store(
+        samples: &mut [[[Color; 4]; W]; H],
+        Pixel(p, color): Pixel<Color>,
+    ) -> Option<Pixel<Color>> {
+        let x = usize::try_from(p.x).ok().filter(|&x| x < 2 * W)?;
+        let y = usize::try_from(p.y).ok().filter(|&y| y < 2 * H)?;
+        let block = &mut samples[y / 2][x / 2];
+        block[(y % 2) * 2 + x % 2] = color;
+        Some(Pixel(Point::new(p.x / 2, p.y / 2), average(block)))
+    }
+}
+
+/// Average of the four source colours of a panel pixel, rounded.
+fn average(block: &[Color; 4]) -> Color {
+    let channel = |f: fn(&Color) -> u8| {
+        let sum: u16 = block.iter().map(|c| u16::from(f(c))).sum();
+        ((sum + 2) / 4) as u8
+    };
+    Color::new(channel(Color::r), channel(Color::g), channel(Color::b))
+}
+
+impl<FB, const W: usize, const H: usize> OriginDimensions for Downscale2x<FB, W, H> {
+    fn size(&self) -> Size {
+        Size::new(2 * W as u32, 2 * H as u32)
+    }
+}
+
+impl<FB, const W: usize, const H: usize> DrawTarget for Downscale2x<FB, W, H>
+where
+    FB: DrawTarget<Color = Color>,
+{
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let samples = &mut self.samples;
+        self.fb.draw_iter(
+            pixels
+                .into_iter()
+                .filter_map(|pixel| Self::store(samples, pixel)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size()));
+        let aligned = area.top_left.x % 2 == 0
+            && area.top_left.y % 2 == 0
+            && area.size.width % 2 == 0
+            && area.size.height % 2 == 0;
+        if !aligned {
+            return self.draw_iter(area.points().map(|p| Pixel(p, color)));
+        }
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        // whole blocks: every panel pixel gets the colour as is
+        let panel = Rectangle::new(area.top_left / 2, area.size / 2);
+        let (left, top) = (panel.top_left.x as usize, panel.top_left.y as usize);
+        let (width, height) = (panel.size.width as usize, panel.size.height as usize);
+        for row in &mut self.samples[top..top + height] {
+            row[left..left + width].fill([color; 4]);
+        }
+        self.fb.fill_solid(&panel, color)
+    }
+}
+
+impl<FB: FrameBufferOperations, const W: usize, const H: usize> FrameBufferOperations
+    for Downscale2x<FB, W, H>
+{
+    /// Erase the framebuffer and reset all source pixels to black.
+    #[inline]
+    fn erase(&mut self) {
+        self.samples = [[[Color::BLACK; 4]; W]; H];
+        self.fb.erase();
+    }
+
+    /// Set source pixel `p`, in source coordinates.
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        if let Some(Pixel(p, color)) = Self::store(&mut self.samples, Pixel(p, color)) {
+            self.fb.set_pixel(p, color);
+        }
+    }
+}
+
+impl<FB: FrameBuffer, const W: usize, const H: usize> FrameBuffer for Downscale2x<FB, W, H> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+impl<FB: MutableFrameBuffer, const W: usize, const H: usize> MutableFrameBuffer
+    for Downscale2x<FB, W, H>
+{
+}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>, const W: usize, const H: usize> ReadBuffer
+    for Downscale2x<FB, W, H>
+{
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+impl<FB: core::fmt::Debug, const W: usize, const H: usize> core::fmt::Debug
+    for Downscale2x<FB, W, H>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Downscale2x")
+            .field("fb", &self.fb)
+            .field("size", &self.size())
+            .finish_non_exhaustive()
+    }
+}
+
+// like `Debug`, leaving out the `12 * W * H` bytes of samples
+#[cfg(feature = "defmt")]
+impl<FB: defmt::Format, const W: usize, const H: usize> defmt::Format for Downscale2x<FB, W, H> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Downscale2x {{ fb: {}, width: {}, height: {}, .. }}",
+            self.fb,
+            2 * W,
+            2 * H
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plain::DmaFrameBuffer;
+    use crate::viewport::Translated;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    fn mock() -> Downscale2x<Translated<MockDisplay<Color>>, 3, 2> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        let panel = Rectangle::new(Point::zero(), Size::new(3, 2));
+        Downscale2x::new(Translated::new(mock, panel))
+    }
+
+    trait Panel {
+        fn panel(&self) -> &MockDisplay<Color>;
+    }
+
+    impl Panel for Downscale2x<Translated<MockDisplay<Color>>, 3, 2> {
+        fn panel(&self) -> &MockDisplay<Color> {
+            self.framebuffer().framebuffer()
+        }
+    }
+
+    #[test]
+    fn test_average() {
+        let block = [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE];
+        assert_eq!(average(&block), Color::new(128, 128, 128));
+        assert_eq!(average(&[Color::new(1, 2, 3); 4]), Color::new(1, 2, 3));
+        assert_eq!(average(&[Color::BLACK; 4]), Color::BLACK);
+    }
+
+    #[test]
+    fn test_draw_iter_averages_blocks() {
+        let mut display = mock();
+        assert_eq!(display.size(), Size::new(6, 4));
+        Line::new(Point::new(0, 1), Point::new(6, 1))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        let half = Color::new(128, 0, 0);
+        assert_eq!(display.panel().get_pixel(Point::new(0, 0)), Some(half));
+        assert_eq!(display.panel().get_pixel(Point::new(2, 0)), Some(half));
+        assert_eq!(display.panel().get_pixel(Point::new(0, 1)), None);
+
+        // a later draw completes the blocks
+        Line::new(Point::new(0, 0), Point::new(5, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(display.pixel(Point::new(1, 0)), Some(Color::new(255, 0, 0)));
+        assert_eq!(display.pixel(Point::new(3, 0)), None);
+        display.panel().assert_pattern(&["RRR"]);
+    }
+
+    #[test]
+    fn test_fill_solid() {
+        let mut display = mock();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(2, 0), Size::new(4, 4)),
+                Color::GREEN,
+            )
+            .unwrap();
+        display.panel().assert_pattern(&[" GG", " GG"]);
+
+        // an unaligned area averages the blocks it covers partly
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-1, 0), Size::new(2, 2)),
+                Color::BLUE,
+            )
+            .unwrap();
+        assert_eq!(display.pixel(Point::zero()), Some(Color::new(0, 0, 128)));
+        assert_eq!(display.pixel(Point::new(0, 1)), Some(Color::BLACK));
+    }
+
+    #[test]
+    #[should_panic(expected = "framebuffer size does not match")]
+    fn test_size_mismatch() {
+        let _ = Downscale2x::<_, 32, 32>::new(DmaFrameBuffer::<16, 32, 8, 3, 7>::new());
+    }
+
+    #[test]
+    fn test_framebuffer() {
+        type SmallFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+        let mut display = Downscale2x::<_, 32, 16>::new(SmallFB::new());
+        assert_eq!(display.size(), Size::new(64, 32));
+        for p in [
+            Point::new(6, 8),
+            Point::new(7, 8),
+            Point::new(6, 9),
+            Point::new(7, 9),
+        ] {
+            display.set_pixel(p, Color::WHITE);
+        }
+        let mut expected = SmallFB::new();
+        expected.set_pixel(Point::new(3, 4), Color::WHITE);
+        assert_eq!(display.framebuffer().as_bytes(), expected.as_bytes());
+
+        let (ptr, len) = unsafe { display.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { display.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!((display.rows(), display.cols()), (16, 32));
+
+        display.erase();
+        assert_eq!(display.pixel(Point::new(3, 4)), Some(Color::BLACK));
+        assert_eq!(display.into_inner().as_bytes(), SmallFB::new().as_bytes());
+    }
+}
@@ -0,0 +1,294 @@
+//! SPI serialization for shift-register HUB75 adapters.
+//!
+//! Some adapter boards generate the HUB75 signals from a chain of 16 shift
+//! registers (e.g. two 74HC595) fed by a fast SPI bus, so MCUs without a
+//! parallel output peripheral can still drive a panel. Every 16 SPI bits are
+//! transferred to the register outputs at once, so one framebuffer word
+//! becomes two 16-bit shift-register frames: one with the HUB75 `CLK` line
+//! low and one with it high.
+//!
+//! [`SpiEncoder`] describes the adapter's wiring (which register output
+//! drives `CLK` and in which order the chain is shifted) and [`SpiBuffer`]
+//! holds the re-serialized stream. The buffer implements
+//! [`embedded_dma::ReadBuffer`], so it can be handed to an SPI DMA transfer
+//! directly.
+//!
+//! Output `Qn` of the chain carries bit `n` of the framebuffer word, so wire
+//! the registers following the bit mapping in [`crate::plain`] or
+//! [`crate::latched`]. `CLK` must use an output that the word does not use.
+//! Framebuffers must use [`crate::ordering::Linear`] ordering.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::spi::{encoded_len, SpiBitOrder, SpiBuffer, SpiEncoder};
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 2;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//! type FBType = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+//! const SPI_BYTES: usize = encoded_len(FRAME_COUNT * NROWS * COLS);
+//!
+//! let fb = FBType::new();
+//! let encoder = SpiEncoder::new(15, SpiBitOrder::MsbFirst);
+//! let mut spi = Box::new(SpiBuffer::<SPI_BYTES>::new());
+//! encoder.encode(&fb, &mut spi);
+//! assert_eq!(spi.as_bytes().len(), SPI_BYTES);
+//! ```
+
+use embedded_dma::ReadBuffer;
+
+use crate::{FrameBuffer, WordSize};
+
+/// Number of SPI bytes per shift-register frame.
+const FRAME_BYTES: usize = 2;
+
+/// Number of SPI bytes needed to serialize `words` framebuffer words.
+#[must_use]
+pub const fn encoded_len(words: usize) -> usize {
+    words * 2 * FRAME_BYTES
+}
+
+/// Order in which the shift-register chain receives its bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpiBitOrder {
+    /// The first bit on the wire ends up on output `Q15`.
+    MsbFirst,
+    /// The first bit on the wire ends up on output `Q0`.
+    LsbFirst,
+}
+
+/// Re-serializes framebuffer words into shift-register frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SpiEncoder {
+    clock_bit: u8,
+    bit_order: SpiBitOrder,
+}
+
+impl SpiEncoder {
+    /// Create an encoder for an adapter with `CLK` on register output
+    /// `clock_bit`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `clock_bit` is not in `0..16`.
+    #[must_use]
+    pub const fn new(clock_bit: u8, bit_order: SpiBitOrder) -> Self {
+        assert!(clock_bit < 16, "clock_bit must be a shift-register output");
+        Self {
+            clock_bit,
+            bit_order,
+        }
+    }
+
+    /// Encode a single framebuffer word into its two shift-register frames.
+    #[must_use]
+    pub const fn encode_word(&self, word: u16) -> [u8; 2 * FRAME_BYTES] {
+        let low = word & !(1 << self.clock_bit);
+        let high = word | (1 << self.clock_bit);
+        let (low, high) = match self.bit_order {
+            SpiBitOrder::MsbFirst => (low, high),
+            SpiBitOrder::LsbFirst => (low.reverse_bits(), high.reverse_bits()),
+        };
+        let [l0, l1] = low.to_be_bytes();
+        let [h0, h1] = high.to_be_bytes();
+        [l0, l1, h0, h1]
+    }
+
+    /// Encode all planes of `fb` back to back into `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is too small, if the framebuffer word uses the
+    /// output reserved for `CLK`, or if it is wider than the 16 register
+    /// outputs.
+    pub fn encode<FB: FrameBuffer + ?Sized, const BYTES: usize>(
+        &self,
+        fb: &FB,
+        out: &mut SpiBuffer<BYTES>,
+    ) {
+        let word_bytes = match fb.get_word_size() {
+            WordSize::Eight => 1,
+            WordSize::Sixteen => 2,
+            WordSize::ThirtyTwo => panic!("32-bit words do not fit the shift registers"),
+        };
+        assert!(
+            word_bytes == 2 || self.clock_bit >= 8,
+            "clock_bit collides with the framebuffer word"
+        );
+
+        out.len = 0;
+        for plane_idx in 0..fb.plane_count() {
+            let (ptr, len) = fb.plane_ptr_len(plane_idx);
+            // SAFETY: the plane stays borrowed through `fb` while we read it.
+            let bytes = unsafe { core::slice::from_raw_parts(ptr, len) };
+            for word in bytes.chunks_exact(word_bytes) {
+                let word = match word {
+                    [b] => u16::from(*b),
+                    [lo, hi] => u16::from_ne_bytes([*lo, *hi]),
+                    _ => unreachable!(),
+                };
+                let encoded = self.encode_word(word);
+                let end = out.len + encoded.len();
+                assert!(end <= BYTES, "SpiBuffer is too small for the framebuffer");
+                out.data[out.len..end].copy_from_slice(&encoded);
+                out.len = end;
+            }
+        }
+    }
+}
+
+/// Holds an SPI-serialized framebuffer.
+///
+/// Size it with [`encoded_len`]. The buffer is usually large, so place it in
+/// a `static` rather than on the stack.
+#[derive(Clone)]
+#[repr(C)]
+pub struct SpiBuffer<const BYTES: usize> {
+    len: usize,
+    data: [u8; BYTES],
+}
+
+impl<const BYTES: usize> Default for SpiBuffer<BYTES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const BYTES: usize> SpiBuffer<BYTES> {
+    /// Create an empty buffer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            data: [0; BYTES],
+        }
+    }
+
+    /// Returns the encoded stream.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl<const BYTES: usize> core::fmt::Debug for SpiBuffer<BYTES> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SpiBuffer")
+            .field("len", &self.len)
+            .field("capacity", &BYTES)
+            .finish_non_exhaustive()
+    }
+}
+
+unsafe impl<const BYTES: usize> ReadBuffer for SpiBuffer<BYTES> {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.data.as_ptr(), self.len)
+    }
+}
+
+unsafe impl<const BYTES: usize> ReadBuffer for &mut SpiBuffer<BYTES> {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.data.as_ptr(), self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::boxed::Box;
+
+    use embedded_graphics::pixelcolor::RgbColor;
+    use embedded_graphics::prelude::Point;
+
+    use super::*;
+    use crate::ordering::Linear;
+    use crate::{compute_frame_count, compute_rows, Color};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 2;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+    const WORDS: usize = FRAME_COUNT * NROWS * COLS;
+
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+
+    #[test]
+    fn test_encode_word_msb_first() {
+        let encoder = SpiEncoder::new(15, SpiBitOrder::MsbFirst);
+        assert_eq!(encoder.encode_word(0x0123), [0x01, 0x23, 0x81, 0x23]);
+    }
+
+    #[test]
+    fn test_encode_word_lsb_first() {
+        let encoder = SpiEncoder::new(15, SpiBitOrder::LsbFirst);
+        // 0x0123 reversed is 0xc480, with CLK it becomes 0xc481
+        assert_eq!(encoder.encode_word(0x0123), [0xc4, 0x80, 0xc4, 0x81]);
+    }
+
+    #[test]
+    fn test_encode_plain_framebuffer() {
+        let mut fb = PlainFB::new();
+        fb.set_pixel(Point::new(5, 7), Color::RED);
+        let encoder = SpiEncoder::new(15, SpiBitOrder::MsbFirst);
+        let mut spi = Box::new(SpiBuffer::<{ encoded_len(WORDS) }>::new());
+
+        encoder.encode(&fb, &mut spi);
+
+        let bytes = spi.as_bytes();
+        assert_eq!(bytes.len(), encoded_len(WORDS));
+        for (word, encoded) in fb.as_words().iter().zip(bytes.chunks_exact(4)) {
+            assert_eq!(encoded, encoder.encode_word(*word));
+        }
+        let (ptr, len) = unsafe { spi.read_buffer() };
+        assert_eq!(ptr, bytes.as_ptr());
+        assert_eq!(len, bytes.len());
+    }
+
+    #[test]
+    fn test_encode_latched_framebuffer() {
+        let fb = LatchedFB::new();
+        let words = fb.as_bytes().len();
+        let encoder = SpiEncoder::new(8, SpiBitOrder::MsbFirst);
+        let mut spi =
+            Box::new(SpiBuffer::<{ encoded_len(FRAME_COUNT * NROWS * (COLS + 4)) }>::new());
+
+        encoder.encode(&fb, &mut spi);
+
+        assert_eq!(spi.as_bytes().len(), encoded_len(words));
+        // latched words only use the low byte, CLK toggles bit 8
+        assert_eq!(spi.as_bytes()[0] & 1, 0);
+        assert_eq!(spi.as_bytes()[2] & 1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn test_buffer_too_small() {
+        let fb = PlainFB::new();
+        let encoder = SpiEncoder::new(15, SpiBitOrder::MsbFirst);
+        let mut spi = Box::new(SpiBuffer::<16>::new());
+        encoder.encode(&fb, &mut spi);
+    }
+
+    #[test]
+    #[should_panic(expected = "collides")]
+    fn test_clock_bit_collides_with_8_bit_word() {
+        let fb = LatchedFB::new();
+        let encoder = SpiEncoder::new(7, SpiBitOrder::MsbFirst);
+        let mut spi = Box::new(SpiBuffer::<16>::new());
+        encoder.encode(&fb, &mut spi);
+    }
+}
@@ -0,0 +1,264 @@
+//! Splitting a wide display across two DMA peripherals.
+//!
+//! The pixel clock of one I2S or LCD peripheral limits how many columns it
+//! can shift out per refresh, so a very wide wall either refreshes slowly
+//! or runs out of BCM bits. Controllers with two such peripherals can drive
+//! the left and right half of the wall on separate HUB75 outputs at the
+//! same time, doubling the pixel budget.
+//!
+//! [`ColumnSplit`] holds one framebuffer per half and draws into them as if
+//! they were a single display twice as wide. Both halves have the same
+//! type, so their DMA streams have the same length and row timing: started
+//! together, e.g. from the same trigger, the two peripherals run in
+//! lockstep and the halves show the same row at the same time.
+//!
+//! # Example
+//! ```rust
+//! use embedded_dma::ReadBuffer;
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::split::ColumnSplit;
+//! use hub75_framebuffer::Color;
+//!
+//! // a 512 pixel wide wall, 256 columns per peripheral
+//! type HalfFB = DmaFrameBuffer<32, 256, 16, 3, 7>;
+//!
+//! let mut wall = ColumnSplit::new(HalfFB::new(), HalfFB::new());
+//! assert_eq!(wall.size(), Size::new(512, 32));
+//! Pixel(Point::new(300, 4), Color::RED).draw(&mut wall).unwrap();
+//!
+//! let (left, right) = wall.halves();
+//! // hand each half to its own peripheral and start both at once
+//! let (left_ptr, left_len) = unsafe { left.read_buffer() };
+//! let (right_ptr, right_len) = unsafe { right.read_buffer() };
+//! assert_eq!(left_len, right_len);
+//! assert_ne!(left_ptr, right_ptr);
+//! ```
+
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBufferOperations};
+
+/// A display split by columns into two framebuffers streamed in parallel,
+/// see the [module documentation](self).
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ColumnSplit<FB> {
+    halves: [FB; 2],
+}
+
+impl<FB> ColumnSplit<FB> {
+    /// Combine the framebuffers of the `left` and `right` half.
+    #[must_use]
+    pub const fn new(left: FB, right: FB) -> Self {
+        Self {
+            halves: [left, right],
+        }
+    }
+
+    /// The framebuffers of the left and right half, e.g. to start DMA on
+    /// both peripherals.
+    #[must_use]
+    pub const fn halves(&self) -> (&FB, &FB) {
+        let [left, right] = &self.halves;
+        (left, right)
+    }
+
+    /// The framebuffers of the left and right half, to draw into one half
+    /// in its own coordinates.
+    pub fn halves_mut(&mut self) -> (&mut FB, &mut FB) {
+        let [left, right] = &mut self.halves;
+        (left, right)
+    }
+
+    /// Unwrap the framebuffers of the left and right half.
+    pub fn into_halves(self) -> (FB, FB) {
+        let [left, right] = self.halves;
+        (left, right)
+    }
+}
+
+impl<FB: OriginDimensions> ColumnSplit<FB> {
+    /// Columns of each half.
+    fn half_width(&self) -> i32 {
+        i32::try_from(self.halves[0].size().width).unwrap_or(i32::MAX)
+    }
+
+    /// The half showing display point `p` and `p` in its coordinates.
+    fn locate(&self, p: Point) -> (usize, Point) {
+        let width = self.half_width();
+        if p.x < width {
+            (0, p)
+        } else {
+            (1, Point::new(p.x - width, p.y))
+        }
+    }
+}
+
+impl<FB: OriginDimensions> OriginDimensions for ColumnSplit<FB> {
+    fn size(&self) -> Size {
+        let half = self.halves[0].size();
+        Size::new(2 * half.width, half.height)
+    }
+}
+
+impl<FB> DrawTarget for ColumnSplit<FB>
+where
+    FB: DrawTarget<Color = Color> + OriginDimensions,
+{
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(p, color) in pixels {
+            let (half, p) = self.locate(p);
+            self.halves[half].draw_iter(core::iter::once(Pixel(p, color)))?;
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let width = self.half_width();
+        let size = self.halves[0].size();
+        let left = area.intersection(&Rectangle::new(Point::zero(), size));
+        let right = area.intersection(&Rectangle::new(Point::new(width, 0), size));
+        if !left.is_zero_sized() {
+            self.halves[0].fill_solid(&left, color)?;
+        }
+        if !right.is_zero_sized() {
+            let right = Rectangle::new(right.top_left - Point::new(width, 0), right.size);
+            self.halves[1].fill_solid(&right, color)?;
+        }
+        Ok(())
+    }
+}
+
+// not a `FrameBuffer` itself, the halves are two separate DMA streams
+impl<FB: FrameBufferOperations + OriginDimensions> ColumnSplit<FB> {
+    /// Erase both halves, see [`FrameBufferOperations::erase`].
+    #[inline]
+    pub fn erase(&mut self) {
+        for half in &mut self.halves {
+            half.erase();
+        }
+    }
+
+    /// Set display pixel `p` in the half showing it, see
+    /// [`FrameBufferOperations::set_pixel`].
+    #[inline]
+    pub fn set_pixel(&mut self, p: Point, color: Color) {
+        let (half, p) = self.locate(p);
+        self.halves[half].set_pixel(p, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::Decoded;
+    use crate::ordering::DefaultOrdering;
+    use crate::plain::{DefaultPins, DmaFrameBuffer};
+    use embedded_dma::ReadBuffer;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    type HalfFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+
+    fn split() -> ColumnSplit<HalfFB> {
+        ColumnSplit::new(HalfFB::new(), HalfFB::new())
+    }
+
+    #[test]
+    fn test_draw_iter_routes_columns() {
+        let mut display = split();
+        assert_eq!(display.size(), Size::new(64, 16));
+        Line::new(Point::new(30, 2), Point::new(33, 2))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(64, 2), Color::RED)
+            .draw(&mut display)
+            .unwrap();
+
+        let (left, right) = display.halves();
+        let red = Color::new(224, 0, 0);
+        assert_eq!(left.get_pixel(Point::new(30, 2)), Some(red));
+        assert_eq!(left.get_pixel(Point::new(31, 2)), Some(red));
+        assert_eq!(right.get_pixel(Point::new(0, 2)), Some(red));
+        assert_eq!(right.get_pixel(Point::new(1, 2)), Some(red));
+        assert_eq!(right.get_pixel(Point::new(2, 2)), Some(Color::BLACK));
+        assert_eq!(right.get_pixel(Point::new(31, 2)), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_fill_solid_splits_area() {
+        let mut display = split();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-4, 3), Size::new(40, 2)),
+                Color::WHITE,
+            )
+            .unwrap();
+        display.set_pixel(Point::new(40, 0), Color::WHITE);
+
+        let (left, right) = display.halves();
+        let mut expected_left = HalfFB::new();
+        expected_left
+            .fill_solid(
+                &Rectangle::new(Point::new(0, 3), Size::new(32, 2)),
+                Color::WHITE,
+            )
+            .unwrap();
+        let mut expected_right = HalfFB::new();
+        expected_right
+            .fill_solid(
+                &Rectangle::new(Point::new(0, 3), Size::new(4, 2)),
+                Color::WHITE,
+            )
+            .unwrap();
+        expected_right.set_pixel(Point::new(8, 0), Color::WHITE);
+        assert_eq!(left.as_bytes(), expected_left.as_bytes());
+        assert_eq!(right.as_bytes(), expected_right.as_bytes());
+
+        display.erase();
+        let (left, right) = display.into_halves();
+        assert_eq!(left.as_bytes(), HalfFB::new().as_bytes());
+        assert_eq!(right.as_bytes(), HalfFB::new().as_bytes());
+    }
+
+    #[test]
+    fn test_halves_stream_in_lockstep() {
+        let mut display = split();
+        display.clear(Color::BLUE).unwrap();
+        Pixel(Point::new(63, 15), Color::RED)
+            .draw(&mut display)
+            .unwrap();
+
+        let (left, right) = display.halves();
+        let (_, left_len) = unsafe { left.read_buffer() };
+        let (_, right_len) = unsafe { right.read_buffer() };
+        assert_eq!(left_len, right_len);
+
+        // same control words at every position, only the colours differ
+        let control = !(crate::plain::color0_mask::<DefaultPins>()
+            | crate::plain::color1_mask::<DefaultPins>());
+        for (l, r) in left.as_words().iter().zip(right.as_words()) {
+            assert_eq!(l & control, r & control);
+        }
+
+        let left = Decoded::<16, 32, 8, 7>::plain::<DefaultOrdering, DefaultPins>(left.as_words())
+            .unwrap();
+        let right =
+            Decoded::<16, 32, 8, 7>::plain::<DefaultOrdering, DefaultPins>(right.as_words())
+                .unwrap();
+        let blue = Color::new(0, 0, 224);
+        assert_eq!(left.pixel(31, 15), blue);
+        assert_eq!(right.pixel(30, 15), blue);
+        assert_eq!(right.pixel(31, 15), Color::new(224, 0, 0));
+    }
+}
@@ -0,0 +1,518 @@
+//! Framebuffer for panels with S-PWM driver ICs (ICN2053, FM6353, MBI5153).
+//!
+//! These driver ICs keep a full frame of 16-bit grey values in internal SRAM
+//! and generate the PWM themselves from a free-running grey-scale clock
+//! (`GCLK`). They ignore the BCM streams of the other framebuffers and show
+//! garbage; instead every new picture is shifted in once, followed by a
+//! vertical sync command that puts it on screen.
+//!
+//! Commands are encoded in the width of the `LAT` pulse, counted in data
+//! clocks: the data latch that stores one channel of every IC, the vertical
+//! sync, and the pre-active and write-configuration pair that programs the
+//! configuration registers. The widths of each chip are given by
+//! [`SpwmDriver`].
+//!
+//! # Hardware Requirements
+//! Same 16-bit parallel interface as [`crate::plain`], with the same bit
+//! mapping. The grey-scale clock is not part of the stream: it is usually
+//! wired to the `OE` pin and must be generated continuously, e.g. by a timer
+//! or PWM peripheral, together with the row scan the panel expects.
+//!
+//! # Data Layout
+//! Every scan line holds 16 latch blocks, one per driver channel. A block is
+//! `COLS` words: the 16-bit grey value, MSB first, of that channel for every
+//! IC in the chain, starting with the IC farthest from the connector. `LAT`
+//! is high during the last [`SpwmDriver::DATA_LATCH`] words of each block.
+//! The vertical sync command follows the last scan line, so a whole frame is
+//! `NROWS * 16 * COLS + 4` words.
+//!
+//! Column `x` is driven by channel `x % 16` of IC `x / 16`; the top and
+//! bottom halves of the panel share the words like in [`crate::plain`].
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::spwm::{ConfigWrite, DmaFrameBuffer, Icn2053};
+//! use hub75_framebuffer::{compute_rows, Color};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const NROWS: usize = compute_rows(ROWS);
+//!
+//! // sent once at start-up, before the first frame
+//! let config = ConfigWrite::<COLS>::new::<Icn2053>(0, 0x1f70);
+//! assert_eq!(config.as_bytes().len(), 2 * 2 * COLS);
+//!
+//! let mut fb = DmaFrameBuffer::<Icn2053, NROWS, COLS>::new();
+//! fb.set_pixel(Point::new(3, 20), Color::RED);
+//! assert_eq!(fb.as_bytes().len(), 2 * (NROWS * 16 * COLS + 4));
+//! ```
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+
+use crate::align::{Alignment, DefaultAlignment};
+use crate::ordering::{map_half_word_index, DefaultOrdering, Ordering};
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+
+/// Channels of one driver IC.
+const CHANNELS: usize = 16;
+/// Words of the vertical sync command at the end of a frame.
+const VSYNC_WORDS: usize = 4;
+
+/// Colour bits of both halves, as in [`crate::plain`].
+const COLOR_MASK: u16 = 0b0111_1110_0000_0000;
+/// Position of the red bit of the top and bottom half.
+const HALF_SHIFT: [u32; 2] = [9, 12];
+/// Latch bit, as in [`crate::plain`].
+const LAT_BIT: u16 = 1 << 5;
+
+/// Command set of an S-PWM driver IC, as `LAT` pulse widths in data clocks.
+pub trait SpwmDriver: Copy + core::fmt::Debug {
+    /// Stores the shifted channel of every IC.
+    const DATA_LATCH: usize = 1;
+    /// Shows the stored frame.
+    const VSYNC: usize = 3;
+    /// Unlocks the following configuration write.
+    const PRE_ACTIVE: usize = 14;
+    /// Writes configuration register `n`, indexed by `n`.
+    const WRITE_CONFIG: &'static [usize];
+}
+
+/// Chipone ICN2053.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Icn2053;
+
+impl SpwmDriver for Icn2053 {
+    const WRITE_CONFIG: &'static [usize] = &[4, 6, 8, 10, 2];
+}
+
+/// Fuman FM6353, which uses the command set of the [`Icn2053`].
+pub type Fm6353 = Icn2053;
+
+/// Macroblock MBI5153.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mbi5153;
+
+impl SpwmDriver for Mbi5153 {
+    const WRITE_CONFIG: &'static [usize] = &[4];
+}
+
+/// One scan line: a latch block of `COLS` words per driver channel.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Line<const COLS: usize> {
+    blocks: [[u16; COLS]; CHANNELS],
+}
+
+/// Sets `LAT` on the last `width` of `words`, in stream order.
+fn set_latch<O: Ordering>(words: &mut [u16], width: usize) {
+    let len = words.len();
+    for i in len - width..len {
+        words[map_half_word_index::<O>(i)] |= LAT_BIT;
+    }
+}
+
+/// Register write for an S-PWM driver chain of `COLS` columns: the
+/// pre-active command followed by the write itself.
+///
+/// Send the writes for all registers the panel needs once at start-up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub struct ConfigWrite<const COLS: usize, O: Ordering = DefaultOrdering> {
+    pre_active: [u16; COLS],
+    write: [u16; COLS],
+    _ordering: PhantomData<O>,
+}
+
+impl<const COLS: usize, O: Ordering> ConfigWrite<COLS, O> {
+    /// Write `value` to configuration register `reg` of every IC of the
+    /// chain.
+    ///
+    /// # Panics
+    /// If `D` has no register `reg`, or `COLS` is not a whole number of
+    /// ICs.
+    #[must_use]
+    pub fn new<D: SpwmDriver>(reg: usize, value: u16) -> Self {
+        assert!(COLS % CHANNELS == 0, "COLS must be a multiple of 16");
+        assert!(
+            reg < D::WRITE_CONFIG.len(),
+            "no configuration register {reg}"
+        );
+        let mut config = Self {
+            pre_active: [0; COLS],
+            write: [0; COLS],
+            _ordering: PhantomData,
+        };
+        for i in 0..COLS {
+            if value & (0x8000 >> (i % 16)) != 0 {
+                config.write[map_half_word_index::<O>(i)] = COLOR_MASK;
+            }
+        }
+        set_latch::<O>(&mut config.pre_active, D::PRE_ACTIVE);
+        set_latch::<O>(&mut config.write, D::WRITE_CONFIG[reg]);
+        config
+    }
+
+    /// Returns the command as bytes, in the order they are sent.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `repr(C)` arrays of `u16` and a zero-sized marker, so
+        // there is no padding.
+        unsafe {
+            core::slice::from_raw_parts(
+                core::ptr::from_ref(self).cast::<u8>(),
+                core::mem::size_of::<Self>(),
+            )
+        }
+    }
+}
+
+// SAFETY: the buffer is owned and not moved while the DMA is reading it.
+unsafe impl<const COLS: usize, O: Ordering> ReadBuffer for ConfigWrite<COLS, O> {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        let bytes = self.as_bytes();
+        (bytes.as_ptr(), bytes.len())
+    }
+}
+
+/// Frame of 16-bit grey values for a chain of S-PWM driver ICs, `COLS`
+/// columns wide with `NROWS` scan lines, aligned to `A`.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct DmaFrameBuffer<
+    D: SpwmDriver,
+    const NROWS: usize,
+    const COLS: usize,
+    O: Ordering = DefaultOrdering,
+    A: Alignment = DefaultAlignment,
+> {
+    _alignment: [A; 0],
+    lines: [Line<COLS>; NROWS],
+    vsync: [u16; VSYNC_WORDS],
+    _driver: PhantomData<(D, O)>,
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    const LAYOUT_OK: () = {
+        assert!(COLS % CHANNELS == 0, "COLS must be a multiple of 16");
+        assert!(D::DATA_LATCH <= COLS, "data latch longer than a block");
+        assert!(D::VSYNC <= VSYNC_WORDS, "vertical sync longer than 4 words");
+    };
+
+    /// Creates a new frame buffer, pre-formatted and ready for use.
+    #[must_use]
+    pub fn new() -> Self {
+        let () = Self::LAYOUT_OK;
+        let mut instance = Self {
+            _alignment: [],
+            lines: [Line {
+                blocks: [[0; COLS]; CHANNELS],
+            }; NROWS],
+            vsync: [0; VSYNC_WORDS],
+            _driver: PhantomData,
+        };
+        instance.format();
+        instance
+    }
+
+    /// Formats the frame buffer with the latch and vertical sync commands,
+    /// turning all pixels off.
+    pub fn format(&mut self) {
+        for line in &mut self.lines {
+            for block in &mut line.blocks {
+                block.fill(0);
+                set_latch::<O>(block, D::DATA_LATCH);
+            }
+        }
+        self.vsync.fill(0);
+        set_latch::<O>(&mut self.vsync, D::VSYNC);
+    }
+
+    /// Erase pixel colors while preserving the commands.
+    pub fn erase(&mut self) {
+        for line in &mut self.lines {
+            for word in line.blocks.as_flattened_mut() {
+                *word &= !COLOR_MASK;
+            }
+        }
+    }
+
+    /// Set a pixel in the framebuffer.
+    pub fn set_pixel(&mut self, p: Point, color: Color) {
+        if p.x < 0 || p.y < 0 {
+            return;
+        }
+        self.set_pixel_internal(p.x as usize, p.y as usize, color);
+    }
+
+    fn set_pixel_internal(&mut self, x: usize, y: usize, color: Color) {
+        if x >= COLS || y >= NROWS * 2 {
+            return;
+        }
+        let shift = HALF_SHIFT[y / NROWS];
+        // scale to 16 bits so that full scale stays full scale
+        let red = u16::from(color.r()) * 257;
+        let green = u16::from(color.g()) * 257;
+        let blue = u16::from(color.b()) * 257;
+
+        let block = &mut self.lines[y % NROWS].blocks[x % CHANNELS];
+        let start = (COLS / CHANNELS - 1 - x / CHANNELS) * 16;
+        for bit in 0..16 {
+            let mask = 0x8000 >> bit;
+            let bits = u16::from(blue & mask != 0) << 2
+                | u16::from(green & mask != 0) << 1
+                | u16::from(red & mask != 0);
+            let word = &mut block[map_half_word_index::<O>(start + bit)];
+            *word = (*word & !(0b111 << shift)) | (bits << shift);
+        }
+    }
+
+    /// Returns the frame as bytes, in the order they are sent.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let (ptr, len) = self.plane_ptr_len(0);
+        // SAFETY: `repr(C)` arrays of `u16` without padding, borrowed from
+        // `self`.
+        unsafe { core::slice::from_raw_parts(ptr, len) }
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment> Default
+    for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    core::fmt::Debug for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DmaFrameBuffer")
+            .field("size", &self.as_bytes().len())
+            .field("alignment", &A::BYTES)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment> defmt::Format
+    for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "DmaFrameBuffer<{}, {}>", NROWS, COLS);
+        defmt::write!(f, " size: {}", self.as_bytes().len());
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment> FrameBuffer
+    for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    fn get_word_size(&self) -> WordSize {
+        WordSize::Sixteen
+    }
+
+    fn plane_count(&self) -> usize {
+        1
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        assert!(
+            plane_idx == 0,
+            "plane_idx {plane_idx} out of range for 1 plane"
+        );
+        let ptr = (&raw const self.lines).cast::<u8>();
+        let len = core::mem::size_of_val(&self.lines) + core::mem::size_of_val(&self.vsync);
+        (ptr, len)
+    }
+
+    fn rows(&self) -> usize {
+        2 * NROWS
+    }
+
+    fn cols(&self) -> usize {
+        COLS
+    }
+
+    fn bits(&self) -> u8 {
+        16
+    }
+
+    fn frame_count(&self) -> usize {
+        1
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    FrameBufferOperations for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    #[inline]
+    fn erase(&mut self) {
+        DmaFrameBuffer::<D, NROWS, COLS, O, A>::erase(self);
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        DmaFrameBuffer::<D, NROWS, COLS, O, A>::set_pixel(self, p, color);
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    MutableFrameBuffer for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    OriginDimensions for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    fn size(&self) -> Size {
+        Size::new(COLS as u32, (NROWS * 2) as u32)
+    }
+}
+
+impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment> DrawTarget
+    for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.set_pixel(pixel.0, pixel.1);
+        }
+        Ok(())
+    }
+}
+
+// SAFETY: the buffer is owned and not moved while the DMA is reading it.
+unsafe impl<D: SpwmDriver, const NROWS: usize, const COLS: usize, O: Ordering, A: Alignment>
+    ReadBuffer for DmaFrameBuffer<D, NROWS, COLS, O, A>
+{
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        self.plane_ptr_len(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::ordering::{Esp32, Linear};
+
+    type TestFrameBuffer = DmaFrameBuffer<Icn2053, 16, 32, Linear>;
+
+    fn latched_words(words: &[u16]) -> std::vec::Vec<usize> {
+        (0..words.len())
+            .filter(|&i| words[i] & LAT_BIT != 0)
+            .collect()
+    }
+
+    #[test]
+    fn test_format_latch_commands() {
+        let fb = DmaFrameBuffer::<Mbi5153, 16, 32, Linear>::new();
+        for line in &fb.lines {
+            for block in &line.blocks {
+                assert_eq!(latched_words(block), [31]);
+            }
+        }
+        assert_eq!(latched_words(&fb.vsync), [1, 2, 3]);
+        assert_eq!(fb.as_bytes().len(), 2 * (16 * 16 * 32 + 4));
+        assert_eq!(fb.as_bytes().len(), unsafe { fb.read_buffer() }.1);
+    }
+
+    #[test]
+    fn test_set_pixel_grey_values() {
+        let mut fb = TestFrameBuffer::new();
+        // column 17 is channel 1 of the second IC, shifted first
+        fb.set_pixel(Point::new(17, 3), Color::new(0x80, 0, 0xff));
+        fb.set_pixel(Point::new(17, 19), Color::new(0, 0x01, 0));
+
+        let block = &fb.lines[3].blocks[1];
+        for (bit, &word) in block[..16].iter().enumerate() {
+            // 0x80 scales to 0x8080
+            assert_eq!(word & (1 << 9) != 0, bit == 0 || bit == 8, "bit {bit}");
+            assert_eq!(word & (1 << 10), 0);
+            assert_ne!(word & (1 << 11), 0);
+            // 0x01 scales to 0x0101
+            assert_eq!(word & (1 << 13) != 0, bit == 7 || bit == 15, "bit {bit}");
+        }
+        // the first IC is untouched
+        assert!(block[16..].iter().all(|w| w & COLOR_MASK == 0));
+        assert_eq!(latched_words(block), [31]);
+
+        fb.erase();
+        assert!(fb.lines[3].blocks[1].iter().all(|w| w & COLOR_MASK == 0));
+        assert_eq!(latched_words(&fb.lines[3].blocks[1]), [31]);
+    }
+
+    #[test]
+    fn test_set_pixel_out_of_bounds() {
+        let mut fb = TestFrameBuffer::new();
+        let before = fb.as_bytes().to_vec();
+        fb.set_pixel(Point::new(-1, 0), Color::WHITE);
+        fb.set_pixel(Point::new(32, 0), Color::WHITE);
+        fb.set_pixel(Point::new(0, 32), Color::WHITE);
+        assert_eq!(fb.as_bytes(), before);
+        assert_eq!(fb.size(), Size::new(32, 32));
+    }
+
+    #[test]
+    fn test_config_write() {
+        let config = ConfigWrite::<32, Linear>::new::<Icn2053>(1, 0x8001);
+        assert_eq!(
+            latched_words(&config.pre_active),
+            (18..32).collect::<std::vec::Vec<_>>()
+        );
+        assert_eq!(
+            latched_words(&config.write),
+            (26..32).collect::<std::vec::Vec<_>>()
+        );
+        // the value is repeated for every IC, MSB first
+        for (i, word) in config.write.iter().enumerate() {
+            assert_eq!(
+                word & COLOR_MASK != 0,
+                i % 16 == 0 || i % 16 == 15,
+                "word {i}"
+            );
+        }
+        assert_eq!(config.as_bytes().len(), 2 * 2 * 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "no configuration register 1")]
+    fn test_config_write_unknown_register() {
+        let _ = ConfigWrite::<32>::new::<Mbi5153>(1, 0);
+    }
+
+    #[test]
+    fn test_swapped_ordering() {
+        let mut linear = TestFrameBuffer::new();
+        let mut esp32 = DmaFrameBuffer::<Icn2053, 16, 32, Esp32>::new();
+        linear.set_pixel(Point::new(5, 7), Color::WHITE);
+        esp32.set_pixel(Point::new(5, 7), Color::WHITE);
+
+        let block = &linear.lines[7].blocks[5];
+        let swapped = &esp32.lines[7].blocks[5];
+        for i in 0..32 {
+            assert_eq!(swapped[map_half_word_index::<Esp32>(i)], block[i]);
+        }
+    }
+}
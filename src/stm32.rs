@@ -0,0 +1,135 @@
+//! DMA buffer adapter for the STM32 HAL crates.
+//!
+//! The DMA `Transfer` APIs of `stm32f4xx-hal` and `stm32h7xx-hal` accept any
+//! buffer implementing [`embedded_dma::ReadBuffer`] as long as it is
+//! `'static` and its `Word` matches the memory-side transfer size configured
+//! for the stream. The framebuffers in this crate present their DMA stream as
+//! bytes, which already fits 8-bit formats such as [`crate::latched`]: a
+//! `&'static mut latched::DmaFrameBuffer<..>` can be handed to the HAL as-is.
+//!
+//! 16-bit formats such as [`crate::plain`] are normally clocked out of a GPIO
+//! port by a timer-triggered DMA stream using half-word transfers. For those,
+//! wrap the `'static` framebuffer reference in a [`HalfWordBuffer`], which
+//! exposes the very same memory as `u16` words.
+//!
+//! Point the stream at the port's `ODR` register: every entry carries both
+//! set and cleared signal bits, and a half-word write to `BSRR` can only ever
+//! set bits.
+//!
+//! # Example
+//! ```rust,no_run
+//! use embedded_dma::ReadBuffer;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::stm32::HalfWordBuffer;
+//! use hub75_framebuffer::{compute_frame_count, compute_rows};
+//!
+//! const ROWS: usize = 32;
+//! const COLS: usize = 64;
+//! const BITS: u8 = 3;
+//! const NROWS: usize = compute_rows(ROWS);
+//! const FRAME_COUNT: usize = compute_frame_count(BITS);
+//!
+//! type FBType = DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+//!
+//! let fb: &'static mut FBType = Box::leak(Box::new(FBType::new()));
+//! let buffer = HalfWordBuffer::new(fb);
+//! // `buffer` now satisfies `ReadBuffer<Word = u16> + 'static`
+//! let (_ptr, _len) = unsafe { buffer.read_buffer() };
+//! ```
+
+use embedded_dma::ReadBuffer;
+
+use crate::{FrameBuffer, WordSize};
+
+/// Presents a 16-bit framebuffer to STM32 DMA streams as half-words.
+///
+/// The wrapped buffer is usually a `&'static mut` reference to a
+/// [`crate::plain::DmaFrameBuffer`] or a [`crate::tiling::TiledFrameBuffer`]
+/// built on top of one.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HalfWordBuffer<B>(B);
+
+impl<B: ReadBuffer<Word = u8> + FrameBuffer> HalfWordBuffer<B> {
+    /// Wrap a framebuffer so it can be used for half-word DMA transfers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framebuffer does not use 16-bit words.
+    #[must_use]
+    pub fn new(buffer: B) -> Self {
+        assert!(
+            buffer.get_word_size() == WordSize::Sixteen,
+            "HalfWordBuffer requires a framebuffer with 16-bit words"
+        );
+        Self(buffer)
+    }
+
+    /// Give back the wrapped framebuffer.
+    #[must_use]
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+}
+
+unsafe impl<B: ReadBuffer<Word = u8>> ReadBuffer for HalfWordBuffer<B> {
+    type Word = u16;
+
+    unsafe fn read_buffer(&self) -> (*const u16, usize) {
+        let (ptr, len) = self.0.read_buffer();
+        debug_assert!(ptr.align_offset(core::mem::align_of::<u16>()) == 0);
+        // 16-bit framebuffers are at least 2-byte aligned and hold whole entries
+        #[allow(clippy::cast_ptr_alignment)]
+        (ptr.cast::<u16>(), len / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::boxed::Box;
+
+    use super::*;
+    use crate::{compute_frame_count, compute_rows};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = compute_rows(ROWS);
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = compute_frame_count(BITS);
+
+    type PlainFB = crate::plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+    type LatchedFB = crate::latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+
+    fn assert_static_read_buffer<B: ReadBuffer<Word = u16> + 'static>(_: &B) {}
+
+    #[test]
+    fn test_half_word_buffer_covers_same_memory() {
+        let fb: &'static mut PlainFB = Box::leak(Box::new(PlainFB::new()));
+        let (byte_ptr, byte_len) = unsafe { fb.read_buffer() };
+
+        let buffer = HalfWordBuffer::new(fb);
+        assert_static_read_buffer(&buffer);
+
+        let (ptr, len) = unsafe { buffer.read_buffer() };
+        assert_eq!(ptr.cast::<u8>(), byte_ptr);
+        assert_eq!(len * 2, byte_len);
+        assert_eq!(len, FRAME_COUNT * NROWS * COLS);
+    }
+
+    #[test]
+    fn test_half_word_buffer_into_inner() {
+        let fb: &'static mut PlainFB = Box::leak(Box::new(PlainFB::new()));
+        let ptr = core::ptr::from_ref::<PlainFB>(fb);
+        let buffer = HalfWordBuffer::new(fb);
+        assert_eq!(core::ptr::from_ref::<PlainFB>(buffer.into_inner()), ptr);
+    }
+
+    #[test]
+    #[should_panic(expected = "16-bit words")]
+    fn test_half_word_buffer_rejects_8_bit_framebuffer() {
+        let fb: &'static mut LatchedFB = Box::leak(Box::new(LatchedFB::new()));
+        let _ = HalfWordBuffer::new(fb);
+    }
+}
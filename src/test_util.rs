@@ -0,0 +1,402 @@
+//! Assertions for tests that compare a framebuffer with a [`MockDisplay`].
+//!
+//! [`assert_draws_like_mock`] draws the same [`Drawable`] onto a framebuffer
+//! and onto a [`MockDisplay`], decodes the framebuffer's DMA stream with
+//! [`Decoded`] and checks that every pixel matches the reference, rounded to
+//! the framebuffer's colour depth. Because only the stream is looked at,
+//! the same harness works for this crate's formats and for downstream
+//! drivers that wrap them.
+//!
+//! A [`MockDisplay`] is 64 by 64 pixels, so only that corner of larger
+//! panels is compared.
+//!
+//! # Conformance
+//! The `assert_*` functions below that take a framebuffer check the
+//! behaviour every [`MutableFrameBuffer`] must have, independent of its
+//! format: out-of-bounds drawing is ignored, `erase()` keeps the control
+//! bits, the DMA buffer is exactly the framebuffer's plane and colours
+//! follow the BCM thresholds. Run them against new formats and
+//! configurations, in this crate or downstream:
+//!
+//! ```rust
+//! use hub75_framebuffer::decode::Decoded;
+//! use hub75_framebuffer::latched::{DefaultPins, DmaFrameBuffer};
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::test_util::*;
+//!
+//! type FB = DmaFrameBuffer<32, 64, 16, 3, 7>;
+//!
+//! assert_ignores_out_of_bounds(&mut FB::new());
+//! assert_erase_preserves_control_bits(&mut FB::new());
+//! assert_read_buffer_is_plane(&FB::new());
+//! assert_bcm_thresholds(&mut FB::new(), |fb| {
+//!     Decoded::<32, 64, 16, 7>::latched::<DefaultOrdering, DefaultPins>(fb.as_bytes())
+//! });
+//! ```
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::mono_font::{ascii::FONT_6X10, MonoTextStyle};
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::text::{Baseline, Text};
+//! use hub75_framebuffer::decode::Decoded;
+//! use hub75_framebuffer::ordering::DefaultOrdering;
+//! use hub75_framebuffer::plain::{DefaultPins, DmaFrameBuffer};
+//! use hub75_framebuffer::test_util::assert_draws_like_mock;
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let style = MonoTextStyle::new(&FONT_6X10, Color::WHITE);
+//! let text = Text::with_baseline("Hi", Point::new(50, 20), style, Baseline::Top);
+//!
+//! assert_draws_like_mock(&mut fb, &text, |fb| {
+//!     Decoded::<32, 64, 16, 7>::plain::<DefaultOrdering, DefaultPins>(fb.as_words())
+//! });
+//! ```
+
+use crate::decode::{DecodeError, Decoded};
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer};
+use embedded_dma::ReadBuffer;
+use embedded_graphics::mock_display::MockDisplay;
+use embedded_graphics::prelude::{
+    Dimensions, DrawTarget, Drawable, Point, PointsIter, RgbColor, Size,
+};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+/// Draw `drawable` onto `fb` and onto a [`MockDisplay`], then assert that
+/// the stream returned by `decode` shows the same pixels.
+///
+/// # Panics
+/// Panics if drawing or decoding fails, or if a pixel differs.
+pub fn assert_draws_like_mock<
+    FB,
+    D,
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const FRAME_COUNT: usize,
+>(
+    fb: &mut FB,
+    drawable: &D,
+    decode: impl FnOnce(&FB) -> Result<Decoded<ROWS, COLS, NROWS, FRAME_COUNT>, DecodeError>,
+) where
+    FB: DrawTarget<Color = Color>,
+    FB::Error: core::fmt::Debug,
+    D: Drawable<Color = Color>,
+{
+    let mut reference: MockDisplay<Color> = MockDisplay::new();
+    reference.set_allow_overdraw(true);
+    reference.set_allow_out_of_bounds_drawing(true);
+    drawable.draw(&mut reference).unwrap();
+    drawable.draw(fb).unwrap();
+
+    match decode(fb) {
+        Ok(decoded) => assert_matches_mock(&decoded, &reference),
+        Err(err) => panic!("framebuffer stream does not decode: {err}"),
+    }
+}
+
+/// Assert that `decoded` shows the pixels drawn onto `reference`, rounded
+/// to `FRAME_COUNT` levels; pixels not drawn onto `reference` must be
+/// black.
+///
+/// # Panics
+/// Panics at the first pixel that differs.
+pub fn assert_matches_mock<
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const FRAME_COUNT: usize,
+>(
+    decoded: &Decoded<ROWS, COLS, NROWS, FRAME_COUNT>,
+    reference: &MockDisplay<Color>,
+) {
+    for point in reference.bounding_box().points() {
+        let (x, y) = (point.x as usize, point.y as usize);
+        if x >= COLS || y >= ROWS {
+            continue;
+        }
+        let drawn = reference.get_pixel(point).unwrap_or(Color::BLACK);
+        let expected = Color::new(
+            quantize::<FRAME_COUNT>(drawn.r()),
+            quantize::<FRAME_COUNT>(drawn.g()),
+            quantize::<FRAME_COUNT>(drawn.b()),
+        );
+        assert_eq!(
+            decoded.pixel(x, y),
+            expected,
+            "pixel ({x}, {y}) drawn as {drawn:?}"
+        );
+    }
+}
+
+/// Assert that drawing outside the panel leaves `fb` unchanged, and that
+/// shapes crossing the edges are clipped.
+///
+/// # Panics
+/// Panics if an out-of-bounds pixel changes the buffer or drawing panics.
+pub fn assert_ignores_out_of_bounds<FB>(fb: &mut FB)
+where
+    FB: MutableFrameBuffer + FrameBufferOperations + Clone,
+{
+    let size = fb.bounding_box().size;
+    let end = Point::zero() + size;
+    let outside = [
+        Point::new(-1, 0),
+        Point::new(0, -1),
+        Point::new(end.x, 0),
+        Point::new(0, end.y),
+        end,
+        Point::new(i32::MIN, i32::MIN),
+        Point::new(i32::MAX, i32::MAX),
+    ];
+
+    let before = fb.clone();
+    for point in outside {
+        FrameBufferOperations::set_pixel(fb, point, Color::WHITE);
+        fb.draw_iter([Pixel(point, Color::WHITE)]).unwrap();
+        assert!(
+            same_bytes(fb, &before),
+            "drawing at {point:?} changed the buffer"
+        );
+    }
+
+    // clipped, so this only must not panic
+    let area = Rectangle::new(Point::new(-4, -4), size + Size::new(8, 8));
+    fb.fill_solid(&area, Color::WHITE).unwrap();
+    assert!(!same_bytes(fb, &before), "clipped fill drew nothing");
+}
+
+/// Assert that `erase()` returns a drawn-on buffer to the bytes of a
+/// freshly formatted one. `fb` must be freshly formatted, e.g. from
+/// `new()`.
+///
+/// # Panics
+/// Panics if drawing does not change the buffer or `erase()` does not
+/// restore it.
+pub fn assert_erase_preserves_control_bits<FB>(fb: &mut FB)
+where
+    FB: MutableFrameBuffer + FrameBufferOperations + Clone,
+{
+    let formatted = fb.clone();
+    let area = fb.bounding_box();
+    fb.fill_solid(&area, Color::WHITE).unwrap();
+    assert!(!same_bytes(fb, &formatted), "fill drew nothing");
+
+    fb.erase();
+    assert!(
+        same_bytes(fb, &formatted),
+        "erase() changed bits other than colours"
+    );
+}
+
+/// Assert that the DMA buffer of `fb` is exactly its single plane and
+/// holds whole words of its word size.
+///
+/// # Panics
+/// Panics if `fb` has more than one plane or the buffers differ.
+pub fn assert_read_buffer_is_plane<FB>(fb: &FB)
+where
+    FB: FrameBuffer + ReadBuffer,
+{
+    assert_eq!(fb.plane_count(), 1, "DMA buffers cover a single plane");
+    // SAFETY: the buffer stays borrowed through `fb` and is not accessed.
+    let (ptr, words) = unsafe { fb.read_buffer() };
+    let (plane_ptr, plane_len) = fb.plane_ptr_len(0);
+    assert_eq!(ptr.cast::<u8>(), plane_ptr, "DMA buffer starts elsewhere");
+    assert_eq!(
+        words * core::mem::size_of::<FB::Word>(),
+        plane_len,
+        "DMA buffer length differs from the plane"
+    );
+    let word_bytes = match fb.get_word_size() {
+        crate::WordSize::Eight => 1,
+        crate::WordSize::Sixteen => 2,
+        crate::WordSize::ThirtyTwo => 4,
+    };
+    assert_eq!(plane_len % word_bytes, 0, "DMA buffer ends mid-word");
+}
+
+/// Assert that every channel value lights its pixel for the number of BCM
+/// frames given by its threshold, in both halves of the panel, as shown by
+/// the stream returned by `decode`.
+///
+/// # Panics
+/// Panics if decoding fails or a pixel is lit for the wrong number of
+/// frames.
+pub fn assert_bcm_thresholds<
+    FB,
+    const ROWS: usize,
+    const COLS: usize,
+    const NROWS: usize,
+    const FRAME_COUNT: usize,
+>(
+    fb: &mut FB,
+    decode: impl Fn(&FB) -> Result<Decoded<ROWS, COLS, NROWS, FRAME_COUNT>, DecodeError>,
+) where
+    FB: MutableFrameBuffer + FrameBufferOperations,
+{
+    let corners = [
+        Point::zero(),
+        Point::zero() + fb.bounding_box().size - Point::new(1, 1),
+    ];
+    for frames in 0..=FRAME_COUNT {
+        // lowest value lit for `frames` frames, and the value just below it
+        let value = (frames * 256).div_ceil(FRAME_COUNT + 1) as u8;
+        let below = value.saturating_sub(1);
+        let expected_below = if frames == 0 { 0 } else { frames - 1 };
+
+        fb.erase();
+        for point in corners {
+            FrameBufferOperations::set_pixel(fb, point, Color::new(value, below, 255));
+        }
+        let decoded = match decode(fb) {
+            Ok(decoded) => decoded,
+            Err(err) => panic!("framebuffer stream does not decode: {err}"),
+        };
+        for point in corners {
+            let (x, y) = (point.x as usize, point.y as usize);
+            let mut lit = [0; 3];
+            for frame in 0..FRAME_COUNT {
+                let (red, green, blue) = decoded.lit(frame, x, y);
+                for (count, on) in lit.iter_mut().zip([red, green, blue]) {
+                    *count += usize::from(on);
+                }
+            }
+            assert_eq!(
+                lit,
+                [frames, expected_below, FRAME_COUNT],
+                "channel values ({value}, {below}, 255) at ({x}, {y})"
+            );
+        }
+    }
+}
+
+fn same_bytes<FB: FrameBuffer>(a: &FB, b: &FB) -> bool {
+    (0..a.plane_count()).all(|plane_idx| plane(a, plane_idx) == plane(b, plane_idx))
+}
+
+fn plane<FB: FrameBuffer>(fb: &FB, plane_idx: usize) -> &[u8] {
+    let (ptr, len) = fb.plane_ptr_len(plane_idx);
+    // SAFETY: the plane stays borrowed through `fb` while we read it.
+    unsafe { core::slice::from_raw_parts(ptr, len) }
+}
+
+/// A channel value as [`Decoded::pixel`] reports it after BCM with
+/// `FRAME_COUNT` frames.
+const fn quantize<const FRAME_COUNT: usize>(value: u8) -> u8 {
+    let frames = value as usize * (FRAME_COUNT + 1) / 256;
+    (frames * 256 / (FRAME_COUNT + 1)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latched;
+    use crate::ordering::{Esp32, Linear};
+    use crate::plain;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+    use embedded_graphics::mono_font::MonoTextStyle;
+    use embedded_graphics::primitives::{Circle, Primitive, PrimitiveStyle};
+    use embedded_graphics::text::{Baseline, Text};
+
+    const ROWS: usize = 32;
+    const COLS: usize = 64;
+    const NROWS: usize = 16;
+    const BITS: u8 = 3;
+    const FRAME_COUNT: usize = 7;
+
+    type Image = Decoded<ROWS, COLS, NROWS, FRAME_COUNT>;
+
+    #[test]
+    fn test_glyph_corners() {
+        let style = MonoTextStyle::new(&FONT_6X10, Color::WHITE);
+        for origin in [Point::zero(), Point::new(58, 22)] {
+            let glyph = Text::with_baseline("A", origin, style, Baseline::Top);
+
+            let mut fb =
+                plain::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>::new();
+            assert_draws_like_mock(&mut fb, &glyph, |fb| {
+                Image::plain::<Esp32, plain::DefaultPins>(fb.as_words())
+            });
+
+            let mut fb =
+                latched::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+            assert_draws_like_mock(&mut fb, &glyph, |fb| {
+                Image::latched::<Linear, latched::DefaultPins>(fb.as_bytes())
+            });
+        }
+    }
+
+    #[test]
+    fn test_rounds_to_colour_depth() {
+        let circle = Circle::new(Point::new(10, 8), 12)
+            .into_styled(PrimitiveStyle::with_fill(Color::new(200, 100, 31)));
+        let mut fb = plain::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        assert_draws_like_mock(&mut fb, &circle, |fb| {
+            Image::plain::<Linear, plain::DefaultPins>(fb.as_words())
+        });
+        assert_eq!(quantize::<FRAME_COUNT>(255), 224);
+        assert_eq!(quantize::<255>(77), 77);
+    }
+
+    #[test]
+    fn test_conformance() {
+        type Plain = plain::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Esp32>;
+        type Latched = latched::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>;
+        type Plain8 = crate::plain8::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+        type Latched16 = crate::latched16::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+        type Plain32 = crate::plain32::DmaFrameBuffer<ROWS, COLS, NROWS, BITS, FRAME_COUNT>;
+        type BitplanePlain = crate::bitplane::plain::DmaFrameBuffer<NROWS, COLS, 8>;
+        type BitplaneLatched = crate::bitplane::latched::DmaFrameBuffer<NROWS, COLS, 8>;
+
+        assert_ignores_out_of_bounds(&mut Plain::new());
+        assert_erase_preserves_control_bits(&mut Plain::new());
+        assert_read_buffer_is_plane(&Plain::new());
+        assert_bcm_thresholds(&mut Plain::new(), |fb| {
+            Image::plain::<Esp32, plain::DefaultPins>(fb.as_words())
+        });
+
+        assert_ignores_out_of_bounds(&mut Latched::new());
+        assert_erase_preserves_control_bits(&mut Latched::new());
+        assert_read_buffer_is_plane(&Latched::new());
+        assert_bcm_thresholds(&mut Latched::new(), |fb| {
+            Image::latched::<Linear, latched::DefaultPins>(fb.as_bytes())
+        });
+
+        assert_ignores_out_of_bounds(&mut Plain8::new());
+        assert_erase_preserves_control_bits(&mut Plain8::new());
+        assert_read_buffer_is_plane(&Plain8::new());
+
+        assert_ignores_out_of_bounds(&mut Latched16::new());
+        assert_erase_preserves_control_bits(&mut Latched16::new());
+        assert_read_buffer_is_plane(&Latched16::new());
+
+        assert_ignores_out_of_bounds(&mut Plain32::new());
+        assert_erase_preserves_control_bits(&mut Plain32::new());
+        assert_read_buffer_is_plane(&Plain32::new());
+
+        assert_ignores_out_of_bounds(&mut BitplanePlain::new());
+        assert_erase_preserves_control_bits(&mut BitplanePlain::new());
+        assert_ignores_out_of_bounds(&mut BitplaneLatched::new());
+        assert_erase_preserves_control_bits(&mut BitplaneLatched::new());
+    }
+
+    #[test]
+    fn test_bcm_thresholds_at_full_depth() {
+        let mut fb = plain::DmaFrameBuffer::<16, 32, 8, 8, 255, Linear>::new();
+        assert_bcm_thresholds(&mut fb, |fb| {
+            Decoded::<16, 32, 8, 255>::plain::<Linear, plain::DefaultPins>(fb.as_words())
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "pixel (3, 4)")]
+    fn test_detects_difference() {
+        let mut fb = plain::DmaFrameBuffer::<ROWS, COLS, NROWS, BITS, FRAME_COUNT, Linear>::new();
+        fb.set_pixel(Point::new(3, 4), Color::RED);
+        let decoded = Image::plain::<Linear, plain::DefaultPins>(fb.as_words()).unwrap();
+        assert_matches_mock(&decoded, &MockDisplay::new());
+    }
+}
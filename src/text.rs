@@ -0,0 +1,495 @@
+//! Text layout helpers.
+//!
+//! Clocks and signs keep repeating the same layout arithmetic: centre a
+//! string on the panel, right-align a value, break a message into lines
+//! that fit. [`TextExt`] adds that to every `DrawTarget` for [`Color`],
+//! including the framebuffers, tiled framebuffers and
+//! [`Hub75Display`](crate::display::Hub75Display), for text in
+//! `embedded-graphics` monospaced fonts.
+//!
+//! All helpers lay text out inside an area, usually the bounding box of the
+//! target, and honour `\n` line breaks. [`WrappedLines`] does the word
+//! wrapping on its own for custom layouts, and [`Ticker`] scrolls messages
+//! across an area.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::mono_font::{ascii::FONT_5X7, MonoTextStyle};
+//! use embedded_graphics::prelude::*;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::text::TextExt;
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let style = MonoTextStyle::new(&FONT_5X7, Color::WHITE);
+//! let area = fb.bounding_box();
+//!
+//! fb.draw_text_centered("12:34", area, style).unwrap();
+//! let rest = fb
+//!     .draw_text_wrapped("the quick brown fox jumps over the lazy dog", area, style)
+//!     .unwrap();
+//! assert_eq!(rest, "");
+//! ```
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::RgbColor;
+use embedded_graphics::prelude::{DrawTarget, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use embedded_graphics::Drawable;
+
+use crate::Color;
+
+/// Number of characters of `style` that fit in `width` pixels.
+#[must_use]
+pub fn chars_per_line(style: &MonoTextStyle<'_, Color>, width: u32) -> usize {
+    let advance = style.font.character_size.width + style.font.character_spacing;
+    if advance == 0 {
+        return 0;
+    }
+    // the last character needs no spacing after it
+    ((width + style.font.character_spacing) / advance) as usize
+}
+
+/// Breaks text into lines of at most `max_chars` characters.
+///
+/// Lines break at `\n` and otherwise at the last space that fits; words
+/// longer than a line are split. Spaces at the breaks are dropped. The
+/// lines are slices of the original text.
+///
+/// # Example
+/// ```rust
+/// use hub75_framebuffer::text::WrappedLines;
+///
+/// let lines: Vec<&str> = WrappedLines::new("wrap this text\nnow", 9).collect();
+/// assert_eq!(lines, ["wrap this", "text", "now"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WrappedLines<'a> {
+    rest: &'a str,
+    max_chars: usize,
+}
+
+impl<'a> WrappedLines<'a> {
+    /// Wrap `text` at `max_chars` characters per line; a `max_chars` of 0
+    /// is treated as 1.
+    #[must_use]
+    pub const fn new(text: &'a str, max_chars: usize) -> Self {
+        Self {
+            rest: text,
+            max_chars: if max_chars == 0 { 1 } else { max_chars },
+        }
+    }
+
+    /// The text not returned as lines yet.
+    #[must_use]
+    pub const fn remainder(&self) -> &'a str {
+        self.rest
+    }
+}
+
+impl<'a> Iterator for WrappedLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let text = self.rest;
+        // byte offset just past `max_chars` characters, if there are more
+        let limit = text.char_indices().nth(self.max_chars).map(|(i, _)| i);
+        let newline = text.find('\n');
+        let (line, rest) = match (newline, limit) {
+            (Some(n), limit) if limit.is_none_or(|limit| n <= limit) => {
+                (&text[..n], &text[n + 1..])
+            }
+            (_, None) => (text, ""),
+            (_, Some(limit)) => {
+                let fits = &text[..limit];
+                let at_space = text[limit..].starts_with(' ');
+                match fits.rfind(' ') {
+                    _ if at_space => (fits, &text[limit..]),
+                    Some(space) if space > 0 => (&fits[..space], &text[space..]),
+                    _ => (fits, &text[limit..]),
+                }
+            }
+        };
+        self.rest = rest.trim_start_matches(' ');
+        Some(line.trim_end_matches(' '))
+    }
+}
+
+/// Text layout for any `DrawTarget` of [`Color`].
+pub trait TextExt: DrawTarget<Color = Color> + Sized {
+    /// Draw `text` centred horizontally and vertically in `area`.
+    ///
+    /// # Errors
+    /// Returns the first error of the target.
+    fn draw_text_centered(
+        &mut self,
+        text: &str,
+        area: Rectangle,
+        style: MonoTextStyle<'_, Color>,
+    ) -> Result<(), Self::Error> {
+        let lines = u32::try_from(text.split('\n').count()).unwrap_or(u32::MAX);
+        let height = style.font.character_size.height.saturating_mul(lines);
+        let spare = i64::from(area.size.height) - i64::from(height);
+        let top = area.top_left.y + i32::try_from(spare / 2).unwrap_or(0);
+        let position = Point::new(area.center().x, top);
+        draw_aligned(self, text, position, Alignment::Center, style)
+    }
+
+    /// Draw `text` at the top of `area`, aligned to its right edge.
+    ///
+    /// # Errors
+    /// Returns the first error of the target.
+    fn draw_text_right_aligned(
+        &mut self,
+        text: &str,
+        area: Rectangle,
+        style: MonoTextStyle<'_, Color>,
+    ) -> Result<(), Self::Error> {
+        let right = area.bottom_right().unwrap_or(area.top_left).x;
+        let position = Point::new(right, area.top_left.y);
+        draw_aligned(self, text, position, Alignment::Right, style)
+    }
+
+    /// Draw `text` word-wrapped to the width of `area`, from its top left,
+    /// as far as whole lines fit.
+    ///
+    /// Returns the text that did not fit, e.g. to show on the next page.
+    ///
+    /// # Errors
+    /// Returns the first error of the target.
+    fn draw_text_wrapped<'a>(
+        &mut self,
+        text: &'a str,
+        area: Rectangle,
+        style: MonoTextStyle<'_, Color>,
+    ) -> Result<&'a str, Self::Error> {
+        let line_height = style.font.character_size.height;
+        let max_lines = area.size.height.checked_div(line_height).unwrap_or(0);
+        let mut lines = WrappedLines::new(text, chars_per_line(&style, area.size.width));
+        let mut position = area.top_left;
+        for _ in 0..max_lines {
+            let Some(line) = lines.next() else {
+                break;
+            };
+            draw_aligned(self, line, position, Alignment::Left, style)?;
+            position.y += i32::try_from(line_height).unwrap_or(i32::MAX);
+        }
+        Ok(lines.remainder())
+    }
+}
+
+impl<D: DrawTarget<Color = Color>> TextExt for D {}
+
+/// Draw `text` with its first line's top at `position`, aligned there.
+fn draw_aligned<D: DrawTarget<Color = Color>>(
+    target: &mut D,
+    text: &str,
+    position: Point,
+    alignment: Alignment,
+    style: MonoTextStyle<'_, Color>,
+) -> Result<(), D::Error> {
+    let text_style = TextStyleBuilder::new()
+        .alignment(alignment)
+        .baseline(Baseline::Top)
+        .build();
+    Text::with_text_style(text, position, style, text_style).draw(target)?;
+    Ok(())
+}
+
+/// A scrolling text ticker.
+///
+/// Messages enter the area from the right and scroll left until they have
+/// left it completely, then the next message follows. Pass
+/// `core::iter::repeat(text)` or `messages.iter().copied().cycle()` to
+/// scroll forever.
+///
+/// Call [`Ticker::tick`] at a steady rate, e.g. from a
+/// [`FramePacer`](crate::vsync::FramePacer), and draw the ticker after
+/// each tick. Drawing fills the area with the background and renders only
+/// the characters in view, clipped to the area, so long messages cost no
+/// more than short ones.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::mono_font::{ascii::FONT_5X7, MonoTextStyle};
+/// use embedded_graphics::prelude::*;
+/// use embedded_graphics::primitives::Rectangle;
+/// use hub75_framebuffer::plain::DmaFrameBuffer;
+/// use hub75_framebuffer::text::Ticker;
+/// use hub75_framebuffer::Color;
+///
+/// let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+/// let area = Rectangle::new(Point::new(0, 24), Size::new(64, 8));
+/// let style = MonoTextStyle::new(&FONT_5X7, Color::YELLOW);
+/// let mut ticker = Ticker::new(area, style, ["Next train: 5 min", "Platform 2"]);
+///
+/// while ticker.tick() {
+///     ticker.draw(&mut fb).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ticker<'a, I> {
+    area: Rectangle,
+    style: MonoTextStyle<'a, Color>,
+    background: Color,
+    speed: u32,
+    messages: I,
+    current: Option<&'a str>,
+    /// Pixels the current message has moved in from the right edge.
+    offset: u32,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Ticker<'a, I> {
+    /// Scroll `messages` through `area` in `style`, one pixel per tick on a
+    /// black background.
+    pub fn new<M>(area: Rectangle, style: MonoTextStyle<'a, Color>, messages: M) -> Self
+    where
+        M: IntoIterator<IntoIter = I>,
+    {
+        let mut messages = messages.into_iter();
+        Self {
+            area,
+            style,
+            background: Color::BLACK,
+            speed: 1,
+            current: messages.next(),
+            messages,
+            offset: 0,
+        }
+    }
+
+    /// Move `pixels` per tick.
+    #[must_use]
+    pub const fn with_speed(mut self, pixels: u32) -> Self {
+        self.speed = pixels;
+        self
+    }
+
+    /// Fill the area with `background` behind the text.
+    #[must_use]
+    pub const fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// The message scrolling through, `None` once all have passed.
+    #[must_use]
+    pub const fn current(&self) -> Option<&'a str> {
+        self.current
+    }
+
+    /// Advance the ticker. Returns `false` once all messages have passed.
+    pub fn tick(&mut self) -> bool {
+        let Some(message) = self.current else {
+            return false;
+        };
+        self.offset = self.offset.saturating_add(self.speed);
+        let width = text_width(&self.style, message);
+        if self.offset >= self.area.size.width.saturating_add(width) {
+            self.current = self.messages.next();
+            self.offset = 0;
+        }
+        self.current.is_some()
+    }
+}
+
+/// Width of `text` in `style`, without trailing spacing.
+fn text_width(style: &MonoTextStyle<'_, Color>, text: &str) -> u32 {
+    let chars = u32::try_from(text.chars().count()).unwrap_or(u32::MAX);
+    let advance = style.font.character_size.width + style.font.character_spacing;
+    (chars.saturating_mul(advance)).saturating_sub(style.font.character_spacing)
+}
+
+impl<I> Drawable for Ticker<'_, I> {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        target.fill_solid(&self.area, self.background)?;
+        let Some(message) = self.current else {
+            return Ok(());
+        };
+        let font = self.style.font;
+        let advance = font.character_size.width + font.character_spacing;
+        if advance == 0 {
+            return Ok(());
+        }
+        let width = self.area.size.width;
+        // characters that scrolled out to the left, and those in view
+        let hidden = self.offset.saturating_sub(width) / advance;
+        let in_view = self.offset.min(width).div_ceil(advance) + 1;
+        let mut chars = message.char_indices().map(|(i, _)| i).skip(hidden as usize);
+        let Some(start) = chars.next() else {
+            return Ok(());
+        };
+        let end = chars.nth(in_view as usize - 1).unwrap_or(message.len());
+
+        // the message starts at `width - offset`, its first visible
+        // character `hidden` advances later
+        let x = i64::from(width) - i64::from(self.offset) + i64::from(hidden * advance);
+        let spare = self
+            .area
+            .size
+            .height
+            .saturating_sub(font.character_size.height);
+        let position = self.area.top_left
+            + Point::new(i32::try_from(x).unwrap_or(i32::MAX), 0)
+            + Size::new(0, spare / 2);
+        let text_style = TextStyleBuilder::new().baseline(Baseline::Top).build();
+        Text::with_text_style(&message[start..end], position, self.style, text_style)
+            .draw(&mut target.clipped(&self.area))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::mono_font::ascii::FONT_4X6;
+
+    fn lines(text: &str, max_chars: usize) -> Vec<&str> {
+        WrappedLines::new(text, max_chars).collect()
+    }
+
+    #[test]
+    fn test_wrapped_lines() {
+        assert_eq!(lines("", 5), Vec::<&str>::new());
+        assert_eq!(lines("short", 5), ["short"]);
+        assert_eq!(lines("two words", 5), ["two", "words"]);
+        assert_eq!(lines("two words", 4), ["two", "word", "s"]);
+        assert_eq!(lines("a  b   c", 3), ["a", "b", "c"]);
+        assert_eq!(lines("one\n\ntwo", 10), ["one", "", "two"]);
+        assert_eq!(lines("abcdefgh", 3), ["abc", "def", "gh"]);
+        assert_eq!(lines("fits exactly", 4), ["fits", "exac", "tly"]);
+        assert_eq!(lines("line\n", 4), ["line"]);
+        // characters, not bytes
+        assert_eq!(lines("größer als", 6), ["größer", "als"]);
+        assert_eq!(lines("abc", 0), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_chars_per_line() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        assert_eq!(chars_per_line(&style, 64), 16);
+        assert_eq!(chars_per_line(&style, 3), 0);
+    }
+
+    #[test]
+    fn test_centered() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let mut display = MockDisplay::new();
+        let area = Rectangle::new(Point::new(10, 10), Size::new(40, 30));
+        display.draw_text_centered("HI\nHI", area, style).unwrap();
+
+        // two 6 pixel lines, 9 pixels from the top and bottom
+        let drawn = display.affected_area();
+        assert_eq!(drawn.top_left.y, 19);
+        let left = drawn.top_left.x - area.top_left.x;
+        let right = area.bottom_right().unwrap().x - drawn.bottom_right().unwrap().x;
+        assert!(left.abs_diff(right) <= 1, "{drawn:?}");
+    }
+
+    #[test]
+    fn test_right_aligned() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let mut display = MockDisplay::new();
+        let area = Rectangle::new(Point::new(0, 2), Size::new(32, 16));
+        display.draw_text_right_aligned("42", area, style).unwrap();
+
+        // "2" ends with a blank column in FONT_4X6
+        let drawn = display.affected_area();
+        assert_eq!(drawn.top_left.y, 2);
+        assert!(drawn.bottom_right().unwrap().x <= 31);
+        assert!(drawn.bottom_right().unwrap().x >= 29);
+    }
+
+    #[test]
+    fn test_wrapped() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let mut display = MockDisplay::new();
+        // 4 characters wide, 2 lines high
+        let area = Rectangle::new(Point::zero(), Size::new(16, 12));
+        let rest = display
+            .draw_text_wrapped("AB CD EF GH", area, style)
+            .unwrap();
+        assert_eq!(rest, "EF GH");
+        assert!(area.contains(display.affected_area().bottom_right().unwrap()));
+
+        let mut display = MockDisplay::new();
+        let rest = display.draw_text_wrapped("", area, style).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(display.affected_area(), Rectangle::zero());
+    }
+
+    /// Expected ticker output: the whole message drawn at `x` and clipped.
+    fn ticker_expected(area: Rectangle, text: &str, x: i32) -> MockDisplay<Color> {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        display.fill_solid(&area, Color::BLACK).unwrap();
+        let text_style = TextStyleBuilder::new().baseline(Baseline::Top).build();
+        Text::with_text_style(text, area.top_left + Point::new(x, 0), style, text_style)
+            .draw(&mut display.clipped(&area))
+            .unwrap();
+        display
+    }
+
+    #[test]
+    fn test_ticker_scrolls() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let area = Rectangle::new(Point::new(2, 1), Size::new(20, 6));
+        let message = "scrolling past";
+        let mut ticker = Ticker::new(area, style, [message]).with_speed(3);
+
+        for step in 0..20 {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+            ticker.draw(&mut display).unwrap();
+            let expected = ticker_expected(area, message, 20 - step * 3);
+            display.assert_eq(&expected);
+            assert!(ticker.tick());
+        }
+    }
+
+    #[test]
+    fn test_ticker_messages() {
+        let style = MonoTextStyle::new(&FONT_4X6, Color::WHITE);
+        let area = Rectangle::new(Point::zero(), Size::new(8, 6));
+        let mut ticker = Ticker::new(area, style, ["A", "BC"]);
+
+        // "A" is 4 pixels wide and needs 8 + 4 ticks to pass
+        let mut ticks = 0;
+        while ticker.current() == Some("A") {
+            assert!(ticker.tick());
+            ticks += 1;
+        }
+        assert_eq!(ticks, 12);
+        assert_eq!(ticker.current(), Some("BC"));
+
+        // the tick that lets "BC" leave returns false
+        let mut ticks = 1;
+        while ticker.tick() {
+            ticks += 1;
+        }
+        assert_eq!(ticks, 16);
+        assert_eq!(ticker.current(), None);
+        assert!(!ticker.tick());
+
+        let mut display = MockDisplay::new();
+        ticker.draw(&mut display).unwrap();
+        display.assert_eq(&ticker_expected(area, "", 0));
+    }
+}
@@ -3,12 +3,16 @@
 //! Currently supported layouts:
 //! - [`ChainTopRightDown`]
 //!
+//! Panels with an internal wiring quirk are handled by wrapping the layout
+//! in a within-panel remap:
+//! - [`ColumnInterleaved`]
+//!
 //! To write to those panels the [`TiledFrameBuffer`] can be used.
 //! A usage example can be found at that structs documentation.
 
 use core::{convert::Infallible, marker::PhantomData};
 
-use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+use crate::{Color, FrameBuffer, FrameBufferOperations, Hub75Error, MutableFrameBuffer, WordSize};
 use embedded_dma::ReadBuffer;
 use embedded_graphics::prelude::{DrawTarget, OriginDimensions, PixelColor, Point, Size};
 
@@ -109,6 +113,7 @@ pub trait PixelRemapper {
 /// * `TILE_ROWS` - Number of panels stacked vertically
 /// * `TILE_COLS` - Number of panels stacked horizontally
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(core::fmt::Debug)]
 pub struct ChainTopRightDown<
     const PANEL_ROWS: usize,
@@ -146,6 +151,59 @@ impl<
     }
 }
 
+/// Within-panel remap for panels that present as twice as wide and half as
+/// high, with the two halves interleaved column by column
+///
+/// Some 64x32 panels are driven like a 128x16 panel: the upper 16 rows of
+/// the panel show on the even columns of the driven framebuffer and the
+/// lower 16 rows on the odd columns, so panel pixel `(x, y)` is driven at
+/// `(2 * x, y)` for `y < 16` and at `(2 * x + 1, y - 16)` otherwise.
+///
+/// `M` is the layout of the panels as they appear, e.g.
+/// `ChainTopRightDown<32, 64, 1, 1>` for a single panel; the framebuffer has
+/// half the rows and twice the columns of `M`'s. Since chained panels sit
+/// side by side in the framebuffer, the interleave applies to every panel of
+/// a tiled layout.
+///
+/// # Example
+/// ```rust
+/// use embedded_graphics::prelude::*;
+/// use hub75_framebuffer::plain::DmaFrameBuffer;
+/// use hub75_framebuffer::tiling::{ChainTopRightDown, ColumnInterleaved, PixelRemapper, TiledFrameBuffer};
+///
+/// // two 64x32 panels side by side, each driven as 128x16
+/// type Layout = ColumnInterleaved<ChainTopRightDown<32, 64, 1, 2>>;
+/// const FB_COLS: usize = Layout::FB_COLS;
+///
+/// type FBType = DmaFrameBuffer<16, FB_COLS, 8, 3, 7>;
+/// // the panel parameters are those of the framebuffer as driven
+/// type TiledFBType = TiledFrameBuffer<FBType, Layout, 16, 128, 8, 3, 7, 1, 2, FB_COLS>;
+///
+/// let fb = TiledFBType::try_new().unwrap();
+/// assert_eq!(fb.size(), Size::new(128, 32));
+/// ```
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(core::fmt::Debug)]
+pub struct ColumnInterleaved<M>(PhantomData<M>);
+
+impl<M: PixelRemapper> PixelRemapper for ColumnInterleaved<M> {
+    const VIRT_ROWS: usize = M::VIRT_ROWS;
+    const VIRT_COLS: usize = M::VIRT_COLS;
+    const FB_ROWS: usize = M::FB_ROWS / 2;
+    const FB_COLS: usize = M::FB_COLS * 2;
+
+    fn remap_xy(x: usize, y: usize) -> (usize, usize) {
+        let (x, y) = M::remap_xy(x, y);
+        if y >= M::FB_ROWS {
+            // off the panels, keep it off
+            return (2 * x, y);
+        }
+        // lower half on the odd columns
+        (2 * x + y / Self::FB_ROWS, y % Self::FB_ROWS)
+    }
+}
+
 /// Tile together multiple displays in a certain configuration to form a single larger display
 ///
 /// This is a wrapper around an actual framebuffer implementation which can be used to tile multiple
@@ -243,6 +301,37 @@ impl<
     pub fn new() -> Self {
         Self(F::default(), PhantomData)
     }
+
+    /// Create a new "virtual display" like [`Self::new`], after checking
+    /// that the type parameters agree with each other and with `M`.
+    ///
+    /// The panel parameters are checked like
+    /// [`DmaFrameBuffer::check_config`](crate::format::DmaFrameBuffer::check_config)
+    /// does for a single chain; check the parameters of `F` itself with its
+    /// own `check_config`.
+    ///
+    /// # Errors
+    /// Returns [`Hub75Error::TileMismatch`] if `FB_COLS` is not the width
+    /// `M` maps to, [`Hub75Error::PanelMismatch`] if the panels and tiles do
+    /// not make up that framebuffer, and the errors of `check_config` for
+    /// mismatching panel parameters.
+    pub fn try_new() -> Result<Self, Hub75Error> {
+        if FB_COLS != M::FB_COLS {
+            return Err(Hub75Error::TileMismatch {
+                expected: M::FB_COLS,
+                actual: FB_COLS,
+            });
+        }
+        let tiles = TILE_ROWS * TILE_COLS;
+        if PANEL_ROWS != M::FB_ROWS || PANEL_COLS * tiles != M::FB_COLS {
+            return Err(Hub75Error::PanelMismatch {
+                expected: (M::FB_ROWS, M::FB_COLS / if tiles == 0 { 1 } else { tiles }),
+                actual: (PANEL_ROWS, PANEL_COLS),
+            });
+        }
+        crate::format::check_panel_config(PANEL_ROWS, NROWS, BITS, FRAME_COUNT, 1)?;
+        Ok(Self::new())
+    }
 }
 
 impl<
@@ -446,6 +535,26 @@ impl<
     fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
         self.0.plane_ptr_len(plane_idx)
     }
+
+    fn rows(&self) -> usize {
+        self.0.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.0.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.0.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.0.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.0.buffer_len_bytes()
+    }
 }
 
 impl<
@@ -611,6 +720,62 @@ mod tests {
         assert_eq!(pixel.0, Point::new(-5, 40));
     }
 
+    #[test]
+    fn test_column_interleaved_remap() {
+        type Layout = ColumnInterleaved<ChainTopRightDown<32, 64, 1, 2>>;
+        assert_eq!(Layout::virtual_size(), (32, 128));
+        assert_eq!(Layout::fb_size(), (16, 256));
+        assert_eq!(Layout::remap_xy(0, 0), (0, 0));
+        assert_eq!(Layout::remap_xy(0, 16), (1, 0));
+        assert_eq!(Layout::remap_xy(1, 0), (2, 0));
+        assert_eq!(Layout::remap_xy(63, 31), (127, 15));
+        // second panel
+        assert_eq!(Layout::remap_xy(64, 16), (129, 0));
+        assert_eq!(Layout::remap_xy(127, 31), (255, 15));
+    }
+
+    #[test]
+    fn test_column_interleaved_decoded() {
+        use crate::decode::Decoded;
+        use crate::ordering::DefaultOrdering;
+        use crate::plain::{DefaultPins, DmaFrameBuffer};
+
+        type Layout = ColumnInterleaved<ChainTopRightDown<32, 64, 1, 2>>;
+        type FBType = DmaFrameBuffer<16, 256, 8, 3, 7>;
+        let mut fb =
+            TiledFrameBuffer::<FBType, Layout, 16, 128, 8, 3, 7, 1, 2, 256>::try_new().unwrap();
+        assert_eq!(fb.size(), Size::new(128, 32));
+        for (p, color) in [
+            (Point::new(0, 0), Color::RED),
+            (Point::new(0, 16), Color::GREEN),
+            (Point::new(1, 0), Color::BLUE),
+            (Point::new(63, 31), Color::WHITE),
+            (Point::new(64, 8), Color::RED),
+        ] {
+            Pixel(p, color).draw(&mut fb).unwrap();
+        }
+
+        let decoded =
+            Decoded::<16, 256, 8, 7>::plain::<DefaultOrdering, DefaultPins>(fb.0.as_words())
+                .unwrap();
+        // the driven columns alternate between the upper and lower half
+        let lit = [
+            ((0, 0), Color::new(224, 0, 0)),
+            ((1, 0), Color::new(0, 224, 0)),
+            ((2, 0), Color::new(0, 0, 224)),
+            ((127, 15), Color::new(224, 224, 224)),
+            ((128, 8), Color::new(224, 0, 0)),
+        ];
+        for ((x, y), color) in lit {
+            assert_eq!(decoded.pixel(x, y), color, "({x}, {y})");
+        }
+        let lit_count = (0..16)
+            .flat_map(|y| (0..256).map(move |x| (x, y)))
+            .filter(|&(x, y)| decoded.pixel(x, y) != Color::BLACK)
+            .count();
+        assert_eq!(lit_count, lit.len());
+    }
+
     #[test]
     fn test_compute_tiled_cols() {
         assert_eq!(192, compute_tiled_cols(32, 3, 2));
@@ -716,6 +881,22 @@ mod tests {
         fn plane_ptr_len(&self, _plane_idx: usize) -> (*const u8, usize) {
             (self.buf.as_ptr(), self.buf.len())
         }
+
+        fn rows(&self) -> usize {
+            1
+        }
+
+        fn cols(&self) -> usize {
+            1
+        }
+
+        fn bits(&self) -> u8 {
+            1
+        }
+
+        fn frame_count(&self) -> usize {
+            1
+        }
     }
 
     impl FrameBufferOperations for TestFrameBuffer {
@@ -990,6 +1171,8 @@ mod tests {
             core::marker::PhantomData,
         );
         assert_eq!(fb.get_word_size(), WordSize::Eight);
+        // the default sums the planes of the wrapped buffer
+        assert_eq!(fb.buffer_len_bytes(), 8);
     }
 
     // Remapper that generates very large coordinates to trigger u16 truncation in remap_point
@@ -1109,6 +1292,80 @@ mod tests {
         assert!(fb_new.0.take_calls().is_empty());
     }
 
+    #[test]
+    fn test_tiled_try_new_checks_parameters() {
+        type Tiled<
+            const NROWS: usize,
+            const BITS: u8,
+            const FRAME_COUNT: usize,
+            const FB_COLS: usize,
+        > = TiledFrameBuffer<
+            TestFrameBuffer,
+            ChainTopRightDown<32, 64, 2, 4>,
+            32,
+            64,
+            NROWS,
+            BITS,
+            FRAME_COUNT,
+            2,
+            4,
+            FB_COLS,
+        >;
+
+        // panels that do not make up the framebuffer `M` maps to
+        type Mismatched<const PANEL_ROWS: usize, const PANEL_COLS: usize> = TiledFrameBuffer<
+            TestFrameBuffer,
+            ChainTopRightDown<32, 64, 2, 4>,
+            PANEL_ROWS,
+            PANEL_COLS,
+            16,
+            3,
+            7,
+            2,
+            4,
+            512,
+        >;
+
+        assert!(Tiled::<16, 3, 7, 512>::try_new().is_ok());
+        assert_eq!(
+            Tiled::<16, 3, 7, 256>::try_new().err(),
+            Some(Hub75Error::TileMismatch {
+                expected: 512,
+                actual: 256
+            })
+        );
+        assert_eq!(
+            Tiled::<16, 9, 511, 512>::try_new().err(),
+            Some(Hub75Error::InvalidBits(9))
+        );
+        assert_eq!(
+            Tiled::<16, 3, 8, 512>::try_new().err(),
+            Some(Hub75Error::FrameCountMismatch {
+                expected: 7,
+                actual: 8
+            })
+        );
+        assert_eq!(
+            Tiled::<8, 3, 7, 512>::try_new().err(),
+            Some(Hub75Error::RowCountMismatch { rows: 32, nrows: 8 })
+        );
+
+        assert_eq!(
+            Mismatched::<16, 64>::try_new().err(),
+            Some(Hub75Error::PanelMismatch {
+                expected: (32, 64),
+                actual: (16, 64),
+            })
+        );
+        assert_eq!(
+            Mismatched::<32, 32>::try_new().err(),
+            Some(Hub75Error::PanelMismatch {
+                expected: (32, 64),
+                actual: (32, 32),
+            })
+        );
+    }
+
     #[test]
     fn test_tiled_origin_dimensions_matches_virtual_size() {
         const TILED_COLS: usize = 5;
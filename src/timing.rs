@@ -0,0 +1,167 @@
+//! BCM timing calculations.
+//!
+//! Every row of every BCM frame is shifted into the panel once per refresh,
+//! so the refresh rate follows from the shift clock, the number of clocks per
+//! row (pixels plus blanking and address overhead), the scan rows and the
+//! colour depth. [`BcmTiming`] does this arithmetic in `const fn`s, so a
+//! configuration can be checked at compile time:
+//!
+//! - Threshold-frame buffers (`plain`, `latched`) output `2^BITS - 1` equally
+//!   long frames; use [`BcmTiming::refresh_hz`] to see whether the result is
+//!   above the flicker threshold (roughly 100 Hz, more for cameras).
+//! - Bit-plane buffers driven with hardware-timed BCM show each plane for a
+//!   time proportional to its weight. [`BcmTiming::bit_display_ns`] and
+//!   [`BcmTiming::bit_display_ticks`] give the duration of each bit, where
+//!   the least significant bit lasts exactly one row shift (the shortest
+//!   possible time, since the next row is shifted while the current one is
+//!   lit).
+//!
+//! Both strategies produce the same refresh rate for the same parameters.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::timing::BcmTiming;
+//!
+//! // 64 columns, 1/16 scan, 6 bits, 4 latch/blanking clocks per row at 20 MHz
+//! const TIMING: BcmTiming = BcmTiming::new(20_000_000, 64, 16, 6, 4);
+//! const _: () = assert!(TIMING.refresh_hz() >= 100, "panel would flicker");
+//!
+//! assert_eq!(TIMING.row_ns(), 3_400);
+//! assert_eq!(TIMING.bit_display_ns(5), 3_400 * 32);
+//! assert_eq!(TIMING.refresh_hz(), 291);
+//! ```
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Timing parameters of a HUB75 refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BcmTiming {
+    clk_hz: u32,
+    row_clocks: u32,
+    nrows: u32,
+    bits: u8,
+}
+
+impl BcmTiming {
+    /// Describe a refresh with shift clock `clk_hz`, `cols` pixel clocks and
+    /// `blanking_clocks` extra clocks per row (e.g. the 4 address words of a
+    /// latched row), `nrows` scan rows and `bits` of colour depth.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `clk_hz`, `cols` or `nrows` is zero, or if `bits` is not in
+    /// `1..=16`.
+    #[must_use]
+    pub const fn new(
+        clk_hz: u32,
+        cols: usize,
+        nrows: usize,
+        bits: u8,
+        blanking_clocks: u32,
+    ) -> Self {
+        assert!(clk_hz > 0, "clk_hz must not be zero");
+        assert!(cols > 0 && nrows > 0, "panel must not be empty");
+        assert!(bits >= 1 && bits <= 16, "bits must be in 1..=16");
+        Self {
+            clk_hz,
+            row_clocks: cols as u32 + blanking_clocks,
+            nrows: nrows as u32,
+            bits,
+        }
+    }
+
+    /// Shift clocks needed for one row, including blanking overhead.
+    #[must_use]
+    pub const fn row_clocks(&self) -> u32 {
+        self.row_clocks
+    }
+
+    /// Time to shift one row in nanoseconds, rounded up.
+    ///
+    /// This is also the display time of the least significant bit.
+    #[must_use]
+    pub const fn row_ns(&self) -> u32 {
+        (self.row_clocks as u64 * NANOS_PER_SEC).div_ceil(self.clk_hz as u64) as u32
+    }
+
+    /// Display time of colour bit `bit` (0 = least significant) of each row
+    /// in nanoseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit` is not below the colour depth.
+    #[must_use]
+    pub const fn bit_display_ns(&self, bit: u8) -> u64 {
+        assert!(bit < self.bits, "bit is out of range");
+        (self.row_ns() as u64) << bit
+    }
+
+    /// Display time of colour bit `bit` in ticks of a timer running at
+    /// `timer_hz`, rounded up so the row shift always fits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit` is not below the colour depth.
+    #[must_use]
+    pub const fn bit_display_ticks(&self, bit: u8, timer_hz: u32) -> u64 {
+        assert!(bit < self.bits, "bit is out of range");
+        ((self.row_clocks as u64 * timer_hz as u64) << bit).div_ceil(self.clk_hz as u64)
+    }
+
+    /// Number of row shifts per refresh (`NROWS * (2^BITS - 1)`).
+    #[must_use]
+    pub const fn rows_per_refresh(&self) -> u64 {
+        self.nrows as u64 * ((1u64 << self.bits) - 1)
+    }
+
+    /// Duration of one complete refresh in nanoseconds.
+    #[must_use]
+    pub const fn frame_ns(&self) -> u64 {
+        self.rows_per_refresh() * self.row_ns() as u64
+    }
+
+    /// Complete refreshes per second, rounded down.
+    #[must_use]
+    pub const fn refresh_hz(&self) -> u32 {
+        let clocks = self.rows_per_refresh() * self.row_clocks as u64;
+        (self.clk_hz as u64 / clocks) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_and_bit_durations() {
+        let timing = BcmTiming::new(10_000_000, 64, 16, 3, 0);
+        assert_eq!(timing.row_clocks(), 64);
+        assert_eq!(timing.row_ns(), 6_400);
+        assert_eq!(timing.bit_display_ns(0), 6_400);
+        assert_eq!(timing.bit_display_ns(2), 25_600);
+        // 1 MHz timer: 6.4 ticks rounded up
+        assert_eq!(timing.bit_display_ticks(0, 1_000_000), 7);
+        assert_eq!(timing.bit_display_ticks(2, 1_000_000), 26);
+    }
+
+    #[test]
+    fn test_refresh_rate() {
+        let timing = BcmTiming::new(10_000_000, 64, 16, 3, 0);
+        assert_eq!(timing.rows_per_refresh(), 16 * 7);
+        assert_eq!(timing.frame_ns(), 16 * 7 * 6_400);
+        // 10 MHz / (64 * 16 * 7) = 1395.09
+        assert_eq!(timing.refresh_hz(), 1395);
+
+        // three chained 64x64 panels at 8 bits barely refresh at all
+        let chained = BcmTiming::new(20_000_000, 3 * 64, 32, 8, 4);
+        assert_eq!(chained.refresh_hz(), 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit is out of range")]
+    fn test_bit_out_of_range() {
+        let timing = BcmTiming::new(10_000_000, 64, 16, 3, 0);
+        let _ = timing.bit_display_ns(3);
+    }
+}
@@ -0,0 +1,311 @@
+//! Drawing into part of a panel.
+//!
+//! Enclosures often hide the outer rows or columns of a panel, or the
+//! visible area does not start at the panel's top left corner.
+//! [`Translated`] wraps a framebuffer and exposes only a sub-rectangle of
+//! it, the viewport: drawing coordinate `(0, 0)` is the viewport's top left
+//! corner and everything outside the viewport is clipped, so application
+//! code can treat the visible part as the whole display.
+//!
+//! Like [`Hub75Display`](crate::display::Hub75Display) the adapter passes
+//! `ReadBuffer` and [`FrameBuffer`] through to the framebuffer it owns, and
+//! the two can be nested: `Hub75Display::new(Translated::new(fb, area))`
+//! rotates within the viewport.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::viewport::Translated;
+//! use hub75_framebuffer::Color;
+//!
+//! // the enclosure hides two columns on each side and the top row
+//! let fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//! let visible = Rectangle::new(Point::new(2, 1), Size::new(60, 31));
+//! let mut display = Translated::new(fb, visible);
+//!
+//! assert_eq!(display.size(), Size::new(60, 31));
+//! let red = Color::new(224, 0, 0);
+//! display.clear(Color::BLUE).unwrap();
+//! Rectangle::new(Point::new(-5, 0), Size::new(10, 10))
+//!     .into_styled(PrimitiveStyle::with_fill(red))
+//!     .draw(&mut display)
+//!     .unwrap();
+//!
+//! let fb = display.framebuffer();
+//! assert_eq!(fb.get_pixel(Point::new(2, 1)), Some(red));
+//! assert_eq!(fb.get_pixel(Point::new(1, 1)), Some(Color::BLACK));
+//! assert_eq!(fb.get_pixel(Point::new(2, 0)), Some(Color::BLACK));
+//! ```
+
+use embedded_dma::ReadBuffer;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point, Size};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::Pixel;
+
+use crate::{Color, FrameBuffer, FrameBufferOperations, MutableFrameBuffer, WordSize};
+
+/// A framebuffer seen through a fixed viewport, see the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct Translated<FB> {
+    fb: FB,
+    offset: Point,
+    size: Size,
+}
+
+impl<FB> Translated<FB> {
+    /// Wrap `fb`, drawing into `viewport` only.
+    ///
+    /// The viewport may extend past the framebuffer; pixels drawn there are
+    /// ignored like any other pixel outside the framebuffer.
+    #[must_use]
+    pub const fn new(fb: FB, viewport: Rectangle) -> Self {
+        Self {
+            fb,
+            offset: viewport.top_left,
+            size: viewport.size,
+        }
+    }
+
+    /// The area of the framebuffer drawn into.
+    #[must_use]
+    pub const fn viewport(&self) -> Rectangle {
+        Rectangle::new(self.offset, self.size)
+    }
+
+    /// Draw into `viewport` from now on. Content already drawn stays where
+    /// it is.
+    pub fn set_viewport(&mut self, viewport: Rectangle) {
+        self.offset = viewport.top_left;
+        self.size = viewport.size;
+    }
+
+    /// The wrapped framebuffer.
+    #[must_use]
+    pub const fn framebuffer(&self) -> &FB {
+        &self.fb
+    }
+
+    /// The wrapped framebuffer, for drawing outside the viewport.
+    pub fn framebuffer_mut(&mut self) -> &mut FB {
+        &mut self.fb
+    }
+
+    /// Unwrap the framebuffer.
+    pub fn into_inner(self) -> FB {
+        self.fb
+    }
+
+    /// Framebuffer coordinates of drawing point `p`, or `None` if it lies
+    /// outside the viewport.
+    fn map_point(&self, p: Point) -> Option<Point> {
+        Rectangle::new(Point::zero(), self.size)
+            .contains(p)
+            .then(|| p + self.offset)
+    }
+}
+
+impl<FB> OriginDimensions for Translated<FB> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<FB: DrawTarget<Color = Color>> DrawTarget for Translated<FB> {
+    type Color = Color;
+    type Error = FB::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = Rectangle::new(Point::zero(), self.size);
+        let offset = self.offset;
+        self.fb.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|Pixel(p, _)| bounds.contains(*p))
+                .map(|Pixel(p, color)| Pixel(p + offset, color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        self.fb.fill_solid(
+            &Rectangle::new(area.top_left + self.offset, area.size),
+            color,
+        )
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        // only the viewport, the hidden border keeps its content
+        self.fb.fill_solid(&self.viewport(), color)
+    }
+}
+
+impl<FB: FrameBufferOperations> FrameBufferOperations for Translated<FB> {
+    /// Erase the whole framebuffer, including the area outside the
+    /// viewport.
+    #[inline]
+    fn erase(&mut self) {
+        self.fb.erase();
+    }
+
+    #[inline]
+    fn set_pixel(&mut self, p: Point, color: Color) {
+        if let Some(p) = self.map_point(p) {
+            self.fb.set_pixel(p, color);
+        }
+    }
+}
+
+impl<FB: FrameBuffer> FrameBuffer for Translated<FB> {
+    fn get_word_size(&self) -> WordSize {
+        self.fb.get_word_size()
+    }
+
+    fn plane_count(&self) -> usize {
+        self.fb.plane_count()
+    }
+
+    fn plane_ptr_len(&self, plane_idx: usize) -> (*const u8, usize) {
+        self.fb.plane_ptr_len(plane_idx)
+    }
+
+    fn rows(&self) -> usize {
+        self.fb.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.fb.cols()
+    }
+
+    fn bits(&self) -> u8 {
+        self.fb.bits()
+    }
+
+    fn frame_count(&self) -> usize {
+        self.fb.frame_count()
+    }
+
+    fn buffer_len_bytes(&self) -> usize {
+        self.fb.buffer_len_bytes()
+    }
+}
+
+impl<FB: MutableFrameBuffer> MutableFrameBuffer for Translated<FB> {}
+
+unsafe impl<T, FB: ReadBuffer<Word = T>> ReadBuffer for Translated<FB> {
+    type Word = T;
+
+    unsafe fn read_buffer(&self) -> (*const T, usize) {
+        self.fb.read_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{Hub75Display, Rotation};
+    use crate::plain::DmaFrameBuffer;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::prelude::*;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    type SmallFB = DmaFrameBuffer<16, 32, 8, 3, 7>;
+
+    fn mock() -> Translated<MockDisplay<Color>> {
+        Translated::new(
+            MockDisplay::new(),
+            Rectangle::new(Point::new(1, 1), Size::new(3, 2)),
+        )
+    }
+
+    #[test]
+    fn test_draw_iter_clips() {
+        let mut display = mock();
+        Line::new(Point::new(-1, 0), Point::new(5, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Color::RED, 1))
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(2, 1), Color::GREEN)
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(2, 2), Color::GREEN)
+            .draw(&mut display)
+            .unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["    ", " RRR", "   G"]);
+    }
+
+    #[test]
+    fn test_fill_solid_clips() {
+        let mut display = mock();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-2, 1), Size::new(4, 5)),
+                Color::BLUE,
+            )
+            .unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["    ", "    ", " BB "]);
+
+        let mut display = mock();
+        display.clear(Color::RED).unwrap();
+        display
+            .framebuffer()
+            .assert_pattern(&["    ", " RRR", " RRR"]);
+
+        let mut display = mock();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(3, 0), Size::new(2, 2)),
+                Color::RED,
+            )
+            .unwrap();
+        display.framebuffer().assert_pattern(&[]);
+    }
+
+    #[test]
+    fn test_frame_buffer_operations() {
+        let viewport = Rectangle::new(Point::new(4, 2), Size::new(8, 6));
+        let mut display = Translated::new(SmallFB::new(), viewport);
+        display.set_pixel(Point::new(0, 0), Color::WHITE);
+        display.set_pixel(Point::new(8, 0), Color::WHITE);
+        display.set_pixel(Point::new(-1, 0), Color::WHITE);
+        let mut expected = SmallFB::new();
+        expected.set_pixel(Point::new(4, 2), Color::WHITE);
+        assert_eq!(display.framebuffer().as_bytes(), expected.as_bytes());
+
+        let (ptr, len) = unsafe { display.read_buffer() };
+        let (fb_ptr, fb_len) = unsafe { display.framebuffer().read_buffer() };
+        assert_eq!((ptr, len), (fb_ptr, fb_len));
+        assert_eq!((display.rows(), display.cols()), (16, 32));
+        assert_eq!(display.viewport(), viewport);
+
+        display.erase();
+        assert_eq!(display.into_inner().as_bytes(), SmallFB::new().as_bytes());
+    }
+
+    #[test]
+    fn test_rotated_viewport() {
+        let viewport = Rectangle::new(Point::new(1, 1), Size::new(3, 2));
+        let mut display = Hub75Display::new(Translated::new(MockDisplay::new(), viewport));
+        display.set_rotation(Rotation::Deg90);
+        assert_eq!(display.size(), Size::new(2, 3));
+        Pixel(Point::new(0, 0), Color::RED)
+            .draw(&mut display)
+            .unwrap();
+        display
+            .framebuffer()
+            .framebuffer()
+            .assert_pattern(&["    ", "   R"]);
+    }
+}
@@ -0,0 +1,386 @@
+//! Frame-boundary notifications.
+//!
+//! Refresh loops call a [`VsyncHook`] every time a complete frame has been
+//! output, right before the next one starts. This is the one point where a
+//! new buffer can be put on screen without tearing, and it is a steady clock
+//! for FPS counters and animation timing, so applications can build on it
+//! without every driver inventing its own callback plumbing.
+//!
+//! Closures taking a [`Vsync`] implement the trait, `()` is a hook that does
+//! nothing, [`FrameCounter`] counts frames and buffer swaps, and
+//! [`FramePacer`] tells an animation loop when to render and swap.
+//!
+//! # Example
+//! ```rust
+//! use hub75_framebuffer::vsync::{FrameCounter, Vsync, VsyncHook};
+//!
+//! let mut counter = FrameCounter::new();
+//! // a refresh loop calls this at every frame boundary
+//! counter.on_vsync(Vsync { frame: 0, swapped: false });
+//! counter.on_vsync(Vsync { frame: 1, swapped: true });
+//! assert_eq!(counter.frames(), 2);
+//! assert_eq!(counter.swaps(), 1);
+//!
+//! let mut last_frame = None;
+//! let mut hook = |vsync: Vsync| last_frame = Some(vsync.frame);
+//! hook.on_vsync(Vsync { frame: 7, swapped: false });
+//! assert_eq!(last_frame, Some(7));
+//! ```
+
+/// Describes a frame boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Vsync {
+    /// Number of the frame that just completed, wrapping on overflow.
+    pub frame: u32,
+    /// `true` if a new buffer is shown from the next frame on.
+    pub swapped: bool,
+}
+
+/// Called by refresh loops at every frame boundary.
+///
+/// Hooks run in the refresh loop, so they must return quickly.
+pub trait VsyncHook {
+    /// A frame has been output completely.
+    fn on_vsync(&mut self, vsync: Vsync);
+}
+
+impl VsyncHook for () {
+    fn on_vsync(&mut self, _vsync: Vsync) {}
+}
+
+impl<F: FnMut(Vsync)> VsyncHook for F {
+    fn on_vsync(&mut self, vsync: Vsync) {
+        self(vsync);
+    }
+}
+
+/// Counts frames and buffer swaps, e.g. to measure refresh and drawing FPS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameCounter {
+    frames: u32,
+    swaps: u32,
+}
+
+impl FrameCounter {
+    /// Create a counter starting at zero.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            frames: 0,
+            swaps: 0,
+        }
+    }
+
+    /// Number of frames output so far, wrapping on overflow.
+    #[must_use]
+    pub const fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Number of buffer swaps so far, wrapping on overflow.
+    #[must_use]
+    pub const fn swaps(&self) -> u32 {
+        self.swaps
+    }
+
+    /// Reset both counts to zero, e.g. at the start of a measurement window.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl VsyncHook for FrameCounter {
+    fn on_vsync(&mut self, vsync: Vsync) {
+        self.frames = self.frames.wrapping_add(1);
+        if vsync.swapped {
+            self.swaps = self.swaps.wrapping_add(1);
+        }
+    }
+}
+
+/// Paces content frames at a target rate on top of the panel refresh.
+///
+/// The panel refreshes at `refresh_hz`, usually much faster than the
+/// content changes. The pacer divides the refreshes into slots of
+/// `1 / target_fps` seconds, one per content frame, and tells the drawing
+/// loop:
+///
+/// - [`Self::should_render`]: draw content frame [`Self::next_frame`] now,
+///   one slot ahead of when it is shown, then call [`Self::rendered`];
+/// - [`Self::should_swap`]: the slot of the rendered frame has begun, so
+///   swap buffers.
+///
+/// It is a [`VsyncHook`], so the refresh loop drives it. When the drawing
+/// loop falls behind, the picture on screen stays up for another slot
+/// (counted by [`Self::duplicated`]) and content frames whose slot has
+/// already passed are skipped (counted by [`Self::dropped`]), so animations
+/// keep their speed.
+///
+/// # Example
+/// ```rust
+/// use hub75_framebuffer::vsync::{FramePacer, Vsync, VsyncHook};
+///
+/// // a 60 FPS animation on a panel refreshed at 240 Hz
+/// let mut pacer = FramePacer::new(240, 60);
+/// let mut swapped = false;
+/// for frame in 0..16 {
+///     if pacer.should_render() {
+///         let _content_frame = pacer.next_frame();
+///         // draw into the back buffer
+///         pacer.rendered();
+///     }
+///     if pacer.should_swap() {
+///         // request the buffer swap at the next frame boundary
+///         swapped = true;
+///     }
+///     // the refresh loop reports the frame boundary
+///     pacer.on_vsync(Vsync { frame, swapped });
+///     swapped = false;
+/// }
+/// assert_eq!(pacer.duplicated(), 0);
+/// assert_eq!(pacer.dropped(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FramePacer {
+    refresh_hz: u32,
+    target_fps: u32,
+    /// Accumulates `target_fps` per refresh; a slot ends every `refresh_hz`.
+    phase: u32,
+    /// Content frame that should be on screen now.
+    slot: u32,
+    /// Content frame to render next.
+    next: u32,
+    /// Rendered content frame waiting to be swapped in.
+    ready: Option<u32>,
+    /// Whether a new picture went on screen during the current slot.
+    swapped_in_slot: bool,
+    shown_any: bool,
+    duplicated: u32,
+    dropped: u32,
+}
+
+impl FramePacer {
+    /// Pace content at `target_fps` on a panel refreshed at `refresh_hz`,
+    /// e.g. from `estimated_refresh_hz()` of the framebuffer.
+    ///
+    /// # Panics
+    /// Panics if `target_fps` is zero or faster than `refresh_hz`.
+    #[must_use]
+    pub const fn new(refresh_hz: u32, target_fps: u32) -> Self {
+        assert!(
+            target_fps > 0 && target_fps <= refresh_hz,
+            "target FPS must be between 1 and the refresh rate"
+        );
+        Self {
+            refresh_hz,
+            target_fps,
+            phase: 0,
+            slot: 0,
+            next: 0,
+            ready: None,
+            swapped_in_slot: false,
+            shown_any: false,
+            duplicated: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Whether the drawing loop should render [`Self::next_frame`] now.
+    #[must_use]
+    pub const fn should_render(&self) -> bool {
+        self.ready.is_none()
+    }
+
+    /// Content frame to render next, for computing the animation state; it
+    /// is shown `next_frame() / target_fps` seconds after the start.
+    #[must_use]
+    pub const fn next_frame(&self) -> u32 {
+        self.next
+    }
+
+    /// The drawing loop finished rendering [`Self::next_frame`].
+    pub fn rendered(&mut self) {
+        self.ready = Some(self.next);
+        self.next = self.next.wrapping_add(1);
+    }
+
+    /// Whether the drawing loop should request a buffer swap now.
+    ///
+    /// It stays `true` until a [`Vsync`] reports the swap.
+    #[must_use]
+    pub fn should_swap(&self) -> bool {
+        self.ready.is_some_and(|frame| frame <= self.slot)
+    }
+
+    /// Content frames whose slot passed without a new picture, so the
+    /// previous one stayed on screen longer.
+    #[must_use]
+    pub const fn duplicated(&self) -> u32 {
+        self.duplicated
+    }
+
+    /// Content frames skipped because the drawing loop fell behind.
+    #[must_use]
+    pub const fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Start a new slot: account for the one that ended and skip content
+    /// frames that are already late.
+    fn next_slot(&mut self) {
+        if self.shown_any && !self.swapped_in_slot {
+            self.duplicated = self.duplicated.wrapping_add(1);
+        }
+        self.swapped_in_slot = false;
+        self.slot = self.slot.wrapping_add(1);
+        // the next frame to render must not be older than the slot that
+        // just began
+        if self.next < self.slot {
+            self.dropped = self.dropped.wrapping_add(self.slot - self.next);
+            self.next = self.slot;
+        }
+    }
+}
+
+impl VsyncHook for FramePacer {
+    fn on_vsync(&mut self, vsync: Vsync) {
+        if vsync.swapped && self.ready.is_some() {
+            self.ready = None;
+            self.swapped_in_slot = true;
+            self.shown_any = true;
+        }
+        self.phase += self.target_fps;
+        if self.phase >= self.refresh_hz {
+            self.phase -= self.refresh_hz;
+            self.next_slot();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_frame_counter_counts_and_resets() {
+        let mut counter = FrameCounter::new();
+        for frame in 0..5 {
+            counter.on_vsync(Vsync {
+                frame,
+                swapped: frame % 2 == 0,
+            });
+        }
+        assert_eq!(counter.frames(), 5);
+        assert_eq!(counter.swaps(), 3);
+
+        counter.reset();
+        assert_eq!(counter, FrameCounter::default());
+    }
+
+    /// Runs `refreshes` refresh frames, rendering whenever `render_at` allows
+    /// it and swapping whenever the pacer says so. Returns the refresh
+    /// frames at which each content frame went on screen.
+    fn run_pacer(
+        pacer: &mut FramePacer,
+        refreshes: u32,
+        render_at: impl Fn(u32) -> bool,
+    ) -> Vec<(u32, u32)> {
+        let mut shown = Vec::new();
+        let mut on_screen_next = None;
+        for frame in 0..refreshes {
+            if pacer.should_render() && render_at(frame) {
+                pacer.rendered();
+            }
+            let swapped = pacer.should_swap();
+            if swapped {
+                on_screen_next = pacer.ready;
+            }
+            pacer.on_vsync(Vsync { frame, swapped });
+            if let Some(content) = on_screen_next.take() {
+                shown.push((content, frame + 1));
+            }
+        }
+        shown
+    }
+
+    #[test]
+    fn test_frame_pacer_on_time() {
+        let mut pacer = FramePacer::new(100, 25);
+        let shown = run_pacer(&mut pacer, 20, |_| true);
+        // one content frame every 4 refreshes, each rendered a slot ahead
+        assert_eq!(shown, [(0, 1), (1, 5), (2, 9), (3, 13), (4, 17)]);
+        assert_eq!(pacer.duplicated(), 0);
+        assert_eq!(pacer.dropped(), 0);
+        assert_eq!(pacer.next_frame(), 6);
+
+        // rates that do not divide evenly average out
+        let mut pacer = FramePacer::new(100, 30);
+        let shown = run_pacer(&mut pacer, 100, |_| true);
+        assert_eq!(shown.len(), 30);
+        assert_eq!(pacer.duplicated(), 0);
+    }
+
+    #[test]
+    fn test_frame_pacer_falls_behind() {
+        let mut pacer = FramePacer::new(100, 25);
+        // the renderer is only ready every 10 refreshes
+        let shown = run_pacer(&mut pacer, 40, |frame| frame % 10 == 0);
+        // frames whose slot passed are skipped, the rest are shown late
+        assert_eq!(shown, [(0, 1), (2, 11), (5, 21), (7, 31)]);
+        // 10 slots, 4 of them with a new picture
+        assert_eq!(pacer.duplicated(), 6);
+        // 1, 3, 4, 6, 8 and 9
+        assert_eq!(pacer.dropped(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "target FPS")]
+    fn test_frame_pacer_faster_than_refresh() {
+        let _ = FramePacer::new(60, 120);
+    }
+
+    #[test]
+    fn test_closure_and_unit_hooks() {
+        let mut seen = [None; 2];
+        let mut idx = 0;
+        {
+            let mut hook = |vsync: Vsync| {
+                seen[idx] = Some(vsync);
+                idx += 1;
+            };
+            hook.on_vsync(Vsync {
+                frame: 1,
+                swapped: false,
+            });
+            hook.on_vsync(Vsync {
+                frame: 2,
+                swapped: true,
+            });
+        }
+        assert_eq!(
+            seen,
+            [
+                Some(Vsync {
+                    frame: 1,
+                    swapped: false
+                }),
+                Some(Vsync {
+                    frame: 2,
+                    swapped: true
+                })
+            ]
+        );
+
+        ().on_vsync(Vsync {
+            frame: 0,
+            swapped: false,
+        });
+    }
+}
@@ -0,0 +1,969 @@
+//! Dashboard and clock widgets.
+//!
+//! Small `embedded-graphics` drawables for the displays HUB75 panels are
+//! mostly used for: [`SevenSegment`] digits, VU-style [`Bargraph`]s,
+//! [`ProgressBar`]s, radial [`Gauge`]s and [`Battery`] and [`SignalBars`]
+//! status icons. Except for the gauge they are built from rectangles and
+//! drawn with `fill_solid`, which the framebuffers implement without
+//! per-pixel iteration overhead.
+//!
+//! # Example
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::primitives::Rectangle;
+//! use hub75_framebuffer::plain::DmaFrameBuffer;
+//! use hub75_framebuffer::widgets::{Bargraph, SegmentStyle, SevenSegment};
+//! use hub75_framebuffer::Color;
+//!
+//! let mut fb = DmaFrameBuffer::<32, 64, 16, 3, 7>::new();
+//!
+//! let style = SegmentStyle::new(Size::new(8, 15), 2, Color::RED);
+//! SevenSegment::new("12:34", Point::new(2, 2), style)
+//!     .draw(&mut fb)
+//!     .unwrap();
+//!
+//! let area = Rectangle::new(Point::new(2, 24), Size::new(60, 6));
+//! Bargraph::horizontal(area, 10, 70, 100).draw(&mut fb).unwrap();
+//! ```
+
+use core::ops::RangeInclusive;
+
+use embedded_graphics::prelude::{DrawTarget, Point, RgbColor, Size};
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+use embedded_graphics::{Drawable, Pixel};
+
+use crate::Color;
+
+/// Segments lit for `c`, bit 0 to 6 being segments a to g: top, top right,
+/// bottom right, bottom, bottom left, top left and middle.
+const fn segments(c: char) -> u8 {
+    match c.to_ascii_uppercase() {
+        '0' | 'O' => 0x3f,
+        '1' | 'I' => 0x06,
+        '2' => 0x5b,
+        '3' => 0x4f,
+        '4' => 0x66,
+        '5' | 'S' => 0x6d,
+        '6' => 0x7d,
+        '7' => 0x07,
+        '8' => 0x7f,
+        '9' => 0x6f,
+        'A' => 0x77,
+        'B' => 0x7c,
+        'C' => 0x39,
+        'D' => 0x5e,
+        'E' => 0x79,
+        'F' => 0x71,
+        'H' => 0x76,
+        'L' => 0x38,
+        'P' => 0x73,
+        'U' => 0x3e,
+        '-' => 0x40,
+        '_' => 0x08,
+        _ => 0,
+    }
+}
+
+/// Size, stroke and colours of [`SevenSegment`] digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentStyle {
+    /// Size of one digit.
+    pub size: Size,
+    /// Width of the segments.
+    pub thickness: u32,
+    /// Colour of lit segments.
+    pub on: Color,
+    /// Colour of unlit segments, `None` to leave them untouched.
+    pub off: Option<Color>,
+    /// Columns between digits.
+    pub spacing: u32,
+}
+
+impl SegmentStyle {
+    /// Digits of `size` with segments `thickness` wide in colour `on`,
+    /// spaced by one thickness.
+    #[must_use]
+    pub const fn new(size: Size, thickness: u32, on: Color) -> Self {
+        Self {
+            size,
+            thickness,
+            on,
+            off: None,
+            spacing: thickness,
+        }
+    }
+
+    /// Draw unlit segments in `off`, like a real display.
+    #[must_use]
+    pub const fn with_off_color(mut self, off: Color) -> Self {
+        self.off = Some(off);
+        self
+    }
+
+    /// Rectangles of the seven segments of a digit at `origin`.
+    fn segment_areas(&self, origin: Point) -> [Rectangle; 7] {
+        let Size { width, height } = self.size;
+        let t = self.thickness.min(width / 2).min(height / 3);
+        let mid = (height - t) / 2;
+        let at = |x, y| origin + Size::new(x, y);
+        [
+            Rectangle::new(at(0, 0), Size::new(width, t)),
+            Rectangle::new(at(width - t, 0), Size::new(t, mid + t)),
+            Rectangle::new(at(width - t, mid), Size::new(t, height - mid)),
+            Rectangle::new(at(0, height - t), Size::new(width, t)),
+            Rectangle::new(at(0, mid), Size::new(t, height - mid)),
+            Rectangle::new(at(0, 0), Size::new(t, mid + t)),
+            Rectangle::new(at(0, mid), Size::new(width, t)),
+        ]
+    }
+}
+
+/// A row of seven-segment digits.
+///
+/// Shows `0`-`9`, the hex letters, a few more letters that seven segments
+/// can show (`H`, `L`, `O`, `P`, `S`, `U`), `-` and `_`; other characters
+/// are blank. `:` and `.` draw a narrow colon or point between digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SevenSegment<'a> {
+    text: &'a str,
+    top_left: Point,
+    style: SegmentStyle,
+}
+
+impl<'a> SevenSegment<'a> {
+    /// `text` with its first digit's top left corner at `top_left`.
+    #[must_use]
+    pub const fn new(text: &'a str, top_left: Point, style: SegmentStyle) -> Self {
+        Self {
+            text,
+            top_left,
+            style,
+        }
+    }
+}
+
+impl Drawable for SevenSegment<'_> {
+    type Color = Color;
+    type Output = Point;
+
+    /// Returns the top left corner for a following digit.
+    fn draw<D>(&self, target: &mut D) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        let style = &self.style;
+        let t = style.thickness;
+        let mut origin = self.top_left;
+        for c in self.text.chars() {
+            if matches!(c, ':' | '.') {
+                let dot = Size::new(t, t);
+                let rows: &[u32] = if c == ':' {
+                    &[style.size.height / 3, style.size.height * 2 / 3]
+                } else {
+                    &[style.size.height.saturating_sub(t)]
+                };
+                for &y in rows {
+                    target.fill_solid(&Rectangle::new(origin + Size::new(0, y), dot), style.on)?;
+                }
+                origin += Size::new(t + style.spacing, 0);
+                continue;
+            }
+            let lit = segments(c);
+            let areas = style.segment_areas(origin);
+            // unlit first, so the shared corners of lit segments stay lit
+            if let Some(off) = style.off {
+                for (i, area) in areas.iter().enumerate() {
+                    if lit & (1 << i) == 0 {
+                        target.fill_solid(area, off)?;
+                    }
+                }
+            }
+            for (i, area) in areas.iter().enumerate() {
+                if lit & (1 << i) != 0 {
+                    target.fill_solid(area, style.on)?;
+                }
+            }
+            origin += Size::new(style.size.width + style.spacing, 0);
+        }
+        Ok(origin)
+    }
+}
+
+/// Direction a [`Bargraph`] grows in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// From the left edge to the right.
+    Right,
+    /// From the bottom edge up.
+    Up,
+}
+
+/// A segmented VU-meter style bar.
+///
+/// The bar is split into `segments` equal blocks with a gap between them,
+/// and lights as many as `value` covers of `max`. Blocks are coloured by
+/// position: green up to 60 %, yellow up to 85 % and red beyond, unless
+/// changed with [`Bargraph::with_colors`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bargraph {
+    area: Rectangle,
+    direction: Direction,
+    segments: u32,
+    value: u32,
+    max: u32,
+    gap: u32,
+    colors: [Color; 3],
+    off: Option<Color>,
+}
+
+impl Bargraph {
+    /// A bar filling `area` from left to right.
+    #[must_use]
+    pub const fn horizontal(area: Rectangle, segments: u32, value: u32, max: u32) -> Self {
+        Self {
+            area,
+            direction: Direction::Right,
+            segments,
+            value,
+            max,
+            gap: 1,
+            colors: [Color::GREEN, Color::YELLOW, Color::RED],
+            off: None,
+        }
+    }
+
+    /// A bar filling `area` from the bottom up.
+    #[must_use]
+    pub const fn vertical(area: Rectangle, segments: u32, value: u32, max: u32) -> Self {
+        let mut bar = Self::horizontal(area, segments, value, max);
+        bar.direction = Direction::Up;
+        bar
+    }
+
+    /// Colours of the low, middle and high blocks.
+    #[must_use]
+    pub const fn with_colors(mut self, low: Color, mid: Color, high: Color) -> Self {
+        self.colors = [low, mid, high];
+        self
+    }
+
+    /// Draw unlit blocks in `off`.
+    #[must_use]
+    pub const fn with_off_color(mut self, off: Color) -> Self {
+        self.off = Some(off);
+        self
+    }
+
+    /// Pixels between blocks; 0 draws a continuous bar.
+    #[must_use]
+    pub const fn with_gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Number of lit blocks, rounded to the nearest.
+    #[must_use]
+    pub fn lit_segments(&self) -> u32 {
+        if self.max == 0 {
+            return 0;
+        }
+        let value = u64::from(self.value.min(self.max));
+        let lit = (value * u64::from(self.segments) * 2 + u64::from(self.max))
+            / (2 * u64::from(self.max));
+        u32::try_from(lit).unwrap_or(self.segments)
+    }
+
+    /// Colour of block `i` when lit.
+    fn color(&self, i: u32) -> Color {
+        let percent = u64::from(i) * 100 / u64::from(self.segments);
+        match percent {
+            0..60 => self.colors[0],
+            60..85 => self.colors[1],
+            _ => self.colors[2],
+        }
+    }
+}
+
+impl Drawable for Bargraph {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        if self.segments == 0 {
+            return Ok(());
+        }
+        let Size { width, height } = self.area.size;
+        let length = match self.direction {
+            Direction::Right => width,
+            Direction::Up => height,
+        };
+        let gaps = self.gap * (self.segments - 1);
+        let block = length.saturating_sub(gaps) / self.segments;
+        let lit = self.lit_segments();
+        for i in 0..self.segments {
+            let color = if i < lit {
+                self.color(i)
+            } else if let Some(off) = self.off {
+                off
+            } else {
+                continue;
+            };
+            let offset = i * (block + self.gap);
+            let area = match self.direction {
+                Direction::Right => Rectangle::new(
+                    self.area.top_left + Size::new(offset, 0),
+                    Size::new(block, height),
+                ),
+                Direction::Up => {
+                    let bottom = self.area.top_left + Size::new(0, height);
+                    Rectangle::new(
+                        bottom - Size::new(0, offset + block),
+                        Size::new(width, block),
+                    )
+                }
+            };
+            target.fill_solid(&area, color)?;
+        }
+        Ok(())
+    }
+}
+
+/// `value`'s position in `min..=max` scaled to `0..=scale`, rounded down
+/// and clamped to the range.
+fn fraction(value: i32, min: i32, max: i32, scale: u32) -> u32 {
+    if max <= min {
+        return if value >= max { scale } else { 0 };
+    }
+    let value = i64::from(value.clamp(min, max)) - i64::from(min);
+    let span = i64::from(max) - i64::from(min);
+    u32::try_from(value * i64::from(scale) / span).unwrap_or(scale)
+}
+
+/// A progress bar showing where a value lies in a range.
+///
+/// The filled part is drawn in one colour, the rest optionally in a track
+/// colour, each with a single `fill_solid`, inside an optional one pixel
+/// border.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressBar {
+    area: Rectangle,
+    direction: Direction,
+    value: i32,
+    range: RangeInclusive<i32>,
+    color: Color,
+    track: Option<Color>,
+    border: Option<Color>,
+}
+
+impl ProgressBar {
+    /// A green bar in `area`, filled from the left by `value` in `range`.
+    #[must_use]
+    pub const fn new(area: Rectangle, value: i32, range: RangeInclusive<i32>) -> Self {
+        Self {
+            area,
+            direction: Direction::Right,
+            value,
+            range,
+            color: Color::GREEN,
+            track: None,
+            border: None,
+        }
+    }
+
+    /// Fill in `direction` instead.
+    #[must_use]
+    pub const fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Colour of the filled part.
+    #[must_use]
+    pub const fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Draw the unfilled part in `track`.
+    #[must_use]
+    pub const fn with_track_color(mut self, track: Color) -> Self {
+        self.track = Some(track);
+        self
+    }
+
+    /// Draw a one pixel border in `border` around the bar.
+    #[must_use]
+    pub const fn with_border(mut self, border: Color) -> Self {
+        self.border = Some(border);
+        self
+    }
+}
+
+impl Drawable for ProgressBar {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        let mut inner = self.area;
+        if let Some(border) = self.border {
+            outline(target, self.area, border)?;
+            inner = Rectangle::new(
+                inner.top_left + Size::new(1, 1),
+                inner.size.saturating_sub(Size::new(2, 2)),
+            );
+        }
+        let Size { width, height } = inner.size;
+        let (min, max) = (*self.range.start(), *self.range.end());
+        let (filled, rest) = match self.direction {
+            Direction::Right => {
+                let length = fraction(self.value, min, max, width);
+                (
+                    Rectangle::new(inner.top_left, Size::new(length, height)),
+                    Rectangle::new(
+                        inner.top_left + Size::new(length, 0),
+                        Size::new(width - length, height),
+                    ),
+                )
+            }
+            Direction::Up => {
+                let length = fraction(self.value, min, max, height);
+                (
+                    Rectangle::new(
+                        inner.top_left + Size::new(0, height - length),
+                        Size::new(width, length),
+                    ),
+                    Rectangle::new(inner.top_left, Size::new(width, height - length)),
+                )
+            }
+        };
+        target.fill_solid(&filled, self.color)?;
+        if let Some(track) = self.track {
+            target.fill_solid(&rest, track)?;
+        }
+        Ok(())
+    }
+}
+
+/// Clockwise angle of `(dx, dy)` from 12 o'clock in degrees, in
+/// `[0, 360)`, with `y` pointing down as on screen.
+///
+/// Uses a polynomial arctangent accurate to about 0.1°, as `core` has no
+/// trigonometry.
+fn clock_angle(dx: f32, dy: f32) -> f32 {
+    const QUARTER: f32 = 90.0;
+    let abs = |v: f32| if v < 0.0 { -v } else { v };
+    let (x, y) = (dx, -dy);
+    let (ax, ay) = (abs(x), abs(y));
+    if ax == 0.0 && ay == 0.0 {
+        return 0.0;
+    }
+    // angle from the y axis towards the x axis, in the first quadrant
+    let z = if ax < ay { ax / ay } else { ay / ax };
+    let atan = (core::f32::consts::FRAC_PI_4 * z - z * (z - 1.0) * (0.2447 + 0.0663 * z))
+        * (180.0 / core::f32::consts::PI);
+    let first = if ax < ay { atan } else { QUARTER - atan };
+    match (x >= 0.0, y >= 0.0) {
+        (true, true) => first,
+        (true, false) => 2.0 * QUARTER - first,
+        (false, false) => 2.0 * QUARTER + first,
+        (false, true) => (4.0 * QUARTER - first) % (4.0 * QUARTER),
+    }
+}
+
+/// `degrees` wrapped to `[0, 360)`.
+fn wrap_degrees(degrees: f32) -> f32 {
+    let wrapped = degrees % 360.0;
+    if wrapped < 0.0 {
+        wrapped + 360.0
+    } else {
+        wrapped
+    }
+}
+
+/// A radial gauge: a ring segment filled clockwise by a value in a range.
+///
+/// By default the gauge spans 270°, from the lower left through the top
+/// to the lower right, like a speedometer. Only the pixels of the ring are
+/// drawn, in a single `draw_iter` pass over its bounding box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    center: Point,
+    radius: u32,
+    thickness: u32,
+    value: i32,
+    range: RangeInclusive<i32>,
+    start: f32,
+    sweep: f32,
+    color: Color,
+    track: Option<Color>,
+}
+
+impl Gauge {
+    /// A green gauge around `center`, `radius` pixels to its outer edge and
+    /// `thickness` wide, showing `value` in `range`.
+    #[must_use]
+    pub const fn new(
+        center: Point,
+        radius: u32,
+        thickness: u32,
+        value: i32,
+        range: RangeInclusive<i32>,
+    ) -> Self {
+        Self {
+            center,
+            radius,
+            thickness,
+            value,
+            range,
+            start: 225.0,
+            sweep: 270.0,
+            color: Color::GREEN,
+            track: None,
+        }
+    }
+
+    /// Start at `start` degrees clockwise from 12 o'clock and span `sweep`
+    /// degrees clockwise.
+    #[must_use]
+    pub const fn with_angles(mut self, start: f32, sweep: f32) -> Self {
+        self.start = start;
+        self.sweep = sweep;
+        self
+    }
+
+    /// Colour of the filled part.
+    #[must_use]
+    pub const fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Draw the unfilled part of the ring in `track`.
+    #[must_use]
+    pub const fn with_track_color(mut self, track: Color) -> Self {
+        self.track = Some(track);
+        self
+    }
+}
+
+impl Drawable for Gauge {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        const STEPS: u16 = 10_000;
+        let outer = i64::from(self.radius);
+        let inner = outer - i64::from(self.thickness.min(self.radius));
+        // pixel centres within half a pixel of the radius are inside
+        let (outer_sq, inner_sq) = (outer * outer + outer, inner * inner + inner);
+        let (min, max) = (*self.range.start(), *self.range.end());
+        let steps = fraction(self.value, min, max, u32::from(STEPS));
+        let filled =
+            self.sweep * f32::from(u16::try_from(steps).unwrap_or(STEPS)) / f32::from(STEPS);
+        let start = wrap_degrees(self.start);
+        let diameter = self.radius * 2 + 1;
+        let bounds = Rectangle::with_center(self.center, Size::new(diameter, diameter));
+        let center = self.center;
+        let (color, track, sweep) = (self.color, self.track, self.sweep);
+
+        target.draw_iter(bounds.points().filter_map(move |p| {
+            let d = p - center;
+            let distance_sq = i64::from(d.x) * i64::from(d.x) + i64::from(d.y) * i64::from(d.y);
+            if distance_sq > outer_sq || (inner > 0 && distance_sq <= inner_sq) {
+                return None;
+            }
+            let to_f32 = |v: i32| f32::from(i16::try_from(v).unwrap_or(i16::MAX));
+            let angle = wrap_degrees(clock_angle(to_f32(d.x), to_f32(d.y)) - start);
+            if angle <= filled {
+                Some(Pixel(p, color))
+            } else if angle <= sweep {
+                track.map(|track| Pixel(p, track))
+            } else {
+                None
+            }
+        }))
+    }
+}
+
+/// Draw the outline of `area`, one pixel wide.
+fn outline<D: DrawTarget<Color = Color>>(
+    target: &mut D,
+    area: Rectangle,
+    color: Color,
+) -> Result<(), D::Error> {
+    let Size { width, height } = area.size;
+    let origin = area.top_left;
+    target.fill_solid(&Rectangle::new(origin, Size::new(width, 1)), color)?;
+    target.fill_solid(
+        &Rectangle::new(
+            origin + Size::new(0, height.saturating_sub(1)),
+            Size::new(width, 1),
+        ),
+        color,
+    )?;
+    target.fill_solid(&Rectangle::new(origin, Size::new(1, height)), color)?;
+    target.fill_solid(
+        &Rectangle::new(
+            origin + Size::new(width.saturating_sub(1), 0),
+            Size::new(1, height),
+        ),
+        color,
+    )
+}
+
+/// A battery icon filled to a charge level.
+///
+/// The body takes the size minus a terminal nub on the right; the charge
+/// is drawn in red at 20 % and below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Battery {
+    area: Rectangle,
+    percent: u8,
+    color: Color,
+}
+
+impl Battery {
+    /// A battery in `area` at `percent` charge, clamped to 100, with the
+    /// outline and charge in `color`.
+    #[must_use]
+    pub const fn new(area: Rectangle, percent: u8, color: Color) -> Self {
+        Self {
+            area,
+            percent: if percent > 100 { 100 } else { percent },
+            color,
+        }
+    }
+}
+
+impl Drawable for Battery {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        let Size { width, height } = self.area.size;
+        let nub = (width / 8).max(1);
+        let body = Rectangle::new(
+            self.area.top_left,
+            Size::new(width.saturating_sub(nub), height),
+        );
+        outline(target, body, self.color)?;
+        let nub_height = height / 2;
+        target.fill_solid(
+            &Rectangle::new(
+                self.area.top_left + Size::new(body.size.width, (height - nub_height) / 2),
+                Size::new(nub, nub_height),
+            ),
+            self.color,
+        )?;
+
+        // one pixel of space inside the outline
+        let inside = body.size.saturating_sub(Size::new(4, 4));
+        let charge = inside.width * u32::from(self.percent) / 100;
+        let color = if self.percent <= 20 {
+            Color::RED
+        } else {
+            self.color
+        };
+        target.fill_solid(
+            &Rectangle::new(
+                self.area.top_left + Size::new(2, 2),
+                Size::new(charge, inside.height),
+            ),
+            color,
+        )
+    }
+}
+
+/// A signal strength icon of four rising bars, e.g. for Wi-Fi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalBars {
+    area: Rectangle,
+    bars: u8,
+    color: Color,
+    off: Option<Color>,
+}
+
+impl SignalBars {
+    /// Four bars in `area` with the first `bars` of them lit in `color`.
+    #[must_use]
+    pub const fn new(area: Rectangle, bars: u8, color: Color) -> Self {
+        Self {
+            area,
+            bars,
+            color,
+            off: None,
+        }
+    }
+
+    /// Draw unlit bars in `off`.
+    #[must_use]
+    pub const fn with_off_color(mut self, off: Color) -> Self {
+        self.off = Some(off);
+        self
+    }
+}
+
+impl Drawable for SignalBars {
+    type Color = Color;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Color>,
+    {
+        const BARS: u32 = 4;
+        let Size { width, height } = self.area.size;
+        // bars and gaps of the same width, without a gap after the last bar
+        let bar = ((width + 1) / (2 * BARS)).max(1);
+        let bottom = self.area.top_left + Size::new(0, height);
+        for i in 0..BARS {
+            let color = if i < u32::from(self.bars) {
+                self.color
+            } else if let Some(off) = self.off {
+                off
+            } else {
+                continue;
+            };
+            let bar_height = (height * (i + 1) / BARS).max(1);
+            let top_left = bottom + Size::new(2 * i * bar, 0) - Size::new(0, bar_height);
+            target.fill_solid(&Rectangle::new(top_left, Size::new(bar, bar_height)), color)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mock_display::MockDisplay;
+
+    fn mock() -> MockDisplay<Color> {
+        let mut mock = MockDisplay::new();
+        mock.set_allow_overdraw(true);
+        mock
+    }
+
+    #[test]
+    fn test_seven_segment() {
+        let style = SegmentStyle::new(Size::new(3, 5), 1, Color::RED);
+        let mut display = mock();
+        let next = SevenSegment::new("8", Point::zero(), style)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(next, Point::new(4, 0));
+        display.assert_pattern(&["RRR", "R R", "RRR", "R R", "RRR"]);
+
+        let mut display = mock();
+        let style = style.with_off_color(Color::BLUE);
+        SevenSegment::new("1:7", Point::zero(), style)
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&[
+            "BBR   RRR", //
+            "B R R B R",
+            "BBR   BBR",
+            "B R R B R",
+            "BBR   BBR",
+        ]);
+    }
+
+    #[test]
+    fn test_segment_table() {
+        assert_eq!(segments('8').count_ones(), 7);
+        assert_eq!(segments('1').count_ones(), 2);
+        assert_eq!(segments('a'), segments('A'));
+        assert_eq!(segments('?'), 0);
+    }
+
+    #[test]
+    fn test_bargraph() {
+        let area = Rectangle::new(Point::zero(), Size::new(19, 2));
+        let mut display = mock();
+        Bargraph::horizontal(area, 10, 75, 100)
+            .with_off_color(Color::BLUE)
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["G G G G G G Y Y B B", "G G G G G G Y Y B B"]);
+
+        let mut display = mock();
+        Bargraph::vertical(Rectangle::new(Point::zero(), Size::new(1, 7)), 4, 9, 10)
+            .with_gap(1)
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["Y", " ", "G", " ", "G", " ", "G"]);
+
+        assert_eq!(Bargraph::horizontal(area, 10, 500, 100).lit_segments(), 10);
+        assert_eq!(Bargraph::horizontal(area, 10, 4, 100).lit_segments(), 0);
+        assert_eq!(Bargraph::horizontal(area, 10, 5, 100).lit_segments(), 1);
+        assert_eq!(Bargraph::horizontal(area, 10, 5, 0).lit_segments(), 0);
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        let area = Rectangle::new(Point::zero(), Size::new(10, 3));
+        let mut display = mock();
+        ProgressBar::new(area, 25, -50..=50)
+            .with_track_color(Color::BLUE)
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["GGGGGGGBBB", "GGGGGGGBBB", "GGGGGGGBBB"]);
+
+        let mut display = mock();
+        ProgressBar::new(Rectangle::new(Point::zero(), Size::new(3, 6)), 200, 0..=100)
+            .with_direction(Direction::Up)
+            .with_color(Color::RED)
+            .with_border(Color::WHITE)
+            .draw(&mut display)
+            .unwrap();
+        display.assert_pattern(&["WWW", "WRW", "WRW", "WRW", "WRW", "WWW"]);
+
+        assert_eq!(fraction(-10, 0, 100, 8), 0);
+        assert_eq!(fraction(50, 0, 100, 8), 4);
+        assert_eq!(fraction(i32::MAX, i32::MIN, i32::MAX, 8), 8);
+        assert_eq!(fraction(5, 5, 5, 8), 8);
+    }
+
+    #[test]
+    fn test_clock_angle() {
+        for (dx, dy, expected) in [
+            (0.0, -1.0, 0.0),
+            (1.0, -1.0, 45.0),
+            (1.0, 0.0, 90.0),
+            (1.0, 3.0, 161.565),
+            (0.0, 1.0, 180.0),
+            (-2.0, 1.0, 243.435),
+            (-1.0, 0.0, 270.0),
+            (-1.0, -1.0, 315.0),
+            (-1.0, -20.0, 357.138),
+        ] {
+            let angle = clock_angle(dx, dy);
+            assert!((angle - expected).abs() < 0.1, "({dx}, {dy}): {angle}");
+        }
+        assert!((wrap_degrees(-90.0) - 270.0).abs() < f32::EPSILON);
+        assert!((wrap_degrees(450.0) - 90.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_gauge() {
+        let center = Point::new(10, 10);
+        let mut display = mock();
+        Gauge::new(center, 8, 2, 60, 0..=100)
+            .with_track_color(Color::BLUE)
+            .draw(&mut display)
+            .unwrap();
+
+        // the ring only
+        assert_eq!(display.get_pixel(center), None);
+        assert_eq!(display.get_pixel(center + Point::new(0, -5)), None);
+        assert_eq!(display.get_pixel(center + Point::new(0, -9)), None);
+        // from the lower left through the top: 60 % is past 12 o'clock
+        assert_eq!(
+            display.get_pixel(center + Point::new(-5, 5)),
+            Some(Color::GREEN)
+        );
+        assert_eq!(
+            display.get_pixel(center + Point::new(-7, 0)),
+            Some(Color::GREEN)
+        );
+        assert_eq!(
+            display.get_pixel(center + Point::new(0, -7)),
+            Some(Color::GREEN)
+        );
+        assert_eq!(
+            display.get_pixel(center + Point::new(7, 0)),
+            Some(Color::BLUE)
+        );
+        assert_eq!(
+            display.get_pixel(center + Point::new(5, 5)),
+            Some(Color::BLUE)
+        );
+        // the gap at the bottom
+        assert_eq!(display.get_pixel(center + Point::new(0, 7)), None);
+
+        // a full circle at the maximum
+        let mut display = mock();
+        Gauge::new(center, 8, 8, 100, 0..=100)
+            .with_angles(0.0, 360.0)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(display.get_pixel(center), Some(Color::GREEN));
+        assert_eq!(
+            display.get_pixel(center + Point::new(0, 8)),
+            Some(Color::GREEN)
+        );
+        assert_eq!(
+            display.get_pixel(center + Point::new(6, 6)),
+            Some(Color::GREEN)
+        );
+        assert_eq!(display.get_pixel(center + Point::new(7, 7)), None);
+    }
+
+    #[test]
+    fn test_battery() {
+        let mut display = mock();
+        Battery::new(
+            Rectangle::new(Point::zero(), Size::new(9, 6)),
+            50,
+            Color::GREEN,
+        )
+        .draw(&mut display)
+        .unwrap();
+        display.assert_pattern(&[
+            "GGGGGGGG ", //
+            "G      GG",
+            "G GG   GG",
+            "G GG   GG",
+            "G      G ",
+            "GGGGGGGG ",
+        ]);
+
+        let mut display = mock();
+        Battery::new(
+            Rectangle::new(Point::zero(), Size::new(9, 6)),
+            10,
+            Color::GREEN,
+        )
+        .draw(&mut display)
+        .unwrap();
+        assert_eq!(display.get_pixel(Point::new(2, 2)), None);
+        let mut display = mock();
+        Battery::new(
+            Rectangle::new(Point::zero(), Size::new(17, 6)),
+            20,
+            Color::GREEN,
+        )
+        .draw(&mut display)
+        .unwrap();
+        assert_eq!(display.get_pixel(Point::new(2, 2)), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_signal_bars() {
+        let mut display = mock();
+        SignalBars::new(
+            Rectangle::new(Point::zero(), Size::new(7, 4)),
+            3,
+            Color::WHITE,
+        )
+        .with_off_color(Color::BLUE)
+        .draw(&mut display)
+        .unwrap();
+        display.assert_pattern(&[
+            "      B", //
+            "    W B", "  W W B", "W W W B",
+        ]);
+    }
+}